@@ -1,10 +1,12 @@
 use crate::json_helpers::{get_bool, get_f64, get_str, get_u64};
+use serde::Deserialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
-/// Anthropic Claude pricing per million tokens, keyed by model name.
+/// Pricing per million tokens.
+#[derive(Clone, Copy, Debug, Deserialize)]
 struct Pricing {
     input: f64,
     output: f64,
@@ -33,14 +35,79 @@ const HAIKU_PRICING: Pricing = Pricing {
     cache_read: 0.08,
 };
 
-fn pricing_for_model(model: &str) -> &'static Pricing {
-    let m = model.to_ascii_lowercase();
-    if m.contains("opus") {
-        &OPUS_PRICING
-    } else if m.contains("haiku") {
-        &HAIKU_PRICING
-    } else {
-        &SONNET_PRICING
+/// Per-model rates, keyed by a substring of each run's `model` field.
+///
+/// Loaded from `pricing.toml` when present:
+///
+/// ```toml
+/// [default]            # models no key matches (optional)
+/// input = 3.00
+/// output = 15.00
+/// cache_creation = 3.75
+/// cache_read = 0.30
+///
+/// [models.opus]        # any model containing "opus"
+/// input = 15.00
+/// # ...
+/// ```
+///
+/// Keys from the file are added to the built-in Anthropic rates, replacing
+/// any with the same name.
+pub struct PricingTable {
+    models: BTreeMap<String, Pricing>,
+    default: Pricing,
+}
+
+#[derive(Deserialize)]
+struct PricingFile {
+    default: Option<Pricing>,
+    #[serde(default)]
+    models: BTreeMap<String, Pricing>,
+}
+
+impl Default for PricingTable {
+    fn default() -> Self {
+        Self {
+            models: BTreeMap::from([
+                ("opus".to_string(), OPUS_PRICING),
+                ("haiku".to_string(), HAIKU_PRICING),
+                ("sonnet".to_string(), SONNET_PRICING),
+            ]),
+            default: SONNET_PRICING,
+        }
+    }
+}
+
+impl PricingTable {
+    /// Built-in rates overlaid with `path`, or just the built-ins when the
+    /// file doesn't exist.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut table = Self::default();
+        if !path.exists() {
+            return Ok(table);
+        }
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let file: PricingFile =
+            toml::from_str(&content).map_err(|e| format!("{}: {e}", path.display()))?;
+        if let Some(default) = file.default {
+            table.default = default;
+        }
+        table.models.extend(
+            file.models
+                .into_iter()
+                .map(|(k, v)| (k.to_ascii_lowercase(), v)),
+        );
+        Ok(table)
+    }
+
+    /// Rates for a model: the longest key contained in its name, else the default.
+    fn for_model(&self, model: &str) -> &Pricing {
+        let m = model.to_ascii_lowercase();
+        self.models
+            .iter()
+            .filter(|(key, _)| m.contains(key.as_str()))
+            .max_by_key(|(key, _)| key.len())
+            .map_or(&self.default, |(_, p)| p)
     }
 }
 
@@ -72,9 +139,8 @@ fn compute_cost_breakdown(run: &Value, pricing: &Pricing) -> CostBreakdown {
 }
 
 /// Estimate cost for a run from its token counts and model pricing.
-fn estimated_cost(run: &Value) -> f64 {
-    let pricing = pricing_for_model(get_str(run, "model"));
-    let b = compute_cost_breakdown(run, pricing);
+fn estimated_cost(run: &Value, pricing: &PricingTable) -> f64 {
+    let b = compute_cost_breakdown(run, pricing.for_model(get_str(run, "model")));
     b.cache_creation_cost + b.cache_read_cost + b.output_cost + b.input_cost
 }
 
@@ -199,7 +265,7 @@ fn merge_tool_calls(runs: &[&Value]) -> HashMap<String, f64> {
     result
 }
 
-pub fn generate_report(results: &[Value]) -> String {
+pub fn generate_report(results: &[Value], pricing: &PricingTable) -> String {
     let valid: Vec<&Value> = results
         .iter()
         .filter(|r| r.get("error").is_none())
@@ -331,12 +397,18 @@ pub fn generate_report(results: &[Value]) -> String {
             for &(label, key) in metrics {
                 let is_cost = key == "estimated_cost";
                 let b_vals: Vec<f64> = if is_cost {
-                    baseline_runs.iter().map(|r| estimated_cost(r)).collect()
+                    baseline_runs
+                        .iter()
+                        .map(|r| estimated_cost(r, pricing))
+                        .collect()
                 } else {
                     baseline_runs.iter().map(|r| get_f64(r, key)).collect()
                 };
                 let g_vals: Vec<f64> = if is_cost {
-                    glean_runs.iter().map(|r| estimated_cost(r)).collect()
+                    glean_runs
+                        .iter()
+                        .map(|r| estimated_cost(r, pricing))
+                        .collect()
                 } else {
                     glean_runs.iter().map(|r| get_f64(r, key)).collect()
                 };
@@ -375,14 +447,14 @@ pub fn generate_report(results: &[Value]) -> String {
             lines.push(String::new());
 
             // Cost breakdown (estimated from tokens)
-            let b_median_run = find_median_run_by(baseline_runs, &estimated_cost);
-            let g_median_run = find_median_run_by(glean_runs, &estimated_cost);
-            let b_pricing = pricing_for_model(get_str(b_median_run, "model"));
-            let g_pricing = pricing_for_model(get_str(g_median_run, "model"));
+            let b_median_run = find_median_run_by(baseline_runs, &|r| estimated_cost(r, pricing));
+            let g_median_run = find_median_run_by(glean_runs, &|r| estimated_cost(r, pricing));
+            let b_pricing = pricing.for_model(get_str(b_median_run, "model"));
+            let g_pricing = pricing.for_model(get_str(g_median_run, "model"));
             let b_costs = compute_cost_breakdown(b_median_run, b_pricing);
             let g_costs = compute_cost_breakdown(g_median_run, g_pricing);
-            let b_total = estimated_cost(b_median_run);
-            let g_total = estimated_cost(g_median_run);
+            let b_total = estimated_cost(b_median_run, pricing);
+            let g_total = estimated_cost(g_median_run, pricing);
             let total_delta = g_total - b_total;
             let b_turns = get_u64(b_median_run, "num_turns");
             let g_turns = get_u64(g_median_run, "num_turns");
@@ -486,7 +558,10 @@ pub fn generate_report(results: &[Value]) -> String {
                 for &(label, key) in metrics {
                     let is_cost = key == "estimated_cost";
                     let vals: Vec<f64> = if is_cost {
-                        mode_results.iter().map(|r| estimated_cost(r)).collect()
+                        mode_results
+                            .iter()
+                            .map(|r| estimated_cost(r, pricing))
+                            .collect()
                     } else {
                         mode_results.iter().map(|r| get_f64(r, key)).collect()
                     };
@@ -544,7 +619,7 @@ pub fn generate_report(results: &[Value]) -> String {
                 let mut m: HashMap<&str, Vec<f64>> = HashMap::new();
                 for r in &baseline_all {
                     let val = if is_cost {
-                        estimated_cost(r)
+                        estimated_cost(r, pricing)
                     } else {
                         get_f64(r, key)
                     };
@@ -556,7 +631,7 @@ pub fn generate_report(results: &[Value]) -> String {
                 let mut m: HashMap<&str, Vec<f64>> = HashMap::new();
                 for r in &glean_all {
                     let val = if is_cost {
-                        estimated_cost(r)
+                        estimated_cost(r, pricing)
                     } else {
                         get_f64(r, key)
                     };
@@ -595,14 +670,18 @@ pub fn generate_report(results: &[Value]) -> String {
     lines.join("\n")
 }
 
-pub fn analyze(results_path: &Path, output_path: Option<&Path>) {
+pub fn analyze(results_path: &Path, output_path: Option<&Path>, pricing_path: &Path) {
     if !results_path.exists() {
         eprintln!("ERROR: File not found: {}", results_path.display());
         std::process::exit(1);
     }
+    let pricing = PricingTable::load(pricing_path).unwrap_or_else(|e| {
+        eprintln!("ERROR: Invalid pricing file {e}");
+        std::process::exit(1);
+    });
 
     let results = load_results(results_path);
-    let report = generate_report(&results);
+    let report = generate_report(&results, &pricing);
 
     if let Some(out) = output_path {
         if let Some(parent) = out.parent() {
@@ -614,3 +693,60 @@ pub fn analyze(results_path: &Path, output_path: Option<&Path>) {
         println!("{report}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn fixture_pricing() -> PricingTable {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pricing.toml");
+        PricingTable::load(&path).unwrap()
+    }
+
+    fn run(model: &str) -> Value {
+        json!({
+            "model": model,
+            "input_tokens": 1_000_000,
+            "output_tokens": 1_000_000,
+            "cache_creation_tokens": 1_000_000,
+            "cache_read_tokens": 1_000_000,
+        })
+    }
+
+    #[test]
+    fn costs_use_loaded_per_model_rates() {
+        let pricing = fixture_pricing();
+        // Overridden built-in key
+        assert!((estimated_cost(&run("claude-sonnet-4"), &pricing) - 36.0).abs() < 1e-9);
+        // Key only in the file
+        assert!((estimated_cost(&run("gpt-4o-mini"), &pricing) - 13.75).abs() < 1e-9);
+        // Built-in key the file doesn't mention
+        assert!((estimated_cost(&run("claude-opus-4"), &pricing) - 110.25).abs() < 1e-9);
+        // No key matches: the file's default
+        assert!((estimated_cost(&run("llama-3"), &pricing) - 3.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn missing_file_uses_builtin_rates() {
+        let pricing = PricingTable::load(Path::new("/nonexistent/pricing.toml")).unwrap();
+        assert!((estimated_cost(&run("claude-sonnet-4"), &pricing) - 22.05).abs() < 1e-9);
+        assert!((estimated_cost(&run("claude-haiku-4"), &pricing) - 5.88).abs() < 1e-9);
+    }
+
+    #[test]
+    fn report_costs_follow_loaded_rates() {
+        let results: Vec<Value> = ["baseline", "glean"]
+            .into_iter()
+            .map(|mode| {
+                let mut r = run("gpt-4o");
+                r["task"] = json!("t");
+                r["mode"] = json!(mode);
+                r
+            })
+            .collect();
+        let report = generate_report(&results, &fixture_pricing());
+        assert!(report.contains("$13.7500"), "{report}");
+    }
+}
@@ -4,6 +4,7 @@ mod config;
 mod eval;
 mod json_helpers;
 mod parse;
+mod perf;
 mod run;
 mod setup;
 mod task;
@@ -76,6 +77,9 @@ enum Commands {
         /// Output path for markdown report (default: stdout)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Per-model token rates (default: benchmark/pricing.toml, built-in rates if absent)
+        #[arg(long)]
+        pricing: Option<PathBuf>,
     },
     /// Compare two JSONL result files
     Compare {
@@ -84,6 +88,15 @@ enum Commands {
         /// New results file
         new: PathBuf,
     },
+    /// Time glean queries locally (no API calls) and report latency percentiles
+    Perf {
+        /// Timed calls per query, after one warm-up call
+        #[arg(long, default_value_t = 20)]
+        iterations: u32,
+        /// Also time queries against cloned repos (`bench setup --repos`)
+        #[arg(long)]
+        repos: bool,
+    },
     /// Set up benchmark fixtures
     Setup {
         /// Clone real-world repos at pinned commits
@@ -172,12 +185,17 @@ fn main() {
         Commands::Analyze {
             results_file,
             output,
+            pricing,
         } => {
-            analyze::analyze(&results_file, output.as_deref());
+            let pricing = pricing.unwrap_or_else(|| config::benchmark_dir().join("pricing.toml"));
+            analyze::analyze(&results_file, output.as_deref(), &pricing);
         }
         Commands::Compare { old, new } => {
             compare::compare(&old, &new);
         }
+        Commands::Perf { iterations, repos } => {
+            perf::perf(iterations, repos);
+        }
         Commands::Setup { repos } => {
             if !repos {
                 println!("Specify --repos to clone real-world repos at pinned commits");
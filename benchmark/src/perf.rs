@@ -0,0 +1,270 @@
+use crate::config;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// A single glean query, labelled by the query type it exercises.
+struct PerfQuery {
+    kind: &'static str,
+    query: &'static str,
+}
+
+/// A directory and the queries to time against it.
+struct Target {
+    name: String,
+    scope: PathBuf,
+    queries: Vec<PerfQuery>,
+}
+
+/// One timed call.
+struct Sample {
+    target: String,
+    kind: &'static str,
+    query: &'static str,
+    elapsed: Duration,
+    ok: bool,
+}
+
+const fn q(kind: &'static str, query: &'static str) -> PerfQuery {
+    PerfQuery { kind, query }
+}
+
+/// Mini fixtures from the glean repo — always present, so results are comparable
+/// across machines and commits.
+fn fixture_targets(fixtures: &Path) -> Vec<Target> {
+    let target = |name: &str, queries: Vec<PerfQuery>| Target {
+        name: name.to_string(),
+        scope: fixtures.join(name),
+        queries,
+    };
+    vec![
+        target(
+            "mini-go",
+            vec![
+                q("symbol", "ServeHTTP"),
+                q("content", "X-Forwarded-For"),
+                q("regex", r"/func \(e \*Engine\)/"),
+                q("glob", "*.go"),
+                q("read", "router.go"),
+            ],
+        ),
+        target(
+            "mini-rust",
+            vec![
+                q("symbol", "Matcher"),
+                q("content", "haystack: &[u8]"),
+                q("glob", "*.rs"),
+                q("read", "src/lib.rs"),
+            ],
+        ),
+        target(
+            "mini-ts",
+            vec![
+                q("symbol", "ZodError"),
+                q("content", "safeParse"),
+                q("glob", "*.ts"),
+                q("read", "src/parse.ts"),
+            ],
+        ),
+        target(
+            "mini-swift",
+            vec![q("symbol", "request"), q("read", "Session.swift")],
+        ),
+    ]
+}
+
+/// Cloned benchmark repos (`bench setup --repos`); missing ones are skipped.
+fn repo_targets(repos_dir: &Path) -> Vec<Target> {
+    let queries: [(&str, Vec<PerfQuery>); 4] = [
+        (
+            "gin",
+            vec![
+                q("symbol", "ServeHTTP"),
+                q("content", "X-Forwarded-For"),
+                q("regex", r"/func \(c \*Context\) (Get|Set)\w+/"),
+                q("glob", "*_test.go"),
+                q("read", "gin.go"),
+            ],
+        ),
+        (
+            "ripgrep",
+            vec![
+                q("symbol", "SearchWorker"),
+                q("content", "--max-columns"),
+                q("glob", "*.rs"),
+                q("read", "crates/core/main.rs"),
+            ],
+        ),
+        (
+            "alamofire",
+            vec![
+                q("symbol", "validate"),
+                q("content", "HTTPMethod"),
+                q("glob", "*.swift"),
+            ],
+        ),
+        (
+            "zod",
+            vec![
+                q("symbol", "ZodError"),
+                q("content", "safeParse"),
+                q("glob", "*.test.ts"),
+            ],
+        ),
+    ];
+    queries
+        .into_iter()
+        .map(|(name, queries)| Target {
+            name: name.to_string(),
+            scope: repos_dir.join(name),
+            queries,
+        })
+        .filter(|t| t.scope.is_dir())
+        .collect()
+}
+
+/// Time every query `iterations` times after one discarded warm-up call.
+/// Each call gets a fresh outline cache so runs don't depend on earlier ones.
+fn measure(targets: &[Target], iterations: u32) -> Vec<Sample> {
+    let mut samples = Vec::new();
+    for target in targets {
+        for pq in &target.queries {
+            let call = || {
+                let cache = glean::cache::OutlineCache::new();
+                let start = Instant::now();
+                let ok = glean::run(pq.query, &target.scope, None, None, &cache).is_ok();
+                (start.elapsed(), ok)
+            };
+            let _ = call();
+            for _ in 0..iterations {
+                let (elapsed, ok) = call();
+                samples.push(Sample {
+                    target: target.name.clone(),
+                    kind: pq.kind,
+                    query: pq.query,
+                    elapsed,
+                    ok,
+                });
+            }
+        }
+    }
+    samples
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn ms(d: Duration) -> String {
+    format!("{:.2}ms", d.as_secs_f64() * 1000.0)
+}
+
+fn stats_row(label: &str, durations: &mut [Duration]) -> String {
+    durations.sort();
+    format!(
+        "| {label} | {} | {} | {} | {} |",
+        durations.len(),
+        ms(percentile(durations, 50.0)),
+        ms(percentile(durations, 95.0)),
+        ms(durations.last().copied().unwrap_or_default()),
+    )
+}
+
+fn report(samples: &[Sample], iterations: u32) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# glean perf — {iterations} iterations per query\n");
+
+    let mut by_kind: BTreeMap<&str, Vec<Duration>> = BTreeMap::new();
+    let mut by_query: BTreeMap<(String, &str, &str), Vec<Duration>> = BTreeMap::new();
+    for s in samples {
+        by_kind.entry(s.kind).or_default().push(s.elapsed);
+        by_query
+            .entry((s.target.clone(), s.kind, s.query))
+            .or_default()
+            .push(s.elapsed);
+    }
+
+    out.push_str("## By query type\n\n");
+    out.push_str("| Type | Samples | p50 | p95 | max |\n|---|---|---|---|---|\n");
+    for (kind, durations) in &mut by_kind {
+        let _ = writeln!(out, "{}", stats_row(kind, durations));
+    }
+
+    out.push_str("\n## By query\n\n");
+    out.push_str("| Query | Samples | p50 | p95 | max |\n|---|---|---|---|---|\n");
+    for ((target, kind, query), durations) in &mut by_query {
+        let label = format!("{target} {kind} `{query}`");
+        let _ = writeln!(out, "{}", stats_row(&label, durations));
+    }
+
+    let failed: Vec<&Sample> = samples.iter().filter(|s| !s.ok).collect();
+    if !failed.is_empty() {
+        let _ = writeln!(
+            out,
+            "\nWARNING: {} calls returned errors (first: {} `{}`)",
+            failed.len(),
+            failed[0].target,
+            failed[0].query
+        );
+    }
+    out
+}
+
+/// `bench perf`: time glean's library entry point directly — no API calls.
+pub fn perf(iterations: u32, repos: bool) {
+    let project_root = config::benchmark_dir()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let mut targets = fixture_targets(&project_root.join("tests/fixtures"));
+    if repos {
+        let found = repo_targets(&config::repos_dir());
+        if found.is_empty() {
+            eprintln!("  NOTE: no cloned repos found — run `bench setup --repos`");
+        }
+        targets.extend(found);
+    }
+
+    let samples = measure(&targets, iterations);
+    print!("{}", report(&samples, iterations));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_nearest_rank() {
+        let d: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&d, 50.0), Duration::from_millis(10));
+        assert_eq!(percentile(&d, 95.0), Duration::from_millis(19));
+        assert_eq!(percentile(&d[..1], 95.0), Duration::from_millis(1));
+    }
+
+    /// Smoke test: every fixture query runs and each query type gets a row.
+    #[test]
+    fn perf_over_fixtures_reports_each_kind() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures");
+        let targets = fixture_targets(&fixtures);
+        let samples = measure(&targets, 2);
+
+        assert!(
+            samples.iter().all(|s| s.ok),
+            "fixture queries should succeed"
+        );
+        let out = report(&samples, 2);
+        for kind in ["symbol", "content", "regex", "glob", "read"] {
+            assert!(
+                out.contains(&format!("| {kind} |")),
+                "missing {kind}:\n{out}"
+            );
+        }
+        assert!(!out.contains("WARNING"), "{out}");
+    }
+}
@@ -9,8 +9,24 @@ pub struct GroundTruth {
     /// File to check for diffs (when non-empty, git diff is validated).
     pub file_path: &'static str,
     pub expected_diff_contains: Vec<&'static str>,
+    /// Definition the answer must point at (locate tasks). `None` keeps the
+    /// substring-only check.
+    pub location: Option<Location>,
 }
 
+/// Where a definition lives: a file (matched by path suffix) and its
+/// 1-indexed inclusive line range.
+#[derive(Clone, Copy, Debug)]
+pub struct Location {
+    pub file: &'static str,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// How far a reported line may drift from the true one and still count —
+/// agents often cite the doc comment or attribute above a definition.
+pub const LINE_TOLERANCE: u32 = 3;
+
 impl Default for GroundTruth {
     fn default() -> Self {
         Self {
@@ -18,6 +34,7 @@ impl Default for GroundTruth {
             forbidden_strings: vec!["I cannot", "I don't have access", "no such file"],
             file_path: "",
             expected_diff_contains: Vec::new(),
+            location: None,
         }
     }
 }
@@ -42,6 +59,76 @@ impl GroundTruth {
             ..Self::default()
         }
     }
+
+    /// Substring check plus the file and line range of a definition, which
+    /// the answer must cite as `file:line`, `file:start-end`, or
+    /// `file ... line(s) N[-M]`.
+    pub fn locate(required: Vec<&'static str>, location: Location) -> Self {
+        Self {
+            required_strings: required,
+            location: Some(location),
+            ..Self::default()
+        }
+    }
+}
+
+impl Location {
+    /// Whether any range reported for this file in `text` lands on the
+    /// definition: the start within `LINE_TOLERANCE` of the true start, and a
+    /// reported end (if any) within tolerance of the true end.
+    pub fn matches(&self, text: &str) -> bool {
+        reported_ranges(text, self.file)
+            .into_iter()
+            .any(|(start, end)| {
+                start.abs_diff(self.start) <= LINE_TOLERANCE
+                    && end.is_none_or(|end| end.abs_diff(self.end) <= LINE_TOLERANCE)
+            })
+    }
+}
+
+/// Line ranges cited right after each mention of `file` (or its basename).
+fn reported_ranges(text: &str, file: &str) -> Vec<(u32, Option<u32>)> {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    let mut ranges = Vec::new();
+    for (idx, _) in text.match_indices(name) {
+        let after = &text[idx + name.len()..];
+        let window: String = after.chars().take(40).collect();
+        let rest = if let Some(r) = window.strip_prefix(':') {
+            Some(r)
+        } else {
+            let lower = window.to_ascii_lowercase();
+            lower.find("line").map(|i| {
+                let r = &window[i + "line".len()..];
+                r.strip_prefix('s').unwrap_or(r).trim_start()
+            })
+        };
+        if let Some(range) = rest.and_then(parse_range) {
+            ranges.push(range);
+        }
+    }
+    ranges
+}
+
+/// `12`, `12-20`, `12–20`, or `L12-L20` at the start of `s`.
+fn parse_range(s: &str) -> Option<(u32, Option<u32>)> {
+    let number = |s: &str| -> Option<(u32, usize)> {
+        let s_trim = s.strip_prefix('L').unwrap_or(s);
+        let skipped = s.len() - s_trim.len();
+        let digits = s_trim.len()
+            - s_trim
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        let n = s_trim[..digits].parse().ok()?;
+        Some((n, skipped + digits))
+    };
+    let (start, used) = number(s)?;
+    let rest = &s[used..];
+    let end = ['-', '–']
+        .iter()
+        .find_map(|sep| rest.strip_prefix(*sep))
+        .and_then(|r| number(r.trim_start()))
+        .map(|(n, _)| n);
+    Some((start, end))
 }
 
 pub trait Task: Sync {
@@ -73,6 +160,9 @@ pub trait Task: Sync {
     /// For edit tasks (non-empty `file_path`): checks git diff for expected
     /// patterns. `required_strings` are checked against *both* the assistant
     /// text and the diff output — a match in either counts.
+    ///
+    /// For locate tasks (`location` set): the answer must also cite the
+    /// definition's file and line range, within `LINE_TOLERANCE`.
     fn check_correctness(&self, result_text: &str, repo_path: &Path) -> (bool, String) {
         let gt = self.ground_truth();
         let text_lower = result_text.to_lowercase();
@@ -123,6 +213,54 @@ pub trait Task: Sync {
             }
         }
 
+        if let Some(loc) = gt.location
+            && !loc.matches(result_text)
+        {
+            return (
+                false,
+                format!(
+                    "Wrong location: expected {}:{}-{}",
+                    loc.file, loc.start, loc.end
+                ),
+            );
+        }
+
         (true, "All checks passed".into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVE_HTTP: Location = Location {
+        file: "router.go",
+        start: 12,
+        end: 20,
+    };
+
+    #[test]
+    fn location_accepts_common_citation_forms() {
+        assert!(SERVE_HTTP.matches("Defined at router.go:12"));
+        assert!(SERVE_HTTP.matches("see `mini-go/router.go:12-20`"));
+        assert!(SERVE_HTTP.matches("In router.go (lines 12–20) the engine..."));
+        assert!(SERVE_HTTP.matches("router.go:L12-L20"));
+    }
+
+    #[test]
+    fn location_tolerates_small_offsets() {
+        // Doc comment line above the func, and an end off by one
+        assert!(SERVE_HTTP.matches("router.go:11-21"));
+        assert!(SERVE_HTTP.matches("router.go line 15"));
+        assert!(!SERVE_HTTP.matches("router.go:23-25"));
+        assert!(!SERVE_HTTP.matches("router.go:12-40"));
+    }
+
+    #[test]
+    fn location_requires_the_right_file() {
+        assert!(!SERVE_HTTP.matches("context.go:12-20"));
+        assert!(!SERVE_HTTP.matches("ServeHTTP is in router.go"));
+        // A later, correct citation still counts
+        assert!(SERVE_HTTP.matches("router.go:3 imports net/http; ServeHTTP is at router.go:12"));
+    }
+}
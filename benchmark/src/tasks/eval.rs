@@ -1,4 +1,4 @@
-use crate::task::{GroundTruth, Task};
+use crate::task::{GroundTruth, Location, Task};
 use std::path::PathBuf;
 
 /// Resolve the project root (parent of `benchmark/`).
@@ -213,3 +213,38 @@ impl Task for SwiftChain {
         Some(fixture_dir("mini-swift"))
     }
 }
+
+// ---------------------------------------------------------------------------
+// Go: locate the ServeHTTP definition (file + line range)
+// ---------------------------------------------------------------------------
+
+pub struct GoLocateServeHttp;
+impl Task for GoLocateServeHttp {
+    fn name(&self) -> &'static str {
+        "eval_go_locate_servehttp"
+    }
+    fn repo(&self) -> &'static str {
+        "mini-go"
+    }
+    fn prompt(&self) -> &'static str {
+        "This is a small Go package in the current directory. Only look at files in this directory. \
+         Where is the `ServeHTTP` method on `Engine` defined? Answer with the file and the line \
+         range of the whole method in the form `file:start-end`."
+    }
+    fn task_type(&self) -> &'static str {
+        "locate"
+    }
+    fn ground_truth(&self) -> GroundTruth {
+        GroundTruth::locate(
+            vec!["ServeHTTP"],
+            Location {
+                file: "router.go",
+                start: 12,
+                end: 20,
+            },
+        )
+    }
+    fn work_dir(&self) -> Option<PathBuf> {
+        Some(fixture_dir("mini-go"))
+    }
+}
@@ -82,6 +82,10 @@ pub fn eval_tasks() -> HashMap<&'static str, Box<dyn Task>> {
     m.insert("eval_go_rename_method", Box::new(eval::GoRenameMethod));
     m.insert("eval_ts_class_usage", Box::new(eval::TsClassUsage));
     m.insert("eval_swift_chain", Box::new(eval::SwiftChain));
+    m.insert(
+        "eval_go_locate_servehttp",
+        Box::new(eval::GoLocateServeHttp),
+    );
 
     m
 }
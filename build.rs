@@ -1,3 +1,5 @@
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
 fn main() {
@@ -20,7 +22,44 @@ fn main() {
 
     println!("cargo:rustc-env=GLEAN_BUILD_COMMIT={commit}");
 
+    // Outline output changes with the code and its grammars, so the on-disk
+    // outline cache is versioned by a hash of both — dirty trees included
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    let mut files = Vec::new();
+    collect_files(Path::new("src"), &mut files);
+    files.sort();
+    files.extend(["Cargo.toml".into(), "Cargo.lock".into()]);
+    for file in &files {
+        for &b in file
+            .to_string_lossy()
+            .as_bytes()
+            .iter()
+            .chain(&fs::read(file).unwrap_or_default())
+        {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    println!("cargo:rustc-env=GLEAN_SOURCE_HASH={hash:016x}");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
     // Re-run if HEAD changes (new commit)
     println!("cargo:rerun-if-changed=.git/HEAD");
     println!("cargo:rerun-if-changed=.git/index");
 }
+
+fn collect_files(dir: &Path, out: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
@@ -1,18 +1,41 @@
-use crate::types::estimate_tokens;
+use serde_json::Value;
+
+use crate::types::{bytes_for_tokens, count_tokens};
+
+/// Output cut to a token budget, and how much of it was kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budgeted {
+    pub output: String,
+    pub truncated: bool,
+    pub original_tokens: u64,
+    pub returned_tokens: u64,
+}
+
+impl Budgeted {
+    fn whole(output: String, tokens: u64) -> Self {
+        Self {
+            output,
+            truncated: false,
+            original_tokens: tokens,
+            returned_tokens: tokens,
+        }
+    }
+}
 
 /// Apply token budget to output. Works backwards from the cap:
 /// 1. Reserve 50 tokens for header
 /// 2. Truncate content at section boundaries to avoid broken output
 /// 3. Never exceed the budget
-pub fn apply(output: &str, budget: u64) -> String {
-    let current = estimate_tokens(output.len() as u64);
+#[must_use]
+pub fn apply(output: &str, budget: u64) -> Budgeted {
+    let current = count_tokens(output);
     if current <= budget {
-        return output.to_string();
+        return Budgeted::whole(output.to_string(), current);
     }
 
     let header_reserve = 50u64;
     let content_budget = budget.saturating_sub(header_reserve);
-    let max_bytes = (content_budget * 4) as usize; // inverse of estimate_tokens
+    let max_bytes = bytes_for_tokens(output, current, content_budget);
 
     // Find the first newline after the header (first line)
     let header_end = output.find('\n').unwrap_or(0);
@@ -20,26 +43,108 @@ pub fn apply(output: &str, budget: u64) -> String {
     let body = &output[header_end..];
 
     if body.len() <= max_bytes {
-        return output.to_string();
+        return Budgeted::whole(output.to_string(), current);
     }
 
     let safe_max = body.floor_char_boundary(max_bytes);
     let truncated = &body[..safe_max];
 
     // Prefer section boundaries (\n\n##) to avoid cutting mid-match in search results
-    let cut_point = truncated
+    let mut cut_point = truncated
         .rfind("\n\n##")
         .or_else(|| truncated.rfind("\n\n"))
         .or_else(|| truncated.rfind('\n'))
-        .unwrap_or(max_bytes);
+        .unwrap_or(safe_max);
+    // Bytes per token varies across real tokenizer output — back off a line at
+    // a time until the kept body fits. Never loops with the bytes/4 estimate.
+    while cut_point > 0 && count_tokens(&body[..cut_point]) > content_budget {
+        cut_point = body[..cut_point].rfind('\n').unwrap_or(0);
+    }
 
     let clean_body = &body[..cut_point];
 
-    let omitted_bytes = output.len() - header_end - cut_point;
-    let remaining_tokens = estimate_tokens(omitted_bytes as u64);
-    format!(
+    let remaining_tokens = count_tokens(&body[cut_point..]);
+    let output = format!(
         "{header}{clean_body}\n\n... truncated ({remaining_tokens} tokens omitted, budget: {budget})"
-    )
+    );
+    Budgeted {
+        returned_tokens: count_tokens(&output),
+        output,
+        truncated: true,
+        original_tokens: current,
+    }
+}
+
+/// Fit JSON search output to `budget` without breaking it: trailing entries
+/// of `matches` are dropped, and the object gains `truncated`,
+/// `original_tokens` and `returned_tokens` as in [`Budgeted`]. An array of results splits the budget with [`allocate`].
+#[must_use]
+pub fn apply_json(value: Value, budget: u64) -> Value {
+    match value {
+        Value::Array(items) => {
+            let sizes: Vec<u64> = items.iter().map(|v| count_tokens(&v.to_string())).collect();
+            let shares = allocate(&sizes, budget.saturating_sub(items.len() as u64));
+            Value::Array(
+                items
+                    .into_iter()
+                    .zip(shares)
+                    .map(|(item, share)| apply_json(item, share))
+                    .collect(),
+            )
+        }
+        Value::Object(mut object) => {
+            // Room for the metadata fields themselves
+            const METADATA: u64 = 20;
+            let tokens = |object: &serde_json::Map<String, Value>| {
+                count_tokens(&serde_json::to_string(object).unwrap_or_default())
+            };
+            let original = tokens(&object);
+            let mut returned = original;
+            if original + METADATA > budget
+                && let Some(Value::Array(matches)) = object.get_mut("matches")
+            {
+                let mut matches = std::mem::take(matches);
+                // Longest prefix of matches that fits; each check reserializes
+                let (mut lo, mut hi) = (0, matches.len());
+                while lo < hi {
+                    let mid = (lo + hi).div_ceil(2);
+                    object.insert("matches".into(), Value::Array(matches[..mid].to_vec()));
+                    if tokens(&object) + METADATA <= budget {
+                        lo = mid;
+                    } else {
+                        hi = mid - 1;
+                    }
+                }
+                matches.truncate(lo);
+                object.insert("matches".into(), Value::Array(matches));
+                returned = tokens(&object);
+            }
+            let truncated = returned < original;
+            object.insert("truncated".into(), truncated.into());
+            object.insert("original_tokens".into(), original.into());
+            object.insert("returned_tokens".into(), returned.into());
+            Value::Object(object)
+        }
+        other => other,
+    }
+}
+
+/// Split `budget` tokens across outputs of `sizes` tokens: each gets an equal
+/// share, and what small outputs leave unused goes to the larger ones. Outputs
+/// that fit are never cut; the rest share the remainder evenly.
+#[must_use]
+pub fn allocate(sizes: &[u64], budget: u64) -> Vec<u64> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| sizes[i]);
+
+    let mut shares = vec![0; sizes.len()];
+    let mut left = budget;
+    for (n, &i) in order.iter().enumerate() {
+        let fair = left / (sizes.len() - n) as u64;
+        shares[i] = sizes[i].min(fair);
+        left -= shares[i];
+    }
+    shares
 }
 
 #[cfg(test)]
@@ -49,7 +154,7 @@ mod tests {
     #[test]
     fn under_budget_passes_through() {
         let output = "# header\nshort content";
-        let result = apply(output, 1000);
+        let result = apply(output, 1000).output;
         assert_eq!(result, output);
     }
 
@@ -66,10 +171,20 @@ mod tests {
         let header = "# header";
         let body = make_long_body();
         let output = format!("{header}{body}");
-        let result = apply(&output, 100);
+        let budgeted = apply(&output, 100);
+        let result = &budgeted.output;
         assert!(result.len() < output.len(), "should be shorter");
         assert!(result.contains("truncated"), "should mention truncation");
         assert!(result.contains("budget:"), "should mention budget");
+
+        assert!(budgeted.truncated);
+        assert_eq!(budgeted.original_tokens, count_tokens(&output));
+        assert_eq!(budgeted.returned_tokens, count_tokens(result));
+        assert!(budgeted.returned_tokens <= 100);
+
+        let whole = apply("# header\nshort", 100);
+        assert!(!whole.truncated);
+        assert_eq!(whole.original_tokens, whole.returned_tokens);
     }
 
     #[test]
@@ -77,10 +192,56 @@ mod tests {
         let header = "# my important header";
         let body = make_long_body();
         let output = format!("{header}{body}");
-        let result = apply(&output, 100);
+        let result = apply(&output, 100).output;
         assert!(
             result.starts_with(header),
             "header should be preserved: {result}"
         );
     }
+
+    #[test]
+    fn allocation_passes_unused_share_on() {
+        // Small outputs keep everything; the two large ones split the rest
+        assert_eq!(allocate(&[100, 5000, 50, 8000], 2000), [100, 925, 50, 925]);
+        // All fit: untouched
+        assert_eq!(allocate(&[10, 20], 100), [10, 20]);
+        assert!(allocate(&[], 100).is_empty());
+    }
+
+    #[test]
+    fn json_drops_trailing_matches_and_says_so() {
+        let matches: Vec<Value> = (0..100)
+            .map(|i| serde_json::json!({ "path": format!("src/file_{i}.rs"), "line": i, "text": "let value = compute();" }))
+            .collect();
+        let result =
+            serde_json::json!({ "query": "compute", "total_found": 100, "matches": matches });
+
+        let cut = apply_json(result.clone(), 400);
+        assert!(count_tokens(&cut.to_string()) <= 400, "{cut}");
+        assert_eq!(cut["truncated"], true);
+        assert_eq!(cut["original_tokens"], count_tokens(&result.to_string()));
+        let kept = cut["matches"].as_array().unwrap();
+        assert!(!kept.is_empty() && kept.len() < 100);
+        assert_eq!(kept[0]["path"], "src/file_0.rs");
+
+        let roomy = apply_json(result, 100_000);
+        assert_eq!(roomy["truncated"], false);
+        assert_eq!(roomy["matches"].as_array().unwrap().len(), 100);
+    }
+
+    /// The kept output fits by whichever count is compiled in — CJK text is
+    /// where bytes/4 and a real tokenizer disagree most.
+    #[test]
+    fn truncated_body_fits_budget() {
+        use std::fmt::Write;
+        let body = (0..200).fold(String::new(), |mut acc, i| {
+            let _ = write!(acc, "\n{i}: 検索結果の行、トークン数を数える");
+            acc
+        });
+        let output = format!("# header{body}");
+        let result = apply(&output, 100).output;
+        let (kept, footer) = result.rsplit_once("\n\n... truncated").unwrap();
+        assert!(count_tokens(kept) <= 100, "{}", count_tokens(kept));
+        assert!(footer.contains("budget: 100"));
+    }
 }
@@ -1,29 +1,96 @@
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant, SystemTime};
 
 use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 
-/// Cached outline entry with insertion timestamp for TTL-based eviction.
+use crate::types::FileType;
+
+/// Cached outline entry with insertion timestamp for TTL-based eviction and
+/// a last-use tick for LRU eviction.
 struct CacheEntry {
     outline: Arc<str>,
     inserted_at: Instant,
+    last_used: AtomicU64,
+}
+
+/// Default bounds for the in-memory outlines: a long MCP session over a huge
+/// repo would otherwise keep every outline it ever produced.
+pub const DEFAULT_MAX_ENTRIES: usize = 10_000;
+pub const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// On-disk outlines live in a directory named for the build that wrote them:
+/// the crate version and a hash of the sources, manifest and lockfile (from
+/// `build.rs`). Any change to outline code or grammars starts afresh.
+const DISK_VERSION: &str = concat!(
+    "outlines-",
+    env!("CARGO_PKG_VERSION"),
+    "-",
+    env!("GLEAN_SOURCE_HASH")
+);
+
+/// On-disk outlines not used for this long are deleted.
+const DISK_MAX_AGE: Duration = Duration::from_hours(30 * 24);
+
+/// Disk pruning runs at most this often, so CLI runs don't rescan the cache.
+const DISK_PRUNE_INTERVAL: Duration = Duration::from_hours(24);
+
+/// Which rendering of a file an entry holds. Callers that view the same
+/// version of a file differently — the map's capped outline, search
+/// context's full one, a forced language, collapsed `#[cfg]` variants — get
+/// separate entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct View {
+    pub(crate) file_type: FileType,
+    pub(crate) capped: bool,
+    pub(crate) dedup_variants: bool,
 }
 
-/// Outline cache keyed by (canonical path, mtime). If the file changes,
+impl View {
+    pub(crate) fn outline(file_type: FileType, capped: bool) -> Self {
+        Self {
+            file_type,
+            capped,
+            dedup_variants: false,
+        }
+    }
+}
+
+/// Outline cache keyed by (canonical path, mtime, view). If the file changes,
 /// mtime changes, old entry is never hit, gets evicted on next prune.
 ///
 /// Value is `Arc<str>` — inline string data in the Arc allocation,
 /// one less indirection than `Arc<String>`.
+///
+/// With a disk directory, misses are looked up on disk before computing and
+/// written back after, so separate CLI runs share outlines.
+///
+/// Memory is bounded by an entry count and a byte budget; past either, the
+/// least recently used outlines are evicted.
 pub struct OutlineCache {
-    entries: DashMap<(PathBuf, SystemTime), CacheEntry>,
+    entries: DashMap<(PathBuf, SystemTime, View), CacheEntry>,
+    /// Versioned directory of on-disk outlines; `None` keeps them in memory only.
+    disk: Option<PathBuf>,
+    max_entries: usize,
+    max_bytes: usize,
+    /// Total outline bytes currently held.
+    bytes: AtomicUsize,
+    /// Monotonic use counter — cheaper and strictly ordered, unlike `Instant`.
+    clock: AtomicU64,
 }
 
 impl Default for OutlineCache {
     fn default() -> Self {
         Self {
             entries: DashMap::new(),
+            disk: None,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_bytes: DEFAULT_MAX_BYTES,
+            bytes: AtomicUsize::new(0),
+            clock: AtomicU64::new(0),
         }
     }
 }
@@ -34,34 +101,412 @@ impl OutlineCache {
         Self::default()
     }
 
+    /// A cache that also persists outlines under `dir`, one file per
+    /// (path, mtime, size, view). Disk errors are ignored — the outline is
+    /// computed. Outlines from other builds and ones unused for 30 days are
+    /// deleted, at most once a day.
+    #[must_use]
+    pub fn with_disk(dir: &Path) -> Self {
+        prune_disk(dir, SystemTime::now());
+        Self {
+            disk: Some(dir.join(DISK_VERSION)),
+            ..Self::default()
+        }
+    }
+
+    /// Cap the in-memory outlines at `max_entries` and `max_bytes` in total.
+    #[must_use]
+    pub fn with_limits(mut self, max_entries: usize, max_bytes: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// `$XDG_CACHE_HOME/glean`, else `~/.cache/glean`.
+    #[must_use]
+    pub fn default_disk_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+        Some(base.join("glean"))
+    }
+
     /// Get cached outline or compute and cache it. Accepts `&Path` (not `&PathBuf`).
     /// Uses `entry()` API to avoid TOCTOU race between get and insert.
-    pub fn get_or_compute(
+    pub(crate) fn get_or_compute(
         &self,
         path: &Path,
         mtime: SystemTime,
+        view: View,
         compute: impl FnOnce() -> String,
     ) -> Arc<str> {
-        match self.entries.entry((path.to_path_buf(), mtime)) {
-            Entry::Occupied(e) => Arc::clone(&e.get().outline),
+        let tick = self.clock.fetch_add(1, Ordering::Relaxed);
+        let outline = match self.entries.entry((path.to_path_buf(), mtime, view)) {
+            Entry::Occupied(e) => {
+                e.get().last_used.store(tick, Ordering::Relaxed);
+                return Arc::clone(&e.get().outline);
+            }
             Entry::Vacant(e) => {
-                let outline: Arc<str> = compute().into();
+                let disk_file = self.disk_file(path, mtime, view);
+                let outline: Arc<str> =
+                    if let Some(outline) = disk_file.as_deref().and_then(|f| read_disk(f, path)) {
+                        outline.into()
+                    } else {
+                        let outline = compute();
+                        if let Some(file) = &disk_file {
+                            write_disk(file, path, &outline);
+                        }
+                        outline.into()
+                    };
+                self.bytes.fetch_add(outline.len(), Ordering::Relaxed);
                 e.insert(CacheEntry {
                     outline: Arc::clone(&outline),
                     inserted_at: Instant::now(),
+                    last_used: AtomicU64::new(tick),
                 });
                 outline
             }
+        };
+        // Outside the entry guard: eviction walks every shard
+        self.evict_lru();
+        outline
+    }
+
+    /// Number of outlines held in memory.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Total bytes of the outlines held in memory.
+    #[must_use]
+    pub fn bytes(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// One-line size report for `glean_session`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "Outline cache: {} / {} entries, {} / {} KB",
+            self.len(),
+            self.max_entries,
+            self.bytes().div_ceil(1024),
+            self.max_bytes / 1024
+        )
+    }
+
+    /// Drop least recently used outlines until both bounds hold. Evicts down
+    /// to 90% of the limits so a full cache doesn't rescan on every insert.
+    fn evict_lru(&self) {
+        if self.entries.len() <= self.max_entries && self.bytes() <= self.max_bytes {
+            return;
+        }
+        let target_entries = self.max_entries - self.max_entries / 10;
+        let target_bytes = self.max_bytes - self.max_bytes / 10;
+
+        let mut by_age: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| (e.last_used.load(Ordering::Relaxed), e.key().clone()))
+            .collect();
+        by_age.sort_unstable_by_key(|(tick, _)| *tick);
+
+        for (_, key) in by_age {
+            if self.entries.len() <= target_entries && self.bytes() <= target_bytes {
+                break;
+            }
+            if let Some((_, entry)) = self.entries.remove(&key) {
+                self.bytes.fetch_sub(entry.outline.len(), Ordering::Relaxed);
+            }
         }
     }
 
+    /// Whether an outline for this version of `path` is cached, in memory or
+    /// on disk.
+    #[must_use]
+    pub(crate) fn contains(&self, path: &Path, mtime: SystemTime, view: View) -> bool {
+        self.entries
+            .contains_key(&(path.to_path_buf(), mtime, view))
+            || self
+                .disk_file(path, mtime, view)
+                .is_some_and(|file| file.is_file())
+    }
+
+    /// On-disk location for this version and view of `path`: a hash of its
+    /// path, mtime, size and view. `None` without a disk directory or when
+    /// `path` can't be stat'd.
+    fn disk_file(&self, path: &Path, mtime: SystemTime, view: View) -> Option<PathBuf> {
+        let dir = self.disk.as_ref()?;
+        let size = fs::metadata(path).ok()?.len();
+        let nanos = mtime
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let mut hash = Fnv::default();
+        hash.write(path.as_os_str().as_encoded_bytes());
+        hash.write(&nanos.to_le_bytes());
+        hash.write(&size.to_le_bytes());
+        // Debug text is stable within a build, and the directory is per build
+        hash.write(format!("{view:?}").as_bytes());
+        Some(dir.join(format!("{:016x}", hash.0)))
+    }
+
     /// Evict entries that were cached more than `max_age` ago.
     pub fn prune(&self, max_age: Duration) {
         let Some(cutoff) = Instant::now().checked_sub(max_age) else {
             // max_age exceeds uptime — evict everything
             self.entries.clear();
+            self.bytes.store(0, Ordering::Relaxed);
             return;
         };
-        self.entries.retain(|_, entry| entry.inserted_at > cutoff);
+        self.entries.retain(|_, entry| {
+            let keep = entry.inserted_at > cutoff;
+            if !keep {
+                self.bytes.fetch_sub(entry.outline.len(), Ordering::Relaxed);
+            }
+            keep
+        });
+    }
+}
+
+/// Stored as the source path on the first line, then the outline. The path
+/// guards against hash collisions. A hit refreshes the file's mtime, which
+/// `prune_disk` reads as its last use.
+fn read_disk(file: &Path, path: &Path) -> Option<String> {
+    let stored = fs::read_to_string(file).ok()?;
+    let (stored_path, outline) = stored.split_once('\n')?;
+    if stored_path != path.to_string_lossy() {
+        return None;
+    }
+    if let Ok(f) = fs::File::options().write(true).open(file) {
+        let _ = f.set_modified(SystemTime::now());
+    }
+    Some(outline.to_string())
+}
+
+/// Delete other builds' outline directories and this build's outlines unused
+/// for `DISK_MAX_AGE`. A `.pruned` marker's mtime records the last run, so
+/// this scans at most once per `DISK_PRUNE_INTERVAL`.
+fn prune_disk(dir: &Path, now: SystemTime) {
+    let marker = dir.join(".pruned");
+    let last = fs::metadata(&marker).and_then(|m| m.modified());
+    if last.is_ok_and(|t| now.duration_since(t).unwrap_or_default() < DISK_PRUNE_INTERVAL) {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if !name.starts_with("outlines-") {
+            continue;
+        }
+        if name != DISK_VERSION {
+            let _ = fs::remove_dir_all(entry.path());
+            continue;
+        }
+        let Ok(files) = fs::read_dir(entry.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let unused = file
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|t| now.duration_since(t).unwrap_or_default() > DISK_MAX_AGE);
+            if unused {
+                let _ = fs::remove_file(file.path());
+            }
+        }
+    }
+    if fs::write(&marker, "").is_ok()
+        && let Ok(f) = fs::File::options().write(true).open(&marker)
+    {
+        let _ = f.set_modified(now);
+    }
+}
+
+/// Write through a temp file and rename, so concurrent runs never read half an entry.
+fn write_disk(file: &Path, path: &Path, outline: &str) {
+    let Some(dir) = file.parent() else {
+        return;
+    };
+    if fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let tmp = file.with_extension(format!("tmp{}", std::process::id()));
+    let body = format!("{}\n{outline}", path.to_string_lossy());
+    if fs::write(&tmp, body).is_ok() && fs::rename(&tmp, file).is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+/// 64-bit FNV-1a — stable across builds and platforms, unlike `DefaultHasher`,
+/// so on-disk names stay valid between runs.
+pub(crate) struct Fnv(pub(crate) u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Lang;
+
+    const RUST: View = View {
+        file_type: FileType::Code(Lang::Rust),
+        capped: false,
+        dedup_variants: false,
+    };
+
+    #[test]
+    fn disk_cache_survives_a_new_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+        let mtime = fs::metadata(&file).unwrap().modified().unwrap();
+        let store = dir.path().join("cache");
+
+        let first = OutlineCache::with_disk(&store);
+        assert_eq!(
+            &*first.get_or_compute(&file, mtime, RUST, || "[1-1] fn main".into()),
+            "[1-1] fn main"
+        );
+
+        // A fresh cache (a new CLI run) reads it back without computing
+        let second = OutlineCache::with_disk(&store);
+        assert!(second.contains(&file, mtime, RUST));
+        let outline = second.get_or_compute(&file, mtime, RUST, || panic!("outline recomputed"));
+        assert_eq!(&*outline, "[1-1] fn main");
+
+        // A different mtime is a different entry
+        let later = mtime + Duration::from_secs(1);
+        let outline = second.get_or_compute(&file, later, RUST, || "changed".into());
+        assert_eq!(&*outline, "changed");
+
+        // Memory-only caches never touch disk
+        let memory = OutlineCache::new();
+        assert_eq!(
+            &*memory.get_or_compute(&file, mtime, RUST, || "fresh".into()),
+            "fresh"
+        );
+    }
+
+    #[test]
+    fn views_of_one_version_are_kept_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+        let mtime = fs::metadata(&file).unwrap().modified().unwrap();
+        let capped = View::outline(FileType::Code(Lang::Rust), true);
+        let store = dir.path().join("cache");
+
+        // The map's capped outline must not stand in for the full one
+        let first = OutlineCache::with_disk(&store);
+        first.get_or_compute(&file, mtime, capped, || "capped".into());
+        assert_eq!(
+            &*first.get_or_compute(&file, mtime, RUST, || "full".into()),
+            "full"
+        );
+        let second = OutlineCache::with_disk(&store);
+        assert_eq!(
+            &*second.get_or_compute(&file, mtime, RUST, || panic!("recomputed")),
+            "full"
+        );
+        assert_eq!(
+            &*second.get_or_compute(&file, mtime, capped, || panic!("recomputed")),
+            "capped"
+        );
+    }
+
+    #[test]
+    fn disk_prune_drops_other_builds_and_unused_outlines() {
+        let dir = tempfile::tempdir().unwrap();
+        let stale = dir.path().join("outlines-0.0.1-0000000000000000");
+        let current = dir.path().join(DISK_VERSION);
+        fs::create_dir_all(&stale).unwrap();
+        fs::create_dir_all(&current).unwrap();
+        fs::write(stale.join("a"), "x").unwrap();
+        fs::write(current.join("recent"), "x").unwrap();
+        fs::write(current.join("old"), "x").unwrap();
+        let now = SystemTime::now();
+        let long_ago = now - DISK_MAX_AGE - Duration::from_mins(1);
+        let f = fs::File::options()
+            .write(true)
+            .open(current.join("old"))
+            .unwrap();
+        f.set_modified(long_ago).unwrap();
+
+        prune_disk(dir.path(), now);
+        assert!(!stale.exists());
+        assert!(current.join("recent").exists());
+        assert!(!current.join("old").exists());
+
+        // Within the interval nothing is scanned
+        fs::create_dir_all(&stale).unwrap();
+        prune_disk(dir.path(), now + Duration::from_mins(1));
+        assert!(stale.exists());
+        prune_disk(
+            dir.path(),
+            now + DISK_PRUNE_INTERVAL + Duration::from_secs(1),
+        );
+        assert!(!stale.exists());
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let cache = OutlineCache::new().with_limits(3, usize::MAX);
+        let mtime = SystemTime::UNIX_EPOCH;
+        let (a, b, c, d) = (
+            Path::new("a.rs"),
+            Path::new("b.rs"),
+            Path::new("c.rs"),
+            Path::new("d.rs"),
+        );
+        cache.get_or_compute(a, mtime, RUST, || "a".into());
+        cache.get_or_compute(b, mtime, RUST, || "b".into());
+        cache.get_or_compute(c, mtime, RUST, || "c".into());
+        // Touch `a` so `b` is now the oldest
+        cache.get_or_compute(a, mtime, RUST, || panic!("a was cached"));
+        cache.get_or_compute(d, mtime, RUST, || "d".into());
+
+        assert!(cache.len() <= 3);
+        assert!(cache.contains(a, mtime, RUST));
+        assert!(!cache.contains(b, mtime, RUST));
+        assert!(cache.contains(d, mtime, RUST));
+        // mtime invalidation still applies within the bound
+        let later = mtime + Duration::from_secs(1);
+        assert_eq!(&*cache.get_or_compute(a, later, RUST, || "a2".into()), "a2");
+
+        // Byte budget: two 600-byte outlines don't fit in 1000
+        let small = OutlineCache::new().with_limits(100, 1000);
+        small.get_or_compute(a, mtime, RUST, || "x".repeat(600));
+        small.get_or_compute(b, mtime, RUST, || "y".repeat(600));
+        assert_eq!(small.len(), 1);
+        assert_eq!(small.bytes(), 600);
+        assert!(small.contains(b, mtime, RUST));
+
+        small.prune(Duration::ZERO);
+        assert_eq!(small.bytes(), 0);
     }
 }
@@ -1,10 +1,36 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use crate::error::GleanError;
 use crate::types::QueryType;
 
+/// Reject a query with nothing in it. An empty pattern would otherwise
+/// classify as content and match every line in scope.
+pub(crate) fn check_query(query: &str) -> Result<(), GleanError> {
+    if query.trim().is_empty() {
+        return Err(empty_query(query));
+    }
+    Ok(())
+}
+
+/// The error for a query with nothing to search for — blank, or only commas
+/// in a multi-symbol search.
+pub(crate) fn empty_query(query: &str) -> GleanError {
+    GleanError::InvalidQuery {
+        query: query.to_string(),
+        reason: "nothing to search for. Pass a symbol name, text, a file path, or a glob \
+                 (e.g. `handleAuth`, `\"TODO: fix\"`, `src/main.rs`, `*.test.ts`)"
+            .into(),
+    }
+}
+
 /// Classify a query string into a `QueryType` by byte-pattern matching.
 /// No regex engine — `matches!` compiles to a jump table.
 pub fn classify(query: &str, scope: &Path) -> QueryType {
+    // Explicit kind prefix (`symbol:Foo`) overrides every heuristic below
+    if let Some(explicit) = explicit_kind(query, scope) {
+        return explicit;
+    }
+
     // 1. Glob — check first because globs can contain path separators.
     //    But only if no spaces: real globs don't have spaces, content like "import { X }" does.
     if !query.contains(' ')
@@ -16,11 +42,13 @@ pub fn classify(query: &str, scope: &Path) -> QueryType {
     }
 
     // 2. File path — contains separator or starts with ./ ../
-    //    But only if no spaces around the separator ("TODO: fix this/that" is content, not a path)
-    if (query.starts_with("./") || query.starts_with("../"))
-        || (query.contains('/') && !query.contains(' '))
+    //    But only if no spaces around the separator ("TODO: fix this/that" is content, not a path).
+    //    A drive letter (C:\...) is a path even with spaces ("C:\Program Files\...").
+    if is_relative_prefix(query)
+        || (has_separator(query) && !query.contains(' '))
+        || has_drive_letter(query)
     {
-        let resolved = scope.join(query);
+        let resolved = resolve_path(scope, query);
         return match resolved.try_exists() {
             Ok(true) => QueryType::FilePath(resolved),
             _ => QueryType::Fallthrough(query.into()),
@@ -29,7 +57,7 @@ pub fn classify(query: &str, scope: &Path) -> QueryType {
 
     // 3. Starts with . — could be dotfile (.gitignore) or relative path
     if query.starts_with('.') {
-        let resolved = scope.join(query);
+        let resolved = resolve_path(scope, query);
         if resolved.try_exists().unwrap_or(false) {
             return QueryType::FilePath(resolved);
         }
@@ -43,7 +71,7 @@ pub fn classify(query: &str, scope: &Path) -> QueryType {
     // 5. Bare filename — only check filesystem for queries that look like filenames
     //    (have an extension or match known extensionless names like README, Makefile, etc.)
     if looks_like_filename(query) {
-        let resolved = scope.join(query);
+        let resolved = resolve_path(scope, query);
         if resolved.try_exists().unwrap_or(false) {
             return QueryType::FilePath(resolved);
         }
@@ -58,9 +86,64 @@ pub fn classify(query: &str, scope: &Path) -> QueryType {
     QueryType::Content(query.into())
 }
 
+/// `symbol:Foo`, `content:foo bar`, `file:src/lib.rs` or `glob:**/*.rs` —
+/// the query after the prefix, as that kind. The prefix must touch what
+/// follows: `file: foo.txt` is text to search for, as in a log line. A file
+/// that doesn't exist stays a file read, so the error says so instead of
+/// falling through to search.
+fn explicit_kind(query: &str, scope: &Path) -> Option<QueryType> {
+    let (kind, rest) = query.split_once(':')?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(match kind {
+        "symbol" => QueryType::Symbol(rest.trim_end().into()),
+        "content" => QueryType::Content(rest.into()),
+        "file" => QueryType::FilePath(resolve_path(scope, rest.trim_end())),
+        "glob" => QueryType::Glob(rest.trim_end().into()),
+        _ => return None,
+    })
+}
+
+/// Resolve a path-like query against `scope`. Absolute and drive-letter paths
+/// replace `scope` (`Path::join` semantics). On Windows, `/` becomes `\` first
+/// so forward-slash queries still yield a path with native separators.
+pub(crate) fn resolve_path(scope: &Path, query: &str) -> PathBuf {
+    if cfg!(windows) {
+        scope.join(query.replace('/', "\\"))
+    } else {
+        scope.join(query)
+    }
+}
+
+/// `/` separates path components everywhere; `\` does too on Windows. On
+/// other platforms a backslash is an ordinary character (`\n` in content).
+fn is_separator(b: u8) -> bool {
+    b == b'/' || (cfg!(windows) && b == b'\\')
+}
+
+fn has_separator(query: &str) -> bool {
+    query.bytes().any(is_separator)
+}
+
+/// `./x`, `../x`, and on Windows `.\x`, `..\x`.
+fn is_relative_prefix(query: &str) -> bool {
+    let rest = query.strip_prefix("..").or_else(|| query.strip_prefix('.'));
+    rest.and_then(|r| r.bytes().next())
+        .is_some_and(is_separator)
+}
+
+/// `C:\src\lib.rs` or `C:/src/lib.rs` — a Windows drive-letter path.
+fn has_drive_letter(query: &str) -> bool {
+    matches!(
+        query.as_bytes(),
+        [drive, b':', sep, ..] if drive.is_ascii_alphabetic() && matches!(sep, b'\\' | b'/')
+    )
+}
+
 /// Does this query look like a filename? Has an extension, or matches known extensionless names.
 fn looks_like_filename(query: &str) -> bool {
-    if query.contains(' ') || query.contains('/') {
+    if query.contains(' ') || has_separator(query) {
         return false;
     }
     // Has a dot followed by an extension (not just a dotfile)
@@ -117,6 +200,16 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn empty_and_blank_queries_rejected() {
+        for query in ["", "   ", "\t\n"] {
+            let err = check_query(query).unwrap_err();
+            assert!(matches!(err, GleanError::InvalidQuery { .. }), "{err}");
+            assert!(err.to_string().contains("nothing to search for"), "{err}");
+        }
+        assert!(check_query(" handleAuth ").is_ok());
+    }
+
     #[test]
     fn glob_patterns() {
         let scope = PathBuf::from(".");
@@ -126,6 +219,21 @@ mod tests {
             QueryType::Glob(_)
         ));
         assert!(matches!(classify("{a,b}.js", &scope), QueryType::Glob(_)));
+        assert!(matches!(classify("src/*", &scope), QueryType::Glob(_)));
+        assert!(matches!(
+            classify("Session.re*", &scope),
+            QueryType::Glob(_)
+        ));
+    }
+
+    #[test]
+    fn trailing_star_identifier_is_glob_unless_symbol_kind() {
+        let scope = PathBuf::from(".");
+        assert!(matches!(classify("Handle*", &scope), QueryType::Glob(_)));
+        assert!(matches!(classify("README*", &scope), QueryType::Glob(_)));
+        assert!(
+            matches!(classify("symbol:parse_*", &scope), QueryType::Symbol(q) if q == "parse_*")
+        );
     }
 
     #[test]
@@ -165,6 +273,111 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn path_prefixes_and_drive_letters() {
+        assert!(is_relative_prefix("./src"));
+        assert!(is_relative_prefix("../src"));
+        assert!(!is_relative_prefix(".gitignore"));
+        assert!(!is_relative_prefix("..."));
+        assert!(has_drive_letter(r"C:\src\lib.rs"));
+        assert!(has_drive_letter("d:/src/lib.rs"));
+        assert!(!has_drive_letter("C:"));
+        assert!(!has_drive_letter("TODO: fix"));
+    }
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn forward_slash_path_resolves_on_every_platform() {
+        let scope = fixture("mini-rust");
+        let QueryType::FilePath(path) = classify("src/lib.rs", &scope) else {
+            panic!("src/lib.rs should classify as a path");
+        };
+        assert!(path.is_file(), "{}", path.display());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn backslash_path_classifies_and_reads() {
+        let scope = fixture("mini-rust");
+        let QueryType::FilePath(path) = classify(r"src\lib.rs", &scope) else {
+            panic!(r"src\lib.rs should classify as a path");
+        };
+        let cache = crate::cache::OutlineCache::new();
+        let out = crate::read::read_file(&path, None, true, None, &cache, false, None, None, false)
+            .unwrap();
+        assert!(out.contains("trait Matcher"), "{out}");
+        assert!(matches!(
+            classify(r".\src\lib.rs", &scope),
+            QueryType::FilePath(_)
+        ));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn drive_letter_path_ignores_scope() {
+        let file = fixture("mini-rust").join(r"src\lib.rs");
+        let query = file.to_str().unwrap();
+        assert!(has_drive_letter(query), "{query}");
+        let QueryType::FilePath(path) = classify(query, Path::new(r"C:\nonexistent")) else {
+            panic!("{query} should classify as a path");
+        };
+        assert_eq!(path, file);
+        let cache = crate::cache::OutlineCache::new();
+        assert!(
+            crate::read::read_file(&path, None, true, None, &cache, false, None, None, false)
+                .is_ok()
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn backslash_is_not_a_separator_off_windows() {
+        let scope = PathBuf::from(".");
+        assert!(matches!(classify(r"\d+", &scope), QueryType::Content(_)));
+    }
+
+    #[test]
+    fn kind_prefix_overrides_heuristics() {
+        let scope = PathBuf::from(".");
+        // Path-like, but forced to a symbol instead of falling through
+        assert!(
+            matches!(classify("symbol:api/handler", &scope), QueryType::Symbol(q) if q == "api/handler")
+        );
+        // An identifier searched as text
+        assert!(
+            matches!(classify("content:handleAuth", &scope), QueryType::Content(q) if q == "handleAuth")
+        );
+        assert!(
+            matches!(classify("content:foo bar", &scope), QueryType::Content(q) if q == "foo bar")
+        );
+        // A missing file stays a file read rather than becoming a search
+        assert!(matches!(
+            classify("file:src/missing.rs", &scope),
+            QueryType::FilePath(p) if p == Path::new("./src/missing.rs")
+        ));
+        assert!(matches!(classify("glob:README", &scope), QueryType::Glob(q) if q == "README"));
+
+        // No prefix, an unknown one, or nothing after it: heuristics as before
+        assert!(matches!(
+            classify("TODO: fix", &scope),
+            QueryType::Content(_)
+        ));
+        assert!(matches!(classify("symbol:", &scope), QueryType::Content(_)));
+        assert!(matches!(
+            classify("file: foo.txt", &scope),
+            QueryType::Content(q) if q == "file: foo.txt"
+        ));
+        assert!(matches!(
+            classify("handleAuth", &scope),
+            QueryType::Symbol(_)
+        ));
+    }
+
     #[test]
     fn is_identifier_checks() {
         assert!(is_identifier("handleAuth"));
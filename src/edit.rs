@@ -1,5 +1,9 @@
+use std::fmt::Write as _;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use grep_matcher::{Captures, Matcher};
+use grep_regex::RegexMatcher;
 
 use crate::error::{GleanError, io_err};
 use crate::format;
@@ -11,18 +15,160 @@ pub struct Edit {
     pub start_hash: u16,
     pub end_line: usize,
     pub end_hash: u16,
+    /// Replacement lines. Empty deletes the range; `"\n"` is one empty line.
     pub content: String,
+    pub mode: EditMode,
+}
+
+/// What an edit does with its anchored lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EditMode {
+    /// Replace the anchored range with `content`.
+    #[default]
+    Replace,
+    /// Insert `content` above the start anchor, keeping it.
+    Before,
+    /// Insert `content` below the start anchor, keeping it.
+    After,
+}
+
+impl EditMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "replace" => Some(Self::Replace),
+            "before" => Some(Self::Before),
+            "after" => Some(Self::After),
+            _ => None,
+        }
+    }
+}
+
+impl Edit {
+    /// An edit that creates its file with `content` as the whole body —
+    /// `"0:new"` in `glean_edit`. It must be the file's only edit.
+    pub fn new_file(content: &str) -> Self {
+        Self {
+            start_line: 0,
+            start_hash: 0,
+            end_line: 0,
+            end_hash: 0,
+            content: content.to_string(),
+            mode: EditMode::Replace,
+        }
+    }
+
+    /// Whether this is a [`Edit::new_file`] edit.
+    pub fn creates_file(&self) -> bool {
+        self.start_line == 0
+    }
+
+    /// The 0-indexed, end-exclusive line range the content is spliced into.
+    /// Inserts are empty ranges next to their anchor.
+    fn span(&self) -> std::ops::Range<usize> {
+        match self.mode {
+            EditMode::Replace => self.start_line - 1..self.end_line,
+            EditMode::Before => self.start_line - 1..self.start_line - 1,
+            EditMode::After => self.start_line..self.start_line,
+        }
+    }
+}
+
+/// How [`apply_edits_with`] treats a batch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EditOptions {
+    /// Apply edits whose lines moved at their new position.
+    pub reanchor: bool,
+    /// Return a unified diff instead of writing.
+    pub dry_run: bool,
 }
 
 /// Result of applying edits to a file.
 #[derive(Debug)]
 pub enum EditResult {
-    /// All edits applied. Contains hashlined context around edit sites.
-    Applied(String),
+    /// All edits applied.
+    Applied {
+        /// Hashlined context around edit sites.
+        context: String,
+        edits_applied: usize,
+        new_line_count: usize,
+    },
+    /// All edits verified; nothing written (`dry_run`).
+    DryRun {
+        /// Unified diff of the changes.
+        diff: String,
+        edits_applied: usize,
+        new_line_count: usize,
+    },
     /// One or more hashes didn't match current content.
-    HashMismatch(String),
+    HashMismatch {
+        /// Human-readable report with fresh hashlines around each failure.
+        message: String,
+        /// The `line:hash` anchors that failed, as the caller sent them.
+        stale_anchors: Vec<String>,
+    },
 }
 
+impl EditResult {
+    /// Machine-readable form for `format: "json"` callers.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            EditResult::Applied {
+                edits_applied,
+                new_line_count,
+                ..
+            } => serde_json::json!({
+                "applied": true,
+                "edits_applied": edits_applied,
+                "new_line_count": new_line_count,
+            }),
+            EditResult::DryRun {
+                diff,
+                edits_applied,
+                new_line_count,
+            } => serde_json::json!({
+                "applied": false,
+                "dry_run": true,
+                "edits_applied": edits_applied,
+                "new_line_count": new_line_count,
+                "diff": diff,
+            }),
+            EditResult::HashMismatch { stale_anchors, .. } => serde_json::json!({
+                "applied": false,
+                "reason": "hash_mismatch",
+                "stale_anchors": stale_anchors,
+            }),
+        }
+    }
+}
+
+fn anchor(line: usize, hash: u16) -> String {
+    format!("{line}:{hash:03x}")
+}
+
+/// Join edited lines back into file content with the original's line
+/// separator and final-newline state. Replacement text never decides either:
+/// a trailing newline in an edit's `content` is dropped by the line split, so
+/// rewriting the last line can't add or remove the file's final newline.
+/// Deleting every line leaves an empty file, not a lone newline.
+fn reassemble(lines: &[String], original: &str) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+    let line_sep = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut output = lines.join(line_sep);
+    if original.ends_with('\n') {
+        output.push_str(line_sep);
+    }
+    output
+}
+
+/// How far from its anchor a moved line is looked for on a hash mismatch.
+const REANCHOR_WINDOW: usize = 20;
+
 /// Apply a batch of edits to a file.
 ///
 /// 1. Read file into lines
@@ -31,9 +177,191 @@ pub enum EditResult {
 /// 4. Splice replacements
 /// 5. Write file
 /// 6. Return hashlined context around edit sites
-pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError> {
+///
+/// When an edit's lines only moved (an earlier edit shifted them), the
+/// mismatch report names their new anchors; with `reanchor` the edit is
+/// applied there instead. With `dry_run` nothing is written and the result
+/// carries a unified diff. Either way the start hash must match exactly one
+/// line within [`REANCHOR_WINDOW`] lines, and the end hash must match at the
+/// same offset.
+pub fn apply_edits_with(
+    path: &Path,
+    edits: &[Edit],
+    opts: EditOptions,
+) -> Result<EditResult, GleanError> {
+    let (result, output) = stage(path, edits, opts)?;
+    if let Some(output) = output {
+        write_atomic(path, &output)?;
+    }
+    Ok(result)
+}
+
+/// Replace `path`'s content through a temp file beside it and a rename, so
+/// an interrupted write never leaves the file half written. A symlink is
+/// followed, not replaced.
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<(), GleanError> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let tmp = write_temp(&target, content.as_bytes()).map_err(io_err(path))?;
+    fs::rename(&tmp, &target).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        io_err(path)(e)
+    })
+}
+
+/// Write `content` to a new temp file next to `target`, with `target`'s
+/// permissions, and return its path.
+fn write_temp(target: &Path, content: &[u8]) -> std::io::Result<PathBuf> {
+    use std::io::Write as _;
+
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = target.with_file_name(format!(".{name}.glean-{}", std::process::id()));
+    let written = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp)
+        .and_then(|mut f| {
+            f.write_all(content)?;
+            if let Ok(meta) = fs::metadata(target) {
+                f.set_permissions(meta.permissions())?;
+            }
+            f.sync_all()
+        });
+    match written {
+        Ok(()) => Ok(tmp),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Apply edits to several files as one batch: every file's anchors are
+/// verified before anything is written, so a stale anchor in one file leaves
+/// all of them untouched. New contents are written beside their files and
+/// swapped in only once all are on disk; if a swap fails, the files already
+/// swapped are restored. Returns each file's result on success, or only the
+/// files that mismatched.
+pub fn apply_edits_atomic(
+    groups: &[(PathBuf, Vec<Edit>)],
+    opts: EditOptions,
+) -> Result<Vec<(PathBuf, EditResult)>, GleanError> {
+    // `./a.rs`, `a.rs` and a symlink to it are one file
+    let canonical: Vec<PathBuf> = groups.iter().map(|(path, _)| canonical(path)).collect();
+    let mut staged = Vec::with_capacity(groups.len());
+    for (i, (path, edits)) in groups.iter().enumerate() {
+        if canonical[..i].contains(&canonical[i]) {
+            return Err(GleanError::InvalidQuery {
+                query: path.display().to_string(),
+                reason: "file listed twice in batch; merge its edits into one group".into(),
+            });
+        }
+        let (result, output) = stage(path, edits, opts)?;
+        staged.push((path, result, output));
+    }
+
+    if staged
+        .iter()
+        .any(|(_, r, _)| matches!(r, EditResult::HashMismatch { .. }))
+    {
+        return Ok(staged
+            .into_iter()
+            .filter(|(_, r, _)| matches!(r, EditResult::HashMismatch { .. }))
+            .map(|(path, r, _)| (path.clone(), r))
+            .collect());
+    }
+
+    // Every new version goes to a temp file beside its target before any
+    // target is replaced, so a failed write leaves all files as they were.
+    // New files have nothing to replace and are created in the swap below.
+    let mut pending: Vec<(&Path, &Path, Option<PathBuf>, &str)> = Vec::new();
+    for (((path, _, output), target), (_, edits)) in staged.iter().zip(&canonical).zip(groups) {
+        let Some(output) = output else {
+            continue;
+        };
+        if edits.iter().any(Edit::creates_file) {
+            pending.push((path, target, None, output));
+            continue;
+        }
+        match write_temp(target, output.as_bytes()) {
+            Ok(tmp) => pending.push((path, target, Some(tmp), output)),
+            Err(e) => {
+                for tmp in pending.iter().filter_map(|(_, _, tmp, _)| tmp.as_ref()) {
+                    let _ = fs::remove_file(tmp);
+                }
+                return Err(io_err(path)(e));
+            }
+        }
+    }
+    // The original of each file swapped so far, `None` for one created
+    let mut replaced: Vec<(&Path, Option<Vec<u8>>)> = Vec::new();
+    for (i, (path, target, tmp, output)) in pending.iter().enumerate() {
+        let swapped = match tmp {
+            Some(tmp) => fs::read(target).and_then(|original| {
+                fs::rename(tmp, target)?;
+                Ok(Some(original))
+            }),
+            None => write_new(target, output).map(|()| None),
+        };
+        match swapped {
+            Ok(original) => replaced.push((target, original)),
+            Err(e) => {
+                // Put back the files already replaced and drop the rest
+                for (target, original) in &replaced {
+                    match original {
+                        Some(original) => {
+                            if let Ok(tmp) = write_temp(target, original) {
+                                let _ = fs::rename(&tmp, target);
+                            }
+                        }
+                        None => {
+                            let _ = fs::remove_file(target);
+                        }
+                    }
+                }
+                for tmp in pending[i..]
+                    .iter()
+                    .filter_map(|(_, _, tmp, _)| tmp.as_ref())
+                {
+                    let _ = fs::remove_file(tmp);
+                }
+                return Err(io_err(path)(e));
+            }
+        }
+    }
+
+    Ok(staged
+        .into_iter()
+        .map(|(path, result, _)| (path.clone(), result))
+        .collect())
+}
+
+/// Verify and apply `edits` in memory. Returns the result and, when the
+/// edits apply and this isn't a dry run, the new file content to write.
+fn stage(
+    path: &Path,
+    edits: &[Edit],
+    opts: EditOptions,
+) -> Result<(EditResult, Option<String>), GleanError> {
+    if let [edit] = edits
+        && edit.creates_file()
+    {
+        let result = new_file_result(path, &edit.content, opts)?;
+        return Ok((result, (!opts.dry_run).then(|| edit.content.clone())));
+    }
+    if edits.iter().any(Edit::creates_file) {
+        return Err(GleanError::InvalidQuery {
+            query: path.display().to_string(),
+            reason: "a new file takes one edit with its whole content".into(),
+        });
+    }
     if edits.is_empty() {
-        return Ok(EditResult::Applied(String::new()));
+        let new_line_count = fs::read_to_string(path).map_or(0, |c| c.lines().count());
+        let result = EditResult::Applied {
+            context: String::new(),
+            edits_applied: 0,
+            new_line_count,
+        };
+        return Ok((result, None));
     }
 
     // Read file
@@ -56,6 +384,9 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
 
     // Phase 1: Verify all hashes
     let mut mismatches: Vec<String> = Vec::new();
+    let mut stale_anchors: Vec<String> = Vec::new();
+
+    let mut resolved: Vec<Edit> = Vec::with_capacity(edits.len());
 
     for edit in edits {
         // Bounds check
@@ -64,6 +395,7 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
                 "Line {} out of bounds (file has {} lines)",
                 edit.start_line, total
             ));
+            stale_anchors.push(anchor(edit.start_line, edit.start_hash));
             continue;
         }
         if edit.end_line < 1 || edit.end_line > total {
@@ -71,6 +403,7 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
                 "Line {} out of bounds (file has {} lines)",
                 edit.end_line, total
             ));
+            stale_anchors.push(anchor(edit.end_line, edit.end_hash));
             continue;
         }
         if edit.end_line < edit.start_line {
@@ -81,6 +414,26 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
             continue;
         }
 
+        let moved = moved_to(&lines, edit);
+        if opts.reanchor
+            && let Some(start_line) = moved
+        {
+            resolved.push(Edit {
+                start_line,
+                end_line: start_line + (edit.end_line - edit.start_line),
+                ..edit.clone()
+            });
+            continue;
+        }
+        let hint = moved.map_or_else(String::new, |start_line| {
+            let end_line = start_line + (edit.end_line - edit.start_line);
+            format!(
+                "Same content now at lines {start_line}-{end_line}: retry with start {}, end {} (or reanchor: true)",
+                anchor(start_line, edit.start_hash),
+                anchor(end_line, edit.end_hash)
+            )
+        });
+
         // Verify start hash
         let start_idx = edit.start_line - 1;
         let start_actual_hash = format::line_hash(lines[start_idx].as_bytes());
@@ -90,9 +443,10 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
             let context_lines: String = lines[context_start..context_end].join("\n");
             let hashlined = format::hashlines(&context_lines, (context_start + 1) as u32);
             mismatches.push(format!(
-                "Hash mismatch at line {} (expected {:03x}, got {:03x}):\n{}",
+                "Hash mismatch at line {} (expected {:03x}, got {:03x}):\n{}{hint}",
                 edit.start_line, edit.start_hash, start_actual_hash, hashlined
             ));
+            stale_anchors.push(anchor(edit.start_line, edit.start_hash));
             continue;
         }
 
@@ -106,15 +460,23 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
                 let context_lines: String = lines[context_start..context_end].join("\n");
                 let hashlined = format::hashlines(&context_lines, (context_start + 1) as u32);
                 mismatches.push(format!(
-                    "Hash mismatch at line {} (expected {:03x}, got {:03x}):\n{}",
+                    "Hash mismatch at line {} (expected {:03x}, got {:03x}):\n{}{hint}",
                     edit.end_line, edit.end_hash, end_actual_hash, hashlined
                 ));
+                stale_anchors.push(anchor(edit.end_line, edit.end_hash));
+                continue;
             }
         }
+        resolved.push(edit.clone());
     }
+    let edits = resolved.as_slice();
 
     if !mismatches.is_empty() {
-        return Ok(EditResult::HashMismatch(mismatches.join("\n\n")));
+        let result = EditResult::HashMismatch {
+            message: mismatches.join("\n\n"),
+            stale_anchors,
+        };
+        return Ok((result, None));
     }
 
     // Check for overlapping ranges
@@ -141,8 +503,6 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
 
     for &idx in &indices {
         let edit = &edits[idx];
-        let start_idx = edit.start_line - 1;
-        let end_idx = edit.end_line; // exclusive end for inclusive range
 
         let replacement: Vec<String> = if edit.content.is_empty() {
             vec![]
@@ -150,22 +510,20 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
             edit.content.lines().map(String::from).collect()
         };
 
-        owned.splice(start_idx..end_idx, replacement);
+        owned.splice(edit.span(), replacement);
     }
 
-    // Phase 3: Write file, preserving original line ending style
-    let line_sep = if content.contains("\r\n") {
-        "\r\n"
-    } else {
-        "\n"
-    };
-    let has_trailing_newline = content.ends_with('\n');
-    let mut output = owned.join(line_sep);
-    if has_trailing_newline {
-        output.push_str(line_sep);
+    if opts.dry_run {
+        let result = EditResult::DryRun {
+            diff: unified_diff(path, &lines, edits),
+            edits_applied: edits.len(),
+            new_line_count: owned.len(),
+        };
+        return Ok((result, None));
     }
 
-    fs::write(path, &output).map_err(io_err(path))?;
+    // Phase 3: New content, preserving original line ending style
+    let output = reassemble(&owned, &content);
 
     // Phase 4: Build response with context around each edit site.
     // Edits were applied in reverse order, so lower-numbered edits shift
@@ -178,8 +536,9 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
 
     for &idx in &ctx_order {
         let edit = &edits[idx];
-        let adjusted = ((edit.start_line as isize - 1) + offset).max(0) as usize;
-        let old_count = edit.end_line - edit.start_line + 1;
+        let span = edit.span();
+        let adjusted = (span.start as isize + offset).max(0) as usize;
+        let old_count = span.len();
         let new_count = if edit.content.is_empty() {
             0
         } else {
@@ -197,13 +556,320 @@ pub fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError
         offset += new_count as isize - old_count as isize;
     }
 
-    Ok(EditResult::Applied(contexts.join("\n---\n")))
+    let result = EditResult::Applied {
+        context: contexts.join("\n---\n"),
+        edits_applied: edits.len(),
+        new_line_count: owned.len(),
+    };
+    Ok((result, Some(output)))
+}
+
+/// Create `path` with `content` as its whole body, making parent
+/// directories as needed. Refuses to overwrite an existing file, even one
+/// that appears while this runs: those are edited through anchors. The
+/// result's context is the new file hashlined, under a `[new file]` header,
+/// so follow-up edits can anchor right away.
+pub fn create_file(
+    path: &Path,
+    content: &str,
+    opts: EditOptions,
+) -> Result<EditResult, GleanError> {
+    let result = new_file_result(path, content, opts)?;
+    if !opts.dry_run {
+        write_new(path, content).map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => already_exists(path),
+            _ => io_err(path)(e),
+        })?;
+    }
+    Ok(result)
+}
+
+/// What creating `path` with `content` reports: the diff for a dry run, the
+/// hashlined file otherwise.
+fn new_file_result(
+    path: &Path,
+    content: &str,
+    opts: EditOptions,
+) -> Result<EditResult, GleanError> {
+    if path.exists() {
+        return Err(already_exists(path));
+    }
+    let new_line_count = content.lines().count();
+    if opts.dry_run {
+        let mut diff = format!(
+            "--- /dev/null\n+++ {}\n@@ -0,0 +1,{new_line_count} @@\n",
+            path.display()
+        );
+        for line in content.lines() {
+            let _ = writeln!(diff, "+{line}");
+        }
+        return Ok(EditResult::DryRun {
+            diff,
+            edits_applied: 1,
+            new_line_count,
+        });
+    }
+    Ok(EditResult::Applied {
+        context: format!(
+            "# {} ({new_line_count} {}) [new file]\n\n{}",
+            path.display(),
+            if new_line_count == 1 { "line" } else { "lines" },
+            format::hashlines(content, 1)
+        ),
+        edits_applied: 1,
+        new_line_count,
+    })
+}
+
+fn already_exists(path: &Path) -> GleanError {
+    GleanError::InvalidQuery {
+        query: path.display().to_string(),
+        reason: "file already exists; read it and edit with anchors".into(),
+    }
+}
+
+/// Write `content` to `path`, which must not exist yet, making parent
+/// directories as needed. A failed write leaves no partial file behind.
+fn write_new(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write as _;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    file.write_all(content.as_bytes()).inspect_err(|_| {
+        let _ = fs::remove_file(path);
+    })
+}
+
+/// `path` with symlinks resolved, so one file listed two ways is seen once.
+/// A file yet to be created resolves through its parent directory.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .ok()
+        .or_else(|| {
+            let parent = path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            Some(fs::canonicalize(parent).ok()?.join(path.file_name()?))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// A regex substitution over a file, as `glean_edit`'s `find`/`replace`.
+#[derive(Debug, Clone)]
+pub struct RegexReplace {
+    /// The pattern, optionally written `/like this/`.
+    pub find: String,
+    /// Replacement text; `$1`/`${name}` refer to capture groups.
+    pub replace: String,
+    /// Only replace within this line range (`"10-40"`, `"-20"`, `"100-"`).
+    pub lines: Option<String>,
+    /// Refuse when more than this many replacements would be made.
+    pub max: Option<usize>,
+}
+
+/// Apply a regex substitution line by line, as ordinary anchored edits so it
+/// verifies, diffs and reports like any other batch. Returns the result and
+/// the number of replacements. Nothing matching, or more matches than
+/// `max`, is an error and nothing is written.
+pub fn replace_regex(
+    path: &Path,
+    rep: &RegexReplace,
+    opts: EditOptions,
+) -> Result<(EditResult, usize), GleanError> {
+    let pattern = rep
+        .find
+        .strip_prefix('/')
+        .and_then(|p| p.strip_suffix('/'))
+        .filter(|p| !p.is_empty())
+        .unwrap_or(&rep.find);
+    let matcher = RegexMatcher::new(pattern).map_err(|e| GleanError::InvalidQuery {
+        query: rep.find.clone(),
+        reason: e.to_string(),
+    })?;
+
+    let content = fs::read_to_string(path).map_err(io_err(path))?;
+    let (first, last) = match &rep.lines {
+        None => (1, usize::MAX),
+        Some(range) => {
+            crate::read::parse_range(range, content.lines().count()).ok_or_else(|| {
+                GleanError::InvalidQuery {
+                    query: range.clone(),
+                    reason: "expected a line range like \"10-40\", \"-20\" or \"100-\"".into(),
+                }
+            })?
+        }
+    };
+    let mut edits = Vec::new();
+    let mut count = 0;
+    for (i, line) in content.lines().enumerate() {
+        let line_num = i + 1;
+        if line_num < first || line_num > last {
+            continue;
+        }
+        let mut caps = matcher
+            .new_captures()
+            .map_err(|e| GleanError::InvalidQuery {
+                query: rep.find.clone(),
+                reason: e.to_string(),
+            })?;
+        let mut replaced = Vec::new();
+        let mut n = 0;
+        let _ = matcher.replace_with_captures(
+            line.as_bytes(),
+            &mut caps,
+            &mut replaced,
+            |caps, dst| {
+                caps.interpolate(
+                    |name| matcher.capture_index(name),
+                    line.as_bytes(),
+                    rep.replace.as_bytes(),
+                    dst,
+                );
+                n += 1;
+                true
+            },
+        );
+        if n == 0 {
+            continue;
+        }
+        count += n;
+        let hash = format::line_hash(line.as_bytes());
+        // A line the replacement empties stays, blank — empty content would
+        // delete it
+        let mut content = String::from_utf8_lossy(&replaced).into_owned();
+        if content.is_empty() {
+            content.push('\n');
+        }
+        edits.push(Edit {
+            start_line: line_num,
+            start_hash: hash,
+            end_line: line_num,
+            end_hash: hash,
+            content,
+            mode: EditMode::Replace,
+        });
+    }
+
+    if count == 0 {
+        return Err(GleanError::InvalidQuery {
+            query: rep.find.clone(),
+            reason: "no matches; nothing replaced".into(),
+        });
+    }
+    if let Some(max) = rep.max
+        && count > max
+    {
+        return Err(GleanError::InvalidQuery {
+            query: rep.find.clone(),
+            reason: format!(
+                "{count} matches on {} lines exceed count {max}; nothing replaced",
+                edits.len()
+            ),
+        });
+    }
+    Ok((apply_edits_with(path, &edits, opts)?, count))
+}
+
+/// Lines of context around each change in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Unified diff of `edits` (verified, non-overlapping) against `lines`.
+/// Hunks come straight from the edit spans, so no line matching is needed.
+fn unified_diff(path: &Path, lines: &[&str], edits: &[Edit]) -> String {
+    let mut sorted: Vec<&Edit> = edits.iter().collect();
+    sorted.sort_by_key(|e| e.span().start);
+
+    let mut out = format!("--- {0}\n+++ {0}\n", path.display());
+    // New-file line shift from edits in earlier hunks
+    let mut shift: isize = 0;
+    let mut i = 0;
+    while i < sorted.len() {
+        // Edits whose context windows touch share a hunk
+        let mut j = i + 1;
+        while j < sorted.len()
+            && sorted[j].span().start <= sorted[j - 1].span().end + 2 * DIFF_CONTEXT
+        {
+            j += 1;
+        }
+        let hunk = &sorted[i..j];
+        let old_start = hunk[0].span().start.saturating_sub(DIFF_CONTEXT);
+        let old_end = (hunk[j - i - 1].span().end + DIFF_CONTEXT).min(lines.len());
+
+        let mut body = String::new();
+        let mut new_len = 0;
+        let mut pos = old_start;
+        for edit in hunk {
+            let span = edit.span();
+            for line in &lines[pos..span.start] {
+                let _ = writeln!(body, " {line}");
+            }
+            new_len += span.start - pos;
+            for line in &lines[span.clone()] {
+                let _ = writeln!(body, "-{line}");
+            }
+            for line in edit.content.lines() {
+                let _ = writeln!(body, "+{line}");
+                new_len += 1;
+            }
+            pos = span.end;
+        }
+        for line in &lines[pos..old_end] {
+            let _ = writeln!(body, " {line}");
+        }
+        new_len += old_end - pos;
+
+        let old_len = old_end - old_start;
+        let new_start = (old_start as isize + shift) as usize;
+        let _ = writeln!(
+            out,
+            "@@ -{},{old_len} +{},{new_len} @@",
+            old_start + usize::from(old_len > 0),
+            new_start + usize::from(new_len > 0)
+        );
+        out.push_str(&body);
+        shift += new_len as isize - old_len as isize;
+        i = j;
+    }
+    out
+}
+
+/// Where `edit`'s lines moved to, when its anchors no longer match in place:
+/// the start hash matches exactly one other line within [`REANCHOR_WINDOW`]
+/// and the end hash matches the same distance below it.
+fn moved_to(lines: &[&str], edit: &Edit) -> Option<usize> {
+    let start_idx = edit.start_line - 1;
+    let hash_at = |idx: usize| lines.get(idx).map(|l| format::line_hash(l.as_bytes()));
+    if hash_at(start_idx) == Some(edit.start_hash)
+        && hash_at(edit.end_line - 1) == Some(edit.end_hash)
+    {
+        return None;
+    }
+
+    let lo = start_idx.saturating_sub(REANCHOR_WINDOW);
+    let hi = (start_idx + REANCHOR_WINDOW + 1).min(lines.len());
+    let mut found = (lo..hi).filter(|&i| i != start_idx && hash_at(i) == Some(edit.start_hash));
+    let idx = found.next()?;
+    if found.next().is_some() {
+        return None;
+    }
+    let end_idx = idx + (edit.end_line - edit.start_line);
+    (hash_at(end_idx) == Some(edit.end_hash)).then_some(idx + 1)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn apply_edits(path: &Path, edits: &[Edit]) -> Result<EditResult, GleanError> {
+        apply_edits_with(path, edits, EditOptions::default())
+    }
+
     #[test]
     fn hash_roundtrip() {
         let line = b"pub fn hello() -> String {";
@@ -228,10 +894,11 @@ mod tests {
             end_line: 2,
             end_hash: hash2,
             content: "replaced first\nreplaced second".to_string(),
+            mode: EditMode::Replace,
         }];
 
         let result = apply_edits(&file, &edits).unwrap();
-        assert!(matches!(result, EditResult::Applied(_)));
+        assert!(matches!(result, EditResult::Applied { .. }));
 
         let new_content = fs::read_to_string(&file).unwrap();
         assert!(new_content.contains("replaced first"));
@@ -253,10 +920,11 @@ mod tests {
             end_line: 1,
             end_hash: 0xBAD,
             content: "should not appear".to_string(),
+            mode: EditMode::Replace,
         }];
 
         let result = apply_edits(&file, &edits).unwrap();
-        assert!(matches!(result, EditResult::HashMismatch(_)));
+        assert!(matches!(result, EditResult::HashMismatch { .. }));
 
         let content = fs::read_to_string(&file).unwrap();
         assert_eq!(content, original, "file should be unchanged");
@@ -280,6 +948,7 @@ mod tests {
                 end_line: 3,
                 end_hash: hash_c,
                 content: "x".to_string(),
+                mode: EditMode::Replace,
             },
             Edit {
                 start_line: 2,
@@ -287,6 +956,7 @@ mod tests {
                 end_line: 4,
                 end_hash: hash_d,
                 content: "y".to_string(),
+                mode: EditMode::Replace,
             },
         ];
 
@@ -308,14 +978,506 @@ mod tests {
             end_line: 2,
             end_hash: hash,
             content: String::new(),
+            mode: EditMode::Replace,
         }];
 
         let result = apply_edits(&file, &edits).unwrap();
-        assert!(matches!(result, EditResult::Applied(_)));
+        assert!(matches!(result, EditResult::Applied { .. }));
 
         let content = fs::read_to_string(&file).unwrap();
         assert!(content.contains("keep"));
         assert!(content.contains("also keep"));
         assert!(!content.contains("delete me"));
     }
+
+    #[test]
+    fn json_result_shapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("test.txt");
+        fs::write(&file, "a\nb\nc\n").unwrap();
+        let hash_a = format::line_hash(b"a");
+        let hash_c = format::line_hash(b"c");
+
+        let edits = vec![Edit {
+            start_line: 1,
+            start_hash: hash_a,
+            end_line: 1,
+            end_hash: hash_a,
+            content: "x\ny".to_string(),
+            mode: EditMode::Replace,
+        }];
+        let json = apply_edits(&file, &edits).unwrap().to_json();
+        assert_eq!(
+            json,
+            serde_json::json!({"applied": true, "edits_applied": 1, "new_line_count": 4})
+        );
+
+        // Line 1 is now "x"; its old anchor is stale, line 4 ("c") is fine
+        let edits = vec![
+            Edit {
+                start_line: 1,
+                start_hash: hash_a,
+                end_line: 1,
+                end_hash: hash_a,
+                content: "z".to_string(),
+                mode: EditMode::Replace,
+            },
+            Edit {
+                start_line: 4,
+                start_hash: hash_c,
+                end_line: 4,
+                end_hash: hash_c,
+                content: String::new(),
+                mode: EditMode::Replace,
+            },
+        ];
+        let json = apply_edits(&file, &edits).unwrap().to_json();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "applied": false,
+                "reason": "hash_mismatch",
+                "stale_anchors": [format!("1:{hash_a:03x}")],
+            })
+        );
+    }
+
+    #[test]
+    fn crlf_anchors_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("crlf.txt");
+        // The last line ends in a lone `\r`, which `lines()` keeps
+        fs::write(&file, "one\r\ntwo\r\nthree\r").unwrap();
+
+        let cache = crate::cache::OutlineCache::new();
+        let read = crate::read::read_file(
+            &file,
+            Some("2-3"),
+            false,
+            None,
+            &cache,
+            true,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        let anchors: Vec<(usize, u16)> = read
+            .lines()
+            .filter_map(|l| format::parse_anchor(l.split_once('|')?.0))
+            .collect();
+        assert_eq!(anchors.len(), 2, "{read}");
+
+        let edits = vec![Edit {
+            start_line: anchors[0].0,
+            start_hash: anchors[0].1,
+            end_line: anchors[1].0,
+            end_hash: anchors[1].1,
+            content: "2\n3".to_string(),
+            mode: EditMode::Replace,
+        }];
+        let result = apply_edits(&file, &edits).unwrap();
+        assert!(matches!(result, EditResult::Applied { .. }));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "one\r\n2\r\n3");
+    }
+
+    #[test]
+    fn moved_lines_reanchored() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("moved.txt");
+        // Anchors taken before two lines were inserted at the top
+        fs::write(&file, "new\nnew\na\nb\nc\n").unwrap();
+        let edits = vec![Edit {
+            start_line: 2,
+            start_hash: format::line_hash(b"b"),
+            end_line: 3,
+            end_hash: format::line_hash(b"c"),
+            content: "B\nC".to_string(),
+            mode: EditMode::Replace,
+        }];
+
+        // Strict by default: the report names the new anchors
+        let EditResult::HashMismatch { message, .. } = apply_edits(&file, &edits).unwrap() else {
+            panic!("expected a mismatch");
+        };
+        let b = format::line_hash(b"b");
+        let c = format::line_hash(b"c");
+        assert!(
+            message.contains(&format!(
+                "Same content now at lines 4-5: retry with start 4:{b:03x}, end 5:{c:03x}"
+            )),
+            "{message}"
+        );
+
+        let result = apply_edits_with(
+            &file,
+            &edits,
+            EditOptions {
+                reanchor: true,
+                ..EditOptions::default()
+            },
+        )
+        .unwrap();
+        assert!(matches!(result, EditResult::Applied { .. }));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new\nnew\na\nB\nC\n");
+    }
+
+    #[test]
+    fn ambiguous_move_not_reanchored() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("dup.txt");
+        fs::write(&file, "x\n}\ny\n}\n").unwrap();
+        let edits = vec![Edit {
+            start_line: 1,
+            start_hash: format::line_hash(b"}"),
+            end_line: 1,
+            end_hash: format::line_hash(b"}"),
+            content: String::new(),
+            mode: EditMode::Replace,
+        }];
+        let result = apply_edits_with(
+            &file,
+            &edits,
+            EditOptions {
+                reanchor: true,
+                ..EditOptions::default()
+            },
+        )
+        .unwrap();
+        assert!(matches!(result, EditResult::HashMismatch { .. }));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "x\n}\ny\n}\n");
+    }
+
+    #[test]
+    fn multi_file_batch_is_all_or_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.rs");
+        let b = dir.path().join("b.rs");
+        fs::write(&a, "fn old() {}\n").unwrap();
+        fs::write(&b, "use a::old;\nold();\n").unwrap();
+        let edit = |line: usize, text: &[u8], content: &str| Edit {
+            start_line: line,
+            start_hash: format::line_hash(text),
+            end_line: line,
+            end_hash: format::line_hash(text),
+            content: content.to_string(),
+            mode: EditMode::Replace,
+        };
+
+        // b.rs changed since it was read: nothing is written anywhere
+        let stale = vec![
+            (a.clone(), vec![edit(1, b"fn old() {}", "fn new() {}")]),
+            (b.clone(), vec![edit(2, b"old(1);", "new(1);")]),
+        ];
+        let results = apply_edits_atomic(&stale, EditOptions::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, b);
+        assert!(matches!(results[0].1, EditResult::HashMismatch { .. }));
+        assert_eq!(fs::read_to_string(&a).unwrap(), "fn old() {}\n");
+
+        let fresh = vec![
+            (a.clone(), vec![edit(1, b"fn old() {}", "fn new() {}")]),
+            (
+                b.clone(),
+                vec![
+                    edit(1, b"use a::old;", "use a::new;"),
+                    edit(2, b"old();", "new();"),
+                ],
+            ),
+        ];
+        let results = apply_edits_atomic(&fresh, EditOptions::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(
+            results
+                .iter()
+                .all(|(_, r)| matches!(r, EditResult::Applied { .. }))
+        );
+        assert_eq!(fs::read_to_string(&a).unwrap(), "fn new() {}\n");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "use a::new;\nnew();\n");
+
+        let twice = vec![(a.clone(), vec![]), (a.clone(), vec![])];
+        assert!(apply_edits_atomic(&twice, EditOptions::default()).is_err());
+        let spelled_twice = vec![
+            (a.clone(), vec![]),
+            (dir.path().join(".").join("a.rs"), vec![]),
+        ];
+        assert!(apply_edits_atomic(&spelled_twice, EditOptions::default()).is_err());
+    }
+
+    #[test]
+    fn dry_run_diffs_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("dry.txt");
+        let original = (1..=14).fold(String::new(), |mut s, n| {
+            let _ = writeln!(s, "l{n}");
+            s
+        });
+        fs::write(&file, &original).unwrap();
+        let edit = |line: usize, mode: EditMode, content: &str| {
+            let hash = format::line_hash(format!("l{line}").as_bytes());
+            Edit {
+                start_line: line,
+                start_hash: hash,
+                end_line: line,
+                end_hash: hash,
+                content: content.to_string(),
+                mode,
+            }
+        };
+        let edits = vec![
+            edit(2, EditMode::Replace, "two"),
+            edit(4, EditMode::After, "four.5"),
+            edit(12, EditMode::Replace, ""),
+        ];
+        let opts = EditOptions {
+            dry_run: true,
+            ..EditOptions::default()
+        };
+        let EditResult::DryRun {
+            diff,
+            edits_applied,
+            new_line_count,
+        } = apply_edits_with(&file, &edits, opts).unwrap()
+        else {
+            panic!("expected a dry run");
+        };
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+        assert_eq!((edits_applied, new_line_count), (3, 14));
+        let path = file.display();
+        assert_eq!(
+            diff,
+            format!(
+                "--- {path}\n+++ {path}\n\
+                 @@ -1,7 +1,8 @@\n l1\n-l2\n+two\n l3\n l4\n+four.5\n l5\n l6\n l7\n\
+                 @@ -9,6 +10,5 @@\n l9\n l10\n l11\n-l12\n l13\n l14\n"
+            )
+        );
+    }
+
+    #[test]
+    fn regex_replace_counts_and_guards() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("rename.rs");
+        let original = "fn load(cfg: Config) {}\nlet c = load(x);\nload(y); load(z);\n";
+        fs::write(&file, original).unwrap();
+        let rep =
+            |find: &str, replace: &str, lines: Option<&str>, max: Option<usize>| RegexReplace {
+                find: find.to_string(),
+                replace: replace.to_string(),
+                lines: lines.map(String::from),
+                max,
+            };
+
+        // More matches than expected: refused, nothing written
+        let err = replace_regex(
+            &file,
+            &rep(r"/\bload\b/", "read", None, Some(3)),
+            EditOptions::default(),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("4 matches on 3 lines exceed count 3"),
+            "{err}"
+        );
+        assert!(
+            replace_regex(&file, &rep("nope", "x", None, None), EditOptions::default()).is_err()
+        );
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+
+        // Capture groups, limited to a line range
+        let (result, count) = replace_regex(
+            &file,
+            &rep(r"load\((\w)\)", "read(&$1)", Some("2-"), None),
+            EditOptions::default(),
+        )
+        .unwrap();
+        assert!(matches!(
+            result,
+            EditResult::Applied {
+                edits_applied: 2,
+                ..
+            }
+        ));
+        assert_eq!(count, 3);
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "fn load(cfg: Config) {}\nlet c = read(&x);\nread(&y); read(&z);\n"
+        );
+
+        // A line emptied by the replacement is kept, blank
+        replace_regex(
+            &file,
+            &rep("^let .*$", "", None, None),
+            EditOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "fn load(cfg: Config) {}\n\nread(&y); read(&z);\n"
+        );
+    }
+
+    #[test]
+    fn create_file_makes_parents_and_refuses_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("src/new/mod.rs");
+        let EditResult::Applied { context, .. } =
+            create_file(&file, "pub fn a() {}\n", EditOptions::default()).unwrap()
+        else {
+            panic!("expected the file to be created");
+        };
+        assert_eq!(fs::read_to_string(&file).unwrap(), "pub fn a() {}\n");
+        let hash = format::line_hash(b"pub fn a() {}");
+        assert_eq!(
+            context,
+            format!(
+                "# {} (1 line) [new file]\n\n1:{hash:03x}|pub fn a() {{}}\n",
+                file.display()
+            )
+        );
+        assert!(create_file(&file, "other", EditOptions::default()).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "pub fn a() {}\n");
+    }
+
+    #[test]
+    fn batch_creates_new_files_alongside_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("lib.rs");
+        fs::write(&old, "mod a;\n").unwrap();
+        let new = dir.path().join("src/a.rs");
+        let edit = Edit {
+            start_line: 1,
+            start_hash: format::line_hash(b"mod a;"),
+            end_line: 1,
+            end_hash: format::line_hash(b"mod a;"),
+            content: "pub mod a;".into(),
+            mode: EditMode::Replace,
+        };
+        let groups = vec![
+            (old.clone(), vec![edit]),
+            (new.clone(), vec![Edit::new_file("pub fn a() {}\n")]),
+        ];
+        let results = apply_edits_atomic(&groups, EditOptions::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(fs::read_to_string(&old).unwrap(), "pub mod a;\n");
+        assert_eq!(fs::read_to_string(&new).unwrap(), "pub fn a() {}\n");
+
+        // Creating it again is refused and leaves it as it was
+        let groups = vec![(new.clone(), vec![Edit::new_file("other\n")])];
+        assert!(apply_edits_atomic(&groups, EditOptions::default()).is_err());
+        assert_eq!(fs::read_to_string(&new).unwrap(), "pub fn a() {}\n");
+    }
+
+    /// Insert `content` next to `line` of `original` with `mode`, return the file after.
+    fn insert(original: &str, line: usize, mode: EditMode, content: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("insert.txt");
+        fs::write(&file, original).unwrap();
+        let hash = format::line_hash(original.lines().nth(line - 1).unwrap().as_bytes());
+        let edits = vec![Edit {
+            start_line: line,
+            start_hash: hash,
+            end_line: line,
+            end_hash: hash,
+            content: content.to_string(),
+            mode,
+        }];
+        let result = apply_edits(&file, &edits).unwrap();
+        assert!(matches!(result, EditResult::Applied { .. }));
+        fs::read_to_string(&file).unwrap()
+    }
+
+    #[test]
+    fn insert_modes_keep_the_anchor() {
+        let original = "a\nb\nc\n";
+        assert_eq!(insert(original, 2, EditMode::Replace, "x"), "a\nx\nc\n");
+        assert_eq!(insert(original, 2, EditMode::Before, "x"), "a\nx\nb\nc\n");
+        assert_eq!(
+            insert(original, 2, EditMode::After, "x\ny"),
+            "a\nb\nx\ny\nc\n"
+        );
+        // Top and bottom of the file
+        assert_eq!(
+            insert(original, 1, EditMode::Before, "top"),
+            "top\na\nb\nc\n"
+        );
+        assert_eq!(
+            insert(original, 3, EditMode::After, "end"),
+            "a\nb\nc\nend\n"
+        );
+        assert_eq!(insert("a\nb", 2, EditMode::After, "end"), "a\nb\nend");
+    }
+
+    #[test]
+    fn inserts_batch_with_replacements() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("batch.txt");
+        fs::write(&file, "a\nb\nc\n").unwrap();
+        let edit = |line: usize, text: &[u8], mode: EditMode, content: &str| Edit {
+            start_line: line,
+            start_hash: format::line_hash(text),
+            end_line: line,
+            end_hash: format::line_hash(text),
+            content: content.to_string(),
+            mode,
+        };
+        let edits = vec![
+            edit(1, b"a", EditMode::Before, "0"),
+            edit(2, b"b", EditMode::Replace, "B"),
+            edit(3, b"c", EditMode::After, "d"),
+        ];
+        let EditResult::Applied {
+            context,
+            new_line_count,
+            ..
+        } = apply_edits(&file, &edits).unwrap()
+        else {
+            panic!("expected edits to apply");
+        };
+        assert_eq!(fs::read_to_string(&file).unwrap(), "0\na\nB\nc\nd\n");
+        assert_eq!(new_line_count, 5);
+        assert!(context.contains("|d"), "{context}");
+    }
+
+    /// Replace the last line of `original` with `content`, return the file after.
+    fn edit_last_line(original: &str, content: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("last.txt");
+        fs::write(&file, original).unwrap();
+
+        let last = original.lines().count();
+        let hash = format::line_hash(original.lines().last().unwrap().as_bytes());
+        let edits = vec![Edit {
+            start_line: last,
+            start_hash: hash,
+            end_line: last,
+            end_hash: hash,
+            content: content.to_string(),
+            mode: EditMode::Replace,
+        }];
+        let result = apply_edits(&file, &edits).unwrap();
+        assert!(matches!(result, EditResult::Applied { .. }));
+        fs::read_to_string(&file).unwrap()
+    }
+
+    #[test]
+    fn last_line_edit_keeps_trailing_newline() {
+        assert_eq!(edit_last_line("a\nb\n", "c"), "a\nc\n");
+        assert_eq!(edit_last_line("a\nb\n", "c\n"), "a\nc\n");
+        assert_eq!(edit_last_line("a\r\nb\r\n", "c"), "a\r\nc\r\n");
+        // Deleting the last line keeps the newline on the new last line
+        assert_eq!(edit_last_line("a\nb\n", ""), "a\n");
+    }
+
+    #[test]
+    fn last_line_edit_keeps_missing_trailing_newline() {
+        assert_eq!(edit_last_line("a\nb", "c"), "a\nc");
+        assert_eq!(edit_last_line("a\nb", "c\n"), "a\nc");
+        assert_eq!(edit_last_line("a\nb", "c\nd"), "a\nc\nd");
+        assert_eq!(edit_last_line("a\nb", ""), "a");
+        // Deleting the only line leaves an empty file either way
+        assert_eq!(edit_last_line("only\n", ""), "");
+        assert_eq!(edit_last_line("only", ""), "");
+    }
 }
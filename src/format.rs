@@ -42,9 +42,12 @@ pub fn search_header(
     format!("# Search: \"{query}\" in {} — {parts}", scope.display())
 }
 
-/// Strip the scope prefix from a path to produce a relative display path.
-/// Falls back to the full path if stripping fails.
-pub fn rel(path: &Path, scope: &Path) -> String {
+/// Display path for a search result: relative to scope unless `absolute`
+/// (`--absolute-paths`). Paths outside scope always show in full.
+pub fn rel(path: &Path, scope: &Path, absolute: bool) -> String {
+    if absolute {
+        return path.display().to_string();
+    }
     path.strip_prefix(scope)
         .unwrap_or(path)
         .display()
@@ -52,7 +55,7 @@ pub fn rel(path: &Path, scope: &Path) -> String {
 }
 
 /// Human-readable file size. Integer math only — no floats.
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     match bytes {
         b if b < 1024 => format!("{b}B"),
         b if b < 1024 * 1024 => format!("{}KB", b / 1024),
@@ -64,6 +67,11 @@ fn format_size(bytes: u64) -> String {
     }
 }
 
+/// `1 file`, `2 files` — the count with its noun in the right number.
+pub(crate) fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{n} {}", if n == 1 { one } else { many })
+}
+
 /// Prefix each line with its 1-indexed line number, right-aligned.
 pub fn number_lines(content: &str, start: u32) -> String {
     let lines: Vec<&str> = content.lines().collect();
@@ -82,8 +90,10 @@ pub fn number_lines(content: &str, start: u32) -> String {
 // ---------------------------------------------------------------------------
 
 /// FNV-1a hash of a line, truncated to 12 bits (3 hex chars).
-/// Used as a per-line content checksum for edit-mode anchors.
+/// Used as a per-line content checksum for edit-mode anchors. A trailing
+/// `\r` is ignored so CRLF files hash the same however the line was split.
 pub(crate) fn line_hash(bytes: &[u8]) -> u16 {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
     let mut h: u32 = 0x811c_9dc5;
     for &b in bytes {
         h ^= u32::from(b);
@@ -194,6 +204,21 @@ mod tests {
         assert!(lines[0].starts_with("1  "));
     }
 
+    #[test]
+    fn display_path_relative_to_scope() {
+        let scope = Path::new("/repo");
+        assert_eq!(rel(Path::new("/repo/src/a.rs"), scope, false), "src/a.rs");
+        // Outside scope: full path, never a misleading partial one
+        assert_eq!(
+            rel(Path::new("/elsewhere/b.rs"), scope, false),
+            "/elsewhere/b.rs"
+        );
+        assert_eq!(
+            rel(Path::new("/repo/src/a.rs"), scope, true),
+            "/repo/src/a.rs"
+        );
+    }
+
     #[test]
     fn search_header_format() {
         let header = search_header("foo", Path::new("/tmp/scope"), 10, 3, 7);
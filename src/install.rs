@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde_json::{Value, json};
 
@@ -11,12 +11,16 @@ use serde_json::{Value, json};
 //   windsurf:       ~/.codeium/windsurf/mcp_config.json       (global)
 //   vscode:         .vscode/mcp.json                          (project scope)
 //   claude-desktop: ~/Library/Application Support/Claude/...  (global)
+//   zed:            ~/.config/zed/settings.json               (global)
+//   neovim:         ~/.config/mcphub/servers.json             (mcphub.nvim)
 const SUPPORTED_HOSTS: &[&str] = &[
     "claude-code",
     "cursor",
     "windsurf",
     "vscode",
     "claude-desktop",
+    "zed",
+    "neovim",
 ];
 
 /// The glean server entry injected into each host config.
@@ -41,18 +45,14 @@ fn glean_server_entry(edit: bool) -> Value {
 /// Write MCP config for the given host, preserving existing config.
 pub fn run(host: &str, edit: bool) -> Result<(), String> {
     let host_info = resolve_host(host)?;
+    let mut config = read_config(&host_info.path)?.unwrap_or_else(|| json!({}));
 
-    let mut config: Value = if host_info.path.exists() {
-        let raw = fs::read_to_string(&host_info.path)
-            .map_err(|e| format!("failed to read {}: {e}", host_info.path.display()))?;
-        serde_json::from_str(&raw)
-            .map_err(|e| format!("invalid JSON in {}: {e}", host_info.path.display()))?
-    } else {
-        json!({})
-    };
-
-    // VS Code uses "servers" key; all others use "mcpServers"
+    // VS Code uses "servers", Zed "context_servers"; all others "mcpServers"
     let servers_key = host_info.servers_key;
+    let mut entry = glean_server_entry(edit);
+    if let Some(source) = host_info.source {
+        entry["source"] = source.into();
+    }
 
     config
         .as_object_mut()
@@ -61,17 +61,13 @@ pub fn run(host: &str, edit: bool) -> Result<(), String> {
         .or_insert(json!({}))
         .as_object_mut()
         .ok_or_else(|| format!("{servers_key} is not a JSON object"))?
-        .insert("glean".into(), glean_server_entry(edit));
+        .insert("glean".into(), entry);
 
     if let Some(parent) = host_info.path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
     }
-
-    let out =
-        serde_json::to_string_pretty(&config).expect("serde_json::Value is always serializable");
-    fs::write(&host_info.path, &out)
-        .map_err(|e| format!("failed to write {}: {e}", host_info.path.display()))?;
+    write_config(&host_info.path, &config)?;
 
     if edit {
         eprintln!("✓ glean (edit mode) added to {}", host_info.path.display());
@@ -84,10 +80,137 @@ pub fn run(host: &str, edit: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Remove glean's entry from the given host's config, leaving other servers
+/// and settings alone. Nothing to remove is not an error.
+pub fn uninstall(host: &str) -> Result<(), String> {
+    let host_info = resolve_host(host)?;
+    let path = &host_info.path;
+    let Some(mut config) = read_config(path)? else {
+        eprintln!(
+            "glean is not configured for {host} ({} not found)",
+            path.display()
+        );
+        return Ok(());
+    };
+    if !remove_entry(&mut config, host_info.servers_key) {
+        eprintln!("glean is not configured in {}", path.display());
+        return Ok(());
+    }
+    write_config(path, &config)?;
+    eprintln!("✓ glean removed from {}", path.display());
+    Ok(())
+}
+
+/// One line per supported host: its config path and whether glean is in it,
+/// with edit mode.
+#[must_use]
+pub fn list() -> String {
+    let width = SUPPORTED_HOSTS.iter().map(|h| h.len()).max().unwrap_or(0);
+    let mut lines = Vec::with_capacity(SUPPORTED_HOSTS.len());
+    for host in SUPPORTED_HOSTS {
+        let line = match resolve_host(host) {
+            Err(e) => format!("{host:<width$}  {e}"),
+            // Read errors already name the file
+            Ok(info) => match read_config(&info.path) {
+                Err(e) => format!("{host:<width$}  {e}"),
+                Ok(config) => {
+                    let status = config.map_or_else(
+                        || "no config".to_string(),
+                        |config| status(&config, info.servers_key).to_string(),
+                    );
+                    format!("{host:<width$}  {status:<21}  {}", info.path.display())
+                }
+            },
+        };
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Whether a host config carries glean, and in which mode.
+#[derive(Debug, PartialEq, Eq)]
+enum Status {
+    Installed,
+    InstalledEdit,
+    Absent,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Installed => write!(f, "installed"),
+            Self::InstalledEdit => write!(f, "installed (edit mode)"),
+            Self::Absent => write!(f, "not installed"),
+        }
+    }
+}
+
+fn status(config: &Value, servers_key: &str) -> Status {
+    let Some(entry) = config.get(servers_key).and_then(|s| s.get("glean")) else {
+        return Status::Absent;
+    };
+    let edit = entry
+        .get("args")
+        .and_then(Value::as_array)
+        .is_some_and(|args| args.iter().any(|a| a == "--edit"));
+    if edit {
+        Status::InstalledEdit
+    } else {
+        Status::Installed
+    }
+}
+
+/// Drop glean from the servers map. False when it wasn't there.
+fn remove_entry(config: &mut Value, servers_key: &str) -> bool {
+    config
+        .get_mut(servers_key)
+        .and_then(Value::as_object_mut)
+        .is_some_and(|servers| servers.remove("glean").is_some())
+}
+
+/// A host config, `None` when the file doesn't exist yet.
+fn read_config(path: &Path) -> Result<Option<Value>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw =
+        fs::read_to_string(path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    // Zed and VS Code settings are JSON with comments
+    serde_json::from_str(&raw)
+        .or_else(|_| serde_json::from_str(&crate::jsonc::strip(&raw)))
+        .map(Some)
+        .map_err(|e| format!("invalid JSON in {}: {e}", path.display()))
+}
+
+/// Write `config` back, keys in their original order. Comments can't survive
+/// the rewrite, so a commented file is first copied to `<name>.bak`.
+fn write_config(path: &Path, config: &Value) -> Result<(), String> {
+    if let Ok(raw) = fs::read_to_string(path)
+        && crate::jsonc::has_comments(&raw)
+    {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        let backup = PathBuf::from(backup);
+        fs::write(&backup, &raw)
+            .map_err(|e| format!("failed to back up {}: {e}", path.display()))?;
+        eprintln!(
+            "  comments in {} are not kept; the original is saved as {}",
+            path.display(),
+            backup.display()
+        );
+    }
+    let out =
+        serde_json::to_string_pretty(config).expect("serde_json::Value is always serializable");
+    fs::write(path, &out).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
 struct HostInfo {
     path: PathBuf,
-    /// JSON key holding the servers map ("mcpServers" or "servers").
+    /// JSON key holding the servers map (`mcpServers`, `servers` or
+    /// `context_servers`).
     servers_key: &'static str,
+    /// `source` tag the host expects on each server entry, if any.
+    source: Option<&'static str>,
     /// Optional note printed after success.
     note: Option<&'static str>,
 }
@@ -101,6 +224,7 @@ fn resolve_host(host: &str) -> Result<HostInfo, String> {
         "claude-code" => Ok(HostInfo {
             path: home.join(".claude.json"),
             servers_key: "mcpServers",
+            source: None,
             note: Some("User scope — available in all projects."),
         }),
 
@@ -108,6 +232,7 @@ fn resolve_host(host: &str) -> Result<HostInfo, String> {
         "cursor" => Ok(HostInfo {
             path: home.join(".cursor/mcp.json"),
             servers_key: "mcpServers",
+            source: None,
             note: None,
         }),
 
@@ -115,6 +240,7 @@ fn resolve_host(host: &str) -> Result<HostInfo, String> {
         "windsurf" => Ok(HostInfo {
             path: home.join(".codeium/windsurf/mcp_config.json"),
             servers_key: "mcpServers",
+            source: None,
             note: None,
         }),
 
@@ -122,15 +248,37 @@ fn resolve_host(host: &str) -> Result<HostInfo, String> {
         "vscode" => Ok(HostInfo {
             path: PathBuf::from(".vscode/mcp.json"),
             servers_key: "servers",
+            source: None,
             note: Some("Project scope — run from your project root."),
         }),
 
         "claude-desktop" => Ok(HostInfo {
             path: claude_desktop_path()?,
             servers_key: "mcpServers",
+            source: None,
             note: None,
         }),
 
+        // Zed global: ~/.config/zed/settings.json → context_servers, each
+        // user-added server tagged `"source": "custom"`
+        "zed" => Ok(HostInfo {
+            path: zed_settings_path()?,
+            servers_key: "context_servers",
+            source: Some("custom"),
+            note: Some("Restart Zed or reload its agent panel to pick it up."),
+        }),
+
+        // mcphub.nvim: ~/.config/mcphub/servers.json → mcpServers, on every
+        // OS (the plugin expands `~/.config`, not XDG or APPDATA)
+        "neovim" => Ok(HostInfo {
+            path: home.join(".config/mcphub/servers.json"),
+            servers_key: "mcpServers",
+            source: None,
+            note: Some(
+                "mcphub.nvim reads this file by default — if you set its `config` option, point it here. Run :MCPHub to check.",
+            ),
+        }),
+
         _ => Err(format!(
             "unknown host: {host}. Supported: {}",
             SUPPORTED_HOSTS.join(", ")
@@ -172,3 +320,82 @@ fn claude_desktop_path() -> Result<PathBuf, String> {
         Err("claude-desktop config path unknown on this OS".into())
     }
 }
+
+fn zed_settings_path() -> Result<PathBuf, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not set")?;
+        Ok(PathBuf::from(appdata).join("Zed/settings.json"))
+    }
+
+    // Zed keeps its settings under ~/.config on macOS too
+    #[cfg(not(target_os = "windows"))]
+    {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map_or_else(
+                || home_dir().map(|home| home.join(".config")),
+                |dir| Ok(dir.into()),
+            )?;
+        Ok(config.join("zed/settings.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_and_removal_leave_other_servers() {
+        let mut config = json!({
+            "theme": "dark",
+            "mcpServers": {
+                "glean": { "command": "glean", "args": ["--mcp", "--edit"] },
+                "other": { "command": "other" },
+            },
+        });
+        assert_eq!(status(&config, "mcpServers"), Status::InstalledEdit);
+        assert_eq!(status(&config, "servers"), Status::Absent);
+
+        assert!(remove_entry(&mut config, "mcpServers"));
+        assert!(!remove_entry(&mut config, "mcpServers"));
+        assert_eq!(status(&config, "mcpServers"), Status::Absent);
+        assert_eq!(
+            config,
+            json!({ "theme": "dark", "mcpServers": { "other": { "command": "other" } } })
+        );
+
+        config["mcpServers"]["glean"] = glean_server_entry(false);
+        assert_eq!(status(&config, "mcpServers"), Status::Installed);
+    }
+
+    /// Settings keep their key order through a rewrite; a file with comments
+    /// is backed up first, since they can't be.
+    #[test]
+    fn rewrite_keeps_key_order_and_backs_up_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let raw = "{\n  // editor\n  \"vim_mode\": true,\n  \"buffer_font_size\": 15,\n}\n";
+        fs::write(&path, raw).unwrap();
+
+        let mut config = read_config(&path).unwrap().unwrap();
+        config["context_servers"] = json!({});
+        write_config(&path, &config).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        let keys: Vec<usize> = ["vim_mode", "buffer_font_size", "context_servers"]
+            .iter()
+            .map(|k| written.find(k).unwrap())
+            .collect();
+        assert!(keys.is_sorted(), "{written}");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("settings.json.bak")).unwrap(),
+            raw
+        );
+
+        // Nothing to lose the second time round
+        fs::remove_file(dir.path().join("settings.json.bak")).unwrap();
+        write_config(&path, &config).unwrap();
+        assert!(!dir.path().join("settings.json.bak").exists());
+    }
+}
@@ -0,0 +1,92 @@
+//! JSON with comments (`//`, `/* */`) and trailing commas — tsconfig files,
+//! Zed and VS Code settings — read as plain JSON.
+
+/// `raw` as plain JSON: comments removed, and any comma left dangling before
+/// a closing bracket, comments in between or not. Strings are copied
+/// untouched, so `"https://..."` keeps its slashes.
+pub(crate) fn strip(raw: &str) -> String {
+    scan(raw).0
+}
+
+/// Whether `raw` has any comment outside a string.
+pub(crate) fn has_comments(raw: &str) -> bool {
+    scan(raw).1
+}
+
+fn scan(raw: &str) -> (String, bool) {
+    let mut out = String::with_capacity(raw.len());
+    let mut comments = false;
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                comments = true;
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                comments = true;
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '}' | ']' => {
+                // Drop a comma left dangling before the closing bracket
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    (out, comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    #[test]
+    fn comments_and_trailing_commas_stripped() {
+        let raw = r#"// Zed settings
+{
+  /* theme */ "theme": "One Dark",
+  "proxy": "http://localhost:8080", // trailing comment
+  "languages": { "Rust": { "tab_size": 4, }, },
+  "quote": "a \" // not a comment",
+  "list": [1, 2, /* last */],
+}
+"#;
+        let value: Value = serde_json::from_str(&strip(raw)).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "theme": "One Dark",
+                "proxy": "http://localhost:8080",
+                "languages": { "Rust": { "tab_size": 4 } },
+                "quote": "a \" // not a comment",
+                "list": [1, 2],
+            })
+        );
+        assert!(has_comments(raw));
+        assert!(!has_comments(r#"{ "url": "http://x", "glob": "/*" }"#));
+    }
+}
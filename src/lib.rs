@@ -13,25 +13,34 @@
     clippy::missing_panics_doc,        // same
 )]
 
-pub(crate) mod budget;
+pub mod budget;
 pub mod cache;
 pub(crate) mod classify;
 pub(crate) mod edit;
 pub mod error;
 pub(crate) mod format;
 pub mod install;
+pub(crate) mod jsonc;
 pub mod map;
 pub mod mcp;
 pub(crate) mod read;
+pub(crate) mod rename;
 pub(crate) mod search;
 pub(crate) mod session;
+pub mod source;
 pub(crate) mod types;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+pub use search::gobuild::GoTarget;
+pub use search::treesitter::DefinitionMode;
+pub use search::{ExcludeGlobs, SearchFilter, parse_languages};
+pub use types::Lang;
 
 use cache::OutlineCache;
 use classify::classify;
 use error::GleanError;
+use source::FileSource;
 use types::QueryType;
 
 /// The single public API. Everything flows through here:
@@ -43,7 +52,8 @@ pub fn run(
     budget_tokens: Option<u64>,
     cache: &OutlineCache,
 ) -> Result<String, GleanError> {
-    run_inner(query, scope, section, budget_tokens, false, cache)
+    let filter = SearchFilter::default();
+    run_inner(query, scope, section, budget_tokens, false, &filter, cache)
 }
 
 /// Full variant — forces full file output, bypassing smart views.
@@ -54,7 +64,263 @@ pub fn run_full(
     budget_tokens: Option<u64>,
     cache: &OutlineCache,
 ) -> Result<String, GleanError> {
-    run_inner(query, scope, section, budget_tokens, true, cache)
+    let filter = SearchFilter::default();
+    run_inner(query, scope, section, budget_tokens, true, &filter, cache)
+}
+
+/// Variant with explicit walk restrictions (`--exclude-tests`) for searches.
+pub fn run_filtered(
+    query: &str,
+    scope: &Path,
+    section: Option<&str>,
+    budget_tokens: Option<u64>,
+    full: bool,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    run_inner(query, scope, section, budget_tokens, full, filter, cache)
+}
+
+/// Query over a `FileSource` instead of the disk — unsaved editor buffers,
+/// or files that only exist in memory. A query naming a file in `source`
+/// reads it; anything else is a symbol search over the source's files under
+/// `scope`. Search matches are listed without inlined code blocks; read the
+/// file for its body.
+pub fn run_source(
+    source: &impl FileSource,
+    query: &str,
+    scope: &Path,
+    section: Option<&str>,
+    budget_tokens: Option<u64>,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    classify::check_query(query)?;
+    let path = classify::resolve_path(scope, query);
+    let output = if source.contains(&path) {
+        read::read_source(
+            source,
+            &path,
+            section,
+            false,
+            None,
+            cache,
+            false,
+            budget_tokens,
+            read::generated::Overrides::for_file(&path).as_ref(),
+            false,
+        )?
+    } else {
+        let filter = SearchFilter::default();
+        let result = search::symbol::search_source(source, query, scope, &filter)?;
+        search::format_symbol_result(&result, cache, filter.absolute_paths)?
+    };
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b).output),
+        None => Ok(output),
+    }
+}
+
+/// Content search over piped input rather than the disk: `query` is literal,
+/// or a regex as `/pattern/`. Matches are reported against `<stdin>`.
+pub fn run_stdin(
+    input: String,
+    query: &str,
+    budget_tokens: Option<u64>,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    classify::check_query(query)?;
+    let scope = Path::new("");
+    let mut source = source::MemorySource::new();
+    source.insert("<stdin>", input);
+    let (pattern, is_regex) = search::parse_pattern(query);
+    let mut result = search::content::search_source(&source, pattern, scope, filter, is_regex)?;
+    // Header reads `in stdin`; match paths stay `<stdin>`
+    result.scope = PathBuf::from("stdin");
+    let output = search::format_content_result(&result, cache, filter.absolute_paths)?;
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b).output),
+        None => Ok(output),
+    }
+}
+
+/// Rename `old` to `new` across `scope`: a preview of every line it changes,
+/// or with `apply`, the files rewritten — all anchors checked before any write.
+pub fn run_rename(
+    old: &str,
+    new: &str,
+    scope: &Path,
+    apply: bool,
+    filter: &SearchFilter,
+) -> Result<String, GleanError> {
+    let renames = rename::rename_edits(old, new, scope, filter)?;
+    if apply {
+        rename::apply(scope, &renames, filter.absolute_paths)
+    } else {
+        Ok(rename::format_preview(
+            old,
+            new,
+            scope,
+            &renames,
+            filter.absolute_paths,
+        ))
+    }
+}
+
+/// Context pack for `root` — a symbol or a file — as one markdown document:
+/// definition, callees, callers and related files, each range shown once.
+/// Capped at `budget_tokens`, or `search::pack::DEFAULT_BUDGET`.
+pub fn run_pack(
+    root: &str,
+    scope: &Path,
+    budget_tokens: Option<u64>,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    classify::check_query(root)?;
+    let output = search::pack::build_pack(root, scope, filter, cache)?;
+    Ok(budget::apply(
+        &output,
+        budget_tokens.unwrap_or(search::pack::DEFAULT_BUDGET),
+    )
+    .output)
+}
+
+/// Prefetch outlines for every file under `scope` into `cache`, in parallel.
+/// Reports how many were cached, already present, and skipped.
+#[must_use]
+pub fn run_warm(scope: &Path, filter: &SearchFilter, cache: &OutlineCache) -> String {
+    let stats = read::warm::warm(scope, filter, cache);
+    format!(
+        "# Warmed {} — {} outline{} cached ({} already cached, {} files skipped)",
+        scope.display(),
+        stats.cached,
+        if stats.cached == 1 { "" } else { "s" },
+        stats.already_cached,
+        stats.skipped,
+    )
+}
+
+/// Module view: outlines of every code file under a directory, concatenated.
+/// `query` is a directory path, resolved against `scope`.
+pub fn run_concat(
+    query: &str,
+    scope: &Path,
+    budget_tokens: Option<u64>,
+    absolute_paths: bool,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    let dir = classify::resolve_path(scope, query);
+    if !dir.is_dir() {
+        return Err(GleanError::InvalidQuery {
+            query: query.to_string(),
+            reason: "--concat expects a directory".into(),
+        });
+    }
+    let output = read::concat::read_directory(&dir, absolute_paths, cache)?;
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b).output),
+        None => Ok(output),
+    }
+}
+
+/// Count-only variant of a symbol or content search: per-file match counts,
+/// highest first, with the definition/usage split.
+pub fn run_count(query: &str, scope: &Path, filter: &SearchFilter) -> Result<String, GleanError> {
+    classify::check_query(query)?;
+    match classify(query, scope) {
+        QueryType::Symbol(name) | QueryType::Fallthrough(name) => {
+            search::search_count(&name, scope, filter, false)
+        }
+        QueryType::Content(text) => search::search_count(&text, scope, filter, true),
+        QueryType::FilePath(_) | QueryType::Glob(_) => Err(GleanError::InvalidQuery {
+            query: query.to_string(),
+            reason: "--count applies to symbol and content searches".into(),
+        }),
+    }
+}
+
+/// Annotation markers in comments across `scope`, grouped by file with the
+/// function each one sits in. Empty `markers` scans for TODO, FIXME, HACK, XXX.
+pub fn run_todos(
+    scope: &Path,
+    markers: &[&str],
+    budget_tokens: Option<u64>,
+    filter: &SearchFilter,
+) -> Result<String, GleanError> {
+    let output = search::search_todos(scope, filter, markers)?;
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b).output),
+        None => Ok(output),
+    }
+}
+
+/// Table of contents of a markdown file: headings down to `max_level`
+/// (1 = `#` only, 2 = `#` and `##`, ...) with line ranges.
+pub fn run_toc(
+    query: &str,
+    scope: &Path,
+    max_level: usize,
+    budget_tokens: Option<u64>,
+) -> Result<String, GleanError> {
+    let QueryType::FilePath(path) = classify(query, scope) else {
+        return Err(GleanError::NotFound {
+            path: scope.join(query),
+            suggestion: None,
+        });
+    };
+    let output = read::read_toc(&path, max_level)?;
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b).output),
+        None => Ok(output),
+    }
+}
+
+/// Read `query` as a file the way it was at git revision `rev` — smart view,
+/// section and budget as for the working copy. The file need not exist on
+/// disk any more.
+pub fn run_revision(
+    query: &str,
+    scope: &Path,
+    rev: &str,
+    section: Option<&str>,
+    budget_tokens: Option<u64>,
+    full: bool,
+    dedup_variants: bool,
+) -> Result<String, GleanError> {
+    classify::check_query(query)?;
+    let path = classify::resolve_path(scope, query);
+    let output = read::read_revision(
+        &path,
+        rev,
+        section,
+        full,
+        None,
+        false,
+        budget_tokens,
+        dedup_variants,
+    )?;
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b).output),
+        None => Ok(output),
+    }
+}
+
+/// Blast radius of changing `symbol`: its definition and every transitive
+/// caller up to `depth` levels (default 3), with the affected-file count.
+pub fn run_impact(
+    symbol: &str,
+    scope: &Path,
+    depth: Option<u32>,
+    budget_tokens: Option<u64>,
+    filter: &SearchFilter,
+) -> Result<String, GleanError> {
+    let depth = depth.unwrap_or(search::impact::DEFAULT_DEPTH);
+    let output = search::search_impact(symbol, scope, filter, depth)?;
+    match budget_tokens {
+        Some(b) => Ok(budget::apply(&output, b).output),
+        None => Ok(output),
+    }
 }
 
 fn run_inner(
@@ -63,31 +329,43 @@ fn run_inner(
     section: Option<&str>,
     budget_tokens: Option<u64>,
     full: bool,
+    filter: &SearchFilter,
     cache: &OutlineCache,
 ) -> Result<String, GleanError> {
+    classify::check_query(query)?;
     let query_type = classify(query, scope);
 
     let output = match query_type {
-        QueryType::FilePath(path) => read::read_file(&path, section, full, cache, false)?,
+        QueryType::FilePath(path) => read::read_file(
+            &path,
+            section,
+            full,
+            filter.lang,
+            cache,
+            false,
+            budget_tokens,
+            read::generated::Overrides::for_file(&path).as_ref(),
+            filter.dedup_variants,
+        )?,
 
-        QueryType::Glob(pattern) => search::search_glob(&pattern, scope, cache)?,
+        QueryType::Glob(pattern) => search::search_glob(&pattern, scope, filter, false, cache)?,
 
-        QueryType::Symbol(name) => search::search_symbol(&name, scope, cache)?,
+        QueryType::Symbol(name) => search::search_symbol(&name, scope, filter, cache)?,
 
-        QueryType::Content(text) => search::search_content(&text, scope, cache)?,
+        QueryType::Content(text) => search::search_content(&text, scope, filter, cache)?,
 
         QueryType::Fallthrough(text) => {
             // Path-like query that didn't resolve. Try symbol, then content.
             // Use structured total_found check, not string matching.
-            let sym_result = search::search_symbol_raw(&text, scope)?;
+            let sym_result = search::search_symbol_raw(&text, scope, filter)?;
             if sym_result.total_found > 0 {
-                search::format_symbol_result(&sym_result, cache)?
+                search::format_symbol_result(&sym_result, cache, filter.absolute_paths)?
             } else {
-                let content_result = search::search_content_raw(&text, scope)?;
+                let content_result = search::search_content_raw(&text, scope, filter)?;
                 if content_result.total_found > 0 {
-                    search::format_content_result(&content_result, cache)?
+                    search::format_content_result(&content_result, cache, filter.absolute_paths)?
                 } else {
-                    let resolved = scope.join(&text);
+                    let resolved = classify::resolve_path(scope, &text);
                     return Err(GleanError::NotFound {
                         path: resolved,
                         suggestion: read::suggest_similar_file(scope, &text),
@@ -98,7 +376,7 @@ fn run_inner(
     };
 
     match budget_tokens {
-        Some(b) => Ok(budget::apply(&output, b)),
+        Some(b) => Ok(budget::apply(&output, b).output),
         None => Ok(output),
     }
 }
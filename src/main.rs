@@ -17,25 +17,40 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
 
-    /// File path, symbol name, glob pattern, or text to search.
+    /// File path, symbol name, glob pattern, or text to search. Prefix with
+    /// `symbol:`, `content:`, `file:` or `glob:` to force the kind.
     query: Option<String>,
 
     /// Directory to search within or resolve relative paths against.
     #[arg(long, default_value = ".")]
     scope: PathBuf,
 
-    /// Line range or markdown heading (e.g. "45-89" or "## Architecture"). Bypasses smart view.
-    #[arg(long)]
+    /// Line range ("45-89", "-50" for the last 50 lines, "100-" to the end) or markdown
+    /// heading ("## Architecture"), or several
+    /// comma-separated ("10-20,45-60,## API"). Bypasses smart view.
+    #[arg(long, allow_hyphen_values = true)]
     section: Option<String>,
 
     /// Max tokens in response. Reduces detail to fit.
     #[arg(long)]
     budget: Option<u64>,
 
+    /// Markdown table of contents down to this heading level (1-6), e.g. 2 for # and ##.
+    #[arg(long, value_name = "N")]
+    heading_level: Option<usize>,
+
     /// Force full output (override smart view).
     #[arg(long)]
     full: bool,
 
+    /// Read the file as of this git revision (`HEAD~1`, `main`, a commit hash).
+    #[arg(long, value_name = "REV", conflicts_with_all = ["stdin", "concat", "count"])]
+    rev: Option<String>,
+
+    /// Content-search piped input instead of files: `cmd | glean ERROR --stdin`.
+    #[arg(long, conflicts_with_all = ["scope", "section"])]
+    stdin: bool,
+
     /// Machine-readable JSON output.
     #[arg(long)]
     json: bool,
@@ -48,10 +63,96 @@ struct Cli {
     #[arg(long)]
     edit: bool,
 
-    /// Generate a structural codebase map.
+    /// With --mcp, keep session state (dedup, search history) across host
+    /// reconnects in a temp file keyed by the workspace root.
+    #[arg(long, requires = "mcp")]
+    persist_session: bool,
+
+    /// With --mcp, cap in-memory outlines at this many megabytes (default 64);
+    /// least recently used outlines are evicted first.
+    #[arg(long, value_name = "MB", requires = "mcp")]
+    cache_mb: Option<usize>,
+
+    /// Only count functions, types and modules as definitions — not top-level
+    /// variable/constant declarations (`const X = ...`, `static FOO`).
+    #[arg(long)]
+    strict_definitions: bool,
+
+    /// Also count JS/TS `const`/`let`/`var` bindings as definitions.
+    #[arg(long, conflicts_with = "strict_definitions")]
+    loose_definitions: bool,
+
+    /// Read a directory as one module: every code file's outline, concatenated.
+    #[arg(long)]
+    concat: bool,
+
+    /// Report per-file match counts for a symbol or content search instead of listing matches.
+    #[arg(long)]
+    count: bool,
+
+    /// Match symbol names regardless of case (HttpClient finds HTTPClient).
+    #[arg(long)]
+    ignore_case: bool,
+
+    /// Match symbol names across snake_case/camelCase conventions (get_user finds getUser).
+    #[arg(long)]
+    loose_case: bool,
+
+    /// Skip Go files whose build constraints exclude a platform: the host by
+    /// default, or GOOS/GOARCH like linux/arm64.
+    #[arg(long, value_name = "OS/ARCH", num_args = 0..=1, default_missing_value = "host")]
+    go_target: Option<String>,
+
+    /// Leave test files and test directories out of search results entirely.
+    #[arg(long)]
+    exclude_tests: bool,
+
+    /// Skip files ignored by .gitignore, .git/info/exclude and .ignore (searched by default).
+    #[arg(long)]
+    respect_gitignore: bool,
+
+    /// Rank search results by each file's last git commit time instead of its mtime.
+    #[arg(long)]
+    git_recency: bool,
+
+    /// Skip paths matching a glob, against the path relative to --scope or the
+    /// file name. Repeatable or comma-separated: --exclude 'vendor/**,*.pb.go'.
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Only search files detected as these languages, comma-separated (e.g.
+    /// rust,go). Unlike --lang, this filters files rather than overriding detection.
+    #[arg(long, value_name = "LIST")]
+    languages: Option<String>,
+
+    /// Read the file as this language instead of detecting it from the name
+    /// (e.g. rust, python, go). Searches apply it to files whose type isn't
+    /// recognised, such as extensionless scripts.
+    #[arg(long, value_name = "LANG")]
+    lang: Option<String>,
+
+    /// Collapse identical #[cfg]/#ifdef copies of a declaration in code
+    /// outlines into one entry that lists every location.
+    #[arg(long)]
+    dedup_variants: bool,
+
+    /// Don't read or write the on-disk outline cache (~/.cache/glean).
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Show full paths in search results instead of paths relative to --scope.
+    #[arg(long)]
+    absolute_paths: bool,
+
+    /// Generate a structural codebase map. With --mcp, expose the glean_map
+    /// tool instead (off by default).
     #[arg(long)]
     map: bool,
 
+    /// Render --map as a diagram: mermaid or dot (Graphviz), with import edges.
+    #[arg(long, value_name = "FORMAT", requires = "map")]
+    map_format: Option<String>,
+
     /// Print shell completions for the given shell.
     #[arg(long, value_name = "SHELL")]
     completions: Option<Shell>,
@@ -60,15 +161,126 @@ struct Cli {
 #[derive(clap::Subcommand)]
 enum Command {
     /// Install glean into an MCP host's config.
-    /// Supported hosts: claude-code, cursor, windsurf, vscode, claude-desktop
+    /// Supported hosts: claude-code, cursor, windsurf, vscode, claude-desktop, zed,
+    /// neovim (mcphub.nvim)
     Install {
         /// MCP host to configure.
-        host: String,
+        #[arg(required_unless_present = "list")]
+        host: Option<String>,
 
         /// Enable edit mode (hashline output + glean_edit tool).
         #[arg(long)]
         edit: bool,
+
+        /// Report which hosts have glean configured, and in which mode.
+        #[arg(long, conflicts_with_all = ["host", "edit"])]
+        list: bool,
+    },
+    /// Remove glean from an MCP host's config, leaving other servers alone.
+    Uninstall {
+        /// MCP host to clean up.
+        host: String,
+    },
+    /// List TODO/FIXME/HACK/XXX markers in comments, grouped by file with the
+    /// enclosing function.
+    Todos {
+        /// Directory to scan.
+        #[arg(long, default_value = ".")]
+        scope: PathBuf,
+
+        /// Marker to look for (repeatable). Replaces the defaults.
+        #[arg(long = "marker", value_name = "MARKER")]
+        markers: Vec<String>,
+
+        /// Leave test files and test directories out.
+        #[arg(long)]
+        exclude_tests: bool,
+
+        /// Max tokens in response.
+        #[arg(long)]
+        budget: Option<u64>,
+    },
+    /// Show what would break if a symbol changed: its definition and every
+    /// transitive caller, grouped by package, with the affected-file count.
+    Impact {
+        /// Function or method name.
+        symbol: String,
+
+        /// Directory to search within.
+        #[arg(long, default_value = ".")]
+        scope: PathBuf,
+
+        /// Caller levels to follow (default 3, max 6).
+        #[arg(long)]
+        depth: Option<u32>,
+
+        /// Leave test files and test directories out.
+        #[arg(long)]
+        exclude_tests: bool,
+
+        /// Max tokens in response.
+        #[arg(long)]
+        budget: Option<u64>,
+    },
+    /// Bundle everything needed to understand a symbol or file into one
+    /// markdown document: definition, callees, callers, related files.
+    Pack {
+        /// Symbol name or file path.
+        root: String,
+
+        /// Directory to search within.
+        #[arg(long, default_value = ".")]
+        scope: PathBuf,
+
+        /// Leave test files and test directories out.
+        #[arg(long)]
+        exclude_tests: bool,
+
+        /// Max tokens in the pack (default 8000).
+        #[arg(long)]
+        budget: Option<u64>,
     },
+    /// Pre-compute outlines for every file in a scope so later reads and
+    /// searches skip the parse. Respects skip dirs and the file size cap.
+    Warm {
+        /// Directory to warm.
+        #[arg(long, default_value = ".")]
+        scope: PathBuf,
+
+        /// Leave test files and test directories out.
+        #[arg(long)]
+        exclude_tests: bool,
+    },
+    /// Rename a symbol: every whole-word occurrence across the scope, one
+    /// hashline-anchored edit per line. Previews unless --apply.
+    Rename {
+        /// Current name.
+        old: String,
+
+        /// New name.
+        new: String,
+
+        /// Directory to rename within.
+        #[arg(long, default_value = ".")]
+        scope: PathBuf,
+
+        /// Write the edits instead of previewing them.
+        #[arg(long)]
+        apply: bool,
+
+        /// Leave test files and test directories untouched.
+        #[arg(long)]
+        exclude_tests: bool,
+    },
+}
+
+/// The CLI runs once per query, so outlines persist on disk between runs
+/// unless `--no-cache` (or there is no cache directory).
+fn outline_cache(no_cache: bool) -> glean::cache::OutlineCache {
+    match glean::cache::OutlineCache::default_disk_dir() {
+        Some(dir) if !no_cache => glean::cache::OutlineCache::with_disk(&dir),
+        _ => glean::cache::OutlineCache::new(),
+    }
 }
 
 fn main() {
@@ -83,19 +295,140 @@ fn main() {
     // Subcommands
     if let Some(cmd) = cli.command {
         match cmd {
-            Command::Install { ref host, edit } => {
-                if let Err(e) = glean::install::run(host, edit) {
+            Command::Install {
+                ref host,
+                edit,
+                list,
+            } => {
+                if list {
+                    println!("{}", glean::install::list());
+                } else if let Some(host) = host
+                    && let Err(e) = glean::install::run(host, edit)
+                {
                     eprintln!("install error: {e}");
                     process::exit(1);
                 }
             }
+            Command::Uninstall { ref host } => {
+                if let Err(e) = glean::install::uninstall(host) {
+                    eprintln!("uninstall error: {e}");
+                    process::exit(1);
+                }
+            }
+            Command::Todos {
+                scope,
+                ref markers,
+                exclude_tests,
+                budget,
+            } => {
+                let scope = scope.canonicalize().unwrap_or(scope);
+                let markers: Vec<&str> = markers.iter().map(String::as_str).collect();
+                let filter = glean::SearchFilter {
+                    exclude_tests,
+                    ..glean::SearchFilter::default()
+                };
+                match glean::run_todos(&scope, &markers, budget, &filter) {
+                    Ok(output) => emit_output(&output, io::stdout().is_terminal()),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        process::exit(e.exit_code());
+                    }
+                }
+            }
+            Command::Impact {
+                ref symbol,
+                scope,
+                depth,
+                exclude_tests,
+                budget,
+            } => {
+                let scope = scope.canonicalize().unwrap_or(scope);
+                let filter = glean::SearchFilter {
+                    exclude_tests,
+                    ..glean::SearchFilter::default()
+                };
+                match glean::run_impact(symbol, &scope, depth, budget, &filter) {
+                    Ok(output) => emit_output(&output, io::stdout().is_terminal()),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        process::exit(e.exit_code());
+                    }
+                }
+            }
+            Command::Pack {
+                ref root,
+                scope,
+                exclude_tests,
+                budget,
+            } => {
+                let scope = scope.canonicalize().unwrap_or(scope);
+                let filter = glean::SearchFilter {
+                    exclude_tests,
+                    ..glean::SearchFilter::default()
+                };
+                let cache = outline_cache(cli.no_cache);
+                match glean::run_pack(root, &scope, budget, &filter, &cache) {
+                    Ok(output) => emit_output(&output, io::stdout().is_terminal()),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        process::exit(e.exit_code());
+                    }
+                }
+            }
+            Command::Warm {
+                scope,
+                exclude_tests,
+            } => {
+                let scope = scope.canonicalize().unwrap_or(scope);
+                let filter = glean::SearchFilter {
+                    exclude_tests,
+                    ..glean::SearchFilter::default()
+                };
+                let cache = outline_cache(cli.no_cache);
+                println!("{}", glean::run_warm(&scope, &filter, &cache));
+            }
+            Command::Rename {
+                ref old,
+                ref new,
+                scope,
+                apply,
+                exclude_tests,
+            } => {
+                let scope = scope.canonicalize().unwrap_or(scope);
+                let filter = glean::SearchFilter {
+                    exclude_tests,
+                    ..glean::SearchFilter::default()
+                };
+                match glean::run_rename(old, new, &scope, apply, &filter) {
+                    Ok(output) => emit_output(&output, io::stdout().is_terminal()),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        process::exit(e.exit_code());
+                    }
+                }
+            }
         }
         return;
     }
 
+    let definitions = if cli.strict_definitions {
+        glean::DefinitionMode::Strict
+    } else if cli.loose_definitions {
+        glean::DefinitionMode::Loose
+    } else {
+        glean::DefinitionMode::Standard
+    };
+
     // MCP mode: JSON-RPC server
     if cli.mcp {
-        if let Err(e) = glean::mcp::run(cli.edit) {
+        if let Err(e) = glean::mcp::run(glean::mcp::ServerOptions {
+            edit: cli.edit,
+            map: cli.map,
+            persist_session: cli.persist_session,
+            cache_bytes: cli.cache_mb.map(|mb| mb * 1024 * 1024),
+            definitions,
+            absolute_paths: cli.absolute_paths,
+        }) {
             eprintln!("mcp error: {e}");
             process::exit(1);
         }
@@ -106,9 +439,18 @@ fn main() {
 
     // Map mode
     if cli.map {
-        let cache = glean::cache::OutlineCache::new();
+        let cache = outline_cache(cli.no_cache);
         let scope = cli.scope.canonicalize().unwrap_or(cli.scope);
-        let output = glean::map::generate(&scope, 3, cli.budget, &cache);
+        let format = cli
+            .map_format
+            .as_deref()
+            .map_or_else(Default::default, |f| {
+                glean::map::MapFormat::parse(f).unwrap_or_else(|| {
+                    eprintln!("invalid --map-format '{f}': expected text, mermaid or dot");
+                    process::exit(3);
+                })
+            });
+        let output = glean::map::generate(&scope, 3, cli.budget, &cache, format);
         emit_output(&output, is_tty);
         return;
     }
@@ -121,25 +463,114 @@ fn main() {
         process::exit(3);
     };
 
-    let cache = glean::cache::OutlineCache::new();
+    let cache = outline_cache(cli.no_cache);
     let scope = cli.scope.canonicalize().unwrap_or(cli.scope);
 
     // When piped (not a TTY), force full output — scripts expect raw content
     let full = cli.full || !is_tty;
 
-    let result = if full {
-        glean::run_full(&query, &scope, cli.section.as_deref(), cli.budget, &cache)
+    let go_target = cli.go_target.as_deref().map(|t| {
+        glean::GoTarget::parse(t).unwrap_or_else(|| {
+            eprintln!("invalid --go-target '{t}': expected GOOS/GOARCH like linux/amd64");
+            process::exit(3);
+        })
+    });
+
+    let lang = cli.lang.as_deref().map(|l| {
+        glean::Lang::parse(l).unwrap_or_else(|| {
+            eprintln!(
+                "invalid --lang '{l}': expected one of {}",
+                glean::Lang::NAMES.join(", ")
+            );
+            process::exit(3);
+        })
+    });
+
+    let exclude = (!cli.exclude.is_empty()).then(|| {
+        glean::ExcludeGlobs::parse(&cli.exclude.join(",")).unwrap_or_else(|e| {
+            eprintln!("invalid --exclude: {e}");
+            process::exit(3);
+        })
+    });
+
+    let languages = cli.languages.as_deref().map(|spec| {
+        glean::parse_languages(spec).unwrap_or_else(|e| {
+            eprintln!("invalid --languages: {e}");
+            process::exit(3);
+        })
+    });
+
+    let filter = glean::SearchFilter {
+        exclude_tests: cli.exclude_tests,
+        ignore_case: cli.ignore_case,
+        loose_case: cli.loose_case,
+        go_target,
+        lang,
+        exclude,
+        respect_gitignore: cli.respect_gitignore,
+        languages,
+        git_recency: cli.git_recency,
+        progress: None,
+        definitions,
+        dedup_variants: cli.dedup_variants,
+        absolute_paths: cli.absolute_paths,
+    };
+
+    // JSON reports what the budget cut, so it's applied when printing
+    let budget = if cli.json { None } else { cli.budget };
+    let result = if cli.stdin {
+        let input = io::read_to_string(io::stdin()).unwrap_or_else(|e| {
+            eprintln!("cannot read stdin: {e}");
+            process::exit(1);
+        });
+        glean::run_stdin(input, &query, budget, &filter, &cache)
+    } else if let Some(rev) = &cli.rev {
+        glean::run_revision(
+            &query,
+            &scope,
+            rev,
+            cli.section.as_deref(),
+            budget,
+            full,
+            cli.dedup_variants,
+        )
+    } else if let Some(level) = cli.heading_level {
+        glean::run_toc(&query, &scope, level, budget)
+    } else if cli.concat {
+        glean::run_concat(&query, &scope, budget, cli.absolute_paths, &cache)
+    } else if cli.count {
+        glean::run_count(&query, &scope, &filter)
     } else {
-        glean::run(&query, &scope, cli.section.as_deref(), cli.budget, &cache)
+        glean::run_filtered(
+            &query,
+            &scope,
+            cli.section.as_deref(),
+            budget,
+            full,
+            &filter,
+            &cache,
+        )
     };
 
     match result {
         Ok(output) => {
             if cli.json {
-                let json = serde_json::json!({
-                    "query": query,
-                    "output": output,
-                });
+                let json = match cli.budget {
+                    Some(budget) => {
+                        let budgeted = glean::budget::apply(&output, budget);
+                        serde_json::json!({
+                            "query": query,
+                            "output": budgeted.output,
+                            "truncated": budgeted.truncated,
+                            "original_tokens": budgeted.original_tokens,
+                            "returned_tokens": budgeted.returned_tokens,
+                        })
+                    }
+                    None => serde_json::json!({
+                        "query": query,
+                        "output": output,
+                    }),
+                };
                 println!(
                     "{}",
                     serde_json::to_string_pretty(&json)
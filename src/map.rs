@@ -8,11 +8,43 @@ use crate::cache::OutlineCache;
 use crate::read::{detect_file_type, outline};
 use crate::types::{FileType, estimate_tokens};
 
+/// How [`generate`] renders the map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MapFormat {
+    /// Indented tree with each code file's symbols.
+    #[default]
+    Text,
+    /// Mermaid `graph`: directories as subgraphs, files as nodes, imports as edges.
+    Mermaid,
+    /// Graphviz `digraph`, laid out like `Mermaid`.
+    Dot,
+}
+
+impl MapFormat {
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(MapFormat::Text),
+            "mermaid" => Some(MapFormat::Mermaid),
+            "dot" | "graphviz" => Some(MapFormat::Dot),
+            _ => None,
+        }
+    }
+}
+
 /// Generate a structural codebase map.
 /// Code files show symbol names from outline cache.
 /// Non-code files show name + token estimate.
+/// Diagram formats draw local import edges instead of symbols, and skip
+/// `budget` since a cut diagram wouldn't render.
 #[must_use]
-pub fn generate(scope: &Path, depth: usize, budget: Option<u64>, cache: &OutlineCache) -> String {
+pub fn generate(
+    scope: &Path,
+    depth: usize,
+    budget: Option<u64>,
+    cache: &OutlineCache,
+    format: MapFormat,
+) -> String {
     let mut tree: BTreeMap<PathBuf, Vec<FileEntry>> = BTreeMap::new();
 
     let walker = WalkBuilder::new(scope)
@@ -22,6 +54,7 @@ pub fn generate(scope: &Path, depth: usize, budget: Option<u64>, cache: &Outline
         .git_exclude(false)
         .ignore(false)
         .parents(false)
+        .follow_links(false)
         .filter_entry(|entry| {
             if entry.file_type().is_some_and(|ft| ft.is_dir())
                 && let Some(name) = entry.file_name().to_str()
@@ -65,10 +98,11 @@ pub fn generate(scope: &Path, depth: usize, budget: Option<u64>, cache: &Outline
                     .and_then(|m| m.modified().ok())
                     .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
 
-                let outline_str = cache.get_or_compute(path, mtime, || {
+                let view = crate::cache::View::outline(file_type, true);
+                let outline_str = cache.get_or_compute(path, mtime, view, || {
                     let content = std::fs::read_to_string(path).unwrap_or_default();
                     let buf = content.as_bytes();
-                    outline::generate(path, file_type, &content, buf, true)
+                    outline::generate(path, file_type, &content, buf, true, false)
                 });
 
                 Some(extract_symbol_names(&outline_str))
@@ -76,18 +110,38 @@ pub fn generate(scope: &Path, depth: usize, budget: Option<u64>, cache: &Outline
             _ => None,
         };
 
+        let imports = if format != MapFormat::Text && symbols.is_some() {
+            std::fs::read_to_string(path).map_or_else(
+                |_| Vec::new(),
+                |content| {
+                    crate::read::imports::resolve_related_files_with_content(path, &content)
+                        .into_iter()
+                        .filter_map(|p| Some(p.strip_prefix(scope).ok()?.to_path_buf()))
+                        .collect()
+                },
+            )
+        } else {
+            Vec::new()
+        };
+
         tree.entry(parent).or_default().push(FileEntry {
             name,
             symbols,
             tokens,
+            imports,
         });
     }
 
+    match format {
+        MapFormat::Text => {}
+        MapFormat::Mermaid | MapFormat::Dot => return format_graph(&tree, format),
+    }
+
     let mut out = format!("# Map: {} (depth {})\n", scope.display(), depth);
     format_tree(&tree, Path::new(""), 0, &mut out);
 
     match budget {
-        Some(b) => crate::budget::apply(&out, b),
+        Some(b) => crate::budget::apply(&out, b).output,
         None => out,
     }
 }
@@ -96,6 +150,8 @@ struct FileEntry {
     name: String,
     symbols: Option<Vec<String>>,
     tokens: u64,
+    /// Local files this one imports, relative to the scope.
+    imports: Vec<PathBuf>,
 }
 
 /// Extract symbol names from an outline string.
@@ -196,3 +252,124 @@ fn format_tree(
         format_tree(tree, subdir, indent + 1, out);
     }
 }
+
+/// Render `tree` as a Mermaid or Graphviz diagram. Files get ids `n0`, `n1`,
+/// ... in tree order; import edges are drawn only between mapped files.
+fn format_graph(tree: &BTreeMap<PathBuf, Vec<FileEntry>>, format: MapFormat) -> String {
+    let mut ids: BTreeMap<PathBuf, usize> = BTreeMap::new();
+    for (dir, files) in tree {
+        for f in files {
+            let next = ids.len();
+            ids.insert(dir.join(&f.name), next);
+        }
+    }
+
+    let mut out = match format {
+        MapFormat::Dot => "digraph map {\n  rankdir=LR;\n  node [shape=box];\n".to_string(),
+        _ => "graph LR\n".to_string(),
+    };
+    let mut clusters = 0;
+    graph_dir(
+        tree,
+        Path::new(""),
+        1,
+        &ids,
+        format,
+        &mut clusters,
+        &mut out,
+    );
+
+    for (dir, files) in tree {
+        for f in files {
+            let from = ids[&dir.join(&f.name)];
+            let mut targets: Vec<usize> = f
+                .imports
+                .iter()
+                .filter_map(|p| ids.get(p).copied())
+                .collect();
+            targets.sort_unstable();
+            targets.dedup();
+            for to in targets.into_iter().filter(|&to| to != from) {
+                let _ = match format {
+                    MapFormat::Dot => writeln!(out, "  n{from} -> n{to};"),
+                    _ => writeln!(out, "  n{from} --> n{to}"),
+                };
+            }
+        }
+    }
+    if format == MapFormat::Dot {
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn graph_dir(
+    tree: &BTreeMap<PathBuf, Vec<FileEntry>>,
+    dir: &Path,
+    indent: usize,
+    ids: &BTreeMap<PathBuf, usize>,
+    format: MapFormat,
+    clusters: &mut usize,
+    out: &mut String,
+) {
+    let prefix = "  ".repeat(indent);
+    if let Some(files) = tree.get(dir) {
+        for f in files {
+            let id = ids[&dir.join(&f.name)];
+            let label = f.name.replace('"', "'");
+            let _ = match format {
+                MapFormat::Dot => writeln!(out, "{prefix}n{id} [label=\"{label}\"];"),
+                _ => writeln!(out, "{prefix}n{id}[\"{label}\"]"),
+            };
+        }
+    }
+
+    let subdirs = tree.keys().filter(|k| k.parent() == Some(dir) && *k != dir);
+    for subdir in subdirs {
+        let name = subdir.file_name().and_then(|n| n.to_str()).unwrap_or("?");
+        let label = format!("{}/", name.replace('"', "'"));
+        let cluster = *clusters;
+        *clusters += 1;
+        let _ = match format {
+            MapFormat::Dot => writeln!(
+                out,
+                "{prefix}subgraph cluster_{cluster} {{\n{prefix}  label=\"{label}\";"
+            ),
+            _ => writeln!(out, "{prefix}subgraph d{cluster}[\"{label}\"]"),
+        };
+        graph_dir(tree, subdir, indent + 1, ids, format, clusters, out);
+        let _ = match format {
+            MapFormat::Dot => writeln!(out, "{prefix}}}"),
+            _ => writeln!(out, "{prefix}end"),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mermaid_and_dot_draw_import_edges() {
+        let scope = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini-ts");
+        let cache = OutlineCache::new();
+
+        let mermaid = generate(&scope, 3, None, &cache, MapFormat::Mermaid);
+        assert!(mermaid.starts_with("graph LR\n"), "{mermaid}");
+        assert!(mermaid.contains("subgraph d0[\"src/\"]"), "{mermaid}");
+        let id = |name: &str| {
+            let line = mermaid
+                .lines()
+                .find(|l| l.ends_with(&format!("[\"{name}\"]")))
+                .unwrap();
+            line.trim().split('[').next().unwrap().to_string()
+        };
+        let edge = format!("{} --> {}", id("parse.ts"), id("errors.ts"));
+        assert!(mermaid.contains(&edge), "{mermaid}");
+
+        let dot = generate(&scope, 3, None, &cache, MapFormat::Dot);
+        assert!(dot.starts_with("digraph map {"), "{dot}");
+        assert!(dot.contains(&edge.replace("-->", "->")), "{dot}");
+        assert!(dot.trim_end().ends_with('}'), "{dot}");
+    }
+}
@@ -1,12 +1,14 @@
 use std::fmt::Write as _;
 use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::cache::OutlineCache;
+use crate::search::Progress;
 use crate::session::Session;
+use crate::types::count_tokens;
 
 // Sent to the LLM via the MCP `instructions` field during initialization.
 // Keeps the strategic guidance from AGENTS.md available to any host.
@@ -36,6 +38,11 @@ Use `paths` to read multiple files in one call — saves round-trips.\n\
 \n\
 glean_files: Find files by glob pattern. Returns paths + token estimates. Respects .gitignore.\n\
 \n\
+glean_todos: TODO/FIXME/HACK/XXX markers in comments, grouped by file with the function each sits in.\n\
+\n\
+glean_impact: Before changing a function's signature or behavior — its definition and every transitive caller, \
+grouped by package, with the number of affected files.\n\
+\n\
 IMPORTANT: Expanded search results include full source code — do NOT re-read files already shown \
 in search output. Answer from what you have rather than exploring further.";
 
@@ -54,7 +61,11 @@ EDIT WORKFLOW:\n\
 2. glean_edit → pass anchors: {\"start\": \"42:a3f\", \"content\": \"new code\"}\n\
    Range: {\"start\": \"42:a3f\", \"end\": \"45:b2c\", \"content\": \"...\"}\n\
    Delete: {\"start\": \"42:a3f\", \"content\": \"\"}\n\
+   Insert: {\"start\": \"42:a3f\", \"mode\": \"after\", \"content\": \"...\"}\n\
 3. Hash mismatch → file changed, re-read and retry\n\
+REPLACE: {path, find: \"/old_name/\", replace: \"new_name\", count: 4} substitutes a regex throughout the file.\n\
+NEW FILE: {path, edits: [{\"start\": \"0:new\", \"content\": \"...\"}]} creates it (and parent dirs).\n\
+MULTI-FILE: files=[{path, edits}, ...] writes every file or none (use for cross-file renames).\n\
 \n\
 LARGE FILES: glean_read returns outline (no hashlines). Use section to get hashlined content.\n\
 BATCH READ: paths=[\"a\",\"b\"] reads multiple files in one call.\n\
@@ -63,14 +74,36 @@ expand inlines source — often avoids a separate read. Expanded definitions inc
 showing resolved callees — follow these instead of searching for each callee. Use `kind: \"callers\"` to find \
 all call sites of a symbol. Re-expanding a previously shown definition shows `[shown earlier]` instead of the full body.";
 
-/// MCP server over stdio. When `edit_mode` is true, exposes `glean_edit` and
-/// switches `glean_read` to hashline output format.
-pub fn run(edit_mode: bool) -> io::Result<()> {
-    let cache = OutlineCache::new();
+/// Which optional tools an MCP server exposes, and its search defaults.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ServerOptions {
+    /// Expose `glean_edit` and switch `glean_read` to hashline output.
+    pub edit: bool,
+    /// Expose `glean_map`. Off by default: in benchmarks agents given a map
+    /// leaned on it instead of searching.
+    pub map: bool,
+    /// Keep session state (`[shown earlier]` dedup, search history) in a temp
+    /// file keyed by workspace root, so a reconnecting host picks it back up.
+    pub persist_session: bool,
+    /// Byte budget for in-memory outlines; `None` uses the default.
+    pub cache_bytes: Option<usize>,
+    /// Definition mode for searches that don't pass `definitions`.
+    pub definitions: crate::search::treesitter::DefinitionMode,
+    /// Show result paths in full instead of relative to the scope.
+    pub absolute_paths: bool,
+}
+
+/// MCP server over stdio, exposing the optional tools `opts` enables.
+pub fn run(opts: ServerOptions) -> io::Result<()> {
+    let cache = match opts.cache_bytes {
+        Some(bytes) => OutlineCache::new().with_limits(crate::cache::DEFAULT_MAX_ENTRIES, bytes),
+        None => OutlineCache::new(),
+    };
     let session = Session::new();
     let stdin = io::stdin();
+    // Locked per message, not for the whole loop: progress notifications are
+    // written from walker threads while a request is being handled
     let stdout = io::stdout();
-    let mut stdout = stdout.lock();
 
     for line in stdin.lock().lines() {
         let line = line?;
@@ -81,7 +114,12 @@ pub fn run(edit_mode: bool) -> io::Result<()> {
         let req: JsonRpcRequest = match serde_json::from_str(&line) {
             Ok(r) => r,
             Err(e) => {
-                write_error(&mut stdout, None, -32700, &format!("parse error: {e}"))?;
+                write_error(
+                    &mut stdout.lock(),
+                    None,
+                    -32700,
+                    &format!("parse error: {e}"),
+                )?;
                 continue;
             }
         };
@@ -91,7 +129,8 @@ pub fn run(edit_mode: bool) -> io::Result<()> {
             continue;
         }
 
-        let response = handle_request(&req, &cache, &session, edit_mode);
+        let response = handle_request(&req, &cache, &session, opts);
+        let mut stdout = stdout.lock();
         serde_json::to_writer(&mut stdout, &response)?;
         stdout.write_all(b"\n")?;
         stdout.flush()?;
@@ -130,11 +169,17 @@ fn handle_request(
     req: &JsonRpcRequest,
     cache: &OutlineCache,
     session: &Session,
-    edit_mode: bool,
+    opts: ServerOptions,
 ) -> JsonRpcResponse {
     match req.method.as_str() {
         "initialize" => {
-            let instructions = if edit_mode {
+            if opts.persist_session
+                && let Some(file) =
+                    workspace_root(&req.params).and_then(|r| Session::store_path(&r))
+            {
+                session.persist_to(file);
+            }
+            let instructions = if opts.edit {
                 EDIT_MODE_INSTRUCTIONS
             } else {
                 SERVER_INSTRUCTIONS
@@ -145,7 +190,9 @@ fn handle_request(
                 result: Some(serde_json::json!({
                     "protocolVersion": "2024-11-05",
                     "capabilities": {
-                        "tools": {}
+                        "tools": {},
+                        "resources": {},
+                        "prompts": {}
                     },
                     "serverInfo": {
                         "name": "glean",
@@ -161,12 +208,31 @@ fn handle_request(
             jsonrpc: "2.0",
             id: req.id.clone(),
             result: Some(serde_json::json!({
-                "tools": tool_definitions(edit_mode)
+                "tools": tool_definitions(opts)
+            })),
+            error: None,
+        },
+
+        "tools/call" => {
+            let response = handle_tool_call(req, cache, session, opts);
+            session.save();
+            response
+        }
+
+        "resources/list" => handle_resources_list(req),
+
+        "resources/read" => handle_resources_read(req, cache, session),
+
+        "prompts/list" => JsonRpcResponse {
+            jsonrpc: "2.0",
+            id: req.id.clone(),
+            result: Some(serde_json::json!({
+                "prompts": prompt_definitions()
             })),
             error: None,
         },
 
-        "tools/call" => handle_tool_call(req, cache, session, edit_mode),
+        "prompts/get" => handle_prompts_get(req, cache, session),
 
         "ping" => JsonRpcResponse {
             jsonrpc: "2.0",
@@ -187,6 +253,46 @@ fn handle_request(
     }
 }
 
+/// The workspace a persisted session belongs to: `rootUri`/`rootPath` when the
+/// host sends them in `initialize`, else the directory the server runs in.
+fn workspace_root(params: &Value) -> Option<PathBuf> {
+    let from_params = params
+        .get("rootUri")
+        .and_then(|v| v.as_str())
+        .map(file_uri_path)
+        .or_else(|| {
+            params
+                .get("rootPath")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from)
+        });
+    let root = from_params.or_else(|| std::env::current_dir().ok())?;
+    Some(root.canonicalize().unwrap_or(root))
+}
+
+/// Path of a `file://` URI, percent-decoded: `file:///my%20repo` is
+/// `/my repo`. Anything that isn't valid escaped UTF-8 is taken as written.
+fn file_uri_path(uri: &str) -> PathBuf {
+    let raw = uri.strip_prefix("file://").unwrap_or(uri);
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| raw.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    PathBuf::from(String::from_utf8(decoded).unwrap_or_else(|_| raw.to_string()))
+}
+
 // ---------------------------------------------------------------------------
 // Tool dispatch
 // ---------------------------------------------------------------------------
@@ -198,15 +304,21 @@ pub(crate) fn dispatch_tool(
     args: &Value,
     cache: &OutlineCache,
     session: &Session,
-    edit_mode: bool,
+    opts: ServerOptions,
+    progress: Option<&Progress>,
 ) -> Result<String, String> {
     match tool {
-        "glean_read" => tool_read(args, cache, session, edit_mode),
-        "glean_search" => tool_search(args, cache, session),
-        "glean_files" => tool_files(args, cache),
-        "glean_map" => Err("glean_map is disabled — use glean_search instead".into()),
-        "glean_session" => tool_session(args, session),
-        "glean_edit" if edit_mode => tool_edit(args, session),
+        "glean_read" => tool_read(args, cache, session, opts),
+        "glean_search" => tool_search(args, cache, session, opts, progress),
+        "glean_files" => tool_files(args, cache, opts, progress),
+        "glean_todos" => tool_todos(args, opts, progress),
+        "glean_impact" => tool_impact(args, opts, progress),
+        "glean_map" if opts.map => tool_map(args, cache, session),
+        "glean_map" => {
+            Err("glean_map is disabled — start the server with --map, or use glean_search".into())
+        }
+        "glean_session" => tool_session(args, session, cache),
+        "glean_edit" if opts.edit => tool_edit(args, session),
         _ => Err(format!("unknown tool: {tool}")),
     }
 }
@@ -215,9 +327,15 @@ fn tool_read(
     args: &Value,
     cache: &OutlineCache,
     session: &Session,
-    edit_mode: bool,
+    opts: ServerOptions,
 ) -> Result<String, String> {
+    let edit_mode = opts.edit;
     let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let lang = resolve_lang(args)?;
+    let dedup_variants = args
+        .get("dedup_variants")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
 
     // Multi-file batch read (capped at 20 to bound I/O)
     if let Some(paths_arr) = args.get("paths").and_then(|v| v.as_array()) {
@@ -227,18 +345,42 @@ fn tool_read(
                 paths_arr.len()
             ));
         }
+        let read = |path: &Path, budget: Option<u64>| match crate::read::read_file(
+            path,
+            None,
+            false,
+            lang,
+            cache,
+            edit_mode,
+            budget,
+            crate::read::generated::Overrides::for_file(path).as_ref(),
+            dedup_variants,
+        ) {
+            Ok(output) => apply_budget(output, budget),
+            Err(e) => format!("# {} — error: {}", path.display(), e),
+        };
+        let mut paths = Vec::with_capacity(paths_arr.len());
         let mut results = Vec::with_capacity(paths_arr.len());
         for p in paths_arr {
             let path_str = p.as_str().ok_or("paths must be an array of strings")?;
             let path = PathBuf::from(path_str);
             session.record_read(&path);
-            match crate::read::read_file(&path, None, false, cache, edit_mode) {
-                Ok(output) => results.push(output),
-                Err(e) => results.push(format!("# {} — error: {}", path.display(), e)),
+            results.push(read(&path, None));
+            paths.push(path);
+        }
+        // Each file gets a fair share, so the first can't starve the rest;
+        // files over their share are read again to fit it
+        if let Some(budget) = budget {
+            let sizes: Vec<u64> = results.iter().map(|r| count_tokens(r)).collect();
+            let separators = 2 * results.len() as u64;
+            let shares = crate::budget::allocate(&sizes, budget.saturating_sub(separators));
+            for (i, share) in shares.into_iter().enumerate() {
+                if sizes[i] > share {
+                    results[i] = read(&paths[i], Some(share));
+                }
             }
         }
-        let combined = results.join("\n\n");
-        return Ok(apply_budget(combined, budget));
+        return Ok(results.join("\n\n"));
     }
 
     // Single file read
@@ -247,18 +389,73 @@ fn tool_read(
         .and_then(|v| v.as_str())
         .ok_or("missing required parameter: path (or use paths for batch read)")?;
     let path = PathBuf::from(path_str);
+
+    // Directory as a module view: concatenated outlines of its code files
+    if args
+        .get("concat")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+        && path.is_dir()
+    {
+        let output = crate::read::concat::read_directory(&path, opts.absolute_paths, cache)
+            .map_err(|e| e.to_string())?;
+        return Ok(apply_budget(output, budget));
+    }
     let section = args.get("section").and_then(|v| v.as_str());
+    if let Some(level) = args
+        .get("heading_level")
+        .and_then(serde_json::Value::as_u64)
+        && section.is_none()
+    {
+        session.record_read(&path);
+        let output = crate::read::read_toc(&path, usize::try_from(level).unwrap_or(6))
+            .map_err(|e| e.to_string())?;
+        return Ok(apply_budget(output, budget));
+    }
     let full = args
         .get("full")
         .and_then(serde_json::Value::as_bool)
         .unwrap_or(false);
 
+    let strip_comments = args
+        .get("strip_comments")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+
+    let rev = args.get("rev").and_then(|v| v.as_str());
+
     session.record_read(&path);
-    let mut output = crate::read::read_file(&path, section, full, cache, edit_mode)
-        .map_err(|e| e.to_string())?;
+    let mut output = if let Some(rev) = rev {
+        crate::read::read_revision(
+            &path,
+            rev,
+            section,
+            full,
+            lang,
+            edit_mode,
+            budget,
+            dedup_variants,
+        )
+    } else if full && strip_comments && section.is_none() {
+        crate::read::strip::read_stripped(&path, lang, cache, edit_mode)
+    } else {
+        crate::read::read_file(
+            &path,
+            section,
+            full,
+            lang,
+            cache,
+            edit_mode,
+            budget,
+            crate::read::generated::Overrides::for_file(&path).as_ref(),
+            dedup_variants,
+        )
+    }
+    .map_err(|e| e.to_string())?;
 
-    // Append related-file hint for outlined code files (not section reads, not batch).
-    if section.is_none() && crate::read::would_outline(&path) {
+    // Append related-file hint for outlined code files (not section reads, not
+    // batch, not past revisions).
+    if section.is_none() && rev.is_none() && crate::read::would_outline(&path) {
         let related = crate::read::imports::resolve_related_files(&path);
         if !related.is_empty() {
             output.push_str("\n\n> Related: ");
@@ -274,11 +471,18 @@ fn tool_read(
     Ok(apply_budget(output, budget))
 }
 
-fn tool_search(args: &Value, cache: &OutlineCache, session: &Session) -> Result<String, String> {
+fn tool_search(
+    args: &Value,
+    cache: &OutlineCache,
+    session: &Session,
+    opts: ServerOptions,
+    progress: Option<&Progress>,
+) -> Result<String, String> {
     let query = args
         .get("query")
         .and_then(|v| v.as_str())
         .ok_or("missing required parameter: query")?;
+    crate::classify::check_query(query).map_err(|e| e.to_string())?;
     let scope = resolve_scope(args)?;
     let kind = args
         .get("kind")
@@ -293,7 +497,115 @@ fn tool_search(args: &Value, cache: &OutlineCache, session: &Session) -> Result<
         .and_then(|v| v.as_str())
         .map(PathBuf::from);
     let context = context_path.as_deref();
+    let calls = match args.get("calls").and_then(|v| v.as_str()) {
+        Some(s) => crate::search::CallsFooter::parse(s)
+            .ok_or_else(|| format!("invalid calls '{s}': expected \"full\" or \"compact\""))?,
+        None => crate::search::CallsFooter::default(),
+    };
+    let related = args
+        .get("related")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
     let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let filter = resolve_filter(args, opts, progress)?;
+    let count = args
+        .get("count")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+
+    if count {
+        session.record_search(query);
+        let output = match kind {
+            "symbol" => crate::search::search_count(query, &scope, &filter, false),
+            "content" => crate::search::search_count(query, &scope, &filter, true),
+            "regex" => crate::search::search_count(&format!("/{query}/"), &scope, &filter, true),
+            _ => return Err(format!("count is not supported for kind: {kind}")),
+        }
+        .map_err(|e| e.to_string())?;
+        return Ok(apply_budget(output, budget));
+    }
+
+    let context_lines = args
+        .get("context_lines")
+        .and_then(serde_json::Value::as_u64)
+        .map(|n| u32::try_from(n).unwrap_or(u32::MAX));
+    let region = match args.get("scope_to").and_then(|v| v.as_str()) {
+        Some(s) => Some(crate::search::content::Region::parse(s).ok_or_else(|| {
+            format!(
+                "invalid scope_to '{s}': expected one of {}",
+                crate::search::content::Region::NAMES.join(", ")
+            )
+        })?),
+        None => None,
+    };
+    let depth = args
+        .get("depth")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(1, |n| u32::try_from(n).unwrap_or(u32::MAX));
+    if depth > 1 && kind != "callers" {
+        return Err(format!("depth applies to callers search, not kind: {kind}"));
+    }
+    if region.is_some() && !matches!(kind, "content" | "regex") {
+        return Err(format!(
+            "scope_to applies to content and regex search, not kind: {kind}"
+        ));
+    }
+    match args.get("output").and_then(|v| v.as_str()) {
+        None | Some("text") => {}
+        Some("json") => {
+            let queries: Vec<&str> = if kind == "symbol" {
+                query
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            } else {
+                vec![query]
+            };
+            let json_opts = crate::search::JsonOptions {
+                expand,
+                context_lines,
+                region,
+            };
+            let mut results = Vec::with_capacity(queries.len());
+            for q in &queries {
+                session.record_search(q);
+                results.push(
+                    crate::search::search_json(q, kind, &scope, &filter, context, json_opts)
+                        .map_err(|e| e.to_string())?,
+                );
+            }
+            let json = if let [one] = results.as_slice() {
+                serde_json::to_value(one)
+            } else {
+                serde_json::to_value(&results)
+            }
+            .map_err(|e| e.to_string())?;
+            let json = match budget {
+                Some(b) => crate::budget::apply_json(json, b),
+                None => json,
+            };
+            return Ok(json.to_string());
+        }
+        Some(other) => return Err(format!("invalid output '{other}': expected text or json")),
+    }
+
+    if let Some(lines) = context_lines
+        && matches!(kind, "content" | "regex")
+    {
+        session.record_search(query);
+        let output = crate::search::search_content_context(
+            query,
+            &scope,
+            &filter,
+            context,
+            kind == "regex",
+            region,
+            lines,
+        )
+        .map_err(|e| e.to_string())?;
+        return Ok(apply_budget(output, budget));
+    }
 
     let output = match kind {
         "symbol" => {
@@ -303,11 +615,12 @@ fn tool_search(args: &Value, cache: &OutlineCache, session: &Session) -> Result<
                 .filter(|s| !s.is_empty())
                 .collect();
             match queries.len() {
-                0 => return Err("missing required parameter: query".into()),
+                0 => return Err(crate::classify::empty_query(query).to_string()),
                 1 => {
                     session.record_search(queries[0]);
                     crate::search::search_symbol_expanded(
-                        queries[0], &scope, cache, session, expand, context,
+                        queries[0], &scope, &filter, cache, session, expand, context, calls,
+                        related,
                     )
                 }
                 2..=5 => {
@@ -315,7 +628,7 @@ fn tool_search(args: &Value, cache: &OutlineCache, session: &Session) -> Result<
                         session.record_search(q);
                     }
                     crate::search::search_multi_symbol_expanded(
-                        &queries, &scope, cache, session, expand, context,
+                        &queries, &scope, &filter, cache, session, expand, context, calls,
                     )
                 }
                 _ => {
@@ -326,25 +639,49 @@ fn tool_search(args: &Value, cache: &OutlineCache, session: &Session) -> Result<
                 }
             }
         }
+        "content" | "regex" if let Some(region) = region => {
+            session.record_search(query);
+            // `/.../` marks a regex for the content searches
+            let pattern = if kind == "regex" {
+                format!("/{query}/")
+            } else {
+                query.to_string()
+            };
+            crate::search::search_region_expanded(
+                &pattern, &scope, &filter, cache, session, expand, context, region,
+            )
+        }
         "content" => {
             session.record_search(query);
-            crate::search::search_content_expanded(query, &scope, cache, session, expand, context)
+            crate::search::search_content_expanded(
+                query, &scope, &filter, cache, session, expand, context,
+            )
+        }
+        "comments" => {
+            session.record_search(query);
+            crate::search::search_comments_expanded(
+                query, &scope, &filter, cache, session, expand, context,
+            )
         }
         "regex" => {
             session.record_search(query);
-            let result = crate::search::content::search(query, &scope, true, context)
+            let result = crate::search::content::search(query, &scope, &filter, true, context)
                 .map_err(|e| e.to_string())?;
-            crate::search::format_content_result(&result, cache)
+            crate::search::format_content_result(&result, cache, filter.absolute_paths)
         }
         "callers" => {
             session.record_search(query);
             crate::search::callers::search_callers_expanded(
-                query, &scope, cache, session, expand, context,
+                query, &scope, &filter, cache, session, expand, context, depth,
             )
         }
+        "implementations" => {
+            session.record_search(query);
+            crate::search::implementations::search_implementations(query, &scope, &filter)
+        }
         _ => {
             return Err(format!(
-                "unknown search kind: {kind}. Use: symbol, content, regex, callers"
+                "unknown search kind: {kind}. Use: symbol, content, regex, comments, callers, implementations"
             ));
         }
     }
@@ -353,20 +690,73 @@ fn tool_search(args: &Value, cache: &OutlineCache, session: &Session) -> Result<
     Ok(apply_budget(output, budget))
 }
 
-fn tool_files(args: &Value, cache: &OutlineCache) -> Result<String, String> {
+fn tool_files(
+    args: &Value,
+    cache: &OutlineCache,
+    opts: ServerOptions,
+    progress: Option<&Progress>,
+) -> Result<String, String> {
     let pattern = args
         .get("pattern")
         .and_then(|v| v.as_str())
         .ok_or("missing required parameter: pattern")?;
     let scope = resolve_scope(args)?;
     let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let filter = resolve_filter(args, opts, progress)?;
+    let group_dirs = args
+        .get("group_dirs")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+
+    let output = crate::search::search_glob(pattern, &scope, &filter, group_dirs, cache)
+        .map_err(|e| e.to_string())?;
+
+    Ok(apply_budget(output, budget))
+}
+
+fn tool_todos(
+    args: &Value,
+    opts: ServerOptions,
+    progress: Option<&Progress>,
+) -> Result<String, String> {
+    let scope = resolve_scope(args)?;
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let filter = resolve_filter(args, opts, progress)?;
+    let markers: Vec<&str> = args
+        .get("markers")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|m| m.as_str()).collect())
+        .unwrap_or_default();
 
-    let output = crate::search::search_glob(pattern, &scope, cache).map_err(|e| e.to_string())?;
+    let output =
+        crate::search::search_todos(&scope, &filter, &markers).map_err(|e| e.to_string())?;
+
+    Ok(apply_budget(output, budget))
+}
+
+fn tool_impact(
+    args: &Value,
+    opts: ServerOptions,
+    progress: Option<&Progress>,
+) -> Result<String, String> {
+    let symbol = args
+        .get("symbol")
+        .and_then(|v| v.as_str())
+        .ok_or("missing required parameter: symbol")?;
+    let scope = resolve_scope(args)?;
+    let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let filter = resolve_filter(args, opts, progress)?;
+    let depth = match args.get("depth").and_then(serde_json::Value::as_u64) {
+        Some(d) => u32::try_from(d).map_err(|_| format!("depth {d} is out of range"))?,
+        None => crate::search::impact::DEFAULT_DEPTH,
+    };
+
+    let output =
+        crate::search::search_impact(symbol, &scope, &filter, depth).map_err(|e| e.to_string())?;
 
     Ok(apply_budget(output, budget))
 }
 
-#[expect(dead_code)] // Map disabled in v0.3.2 — kept for potential re-enable
 fn tool_map(args: &Value, cache: &OutlineCache, session: &Session) -> Result<String, String> {
     let scope = resolve_scope(args)?;
     let depth = args
@@ -374,12 +764,17 @@ fn tool_map(args: &Value, cache: &OutlineCache, session: &Session) -> Result<Str
         .and_then(serde_json::Value::as_u64)
         .unwrap_or(3) as usize;
     let budget = args.get("budget").and_then(serde_json::Value::as_u64);
+    let format = match args.get("format").and_then(|v| v.as_str()) {
+        Some(f) => crate::map::MapFormat::parse(f)
+            .ok_or_else(|| format!("invalid format '{f}': expected text, mermaid or dot"))?,
+        None => crate::map::MapFormat::Text,
+    };
 
     session.record_map();
-    Ok(crate::map::generate(&scope, depth, budget, cache))
+    Ok(crate::map::generate(&scope, depth, budget, cache, format))
 }
 
-fn tool_session(args: &Value, session: &Session) -> Result<String, String> {
+fn tool_session(args: &Value, session: &Session, cache: &OutlineCache) -> Result<String, String> {
     let action = args
         .get("action")
         .and_then(|v| v.as_str())
@@ -389,22 +784,180 @@ fn tool_session(args: &Value, session: &Session) -> Result<String, String> {
             session.reset();
             Ok("Session reset.".to_string())
         }
-        _ => Ok(session.summary()),
+        _ => Ok(format!("{}\n{}", session.summary(), cache.summary())),
     }
 }
 
 fn tool_edit(args: &Value, session: &Session) -> Result<String, String> {
+    let flag = |name: &str| {
+        args.get(name)
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    };
+    let opts = crate::edit::EditOptions {
+        reanchor: flag("reanchor"),
+        dry_run: flag("dry_run"),
+    };
+    let json = args.get("format").and_then(|v| v.as_str()) == Some("json");
+
+    // Multi-file batch: all anchors verified before any file is written
+    if let Some(files) = args.get("files").and_then(|v| v.as_array()) {
+        let mut groups = Vec::with_capacity(files.len());
+        for (f, file) in files.iter().enumerate() {
+            let path_str = file
+                .get("path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("files[{f}]: missing 'path'"))?;
+            let edits_val = file
+                .get("edits")
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| format!("files[{f}]: missing 'edits'"))?;
+            let edits = parse_edits(edits_val).map_err(|e| format!("files[{f}].{e}"))?;
+            groups.push((PathBuf::from(path_str), edits));
+        }
+        for (path, _) in &groups {
+            session.record_read(path);
+        }
+
+        let results = crate::edit::apply_edits_atomic(&groups, opts).map_err(|e| e.to_string())?;
+        let mismatched = results
+            .iter()
+            .any(|(_, r)| matches!(r, crate::edit::EditResult::HashMismatch { .. }));
+        let applied = !mismatched && !opts.dry_run;
+
+        if json {
+            let files: Vec<Value> = results
+                .iter()
+                .map(|(path, r)| {
+                    let mut v = r.to_json();
+                    v["path"] = Value::String(path.display().to_string());
+                    v
+                })
+                .collect();
+            return Ok(serde_json::json!({ "applied": applied, "files": files }).to_string());
+        }
+
+        let sections: Vec<String> = results
+            .into_iter()
+            .map(|(path, r)| match r {
+                crate::edit::EditResult::Applied { context, .. }
+                | crate::edit::EditResult::HashMismatch {
+                    message: context, ..
+                } => format!("# {}\n{context}", path.display()),
+                crate::edit::EditResult::DryRun { diff, .. } => diff,
+            })
+            .collect();
+        return if !mismatched && opts.dry_run {
+            Ok(sections.concat())
+        } else if applied {
+            Ok(sections.join("\n\n"))
+        } else {
+            Err(format!(
+                "hash mismatch — files changed since last read; nothing was written:\n\n{}",
+                sections.join("\n\n")
+            ))
+        };
+    }
+
     let path_str = args
         .get("path")
         .and_then(|v| v.as_str())
-        .ok_or("missing required parameter: path")?;
+        .ok_or("missing required parameter: path (or use files for a multi-file batch)")?;
     let path = PathBuf::from(path_str);
 
+    // Regex replace across the file instead of anchored edits
+    if let Some(find) = args.get("find").and_then(|v| v.as_str()) {
+        let replace = args
+            .get("replace")
+            .and_then(|v| v.as_str())
+            .ok_or("find requires 'replace' (use \"\" to delete matches)")?;
+        let max = match args.get("count").and_then(serde_json::Value::as_u64) {
+            Some(n) => Some(usize::try_from(n).map_err(|_| format!("count {n} is out of range"))?),
+            None => None,
+        };
+        let rep = crate::edit::RegexReplace {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            lines: args.get("lines").and_then(|v| v.as_str()).map(String::from),
+            max,
+        };
+        session.record_read(&path);
+        let (result, count) =
+            crate::edit::replace_regex(&path, &rep, opts).map_err(|e| e.to_string())?;
+        if json {
+            let mut v = result.to_json();
+            v["replacements"] = count.into();
+            return Ok(v.to_string());
+        }
+        return match result {
+            crate::edit::EditResult::Applied { context, .. } => Ok(format!(
+                "Replaced {count} match{}.\n\n{context}",
+                if count == 1 { "" } else { "es" }
+            )),
+            crate::edit::EditResult::DryRun { diff, .. } => Ok(diff),
+            crate::edit::EditResult::HashMismatch { message, .. } => Err(format!(
+                "hash mismatch — file changed during replace:\n\n{message}"
+            )),
+        };
+    }
+
     let edits_val = args
         .get("edits")
         .and_then(|v| v.as_array())
         .ok_or("missing required parameter: edits")?;
 
+    let edits = parse_edits(edits_val)?;
+
+    if let [edit] = edits.as_slice()
+        && edit.creates_file()
+    {
+        let result =
+            crate::edit::create_file(&path, &edit.content, opts).map_err(|e| e.to_string())?;
+        session.record_read(&path);
+        return Ok(if json {
+            result.to_json().to_string()
+        } else {
+            match result {
+                crate::edit::EditResult::Applied { context, .. } => context,
+                crate::edit::EditResult::DryRun { diff, .. } => diff,
+                crate::edit::EditResult::HashMismatch { message, .. } => message,
+            }
+        });
+    }
+
+    session.record_read(&path);
+
+    let result = crate::edit::apply_edits_with(&path, &edits, opts).map_err(|e| e.to_string())?;
+
+    // Structured result: a mismatch is data for the caller, not a tool error
+    if json {
+        return Ok(result.to_json().to_string());
+    }
+
+    match result {
+        crate::edit::EditResult::Applied { context, .. } => Ok(context),
+        crate::edit::EditResult::DryRun { diff, .. } => Ok(diff),
+        crate::edit::EditResult::HashMismatch { message, .. } => Err(format!(
+            "hash mismatch — file changed since last read:\n\n{message}"
+        )),
+    }
+}
+
+/// Parse a `glean_edit` edits array into anchored edits. A single unanchored
+/// edit (no start, or `"0:new"`) creates the file instead.
+fn parse_edits(edits_val: &[Value]) -> Result<Vec<crate::edit::Edit>, String> {
+    if let [edit] = edits_val
+        && edit
+            .get("start")
+            .and_then(|v| v.as_str())
+            .is_none_or(|s| s == "0:new")
+    {
+        let content = edit
+            .get("content")
+            .and_then(|v| v.as_str())
+            .ok_or("edit[0]: missing 'content'")?;
+        return Ok(vec![crate::edit::Edit::new_file(content)]);
+    }
     let mut edits = Vec::with_capacity(edits_val.len());
     for (i, e) in edits_val.iter().enumerate() {
         let start_str = e
@@ -414,6 +967,18 @@ fn tool_edit(args: &Value, session: &Session) -> Result<String, String> {
         let (start_line, start_hash) = crate::format::parse_anchor(start_str)
             .ok_or_else(|| format!("edit[{i}]: invalid start anchor '{start_str}'"))?;
 
+        let mode = match e.get("mode").and_then(|v| v.as_str()) {
+            None => crate::edit::EditMode::Replace,
+            Some(m) => crate::edit::EditMode::parse(m).ok_or_else(|| {
+                format!("edit[{i}]: invalid mode '{m}' (expected replace, before or after)")
+            })?,
+        };
+        if mode != crate::edit::EditMode::Replace && e.get("end").is_some() {
+            return Err(format!(
+                "edit[{i}]: 'end' only applies to mode 'replace'; inserts anchor on 'start'"
+            ));
+        }
+
         let (end_line, end_hash) = if let Some(end_str) = e.get("end").and_then(|v| v.as_str()) {
             crate::format::parse_anchor(end_str)
                 .ok_or_else(|| format!("edit[{i}]: invalid end anchor '{end_str}'"))?
@@ -432,17 +997,11 @@ fn tool_edit(args: &Value, session: &Session) -> Result<String, String> {
             end_line,
             end_hash,
             content: content.to_string(),
+            mode,
         });
     }
 
-    session.record_read(&path);
-
-    match crate::edit::apply_edits(&path, &edits).map_err(|e| e.to_string())? {
-        crate::edit::EditResult::Applied(output) => Ok(output),
-        crate::edit::EditResult::HashMismatch(msg) => Err(format!(
-            "hash mismatch — file changed since last read:\n\n{msg}"
-        )),
-    }
+    Ok(edits)
 }
 
 /// Canonicalize scope path, returning an error if the path doesn't exist.
@@ -462,9 +1021,86 @@ fn resolve_scope(args: &Value) -> Result<PathBuf, String> {
     })
 }
 
+/// Walk restrictions shared by `glean_search` and `glean_files`, and the
+/// definition mode (`opts.definitions` unless `definitions` is given).
+fn resolve_filter(
+    args: &Value,
+    opts: ServerOptions,
+    progress: Option<&Progress>,
+) -> Result<crate::search::SearchFilter, String> {
+    let definitions = match args.get("definitions").and_then(|v| v.as_str()) {
+        Some(s) => crate::search::treesitter::DefinitionMode::parse(s).ok_or_else(|| {
+            format!("invalid definitions '{s}': expected \"standard\", \"loose\" or \"strict\"")
+        })?,
+        None => opts.definitions,
+    };
+    let go_target = match args.get("go_target").and_then(|v| v.as_str()) {
+        Some(s) => Some(crate::search::gobuild::GoTarget::parse(s).ok_or_else(|| {
+            format!(
+                "invalid go_target '{s}': expected \"host\" or GOOS/GOARCH like \"linux/amd64\""
+            )
+        })?),
+        None => None,
+    };
+    Ok(crate::search::SearchFilter {
+        exclude_tests: args
+            .get("exclude_tests")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+        ignore_case: args
+            .get("ignore_case")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+        loose_case: args
+            .get("loose_case")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+        go_target,
+        lang: resolve_lang(args)?,
+        exclude: args
+            .get("exclude")
+            .and_then(|v| v.as_str())
+            .map(crate::search::ExcludeGlobs::parse)
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        respect_gitignore: args
+            .get("respect_gitignore")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+        languages: args
+            .get("languages")
+            .and_then(|v| v.as_str())
+            .map(crate::search::parse_languages)
+            .transpose()
+            .map_err(|e| e.to_string())?,
+        git_recency: args
+            .get("git_recency")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+        progress: progress.cloned(),
+        definitions,
+        dedup_variants: false,
+        absolute_paths: opts.absolute_paths,
+    })
+}
+
+/// The `lang` override, if given: forces the grammar instead of detecting it
+/// from the file name.
+fn resolve_lang(args: &Value) -> Result<Option<crate::types::Lang>, String> {
+    match args.get("lang").and_then(|v| v.as_str()) {
+        Some(s) => crate::types::Lang::parse(s).map(Some).ok_or_else(|| {
+            format!(
+                "invalid lang '{s}': expected one of {}",
+                crate::types::Lang::NAMES.join(", ")
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
 fn apply_budget(output: String, budget: Option<u64>) -> String {
     match budget {
-        Some(b) => crate::budget::apply(&output, b),
+        Some(b) => crate::budget::apply(&output, b).output,
         None => output,
     }
 }
@@ -477,23 +1113,25 @@ fn handle_tool_call(
     req: &JsonRpcRequest,
     cache: &OutlineCache,
     session: &Session,
-    edit_mode: bool,
+    opts: ServerOptions,
 ) -> JsonRpcResponse {
     let params = &req.params;
     let tool_name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
     let args = params.get("arguments").unwrap_or(&Value::Null);
+    let progress = params
+        .get("_meta")
+        .and_then(|m| m.get("progressToken"))
+        .cloned()
+        .map(|token| Progress::new(move |scanned| notify_progress(&token, scanned)));
 
-    let result = dispatch_tool(tool_name, args, cache, session, edit_mode);
+    let result = dispatch_tool(tool_name, args, cache, session, opts, progress.as_ref());
 
     match result {
         Ok(output) => JsonRpcResponse {
             jsonrpc: "2.0",
             id: req.id.clone(),
             result: Some(serde_json::json!({
-                "content": [{
-                    "type": "text",
-                    "text": output
-                }]
+                "content": text_blocks(&output)
             })),
             error: None,
         },
@@ -512,12 +1150,327 @@ fn handle_tool_call(
     }
 }
 
+/// `notifications/progress` for a walk that has scanned `scanned` files.
+fn progress_notification(token: &Value, scanned: usize) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": {
+            "progressToken": token,
+            "progress": scanned,
+            "message": format!("{scanned} files scanned")
+        }
+    })
+}
+
+/// Sent from the walker threads while the response is still being built; each
+/// line is written under the stdout lock so it can't interleave with another.
+fn notify_progress(token: &Value, scanned: usize) {
+    let mut stdout = io::stdout().lock();
+    let _ = serde_json::to_writer(&mut stdout, &progress_notification(token, scanned));
+    let _ = stdout.write_all(b"\n");
+    let _ = stdout.flush();
+}
+
+/// Responses above this size are split into multiple content blocks.
+const CHUNK_BYTES: usize = 16_000;
+
+/// Split large tool output into several text blocks at `\n\n##` section
+/// boundaries so each block stays coherent. Small output stays one block;
+/// a single oversized section is never cut mid-section.
+fn text_blocks(output: &str) -> Vec<Value> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    let mut last_boundary = 0;
+
+    for (pos, _) in output.match_indices("\n\n##") {
+        if pos - start > CHUNK_BYTES && last_boundary > start {
+            blocks.push(&output[start..last_boundary]);
+            start = last_boundary;
+        }
+        last_boundary = pos;
+    }
+    if output.len() - start > CHUNK_BYTES && last_boundary > start {
+        blocks.push(&output[start..last_boundary]);
+        start = last_boundary;
+    }
+    blocks.push(&output[start..]);
+
+    blocks
+        .into_iter()
+        .map(|text| {
+            serde_json::json!({
+                "type": "text",
+                "text": text.trim_start_matches('\n')
+            })
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Resources
+// ---------------------------------------------------------------------------
+
+/// Resources per `resources/list` page — hosts render each page whole, so
+/// keep it bounded and hand out a `nextCursor` for the rest.
+const MAX_RESOURCES: usize = 1000;
+
+/// Resources are the files under the server's working directory.
+fn resource_scope() -> Result<PathBuf, String> {
+    std::env::current_dir()
+        .and_then(|cwd| cwd.canonicalize())
+        .map_err(|e| format!("cannot resolve working directory: {e}"))
+}
+
+fn handle_resources_list(req: &JsonRpcRequest) -> JsonRpcResponse {
+    let scope = match resource_scope() {
+        Ok(scope) => scope,
+        Err(e) => return rpc_error(req, -32603, e),
+    };
+    // The cursor is the offset of the page, as handed out in `nextCursor`
+    let offset = match req.params.get("cursor").and_then(|v| v.as_str()) {
+        Some(cursor) => match cursor.parse() {
+            Ok(offset) => offset,
+            Err(_) => return rpc_error(req, -32602, format!("invalid cursor: {cursor}")),
+        },
+        None => 0,
+    };
+
+    let (resources, next) = list_resources(&scope, offset);
+    let mut result = serde_json::json!({ "resources": resources });
+    if let Some(next) = next {
+        result["nextCursor"] = Value::String(next.to_string());
+    }
+    JsonRpcResponse {
+        jsonrpc: "2.0",
+        id: req.id.clone(),
+        result: Some(result),
+        error: None,
+    }
+}
+
+fn handle_resources_read(
+    req: &JsonRpcRequest,
+    cache: &OutlineCache,
+    session: &Session,
+) -> JsonRpcResponse {
+    let Some(uri) = req.params.get("uri").and_then(|v| v.as_str()) else {
+        return rpc_error(req, -32602, "missing required parameter: uri".into());
+    };
+
+    match resource_scope().and_then(|scope| read_resource(uri, &scope, cache, session)) {
+        Ok(text) => JsonRpcResponse {
+            jsonrpc: "2.0",
+            id: req.id.clone(),
+            result: Some(serde_json::json!({
+                "contents": [{
+                    "uri": uri,
+                    "mimeType": "text/plain",
+                    "text": text
+                }]
+            })),
+            error: None,
+        },
+        Err(e) => rpc_error(req, -32002, e),
+    }
+}
+
+/// One page of the files under `scope` (skip dirs excluded) as MCP resources,
+/// sorted by path, starting at `offset`. Also returns the next page's offset.
+fn list_resources(scope: &Path, offset: usize) -> (Vec<Value>, Option<usize>) {
+    let mut paths = crate::search::walk_collect(
+        scope,
+        &crate::search::SearchFilter::default(),
+        None,
+        None,
+        |entry| vec![entry.path().to_path_buf()],
+    );
+    paths.sort();
+
+    let end = offset.saturating_add(MAX_RESOURCES).min(paths.len());
+    let next = (end < paths.len()).then_some(end);
+    let page = paths
+        .get(offset..end)
+        .unwrap_or_default()
+        .iter()
+        .map(|path| {
+            serde_json::json!({
+                "uri": format!("file://{}", path.display()),
+                "name": crate::format::rel(path, scope, false),
+                "mimeType": "text/plain"
+            })
+        })
+        .collect();
+    (page, next)
+}
+
+/// Serve a `file://` resource through the same smart view as `glean_read`.
+/// Only files `list_resources` could list are served: inside `scope` and
+/// outside `SKIP_DIRS`.
+fn read_resource(
+    uri: &str,
+    scope: &Path,
+    cache: &OutlineCache,
+    session: &Session,
+) -> Result<String, String> {
+    let path = uri
+        .strip_prefix("file://")
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("unsupported resource uri: {uri}"))?;
+
+    let canonical = path.canonicalize().map_err(|e| format!("{uri}: {e}"))?;
+    let listed = canonical.strip_prefix(scope).is_ok_and(|rel| {
+        !rel.components().any(|c| {
+            c.as_os_str()
+                .to_str()
+                .is_some_and(|name| crate::search::SKIP_DIRS.contains(&name))
+        })
+    });
+    if !listed {
+        return Err(format!(
+            "resource outside the workspace: {uri} (serving {})",
+            scope.display()
+        ));
+    }
+
+    session.record_read(&path);
+    crate::read::read_file(
+        &path,
+        None,
+        false,
+        None,
+        cache,
+        false,
+        None,
+        crate::read::generated::Overrides::for_file(&path).as_ref(),
+        false,
+    )
+    .map_err(|e| e.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Prompts
+// ---------------------------------------------------------------------------
+
+fn prompt_definitions() -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "name": "explain_file",
+            "description": "Explain what a file does, starting from its glean_read view.",
+            "arguments": [{
+                "name": "path",
+                "description": "File to explain",
+                "required": true
+            }]
+        }),
+        serde_json::json!({
+            "name": "trace_symbol",
+            "description": "Find a symbol's definition and trace its callers and callees.",
+            "arguments": [{
+                "name": "symbol",
+                "description": "Symbol name to trace",
+                "required": true
+            }]
+        }),
+    ]
+}
+
+fn handle_prompts_get(
+    req: &JsonRpcRequest,
+    cache: &OutlineCache,
+    session: &Session,
+) -> JsonRpcResponse {
+    let name = req
+        .params
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let args = req.params.get("arguments").unwrap_or(&Value::Null);
+
+    match render_prompt(name, args, cache, session) {
+        Ok((description, text)) => JsonRpcResponse {
+            jsonrpc: "2.0",
+            id: req.id.clone(),
+            result: Some(serde_json::json!({
+                "description": description,
+                "messages": [{
+                    "role": "user",
+                    "content": {
+                        "type": "text",
+                        "text": text
+                    }
+                }]
+            })),
+            error: None,
+        },
+        Err(e) => rpc_error(req, -32602, e),
+    }
+}
+
+/// Fill a built-in prompt template. Returns (description, message text).
+fn render_prompt(
+    name: &str,
+    args: &Value,
+    cache: &OutlineCache,
+    session: &Session,
+) -> Result<(String, String), String> {
+    let arg = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| format!("prompt {name} requires argument: {key}"))
+    };
+
+    match name {
+        "explain_file" => {
+            let path = arg("path")?;
+            let view = tool_read(
+                &serde_json::json!({ "path": path }),
+                cache,
+                session,
+                ServerOptions::default(),
+            )?;
+            Ok((
+                format!("Explain {path}"),
+                format!(
+                    "Explain what `{path}` does: its purpose, main types and functions, \
+                     and how it fits into the project. Use glean_read with `section` \
+                     to drill into anything the view below elides.\n\n{view}"
+                ),
+            ))
+        }
+        "trace_symbol" => {
+            let symbol = arg("symbol")?;
+            Ok((
+                format!("Trace {symbol}"),
+                format!(
+                    "Trace `{symbol}` through the codebase.\n\
+                     1. glean_search with query \"{symbol}\" and expand=1 to find its definition.\n\
+                     2. glean_search with query \"{symbol}\" and kind \"callers\" to find its call sites.\n\
+                     3. Follow the `── calls ──` footer of the expanded definition for its callees.\n\
+                     Summarize the call chain, citing file:line for each step."
+                ),
+            ))
+        }
+        _ => Err(format!("unknown prompt: {name}")),
+    }
+}
+
+fn rpc_error(req: &JsonRpcRequest, code: i32, message: String) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0",
+        id: req.id.clone(),
+        result: None,
+        error: Some(JsonRpcError { code, message }),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tool definitions
 // ---------------------------------------------------------------------------
 
-fn tool_definitions(edit_mode: bool) -> Vec<Value> {
-    let read_desc = if edit_mode {
+fn tool_definitions(opts: ServerOptions) -> Vec<Value> {
+    let read_desc = if opts.edit {
         "Read a file with smart outlining. Output uses hashline format (line:hash|content) — \
          the line:hash anchors are required by glean_edit. Small files return full hashlined content. \
          Large files return a structural outline (no hashlines); use `section` to get hashlined \
@@ -539,7 +1492,7 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                 "properties": {
                     "query": {
                         "type": "string",
-                        "description": "Symbol name, text string, or regex pattern to search for. For symbol search, comma-separated names for multi-symbol lookup."
+                        "description": "Symbol name, text string, or regex pattern to search for. For symbol search, comma-separated names for multi-symbol lookup, or a trailing * for every name with that prefix (parse_*)."
                     },
                     "scope": {
                         "type": "string",
@@ -547,9 +1500,9 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                     },
                     "kind": {
                         "type": "string",
-                        "enum": ["symbol", "content", "regex", "callers"],
+                        "enum": ["symbol", "content", "regex", "comments", "callers", "implementations"],
                         "default": "symbol",
-                        "description": "Search type. symbol: structural definitions + usages. content: literal text. regex: regex pattern. callers: find all call sites of a symbol."
+                        "description": "Search type. symbol: structural definitions + usages. content: literal text. regex: regex pattern. comments: literal text (or /regex/) only inside comments and docstrings — TODO, FIXME, @deprecated. callers: find all call sites of a symbol. implementations: a trait/interface/type and every impl or implementer of it across files, each body expanded."
                     },
                     "expand": {
                         "type": "number",
@@ -558,7 +1511,89 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                     },
                     "context": {
                         "type": "string",
-                        "description": "Path to the file the agent is currently editing. Boosts ranking of matches in the same directory or package."
+                        "description": "Path to the file the agent is currently editing. Boosts ranking of matches in files it imports or that import it, then the same directory or package."
+                    },
+                    "scope_to": {
+                        "type": "string",
+                        "enum": ["comments", "strings", "code"],
+                        "description": "For content and regex search: keep only matches inside comments, inside string literals, or in code outside both. Decided by parsing each file; files without a grammar keep every match (comments fall back to a // or # guess)."
+                    },
+                    "context_lines": {
+                        "type": "number",
+                        "description": "For content and regex search: show this many lines before and after each match (like rg -C, max 20) instead of outline context and expansion."
+                    },
+                    "depth": {
+                        "type": "number",
+                        "default": 1,
+                        "description": "For callers search: caller levels to follow. 2 also finds the callers of each caller, shown as an indented call tree (max 5, at most 50 call sites)."
+                    },
+                    "calls": {
+                        "type": "string",
+                        "enum": ["full", "compact"],
+                        "default": "full",
+                        "description": "Calls footer under expanded definitions. full: one line per resolved callee with location and signature. compact: names only — resolved with file:line, unresolved bare."
+                    },
+                    "output": {
+                        "type": "string",
+                        "enum": ["text", "json"],
+                        "default": "text",
+                        "description": "json (symbol, content, regex): {query, total_found, definitions, usages, matches: [{path, line, text, is_definition, def_range, def_name, source?}]} instead of markdown; source is inlined for the first expand definitions. Comma-separated symbols return an array. With budget, trailing matches are dropped to fit and {truncated, original_tokens, returned_tokens} are added."
+                    },
+                    "related": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Single-symbol search: append up to 5 neighbouring definitions of the top match — other methods of its impl/class, or other items in its file."
+                    },
+                    "exclude": {
+                        "type": "string",
+                        "description": "Comma-separated globs of paths to skip, matched against the path relative to scope or the file name, e.g. \"**/generated/**,*.pb.go,migrations\"."
+                    },
+                    "respect_gitignore": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Skip files ignored by .gitignore, .git/info/exclude and .ignore. Off by default so gitignored but relevant files (local configs, generated code) stay searchable; turn on to avoid walking large ignored build or data directories."
+                    },
+                    "languages": {
+                        "type": "string",
+                        "description": "Comma-separated languages to restrict the search to, e.g. \"rust,go\". Only files detected as those languages are walked. Unlike lang, this filters files rather than overriding detection."
+                    },
+                    "exclude_tests": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Skip test files and test directories entirely (not just rank them lower)."
+                    },
+                    "git_recency": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Rank recently committed files higher, using each file's last git commit time instead of its mtime. Useful on a fresh clone or checkout where every mtime is the same."
+                    },
+                    "go_target": {
+                        "type": "string",
+                        "description": "Skip Go files whose build tags or _GOOS/_GOARCH filename suffix exclude this platform. \"host\" for the current platform, or GOOS/GOARCH like \"linux/arm64\"."
+                    },
+                    "lang": {
+                        "type": "string",
+                        "description": "Parse files whose type isn't recognised (extensionless, unknown extensions) as this language, e.g. \"rust\", \"python\". Recognised files keep their own language."
+                    },
+                    "ignore_case": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Match symbol names regardless of case: HttpClient also finds HTTPClient and httpClient. Exact-case matches still rank first."
+                    },
+                    "loose_case": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Match symbols across naming conventions: get_user also finds getUser, GetUser and GET_USER. Exact-name matches still rank first."
+                    },
+                    "definitions": {
+                        "type": "string",
+                        "enum": ["standard", "loose", "strict"],
+                        "description": "What counts as a definition. standard: functions, types, modules and Rust/C constants. loose: also JS/TS const/let/var bindings. strict: no variable or constant declarations, except consts bound to functions."
+                    },
+                    "count": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Return only per-file match counts (definitions vs usages), highest first. Cheap overview of where a symbol is used. Not supported for callers."
                     },
                     "budget": {
                         "type": "number",
@@ -580,16 +1615,43 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                     "paths": {
                         "type": "array",
                         "items": { "type": "string" },
-                        "description": "Multiple file paths to read in one call. Each file gets independent smart handling. Saves round-trips vs multiple single reads."
+                        "description": "Multiple file paths to read in one call. Each file gets independent smart handling. Saves round-trips vs multiple single reads. With budget, each file gets a fair share of it."
                     },
                     "section": {
                         "type": "string",
-                        "description": "Line range e.g. '45-89', or heading e.g. '## Architecture'. Bypasses smart view."
+                        "description": "Line range e.g. '45-89', '-50' for the last 50 lines, '100-' from line 100 to the end, or heading e.g. '## Architecture'. Comma-separate several to read them in one call: '10-20,45-60,## API' — each slice gets its own header, and out-of-bounds ones are reported without failing the rest. Bypasses smart view."
+                    },
+                    "heading_level": {
+                        "type": "number",
+                        "description": "Markdown only: a table of contents of headings down to this level (1-6) with line ranges, whatever the file size. 2 shows # and ## — pick a section, then read it with section."
                     },
                     "full": {
                         "type": "boolean",
                         "default": false,
-                        "description": "Force full content output, bypass smart outlining."
+                        "description": "Force full content output, bypass smart outlining and generated-file detection."
+                    },
+                    "dedup_variants": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Collapse identical #[cfg]/#ifdef copies of a declaration in code outlines into one entry listing every location."
+                    },
+                    "strip_comments": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "With full: blank out comments and docstrings in code files. Line numbers are unchanged — comment-only lines become empty — so sections and edits still line up."
+                    },
+                    "lang": {
+                        "type": "string",
+                        "description": "Read the file as this language (e.g. \"rust\", \"python\") when its name doesn't say — extensionless scripts, misnamed files."
+                    },
+                    "rev": {
+                        "type": "string",
+                        "description": "Read the file as it was at this git revision, e.g. 'HEAD~1', 'main' or a commit hash — the previous version when reviewing a change. Same smart view and section handling; the file need not exist in the working tree."
+                    },
+                    "concat": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "When path is a directory, return the outlines of all its code files concatenated as one module overview instead of a file listing."
                     },
                     "budget": {
                         "type": "number",
@@ -600,7 +1662,7 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
         }),
         serde_json::json!({
             "name": "glean_files",
-            "description": "Find files matching a glob pattern. Returns matched file paths with token estimates. Respects .gitignore.",
+            "description": "Find files matching a glob pattern. Returns matched file paths with token estimates. Searches gitignored files unless respect_gitignore is set.",
             "inputSchema": {
                 "type": "object",
                 "required": ["pattern"],
@@ -609,10 +1671,96 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                         "type": "string",
                         "description": "Glob pattern e.g. '*.rs', 'src/**/*.ts', '*.test.*'"
                     },
+                    "group_dirs": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Return each top-level directory with its matching-file count and summed token estimate, largest first, instead of a file list. Re-run with that directory as scope to drill down."
+                    },
                     "scope": {
                         "type": "string",
                         "description": "Directory to search within. Default: current directory."
                     },
+                    "exclude": {
+                        "type": "string",
+                        "description": "Comma-separated globs of paths to skip, matched against the path relative to scope or the file name, e.g. \"**/generated/**,*.pb.go,migrations\"."
+                    },
+                    "respect_gitignore": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Skip files ignored by .gitignore, .git/info/exclude and .ignore. Off by default so gitignored but relevant files (local configs, generated code) stay searchable; turn on to avoid walking large ignored build or data directories."
+                    },
+                    "languages": {
+                        "type": "string",
+                        "description": "Comma-separated languages to restrict the search to, e.g. \"rust,go\". Only files detected as those languages are walked."
+                    },
+                    "exclude_tests": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Skip test files and test directories entirely (not just rank them lower)."
+                    },
+                    "go_target": {
+                        "type": "string",
+                        "description": "Skip Go files whose build tags or _GOOS/_GOARCH filename suffix exclude this platform. \"host\" for the current platform, or GOOS/GOARCH like \"linux/arm64\"."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "glean_todos",
+            "description": "List annotation markers (TODO, FIXME, HACK, XXX) found in comments and docstrings, grouped by file. Each hit shows its line and the enclosing function or type. String literals are ignored.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory to search within. Default: current directory."
+                    },
+                    "markers": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Markers to look for, replacing the defaults (TODO, FIXME, HACK, XXX). e.g. [\"TODO\", \"@deprecated\"]"
+                    },
+                    "exclude_tests": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Skip test files and test directories entirely."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response."
+                    }
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "glean_impact",
+            "description": "Blast radius of changing a function: its definition plus every direct and transitive caller (callers of callers...), grouped by package and file, with the count of affected files. Callers are matched structurally by name.",
+            "inputSchema": {
+                "type": "object",
+                "required": ["symbol"],
+                "properties": {
+                    "symbol": {
+                        "type": "string",
+                        "description": "Function or method name, e.g. \"handleRequest\"."
+                    },
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory to search within. Default: current directory."
+                    },
+                    "depth": {
+                        "type": "number",
+                        "default": 3,
+                        "description": "Caller levels to follow (1 = direct callers only, max 6)."
+                    },
+                    "exclude_tests": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Skip test files and test directories entirely."
+                    },
                     "budget": {
                         "type": "number",
                         "description": "Max tokens in response."
@@ -620,21 +1768,71 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                 }
             }
         }),
-        // glean_map disabled — benchmark data shows 62% of losing tasks use map
-        // vs 22% of winners. Re-enable after measuring impact.
-        // serde_json::json!({
-        //     "name": "glean_map",
-        //     ...
-        // }),
     ];
 
-    if edit_mode {
+    // Opt-in only: benchmark data showed 62% of losing tasks used map vs 22%
+    // of winners.
+    if opts.map {
+        tools.push(serde_json::json!({
+            "name": "glean_map",
+            "description": "Structural overview of a codebase: directory tree with each file's top-level symbols and token estimates. Use once to orient in an unfamiliar repo, then switch to glean_search.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scope": {
+                        "type": "string",
+                        "description": "Directory to map. Default: current directory."
+                    },
+                    "depth": {
+                        "type": "number",
+                        "default": 3,
+                        "description": "Directory levels to descend."
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["text", "mermaid", "dot"],
+                        "default": "text",
+                        "description": "mermaid/dot: a diagram of directories and files with import edges, for rendering or pasting into docs."
+                    },
+                    "budget": {
+                        "type": "number",
+                        "description": "Max tokens in response."
+                    }
+                }
+            }
+        }));
+    }
+
+    if opts.edit {
+        let edit_item = serde_json::json!({
+            "type": "object",
+            "required": ["content"],
+            "properties": {
+                "start": {
+                    "type": "string",
+                    "description": "Start anchor: 'line:hash' (e.g. '42:a3f'). Hash from glean_read hashline output. '0:new' (or omitting start on a lone edit) creates path with content as its body."
+                },
+                "end": {
+                    "type": "string",
+                    "description": "End anchor: 'line:hash'. If omitted, replaces only the start line."
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Replacement text (can be multi-line). Empty string to delete the line(s)."
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["replace", "before", "after"],
+                    "default": "replace",
+                    "description": "replace: swap the start..end lines for content. before/after: insert content above/below the start line, which is kept."
+                }
+            }
+        });
         tools.push(serde_json::json!({
             "name": "glean_edit",
-            "description": "Apply edits to a file using hashline anchors from glean_read. Each edit targets a line range by line:hash anchors. Edits are verified against content hashes and rejected if the file has changed since the last read.",
+            "description": "Apply edits to a file using hashline anchors from glean_read. Each edit targets a line range by line:hash anchors. Edits are verified against content hashes and rejected if the file has changed since the last read. Use files to edit several files atomically (e.g. a cross-file rename).",
             "inputSchema": {
                 "type": "object",
-                "required": ["path", "edits"],
                 "properties": {
                     "path": {
                         "type": "string",
@@ -643,24 +1841,51 @@ fn tool_definitions(edit_mode: bool) -> Vec<Value> {
                     "edits": {
                         "type": "array",
                         "description": "Array of edit operations, applied atomically.",
+                        "items": edit_item.clone()
+                    },
+                    "files": {
+                        "type": "array",
+                        "description": "Edit several files in one batch instead of path + edits. Every file's anchors are checked first; if any is stale, no file is written. A file whose only edit is '0:new' is created.",
                         "items": {
                             "type": "object",
-                            "required": ["start", "content"],
+                            "required": ["path", "edits"],
                             "properties": {
-                                "start": {
-                                    "type": "string",
-                                    "description": "Start anchor: 'line:hash' (e.g. '42:a3f'). Hash from glean_read hashline output."
-                                },
-                                "end": {
-                                    "type": "string",
-                                    "description": "End anchor: 'line:hash'. If omitted, replaces only the start line."
-                                },
-                                "content": {
-                                    "type": "string",
-                                    "description": "Replacement text (can be multi-line). Empty string to delete the line(s)."
-                                }
+                                "path": { "type": "string" },
+                                "edits": { "type": "array", "items": edit_item }
                             }
                         }
+                    },
+                    "find": {
+                        "type": "string",
+                        "description": "Regex (optionally /slashed/) to replace on every line of path, instead of edits. Use for renaming an identifier throughout a file. Fails if nothing matches."
+                    },
+                    "replace": {
+                        "type": "string",
+                        "description": "Replacement for find; $1 or ${name} insert capture groups."
+                    },
+                    "count": {
+                        "type": "integer",
+                        "description": "With find: the most replacements expected. More matches than this is an error and nothing is written."
+                    },
+                    "lines": {
+                        "type": "string",
+                        "description": "With find: only replace within this line range, e.g. '10-40', '-20', '100-'."
+                    },
+                    "reanchor": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Apply an edit whose lines moved (same hashes, unique within 20 lines) at their new position instead of rejecting it. Without this, the mismatch report names the new anchors."
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Verify the edits and return a unified diff of what they would change, without writing."
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["text", "json"],
+                        "default": "text",
+                        "description": "json: return {applied, edits_applied, new_line_count} on success or {applied: false, reason: \"hash_mismatch\", stale_anchors} naming the anchors to re-read. With files: {applied, files: [{path, ...}]}."
                     }
                 }
             }
@@ -684,3 +1909,351 @@ fn write_error(w: &mut impl Write, id: Option<Value>, code: i32, msg: &str) -> i
     w.write_all(b"\n")?;
     w.flush()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    fn request(method: &str, params: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            _jsonrpc: "2.0".into(),
+            id: Some(Value::from(1)),
+            method: method.into(),
+            params,
+        }
+    }
+
+    #[test]
+    fn initialize_advertises_resources_and_prompts() {
+        let resp = handle_request(
+            &request("initialize", Value::Null),
+            &OutlineCache::new(),
+            &Session::new(),
+            ServerOptions::default(),
+        );
+        let caps = &resp.result.unwrap()["capabilities"];
+        assert!(caps.get("resources").is_some(), "caps: {caps}");
+        assert!(caps.get("prompts").is_some(), "caps: {caps}");
+    }
+
+    #[test]
+    fn workspace_root_uri_is_percent_decoded() {
+        assert_eq!(
+            file_uri_path("file:///home/dev/my%20repo/caf%C3%A9"),
+            PathBuf::from("/home/dev/my repo/café")
+        );
+        assert_eq!(
+            file_uri_path("file:///tmp/100%"),
+            PathBuf::from("/tmp/100%")
+        );
+        assert_eq!(file_uri_path("file:///tmp/%FF"), PathBuf::from("/tmp/%FF"));
+    }
+
+    #[test]
+    fn prompts_list_and_get_fill_arguments() {
+        let cache = OutlineCache::new();
+        let session = Session::new();
+
+        let resp = handle_request(
+            &request("prompts/list", Value::Null),
+            &cache,
+            &session,
+            ServerOptions::default(),
+        );
+        let prompts = resp.result.unwrap()["prompts"].clone();
+        let names: Vec<_> = prompts
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, ["explain_file", "trace_symbol"]);
+
+        let resp = handle_request(
+            &request(
+                "prompts/get",
+                serde_json::json!({ "name": "trace_symbol", "arguments": { "symbol": "HandleRequest" } }),
+            ),
+            &cache,
+            &session,
+            ServerOptions::default(),
+        );
+        let text = resp.result.unwrap()["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(text.contains("query \"HandleRequest\""), "text: {text}");
+        assert!(!text.contains("{symbol}"), "text: {text}");
+
+        let path = fixture("mini-go/router.go");
+        let resp = handle_request(
+            &request(
+                "prompts/get",
+                serde_json::json!({ "name": "explain_file", "arguments": { "path": path } }),
+            ),
+            &cache,
+            &session,
+            ServerOptions::default(),
+        );
+        let text = resp.result.unwrap()["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(
+            text.contains(&format!("`{}`", path.display())),
+            "text: {text}"
+        );
+        assert!(text.contains("func "), "read view embedded: {text}");
+
+        let resp = handle_request(
+            &request("prompts/get", serde_json::json!({ "name": "explain_file" })),
+            &cache,
+            &session,
+            ServerOptions::default(),
+        );
+        assert!(resp.error.is_some(), "missing argument is an error");
+    }
+
+    #[test]
+    fn small_output_is_one_block() {
+        let blocks = text_blocks("# Search: \"x\"\n\n## a.rs:1 [usage]\n→ [1]   x");
+        assert_eq!(blocks.len(), 1);
+    }
+
+    #[test]
+    fn large_search_output_splits_at_sections() {
+        let mut output = String::from("# Search: \"handler\" in src — 40 matches");
+        for i in 0..40 {
+            let _ = write!(
+                output,
+                "\n\n## src/file{i}.rs:{i} [usage]\n→ [{i}]   {}",
+                "handler(); ".repeat(100)
+            );
+        }
+        assert!(output.len() > CHUNK_BYTES * 2);
+
+        let blocks = text_blocks(&output);
+        assert!(blocks.len() > 1, "large output should be chunked");
+
+        let texts: Vec<&str> = blocks.iter().map(|b| b["text"].as_str().unwrap()).collect();
+        assert!(texts[0].starts_with("# Search:"));
+        for text in &texts[1..] {
+            assert!(
+                text.starts_with("## src/file"),
+                "block starts mid-section: {text:.40}"
+            );
+        }
+        for text in &texts {
+            assert!(
+                text.len() <= CHUNK_BYTES + 2000,
+                "block too large: {}",
+                text.len()
+            );
+        }
+
+        // Nothing lost: every section survives exactly once
+        let rejoined = texts.join("\n\n");
+        assert_eq!(rejoined, output);
+    }
+
+    #[test]
+    fn resources_list_then_read_fixture_file() {
+        let scope = fixture("mini-go").canonicalize().unwrap();
+        let (resources, next) = list_resources(&scope, 0);
+        assert_eq!(next, None);
+        let router = resources
+            .iter()
+            .find(|r| r["name"] == "router.go")
+            .expect("router.go listed as a resource");
+        let uri = router["uri"].as_str().unwrap();
+        assert!(uri.starts_with("file://"), "uri: {uri}");
+
+        let resp = handle_request(
+            &request("resources/read", serde_json::json!({ "uri": uri })),
+            &OutlineCache::new(),
+            &Session::new(),
+            ServerOptions::default(),
+        );
+        let contents = &resp.result.expect("read succeeds")["contents"][0];
+        assert_eq!(contents["uri"], uri);
+        assert!(
+            contents["text"].as_str().unwrap().contains("router.go"),
+            "text: {}",
+            contents["text"]
+        );
+    }
+
+    #[test]
+    fn resources_page_and_stay_in_scope() {
+        let scope = fixture("").canonicalize().unwrap();
+        let (all, _) = list_resources(&scope, 0);
+        let (rest, next) = list_resources(&scope, 3);
+        assert_eq!(rest[0], all[3]);
+        assert_eq!(next, None);
+        assert!(list_resources(&scope, usize::MAX).0.is_empty());
+
+        let mini = fixture("mini-go").canonicalize().unwrap();
+        let cache = OutlineCache::new();
+        let session = Session::new();
+        let outside = format!("file://{}", scope.join("mini-rust").display());
+        let err = read_resource(&outside, &mini, &cache, &session).unwrap_err();
+        assert!(err.contains("outside the workspace"), "{err}");
+        let inside = format!("file://{}", mini.join("router.go").display());
+        assert!(read_resource(&inside, &mini, &cache, &session).is_ok());
+
+        let resp = handle_request(
+            &request("resources/list", serde_json::json!({ "cursor": "next" })),
+            &cache,
+            &session,
+            ServerOptions::default(),
+        );
+        assert_eq!(resp.error.unwrap().code, -32602);
+    }
+
+    #[test]
+    fn map_tool_only_when_opted_in() {
+        let names = |opts| {
+            tool_definitions(opts)
+                .iter()
+                .map(|t| t["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert!(!names(ServerOptions::default()).contains(&"glean_map".to_string()));
+        let map = ServerOptions {
+            map: true,
+            ..ServerOptions::default()
+        };
+        assert!(names(map).contains(&"glean_map".to_string()));
+
+        let args = serde_json::json!({ "scope": fixture("mini-go") });
+        let cache = OutlineCache::new();
+        let session = Session::new();
+        let err = dispatch_tool(
+            "glean_map",
+            &args,
+            &cache,
+            &session,
+            ServerOptions::default(),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("--map"), "{err}");
+        let out = dispatch_tool("glean_map", &args, &cache, &session, map, None).unwrap();
+        assert!(out.contains(".go"), "{out}");
+    }
+
+    #[test]
+    fn progress_notification_carries_token_and_count() {
+        let note = progress_notification(&serde_json::json!("search-1"), 1000);
+        assert_eq!(note["method"], "notifications/progress");
+        assert_eq!(note["params"]["progressToken"], "search-1");
+        assert_eq!(note["params"]["progress"], 1000);
+        // Notifications carry no id
+        assert!(note.get("id").is_none());
+    }
+
+    #[test]
+    fn empty_blank_and_comma_only_queries_rejected() {
+        let scope = fixture("mini-go");
+        for (query, kind) in [("", "symbol"), ("   ", "content"), (" , ,", "symbol")] {
+            let args = serde_json::json!({ "query": query, "kind": kind, "scope": scope });
+            let err = dispatch_tool(
+                "glean_search",
+                &args,
+                &OutlineCache::new(),
+                &Session::new(),
+                ServerOptions::default(),
+                None,
+            )
+            .unwrap_err();
+            assert!(err.contains("nothing to search for"), "{query:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn content_search_with_context_lines() {
+        let scope = fixture("mini-go");
+        let args = serde_json::json!({
+            "query": "c.index++",
+            "kind": "content",
+            "scope": scope,
+            "context_lines": 1,
+        });
+        let out = dispatch_tool(
+            "glean_search",
+            &args,
+            &OutlineCache::new(),
+            &Session::new(),
+            ServerOptions::default(),
+            None,
+        )
+        .unwrap();
+        // Both hits share one block: their windows touch
+        assert!(
+            out.contains("## context.go:18\n```context.go:17-22\n"),
+            "{out}"
+        );
+        assert!(out.contains("→  18 │ \tc.index++"), "{out}");
+        assert!(out.contains("   19 │ \tfor c.index"), "{out}");
+        assert!(out.contains("→  21 │ \t\tc.index++"), "{out}");
+        assert!(!out.contains("   23 │"), "{out}");
+    }
+
+    #[test]
+    fn unknown_language_filter_lists_valid_names() {
+        let scope = fixture("mini-go");
+        let args =
+            serde_json::json!({ "pattern": "*.go", "scope": scope, "languages": "go,golfscript" });
+        let err = dispatch_tool(
+            "glean_files",
+            &args,
+            &OutlineCache::new(),
+            &Session::new(),
+            ServerOptions::default(),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("golfscript"), "{err}");
+        assert!(err.contains("rust, typescript, tsx"), "{err}");
+    }
+
+    #[test]
+    fn batch_read_shares_budget_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+        for name in ["alpha", "beta", "gamma"] {
+            let mut source = String::new();
+            for i in 0..150 {
+                let _ = write!(
+                    source,
+                    "pub fn {name}_{i}(x: u64) -> u64 {{\n    x * {i} + 1\n}}\n\n"
+                );
+            }
+            let path = dir.path().join(format!("{name}.rs"));
+            std::fs::write(&path, source).unwrap();
+            paths.push(path);
+        }
+        let args = serde_json::json!({ "paths": paths, "budget": 1500 });
+        let out = dispatch_tool(
+            "glean_read",
+            &args,
+            &OutlineCache::new(),
+            &Session::new(),
+            ServerOptions::default(),
+            None,
+        )
+        .unwrap();
+        assert!(count_tokens(&out) <= 1500, "{out}");
+        // Every file gets a slice, not just the first
+        for name in ["alpha", "beta", "gamma"] {
+            assert!(out.contains(&format!("{name}.rs")), "{out}");
+            assert!(out.contains(&format!("fn {name}_0")), "{out}");
+        }
+    }
+}
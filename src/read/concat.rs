@@ -0,0 +1,115 @@
+//! Module view: read a directory as the concatenated outlines of its code files.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cache::OutlineCache;
+use crate::error::GleanError;
+use crate::format;
+use crate::search::SearchFilter;
+use crate::types::{FileType, estimate_tokens};
+
+use super::{FILE_SIZE_CAP, detect_file_type, outline};
+
+/// Most files shown in one module view.
+const MAX_FILES: usize = 20;
+/// Stop adding files once the view reaches this many tokens.
+const MAX_TOKENS: u64 = 8_000;
+
+/// Outline every code file under `dir` (skip dirs excluded), one `## file`
+/// section each, until the file or token cap is hit.
+pub fn read_directory(
+    dir: &Path,
+    absolute: bool,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    let mut files: Vec<PathBuf> = crate::search::walk_collect(
+        dir,
+        &SearchFilter::default(),
+        None,
+        Some(FILE_SIZE_CAP),
+        |entry| {
+            let path = entry.path();
+            if matches!(detect_file_type(path), FileType::Code(_)) {
+                vec![path.to_path_buf()]
+            } else {
+                Vec::new()
+            }
+        },
+    );
+    files.sort();
+
+    let total_files = files.len();
+    let mut body = String::new();
+    let mut shown = 0;
+
+    for path in &files {
+        if shown == MAX_FILES || estimate_tokens(body.len() as u64) >= MAX_TOKENS {
+            break;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let file_type = detect_file_type(path);
+        let mtime = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+        let key = crate::cache::View::outline(file_type, false);
+        let view = cache.get_or_compute(path, mtime, key, || {
+            outline::generate(path, file_type, &content, content.as_bytes(), false, false)
+        });
+
+        let _ = write!(
+            body,
+            "\n\n## {} ({} lines, ~{} tokens)\n{}",
+            format::rel(path, dir, absolute),
+            content.lines().count(),
+            estimate_tokens(content.len() as u64),
+            view.trim_end()
+        );
+        shown += 1;
+    }
+
+    let mut out = format!("# {} ({total_files} code files) [module]", dir.display());
+    if total_files == 0 {
+        out.push_str("\n\nno code files");
+        return Ok(out);
+    }
+    out.push_str(&body);
+    if shown < total_files {
+        let _ = write!(
+            out,
+            "\n\n... {} more files not shown (cap: {MAX_FILES} files, ~{MAX_TOKENS} tokens). \
+             Read a subdirectory or individual files.",
+            total_files - shown
+        );
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_view_outlines_each_file_under_its_own_header() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini-go");
+        let output = read_directory(&dir, false, &OutlineCache::new()).unwrap();
+
+        assert!(
+            output.starts_with(&format!("# {}", dir.display())),
+            "{output}"
+        );
+        for name in ["context.go", "middleware.go", "router.go", "router_test.go"] {
+            assert!(
+                output.contains(&format!("\n## {name} (")),
+                "missing section for {name}:\n{output}"
+            );
+        }
+        // Outlines, not file bodies: signatures without function contents
+        assert!(output.contains("ServeHTTP"), "{output}");
+        assert!(!output.contains("e.handleRequest(c)"), "{output}");
+        assert!(!output.contains("go.mod"), "only code files: {output}");
+    }
+}
@@ -1,3 +1,10 @@
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use crate::error::{GleanError, io_err};
+
 /// Check filename against known generated/lock files.
 pub fn is_generated_by_name(name: &str) -> bool {
     matches!(
@@ -36,3 +43,178 @@ pub fn is_generated_by_content(buf: &[u8]) -> bool {
         .iter()
         .any(|m| memchr::memmem::find(window, m).is_some())
 }
+
+/// Per-project config file, found in the scope or any directory above it.
+pub const CONFIG_FILE: &str = ".glean.toml";
+
+/// Globs from `.glean.toml` that override the generated-file heuristics:
+///
+/// ```toml
+/// [generated]
+/// never = ["src/schema.rs"]      # hand-maintained despite its header
+/// always = ["proto/gen/**"]
+/// ```
+///
+/// Globs match paths relative to the config's directory, or file names.
+#[derive(Debug)]
+pub struct Overrides {
+    root: PathBuf,
+    never: GlobSet,
+    always: GlobSet,
+}
+
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    generated: GeneratedSection,
+}
+
+#[derive(Deserialize, Default)]
+struct GeneratedSection {
+    #[serde(default)]
+    never: Vec<String>,
+    #[serde(default)]
+    always: Vec<String>,
+}
+
+impl Overrides {
+    /// The overrides of the nearest `.glean.toml` at or above `scope`.
+    /// `None` when there is no config or it lists no globs.
+    pub fn find(scope: &Path) -> Result<Option<Self>, GleanError> {
+        let scope = scope.canonicalize().unwrap_or_else(|_| scope.to_path_buf());
+        let Some(config) = scope
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|p| p.is_file())
+        else {
+            return Ok(None);
+        };
+        let text = std::fs::read_to_string(&config).map_err(io_err(&config))?;
+        let parsed: ConfigFile = toml::from_str(&text).map_err(|e| GleanError::ParseError {
+            path: config.clone(),
+            reason: e.message().to_string(),
+        })?;
+        let GeneratedSection { never, always } = parsed.generated;
+        if never.is_empty() && always.is_empty() {
+            return Ok(None);
+        }
+        let root = config.parent().unwrap_or(Path::new("/")).to_path_buf();
+        Ok(Some(Self {
+            never: glob_set(&config, &never)?,
+            always: glob_set(&config, &always)?,
+            root,
+        }))
+    }
+
+    /// The overrides that apply to the file at `path`: the nearest config at
+    /// or above its directory. A malformed config is reported on stderr and
+    /// skipped, leaving the verdict to the heuristics rather than failing the
+    /// read.
+    pub fn for_file(path: &Path) -> Option<Self> {
+        let dir = path
+            .parent()
+            .filter(|d| !d.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        Self::find(dir).unwrap_or_else(|e| {
+            eprintln!("glean: warning: {e}; ignoring its generated-file overrides");
+            None
+        })
+    }
+
+    /// `Some(false)` for a path listed under `never`, `Some(true)` under
+    /// `always` (`never` wins), `None` to leave it to the heuristics.
+    pub fn verdict(&self, path: &Path) -> Option<bool> {
+        let abs = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let rel = abs.strip_prefix(&self.root).ok()?;
+        let matches = |set: &GlobSet| {
+            set.is_match(rel) || rel.file_name().is_some_and(|name| set.is_match(name))
+        };
+        if matches(&self.never) {
+            Some(false)
+        } else if matches(&self.always) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+fn glob_set(config: &Path, patterns: &[String]) -> Result<GlobSet, GleanError> {
+    let invalid = |e: globset::Error| GleanError::ParseError {
+        path: config.to_path_buf(),
+        reason: format!("invalid glob: {e}"),
+    };
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(invalid)?);
+    }
+    builder.build().map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_overrides_heuristics() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[generated]\nnever = [\"src/schema.rs\"]\nalways = [\"gen/**\", \"*.pb.rs\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        let overrides = Overrides::find(&dir.path().join("src")).unwrap().unwrap();
+
+        assert_eq!(
+            overrides.verdict(&dir.path().join("src/schema.rs")),
+            Some(false)
+        );
+        assert_eq!(
+            overrides.verdict(&dir.path().join("gen/api.rs")),
+            Some(true)
+        );
+        assert_eq!(
+            overrides.verdict(&dir.path().join("src/user.pb.rs")),
+            Some(true)
+        );
+        assert_eq!(overrides.verdict(&dir.path().join("src/lib.rs")), None);
+        assert_eq!(overrides.verdict(Path::new("/elsewhere/gen/x.rs")), None);
+    }
+
+    #[test]
+    fn missing_or_empty_config_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Overrides::find(dir.path()).unwrap().is_none());
+        std::fs::write(dir.path().join(CONFIG_FILE), "[other]\nx = 1\n").unwrap();
+        assert!(Overrides::find(dir.path()).unwrap().is_none());
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[generated]\nnever = [\"[\"]\n",
+        )
+        .unwrap();
+        assert!(Overrides::find(dir.path()).is_err());
+        assert!(
+            Overrides::for_file(&dir.path().join("lib.rs")).is_none(),
+            "a broken config falls back to the heuristics"
+        );
+    }
+
+    /// Each file gets the config nearest to it, not the one at the scope.
+    #[test]
+    fn for_file_uses_the_files_own_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("vendor/lib");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            nested.join(CONFIG_FILE),
+            "[generated]\nalways = [\"*.rs\"]\n",
+        )
+        .unwrap();
+
+        let file = nested.join("api.rs");
+        let overrides = Overrides::for_file(&file).unwrap();
+        assert_eq!(overrides.verdict(&file), Some(true));
+        assert!(Overrides::for_file(&dir.path().join("main.rs")).is_none());
+    }
+}
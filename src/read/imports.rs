@@ -1,6 +1,7 @@
 //! Resolve import statements to local file paths.
 //! Used by the MCP layer to hint related files after an outlined read.
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -28,6 +29,10 @@ pub fn resolve_related_files_with_content(file_path: &Path, content: &str) -> Ve
         return Vec::new();
     };
 
+    if lang == Lang::Go {
+        return resolve_go_imports(dir, content);
+    }
+
     let mut results = Vec::new();
     for line in content.lines() {
         if results.len() >= MAX_SUGGESTIONS {
@@ -36,6 +41,14 @@ pub fn resolve_related_files_with_content(file_path: &Path, content: &str) -> Ve
         if !is_import_line(line, lang) {
             continue;
         }
+        if lang == Lang::Python {
+            for path in resolve_python_line(dir, line) {
+                if results.len() < MAX_SUGGESTIONS && !results.contains(&path) {
+                    results.push(path);
+                }
+            }
+            continue;
+        }
         let source = super::outline::code::extract_import_source(line);
         if source.is_empty() || is_external(&source, lang) {
             continue;
@@ -49,6 +62,33 @@ pub fn resolve_related_files_with_content(file_path: &Path, content: &str) -> Ve
     results
 }
 
+/// Module specifier of a JS/TS re-export (`export { Foo } from "./foo"`,
+/// `export * from "./foo"`). `None` if the line isn't a re-export.
+pub fn reexport_source(line: &str) -> Option<&str> {
+    let trimmed = line.trim().trim_end_matches(';');
+    if !trimmed.starts_with("export") {
+        return None;
+    }
+    let from_pos = trimmed.rfind(" from ")?;
+    let source = trimmed[from_pos + 6..]
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'');
+    (!source.is_empty()).then_some(source)
+}
+
+/// Resolve a barrel re-export line to the local module it forwards to.
+pub fn resolve_reexport(file_path: &Path, line: &str) -> Option<PathBuf> {
+    let source = reexport_source(line)?;
+    let resolved = resolve_js_import(file_path.parent()?, source)?;
+    // `dir.join("./foo")` keeps the `.` component — drop it for display
+    Some(
+        resolved
+            .components()
+            .filter(|c| !matches!(c, std::path::Component::CurDir))
+            .collect(),
+    )
+}
+
 fn is_import_line(line: &str, lang: Lang) -> bool {
     let trimmed = line.trim_start();
     match lang {
@@ -70,12 +110,12 @@ fn is_external(source: &str, lang: Lang) -> bool {
                 || source.starts_with("self::")
                 || source.starts_with("super::"))
         }
-        Lang::TypeScript | Lang::Tsx | Lang::JavaScript => {
-            !(source.starts_with('.') || source.starts_with("@/") || source.starts_with("~/"))
-        }
-        Lang::Python => !source.starts_with('.'),
+        // Bare specifiers may be tsconfig path aliases; `resolve_js_import`
+        // drops the real packages
+        Lang::TypeScript | Lang::Tsx | Lang::JavaScript => false,
         Lang::C | Lang::Cpp => !source.starts_with('"'),
-        // Go, Java, Kotlin — can't resolve without build system knowledge.
+        // Java, Kotlin — can't resolve without build system knowledge.
+        // Go and Python take their own paths in `resolve_related_files_with_content`.
         _ => true,
     }
 }
@@ -83,8 +123,7 @@ fn is_external(source: &str, lang: Lang) -> bool {
 fn resolve(dir: &Path, source: &str, lang: Lang) -> Option<PathBuf> {
     match lang {
         Lang::Rust => resolve_rust(dir, source),
-        Lang::TypeScript | Lang::Tsx | Lang::JavaScript => resolve_js(dir, source),
-        Lang::Python => resolve_python(dir, source),
+        Lang::TypeScript | Lang::Tsx | Lang::JavaScript => resolve_js_import(dir, source),
         Lang::C | Lang::Cpp => resolve_c_include(dir, source),
         _ => None,
     }
@@ -143,6 +182,165 @@ fn find_src_ancestor(start: &Path) -> Option<&Path> {
 
 // --- JS/TS ---
 
+/// Relative specifiers resolve against `dir`; bare ones only through the
+/// nearest tsconfig/jsconfig `paths` or `baseUrl`, so packages stay external.
+fn resolve_js_import(dir: &Path, source: &str) -> Option<PathBuf> {
+    if source.starts_with('.') {
+        return resolve_js(dir, source);
+    }
+    tsconfig_for(dir)?.resolve(source)
+}
+
+/// Module resolution settings from a tsconfig.json (or jsconfig.json).
+#[derive(Debug)]
+struct TsPaths {
+    /// Directory `paths` targets and bare imports resolve against.
+    base: PathBuf,
+    /// `paths` entries: pattern (at most one `*`) → targets.
+    paths: Vec<(String, Vec<String>)>,
+    /// Whether `baseUrl` was set, making every bare import a candidate.
+    has_base_url: bool,
+}
+
+impl TsPaths {
+    /// Parse a config, following a relative `extends` chain a few levels:
+    /// the child's `baseUrl` and `paths` each replace the parent's.
+    fn parse(config_dir: &Path, raw: &str, depth: usize) -> Option<Self> {
+        let json: serde_json::Value = serde_json::from_str(&crate::jsonc::strip(raw)).ok()?;
+        let inherited = json
+            .get("extends")
+            .and_then(|v| v.as_str())
+            .filter(|e| e.starts_with('.') && depth < 5)
+            .and_then(|e| {
+                // `./tsconfig.base` means `./tsconfig.base.json`
+                let mut path = config_dir.join(e);
+                if !path.is_file() {
+                    path.as_mut_os_string().push(".json");
+                }
+                let raw = fs::read_to_string(&path).ok()?;
+                Self::parse(path.parent()?, &raw, depth + 1)
+            });
+        let options = json.get("compilerOptions");
+        let base_url = options
+            .and_then(|o| o.get("baseUrl"))
+            .and_then(|v| v.as_str());
+        let paths: Option<Vec<(String, Vec<String>)>> = options
+            .and_then(|o| o.get("paths"))
+            .and_then(|v| v.as_object())
+            .map(|map| {
+                map.iter()
+                    .map(|(pattern, targets)| {
+                        let targets = targets
+                            .as_array()
+                            .map(|a| {
+                                a.iter()
+                                    .filter_map(|t| t.as_str().map(String::from))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (pattern.clone(), targets)
+                    })
+                    .collect()
+            });
+
+        match (inherited, base_url) {
+            // Without a baseUrl anywhere, paths are relative to their own config
+            (Some(parent), None) => Some(TsPaths {
+                base: if paths.is_some() && !parent.has_base_url {
+                    config_dir.to_path_buf()
+                } else {
+                    parent.base
+                },
+                paths: paths.unwrap_or(parent.paths),
+                has_base_url: parent.has_base_url,
+            }),
+            (_, base_url) if base_url.is_some() || paths.is_some() => Some(TsPaths {
+                base: config_dir.join(base_url.unwrap_or(".")),
+                paths: paths.unwrap_or_default(),
+                has_base_url: base_url.is_some(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// The file an aliased `source` points at. Exact patterns win over
+    /// wildcards, and longer wildcard prefixes over shorter ones, as in tsc.
+    fn resolve(&self, source: &str) -> Option<PathBuf> {
+        let mut candidates: Vec<(usize, &str, &[String])> = self
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let rank = match pattern.split_once('*') {
+                    None if pattern == source => usize::MAX,
+                    None => return None,
+                    Some((prefix, suffix)) => {
+                        let matches = source.len() >= prefix.len() + suffix.len()
+                            && source.starts_with(prefix)
+                            && source.ends_with(suffix);
+                        if !matches {
+                            return None;
+                        }
+                        prefix.len()
+                    }
+                };
+                let star = pattern.split_once('*').map_or("", |(prefix, suffix)| {
+                    &source[prefix.len()..source.len() - suffix.len()]
+                });
+                Some((rank, star, targets.as_slice()))
+            })
+            .collect();
+        candidates.sort_by_key(|&(rank, ..)| std::cmp::Reverse(rank));
+
+        for (_, star, targets) in candidates {
+            for target in targets {
+                if let Some(found) = resolve_js(&self.base, &target.replacen('*', star, 1)) {
+                    return Some(clean_path(&found));
+                }
+            }
+        }
+        if self.has_base_url {
+            return resolve_js(&self.base, source).map(|p| clean_path(&p));
+        }
+        None
+    }
+}
+
+/// Drop `.` components that `dir.join("./x")` leaves behind.
+fn clean_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+/// Nearest tsconfig.json or jsconfig.json at or above `dir`, parsed once per
+/// config file and re-read when it changes.
+fn tsconfig_for(dir: &Path) -> Option<std::sync::Arc<TsPaths>> {
+    type Parsed = (std::time::SystemTime, Option<std::sync::Arc<TsPaths>>);
+    static CONFIGS: std::sync::OnceLock<std::sync::Mutex<HashMap<PathBuf, Parsed>>> =
+        std::sync::OnceLock::new();
+
+    let config = dir.ancestors().find_map(|d| {
+        ["tsconfig.json", "jsconfig.json"]
+            .iter()
+            .map(|name| d.join(name))
+            .find(|p| p.is_file())
+    })?;
+    let mtime = fs::metadata(&config).and_then(|m| m.modified()).ok()?;
+
+    let configs = CONFIGS.get_or_init(Default::default);
+    if let Some((seen, parsed)) = configs.lock().ok()?.get(&config)
+        && *seen == mtime
+    {
+        return parsed.clone();
+    }
+    let parsed = fs::read_to_string(&config)
+        .ok()
+        .and_then(|raw| TsPaths::parse(config.parent()?, &raw, 0))
+        .map(std::sync::Arc::new);
+    configs.lock().ok()?.insert(config, (mtime, parsed.clone()));
+    parsed
+}
+
 fn resolve_js(dir: &Path, source: &str) -> Option<PathBuf> {
     let base = dir.join(source);
     // Try with extensions
@@ -168,32 +366,191 @@ fn resolve_js(dir: &Path, source: &str) -> Option<PathBuf> {
 
 // --- Python ---
 
+/// Files a Python import line pulls in. `from pkg import a, b` tries the
+/// submodules `pkg.a` and `pkg.b` first and falls back to `pkg` itself, so
+/// `from . import handlers` lands on `handlers.py` rather than `__init__.py`.
+fn resolve_python_line(dir: &Path, line: &str) -> Vec<PathBuf> {
+    let trimmed = line.trim();
+    let mut found = Vec::new();
+    if let Some(rest) = trimmed.strip_prefix("from ") {
+        let Some((module, names)) = rest.split_once(" import ") else {
+            return found;
+        };
+        let module = module.trim();
+        let names = names.trim().trim_start_matches('(').trim_end_matches(')');
+        let mut fallback = false;
+        for name in names.split(',').filter_map(|n| n.split_whitespace().next()) {
+            let sub = if module.bytes().all(|b| b == b'.') {
+                format!("{module}{name}")
+            } else {
+                format!("{module}.{name}")
+            };
+            match resolve_python(dir, &sub) {
+                Some(path) => found.push(path),
+                None => fallback = true,
+            }
+        }
+        if (fallback || found.is_empty())
+            && let Some(path) = resolve_python(dir, module)
+        {
+            found.push(path);
+        }
+    } else if let Some(rest) = trimmed.strip_prefix("import ") {
+        // `import a.b as c, d`
+        found.extend(
+            rest.split(',')
+                .filter_map(|m| m.split_whitespace().next())
+                .filter_map(|m| resolve_python(dir, m)),
+        );
+    }
+    found
+}
+
+/// Resolve a dotted module to `mod.py` or `mod/__init__.py`. Relative modules
+/// climb from `dir`; absolute ones are looked up from each ancestor up to the
+/// project root (and its `src/` layout), since the import root isn't known.
 fn resolve_python(dir: &Path, source: &str) -> Option<PathBuf> {
     let dots = source.bytes().take_while(|&b| b == b'.').count();
     if dots == 0 {
-        return None;
+        return python_roots(dir)
+            .iter()
+            .find_map(|root| python_module_at(root, source));
     }
     // Each dot beyond the first goes up one directory.
     let mut base = dir.to_path_buf();
     for _ in 1..dots {
         base = base.parent()?.to_path_buf();
     }
-    let module_part = &source[dots..];
-    if module_part.is_empty() {
+    python_module_at(&base, &source[dots..])
+}
+
+fn python_module_at(base: &Path, module: &str) -> Option<PathBuf> {
+    if module.is_empty() {
         // Bare `from . import X`
         let init = base.join("__init__.py");
-        return if init.exists() { Some(init) } else { None };
+        return init.exists().then_some(init);
     }
-    let rel = module_part.replace('.', "/");
+    let rel = module.replace('.', "/");
     let as_file = base.join(format!("{rel}.py"));
     if as_file.exists() {
         return Some(as_file);
     }
     let as_pkg = base.join(&rel).join("__init__.py");
-    if as_pkg.exists() {
-        return Some(as_pkg);
+    as_pkg.exists().then_some(as_pkg)
+}
+
+/// Candidate import roots for absolute Python modules: `dir` and its
+/// ancestors, stopping at the first one that looks like a project root.
+fn python_roots(dir: &Path) -> Vec<PathBuf> {
+    const MARKERS: [&str; 4] = ["pyproject.toml", "setup.py", "setup.cfg", ".git"];
+    let mut roots = Vec::new();
+    for ancestor in dir.ancestors() {
+        roots.push(ancestor.to_path_buf());
+        if MARKERS.iter().any(|m| ancestor.join(m).exists()) {
+            let src = ancestor.join("src");
+            if src.is_dir() {
+                roots.push(src);
+            }
+            break;
+        }
     }
-    None
+    roots
+}
+
+// --- Go ---
+
+/// Package files for imports inside the enclosing module. Go imports name
+/// directories, so each resolves to that package's non-test `.go` files.
+fn resolve_go_imports(dir: &Path, content: &str) -> Vec<PathBuf> {
+    let Some((root, module)) = go_module(dir) else {
+        return Vec::new();
+    };
+    let mut results = Vec::new();
+    for source in go_import_paths(content) {
+        let rel = if source == module {
+            ""
+        } else if let Some(rel) = source
+            .strip_prefix(&module)
+            .and_then(|r| r.strip_prefix('/'))
+        {
+            rel
+        } else {
+            continue;
+        };
+        for path in go_package_files(&root.join(rel)) {
+            if results.len() >= MAX_SUGGESTIONS {
+                return results;
+            }
+            if !results.contains(&path) {
+                results.push(path);
+            }
+        }
+    }
+    results
+}
+
+/// Import paths from single-line and grouped `import (...)` declarations.
+fn go_import_paths(content: &str) -> Vec<&str> {
+    let mut paths = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let spec = if in_block {
+            if trimmed.starts_with(')') {
+                in_block = false;
+                continue;
+            }
+            trimmed
+        } else if let Some(rest) = trimmed.strip_prefix("import") {
+            let rest = rest.trim_start();
+            if let Some(rest) = rest.strip_prefix('(') {
+                in_block = true;
+                rest
+            } else {
+                rest
+            }
+        } else {
+            continue;
+        };
+        // `alias "path"`, `_ "path"`, `"path" // comment`
+        if let Some((_, rest)) = spec.split_once('"')
+            && let Some((path, _)) = rest.split_once('"')
+        {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Root directory and module path from the nearest go.mod.
+fn go_module(dir: &Path) -> Option<(PathBuf, String)> {
+    let root = dir.ancestors().find(|d| d.join("go.mod").is_file())?;
+    let raw = fs::read_to_string(root.join("go.mod")).ok()?;
+    let module = raw.lines().find_map(|l| l.trim().strip_prefix("module "))?;
+    Some((
+        root.to_path_buf(),
+        module.trim().trim_matches('"').to_string(),
+    ))
+}
+
+fn go_package_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension().is_some_and(|e| e == "go")
+                && !p
+                    .file_stem()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.ends_with("_test"))
+        })
+        .collect();
+    files.sort();
+    files
 }
 
 // --- C/C++ ---
@@ -207,3 +564,66 @@ fn resolve_c_include(dir: &Path, source: &str) -> Option<PathBuf> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tsconfig_path_aliases_resolve() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ts-paths");
+        let related = resolve_related_files(&root.join("src/app.tsx"));
+        let rel: Vec<_> = related
+            .iter()
+            .map(|p| p.strip_prefix(&root).unwrap().to_path_buf())
+            .collect();
+        // Wildcard alias, exact alias and a bare baseUrl import; react stays external
+        assert_eq!(
+            rel,
+            [
+                PathBuf::from("src/components/Price.tsx"),
+                PathBuf::from("src/lib/config.ts"),
+                PathBuf::from("src/lib/format.ts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn python_package_imports_resolve() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/py-pkg");
+        let related = resolve_related_files(&root.join("service/api/routes.py"));
+        let rel: Vec<_> = related
+            .iter()
+            .map(|p| p.strip_prefix(&root).unwrap().to_path_buf())
+            .collect();
+        // Absolute module, package __init__, submodule via `from .`, parent-relative; os is stdlib
+        assert_eq!(
+            rel,
+            [
+                PathBuf::from("service/db.py"),
+                PathBuf::from("service/util/__init__.py"),
+                PathBuf::from("service/api/handlers.py"),
+                PathBuf::from("service/models.py"),
+            ]
+        );
+    }
+
+    #[test]
+    fn go_module_imports_resolve() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/go-mod");
+        let related = resolve_related_files(&root.join("cmd/server/main.go"));
+        let rel: Vec<_> = related
+            .iter()
+            .map(|p| p.strip_prefix(&root).unwrap().to_path_buf())
+            .collect();
+        // Grouped and single-line imports; stdlib, other modules and tests skipped
+        assert_eq!(
+            rel,
+            [
+                PathBuf::from("internal/store/memory.go"),
+                PathBuf::from("internal/store/store.go"),
+                PathBuf::from("internal/util/strings.go"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::format::format_size;
+use crate::types::estimate_tokens;
+
+/// A line at least this long is minified code, not something a person wrote.
+const LONG_LINE: usize = 500;
+
+/// Share of the file's bytes, in fifths, that must sit on long lines.
+const LONG_SHARE_FIFTHS: usize = 4;
+
+/// Names shown from each end of the file.
+const EDGE_NAMES: usize = 5;
+
+/// Longest CSS selector shown before it's cut.
+const SELECTOR_CAP: usize = 40;
+
+/// Minified bundles: a script or stylesheet (or any `.min.*` file) too big to
+/// read whole, with at least four fifths of its bytes on very long lines. A
+/// license banner doesn't hide a one-line blob, and one long data line in
+/// ordinary source doesn't make it minified.
+pub fn is_minified(path: &Path, buf: &[u8]) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let is_bundle_type = name.contains(".min.")
+        || matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("js" | "mjs" | "cjs" | "css")
+        );
+    if !is_bundle_type || estimate_tokens(buf.len() as u64) <= super::TOKEN_THRESHOLD {
+        return false;
+    }
+    let long_bytes: usize = buf
+        .split(|&b| b == b'\n')
+        .filter(|l| l.len() >= LONG_LINE)
+        .map(<[u8]>::len)
+        .sum();
+    long_bytes * 5 >= buf.len() * LONG_SHARE_FIFTHS
+}
+
+/// Compact view of a minified file instead of its content: size, estimated
+/// symbol count, and the first and last declared names. For JS that's the
+/// names after `function`/`class`/`const`/`let`/`var`; for stylesheets, the
+/// rule selectors.
+pub fn summary(path: &Path, buf: &[u8]) -> String {
+    let content = String::from_utf8_lossy(buf);
+    let lines = content.lines().count().max(1);
+    let is_css = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("css" | "scss" | "less")
+    );
+    let (names, unit) = if is_css {
+        (css_selectors(&content), "rules")
+    } else {
+        (js_declarations(&content), "declarations")
+    };
+
+    let mut out = format!(
+        "{} on {lines} {} — ~{} {unit}",
+        format_size(buf.len() as u64),
+        if lines == 1 { "line" } else { "lines" },
+        names.len()
+    );
+    if names.is_empty() {
+        return out;
+    }
+    let head = names.len().min(EDGE_NAMES);
+    let _ = write!(out, "\nfirst: {}", names[..head].join(", "));
+    if names.len() > EDGE_NAMES {
+        let tail = (names.len() - EDGE_NAMES).max(head);
+        let _ = write!(out, "\nlast: {}", names[tail..].join(", "));
+    }
+    out
+}
+
+/// Names following a declaration keyword, in file order. Only whitespace may
+/// separate the two, so `function(){...}` doesn't name `return`.
+fn js_declarations(content: &str) -> Vec<&str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let mut names = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(is_ident) {
+        let word_len = rest[start..]
+            .find(|c| !is_ident(c))
+            .unwrap_or(rest.len() - start);
+        let word = &rest[start..start + word_len];
+        rest = &rest[start + word_len..];
+        if !matches!(word, "function" | "class" | "const" | "let" | "var") {
+            continue;
+        }
+        let after = rest.trim_start();
+        if after.len() < rest.len()
+            && after.starts_with(|c: char| is_ident(c) && !c.is_ascii_digit())
+        {
+            let len = after.find(|c| !is_ident(c)).unwrap_or(after.len());
+            names.push(&after[..len]);
+            rest = &after[len..];
+        }
+    }
+    names
+}
+
+/// The selector of every rule, at-rules (`@media`) excluded.
+fn css_selectors(content: &str) -> Vec<&str> {
+    content
+        .split('{')
+        .filter_map(|chunk| {
+            let selector = chunk.rsplit(['}', ';']).next()?.trim();
+            (!selector.is_empty() && !selector.starts_with('@'))
+                .then(|| crate::types::truncate_str(selector, SELECTOR_CAP))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_line_bundle_is_summarised() {
+        let mut js = String::from("/*! lib v1 | MIT */\n");
+        for i in 0..200 {
+            let _ = write!(js, "function f{i}(a,b){{return a+b}}var v{i}=f{i}(1,2);");
+        }
+        js.push('\n');
+        assert!(is_minified(
+            Path::new("bundle.min.js"),
+            js.repeat(2).as_bytes()
+        ));
+
+        let summary = summary(Path::new("bundle.min.js"), js.as_bytes());
+        assert!(
+            summary.contains("on 2 lines — ~400 declarations"),
+            "{summary}"
+        );
+        assert!(summary.contains("first: f0, v0, f1, v1, f2"), "{summary}");
+        assert!(
+            summary.contains("last: v197, f198, v198, f199, v199"),
+            "{summary}"
+        );
+    }
+
+    #[test]
+    fn ordinary_source_is_not_minified() {
+        let source = "function main() {\n    console.log(\"hello\");\n}\n".repeat(400);
+        assert!(!is_minified(Path::new("main.js"), source.as_bytes()));
+        assert!(
+            !is_minified(Path::new("app.min.js"), &[b'x'; 2000]),
+            "too small to matter"
+        );
+
+        // One long data line among ordinary code
+        let data = format!("{source}const TABLE = \"{}\";\n", "ab".repeat(10_000));
+        assert!(!is_minified(Path::new("table.js"), data.as_bytes()));
+
+        // Only scripts, stylesheets and `.min.*` files are candidates
+        let go = format!("package blob\n\nvar Blob = \"{}\"\n", "x".repeat(90_000));
+        assert!(!is_minified(Path::new("blob.go"), go.as_bytes()));
+    }
+
+    #[test]
+    fn stylesheet_lists_selectors() {
+        let css = ".btn{color:red}@media (max-width:600px){.nav a{display:none}}#main{margin:0}"
+            .repeat(40);
+        let summary = summary(Path::new("site.min.css"), css.as_bytes());
+        assert!(summary.contains("~120 rules"), "{summary}");
+        assert!(
+            summary.contains("first: .btn, .nav a, #main, .btn, .nav a"),
+            "{summary}"
+        );
+    }
+}
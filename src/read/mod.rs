@@ -1,28 +1,37 @@
 pub mod binary;
+pub mod concat;
 pub mod generated;
 pub mod imports;
+pub mod minified;
 pub mod outline;
+pub mod strip;
+pub mod warm;
 
 use std::fs;
 use std::path::Path;
 
 use memmap2::Mmap;
 
-use crate::cache::OutlineCache;
+use crate::cache::{OutlineCache, View};
 use crate::error::{GleanError, io_err};
 use crate::format;
-use crate::types::{FileType, Lang, ViewMode, estimate_tokens};
+use crate::source::FileSource;
+use crate::types::{FileType, Lang, ViewMode, count_tokens, estimate_tokens};
 
 pub(crate) const TOKEN_THRESHOLD: u64 = 3_500;
-const FILE_SIZE_CAP: u64 = 500_000; // 500KB
+pub(crate) const FILE_SIZE_CAP: u64 = 500_000; // 500KB
 
 /// Main entry point for read mode. Routes through the decision tree.
 pub fn read_file(
     path: &Path,
     section: Option<&str>,
     full: bool,
+    lang: Option<Lang>,
     cache: &OutlineCache,
     edit_mode: bool,
+    budget: Option<u64>,
+    overrides: Option<&generated::Overrides>,
+    dedup_variants: bool,
 ) -> Result<String, GleanError> {
     let meta = match fs::metadata(path) {
         Ok(m) => m,
@@ -68,17 +77,200 @@ pub fn read_file(
     // lifetime of the Mmap, preventing use-after-close. The mapped region is
     // accessed only as a &[u8] slice.
     let mmap = unsafe { Mmap::map(&file) }.map_err(io_err(path))?;
-    let buf = &mmap[..];
+    let mtime = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+    render(
+        path,
+        &mmap,
+        mtime,
+        full,
+        lang,
+        cache,
+        edit_mode,
+        budget,
+        overrides,
+        dedup_variants,
+    )
+}
+
+/// `read_file` over a `FileSource` instead of the disk: the same views, with
+/// the bytes and modification time coming from `source`.
+pub fn read_source(
+    source: &impl FileSource,
+    path: &Path,
+    section: Option<&str>,
+    full: bool,
+    lang: Option<Lang>,
+    cache: &OutlineCache,
+    edit_mode: bool,
+    budget: Option<u64>,
+    overrides: Option<&generated::Overrides>,
+    dedup_variants: bool,
+) -> Result<String, GleanError> {
+    let buf = source.read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => GleanError::NotFound {
+            path: path.to_path_buf(),
+            suggestion: None,
+        },
+        _ => GleanError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        },
+    })?;
+    if let Some(range) = section {
+        return section_view(path, &buf, range, edit_mode);
+    }
+    if buf.is_empty() {
+        return Ok(format::file_header(path, 0, 0, ViewMode::Empty));
+    }
+    let mtime = source
+        .modified(path)
+        .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+    render(
+        path,
+        &buf,
+        mtime,
+        full,
+        lang,
+        cache,
+        edit_mode,
+        budget,
+        overrides,
+        dedup_variants,
+    )
+}
+
+/// `read_file` of `path` as it was at git revision `rev` (`HEAD~1`, `main`, a
+/// commit hash): the same views over `git show <rev>:<path>`, headers marked
+/// `@ <rev>`. The outline cache is keyed by the working-tree path, so a
+/// revision gets its own throwaway one.
+pub fn read_revision(
+    path: &Path,
+    rev: &str,
+    section: Option<&str>,
+    full: bool,
+    lang: Option<Lang>,
+    edit_mode: bool,
+    budget: Option<u64>,
+    dedup_variants: bool,
+) -> Result<String, GleanError> {
+    let buf = git_show(path, rev)?;
+    let view = if let Some(range) = section {
+        section_view(path, &buf, range, edit_mode)?
+    } else if buf.is_empty() {
+        format::file_header(path, 0, 0, ViewMode::Empty)
+    } else {
+        let cache = OutlineCache::new();
+        let mtime = std::time::SystemTime::UNIX_EPOCH;
+        render(
+            path,
+            &buf,
+            mtime,
+            full,
+            lang,
+            &cache,
+            edit_mode,
+            budget,
+            None,
+            dedup_variants,
+        )?
+    };
 
+    // Mark every header line (one per section) with the revision
+    let header = format!("# {}", path.display());
+    let marked = format!("{header} @ {rev}");
+    Ok(view
+        .split('\n')
+        .map(|line| match line.strip_prefix(&header) {
+            Some(rest) if rest.starts_with(" (") || rest.starts_with(" — ") => {
+                format!("{marked}{rest}")
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Bytes of `path` at `rev`, from `git show` run in the file's directory.
+fn git_show(path: &Path, rev: &str) -> Result<Vec<u8>, GleanError> {
+    let query = format!("{}@{rev}", path.display());
+    if rev.is_empty() || rev.starts_with('-') {
+        return Err(GleanError::InvalidQuery {
+            query,
+            reason: "expected a git revision like HEAD~1 or main".into(),
+        });
+    }
+    let name = path.file_name().ok_or_else(|| GleanError::InvalidQuery {
+        query: query.clone(),
+        reason: "not a file path".into(),
+    })?;
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty());
+    // `<rev>:./<name>` resolves against `-C`, not the repository root
+    let mut object = std::ffi::OsString::from(format!("{rev}:./"));
+    object.push(name);
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir.unwrap_or(Path::new(".")))
+        .arg("show")
+        .arg(object)
+        .output()
+        .map_err(io_err(path))?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let reason = stderr
+            .lines()
+            .next()
+            .unwrap_or("git show failed")
+            .trim_start_matches("fatal: ")
+            .to_string();
+        return Err(GleanError::InvalidQuery { query, reason });
+    }
+    Ok(out.stdout)
+}
+
+/// The view of a non-empty file's bytes: binary header, lockfile summary,
+/// full content or outline, by type and size. With a `budget`, an outline is
+/// generated to fit it. `overrides` settle whether the file counts as
+/// generated; `full` reads it regardless. `dedup_variants` collapses
+/// identical `#[cfg]`/`#ifdef` copies in code outlines.
+fn render(
+    path: &Path,
+    buf: &[u8],
+    mtime: std::time::SystemTime,
+    full: bool,
+    lang: Option<Lang>,
+    cache: &OutlineCache,
+    edit_mode: bool,
+    budget: Option<u64>,
+    overrides: Option<&generated::Overrides>,
+    dedup_variants: bool,
+) -> Result<String, GleanError> {
+    let byte_len = buf.len() as u64;
     if binary::is_binary(buf) {
         let mime = mime_from_ext(path);
         return Ok(format::binary_header(path, byte_len, mime));
     }
 
     let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let file_type = file_type_with(path, lang, buf);
+
+    // Lockfile → dependency summary, any size (checked before generated —
+    // lockfiles are generated too, but the summary is worth showing)
+    if file_type == FileType::Lockfile {
+        let content = String::from_utf8_lossy(buf);
+        let line_count = memchr::memchr_iter(b'\n', buf).count() as u32 + 1;
+        let view = View::outline(file_type, false);
+        let summary = cache.get_or_compute(path, mtime, view, || {
+            outline::generate(path, file_type, &content, buf, false, false)
+        });
+        let header = format::file_header(path, byte_len, line_count, ViewMode::Lockfile);
+        return Ok(format!("{header}\n\n{summary}"));
+    }
 
-    // Generated
-    if generated::is_generated_by_name(name) || generated::is_generated_by_content(buf) {
+    // Generated, unless the config says otherwise or the caller wants it all
+    let is_generated = overrides.and_then(|o| o.verdict(path)).unwrap_or_else(|| {
+        generated::is_generated_by_name(name) || generated::is_generated_by_content(buf)
+    });
+    if is_generated && !full {
         let line_count = memchr::memchr_iter(b'\n', buf).count() as u32 + 1;
         return Ok(format::file_header(
             path,
@@ -88,9 +280,20 @@ pub fn read_file(
         ));
     }
 
+    let line_count = memchr::memchr_iter(b'\n', buf).count() as u32 + 1;
+
+    // Minified bundle → size and names, not one enormous line (unless asked
+    // for in full)
+    if !full
+        && matches!(file_type, FileType::Code(_) | FileType::Other)
+        && minified::is_minified(path, buf)
+    {
+        let header = format::file_header(path, byte_len, line_count, ViewMode::Minified);
+        return Ok(format!("{header}\n\n{}", minified::summary(path, buf)));
+    }
+
     let tokens = estimate_tokens(byte_len);
     let content = String::from_utf8_lossy(buf);
-    let line_count = memchr::memchr_iter(b'\n', buf).count() as u32 + 1;
 
     // Full mode or small file → return full content (skip smart view)
     if full || tokens <= TOKEN_THRESHOLD {
@@ -103,137 +306,170 @@ pub fn read_file(
     }
 
     // Large file → smart view by file type
-    let file_type = detect_file_type(path);
-    let mtime = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-
     let capped = byte_len > FILE_SIZE_CAP;
 
-    let outline = cache.get_or_compute(path, mtime, || {
-        outline::generate(path, file_type, &content, buf, capped)
-    });
-
     let mode = match file_type {
         FileType::StructuredData => ViewMode::Keys,
         _ => ViewMode::Outline,
     };
     let header = format::file_header(path, byte_len, line_count, mode);
+
+    // A budget bypasses the cache: the cached full outline may not fit it
+    let outline: std::sync::Arc<str> = if let Some(budget) = budget {
+        let room = budget.saturating_sub(count_tokens(&header) + 1);
+        outline::generate_within(path, file_type, &content, buf, capped, room, dedup_variants)
+            .into()
+    } else {
+        let view = View {
+            dedup_variants,
+            ..View::outline(file_type, capped)
+        };
+        cache.get_or_compute(path, mtime, view, || {
+            outline::generate(path, file_type, &content, buf, capped, dedup_variants)
+        })
+    };
+
     Ok(format!("{header}\n\n{outline}"))
 }
 
 /// Would this file produce an outline (rather than full content) in default read mode?
 /// Used by the MCP layer to decide whether to append related-file hints.
 pub fn would_outline(path: &Path) -> bool {
-    std::fs::metadata(path)
-        .map(|m| !m.is_dir() && estimate_tokens(m.len()) > TOKEN_THRESHOLD)
-        .unwrap_or(false)
+    std::fs::metadata(path).is_ok_and(|m| !m.is_dir() && estimate_tokens(m.len()) > TOKEN_THRESHOLD)
+}
+
+/// Table of contents of a markdown file: headings down to `max_level`, each
+/// with its line range, whatever the file's size.
+pub fn read_toc(path: &Path, max_level: usize) -> Result<String, GleanError> {
+    if detect_file_type(path) != FileType::Markdown {
+        return Err(GleanError::InvalidQuery {
+            query: path.display().to_string(),
+            reason: "heading level applies to markdown files only".into(),
+        });
+    }
+    let buf = fs::read(path).map_err(io_err(path))?;
+    let line_count = memchr::memchr_iter(b'\n', &buf).count() as u32 + 1;
+    let header = format::file_header(path, buf.len() as u64, line_count, ViewMode::Outline);
+    let toc = outline::markdown::outline_to_level(&buf, usize::MAX, max_level.clamp(1, 6));
+    Ok(format!("{header}\n\n{toc}"))
 }
 
 /// Resolve a heading address to a line range in a markdown file.
 /// Returns `(start_line, end_line)` as 1-indexed inclusive range.
-/// Returns `None` if heading not found.
+/// Returns `None` if heading not found. Setext headings (`Title` over
+/// `===`/`---`) answer to `# Title`/`## Title` like their ATX forms.
 fn resolve_heading(buf: &[u8], heading: &str) -> Option<(usize, usize)> {
-    let heading_trimmed = heading.trim_end();
-    let heading_level = heading_trimmed.chars().take_while(|&c| c == '#').count();
-
-    if heading_level == 0 {
+    let heading = heading.trim();
+    let level = heading.chars().take_while(|&c| c == '#').count();
+    if level == 0 {
         return None;
     }
+    let text = outline::markdown::atx_text(&heading[level..]);
+
+    let scan = outline::markdown::scan(buf);
+    let idx = scan
+        .headings
+        .iter()
+        .position(|h| h.level == level && h.text == text)?;
+    let start_line = scan.headings[idx].line;
+
+    // The section ends before the next heading of same or higher level
+    let end_line = scan.headings[idx + 1..]
+        .iter()
+        .find(|h| h.level <= level)
+        .map_or(scan.total_lines, |h| h.line - 1);
+    Some((start_line, end_line))
+}
 
-    // Build line offsets
-    let mut line_offsets: Vec<usize> = vec![0];
-    for pos in memchr::memchr_iter(b'\n', buf) {
-        line_offsets.push(pos + 1);
-    }
-    // Exclude phantom empty line after trailing newline (match outline's count)
-    let total_lines = if buf.last() == Some(&b'\n') {
-        line_offsets.len() - 1
-    } else {
-        line_offsets.len()
-    };
-
-    let mut in_code_block = false;
-    let mut found_line: Option<usize> = None;
-
-    // Scan for the target heading
-    for (line_idx, &offset) in line_offsets.iter().enumerate() {
-        let line_end = if line_idx + 1 < line_offsets.len() {
-            line_offsets[line_idx + 1] - 1 // exclude newline
-        } else {
-            buf.len()
-        };
-
-        if let Ok(line_str) = std::str::from_utf8(&buf[offset..line_end]) {
-            let trimmed = line_str.trim_end();
-
-            // Track code blocks
-            if trimmed.starts_with("```") {
-                in_code_block = !in_code_block;
-                continue;
-            }
+/// Read a specific line range from a file.
+/// Uses memchr to find the Nth newline offset and slice the mmap buffer directly
+/// instead of collecting all lines into a Vec.
+fn read_section(path: &Path, range: &str, edit_mode: bool) -> Result<String, GleanError> {
+    let file = fs::File::open(path).map_err(io_err(path))?;
+    // SAFETY: The file is opened read-only and we hold the File handle for the
+    // lifetime of the Mmap, preventing use-after-close. The mapped region is
+    // accessed only as a &[u8] slice.
+    let mmap = unsafe { Mmap::map(&file) }.map_err(io_err(path))?;
+    section_view(path, &mmap, range, edit_mode)
+}
 
-            // Skip headings inside code blocks
-            if in_code_block {
-                continue;
-            }
+/// Lines `range` (or a markdown heading's section) of `buf`, numbered.
+/// A comma-separated list (`"10-20,45-60,## API"`) returns every slice, each
+/// under its own header; slices that fail are reported in place, and the call
+/// only fails when all of them do.
+fn section_view(
+    path: &Path,
+    buf: &[u8],
+    range: &str,
+    edit_mode: bool,
+) -> Result<String, GleanError> {
+    let parts = split_sections(range);
+    if parts.len() < 2 {
+        return section_slice(path, buf, range, edit_mode);
+    }
 
-            // Check if this line matches the heading
-            if trimmed == heading_trimmed {
-                found_line = Some(line_idx + 1); // 1-indexed
-                break;
+    let mut slices = Vec::with_capacity(parts.len());
+    let mut failed = Vec::new();
+    for part in &parts {
+        match section_slice(path, buf, part, edit_mode) {
+            Ok(slice) => slices.push(slice),
+            Err(GleanError::InvalidQuery { reason, .. }) => {
+                slices.push(format!(
+                    "# {} — section \"{part}\": {reason}",
+                    path.display()
+                ));
+                failed.push(format!("\"{part}\": {reason}"));
             }
+            Err(e) => return Err(e),
         }
     }
+    if failed.len() == parts.len() {
+        return Err(GleanError::InvalidQuery {
+            query: range.to_string(),
+            reason: failed.join("; "),
+        });
+    }
+    Ok(slices.join("\n\n"))
+}
 
-    let start_line = found_line?;
-
-    // Find the next heading of same or higher level
-    in_code_block = false;
-    let start_idx = start_line - 1; // convert back to 0-indexed for iteration
-
-    for (line_idx, &offset) in line_offsets.iter().enumerate().skip(start_idx + 1) {
-        let line_end = if line_idx + 1 < line_offsets.len() {
-            line_offsets[line_idx + 1] - 1
-        } else {
-            buf.len()
-        };
-
-        if let Ok(line_str) = std::str::from_utf8(&buf[offset..line_end]) {
-            let trimmed = line_str.trim_end();
-
-            if trimmed.starts_with("```") {
-                in_code_block = !in_code_block;
-                continue;
-            }
-
-            if in_code_block {
-                continue;
-            }
-
-            // Check if this is a heading
-            if trimmed.starts_with('#') {
-                let level = trimmed.chars().take_while(|&c| c == '#').count();
-                if level <= heading_level {
-                    // 0-based line_idx of next heading = 1-indexed line before it
-                    return Some((start_line, line_idx));
-                }
+/// `"10-20, 45-60,## API, usage"` → `["10-20", "45-60", "## API, usage"]`:
+/// a piece that doesn't start like a range or heading belongs to the heading before it.
+fn split_sections(range: &str) -> Vec<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for piece in range.split(',') {
+        let starts_part = piece
+            .trim_start()
+            .starts_with(|c: char| c.is_ascii_digit() || c == '#' || c == '-');
+        match parts.last_mut() {
+            Some(last) if !starts_part => {
+                last.push(',');
+                last.push_str(piece);
             }
+            _ => parts.push(piece.trim_start().to_string()),
         }
     }
-
-    // No next heading found — section goes to end of file
-    Some((start_line, total_lines))
+    parts.iter().map(|p| p.trim_end().to_string()).collect()
 }
 
-/// Read a specific line range from a file.
-/// Uses memchr to find the Nth newline offset and slice the mmap buffer directly
-/// instead of collecting all lines into a Vec.
-fn read_section(path: &Path, range: &str, edit_mode: bool) -> Result<String, GleanError> {
-    let file = fs::File::open(path).map_err(io_err(path))?;
-    // SAFETY: The file is opened read-only and we hold the File handle for the
-    // lifetime of the Mmap, preventing use-after-close. The mapped region is
-    // accessed only as a &[u8] slice.
-    let mmap = unsafe { Mmap::map(&file) }.map_err(io_err(path))?;
-    let buf = &mmap[..];
+/// One line range or heading section of `buf`, numbered.
+fn section_slice(
+    path: &Path,
+    buf: &[u8],
+    range: &str,
+    edit_mode: bool,
+) -> Result<String, GleanError> {
+    // Find line offsets using memchr — no full-file Vec<&str> allocation
+    let mut line_offsets: Vec<usize> = vec![0];
+    for pos in memchr::memchr_iter(b'\n', buf) {
+        line_offsets.push(pos + 1);
+    }
+    let total = line_offsets.len();
+    // Open-ended ranges count real lines, not the empty one after a trailing newline
+    let last_line = if buf.last() == Some(&b'\n') {
+        total - 1
+    } else {
+        total
+    };
 
     // Check if this is a heading-based address (markdown)
     let (start, end) = if range.starts_with('#') {
@@ -242,19 +478,12 @@ fn read_section(path: &Path, range: &str, edit_mode: bool) -> Result<String, Gle
             reason: "heading not found in file".into(),
         })?
     } else {
-        parse_range(range).ok_or_else(|| GleanError::InvalidQuery {
+        parse_range(range, last_line).ok_or_else(|| GleanError::InvalidQuery {
             query: range.to_string(),
-            reason: "expected format: \"start-end\" (e.g. \"45-89\") or heading (e.g. \"## Architecture\")".into(),
+            reason: "expected format: \"start-end\" (e.g. \"45-89\"), \"-N\" for the last N lines, \"start-\" to the end, or heading (e.g. \"## Architecture\")".into(),
         })?
     };
 
-    // Find line offsets using memchr — no full-file Vec<&str> allocation
-    let mut line_offsets: Vec<usize> = vec![0];
-    for pos in memchr::memchr_iter(b'\n', buf) {
-        line_offsets.push(pos + 1);
-    }
-    let total = line_offsets.len();
-
     let s = (start.saturating_sub(1)).min(total);
     let e = end.min(total);
 
@@ -284,12 +513,24 @@ fn read_section(path: &Path, range: &str, edit_mode: bool) -> Result<String, Gle
     Ok(format!("{header}\n\n{formatted}"))
 }
 
-/// Parse "45-89" into (45, 89). 1-indexed.
-fn parse_range(s: &str) -> Option<(usize, usize)> {
+/// Parse "45-89" into (45, 89). 1-indexed. Open-ended forms resolve against
+/// `last_line`: "-50" is the last 50 lines, "100-" runs from line 100 to the end.
+pub(crate) fn parse_range(s: &str, last_line: usize) -> Option<(usize, usize)> {
     let (a, b) = s.split_once('-')?;
-    let start: usize = a.trim().parse().ok()?;
-    let end: usize = b.trim().parse().ok()?;
-    if start == 0 || end < start {
+    let (a, b) = (a.trim(), b.trim());
+    let (start, end) = match (a.is_empty(), b.is_empty()) {
+        (true, true) => return None,
+        (true, false) => {
+            let count: usize = b.parse().ok()?;
+            if count == 0 {
+                return None;
+            }
+            (last_line.saturating_sub(count) + 1, last_line.max(1))
+        }
+        (false, true) => (a.parse().ok()?, last_line),
+        (false, false) => (a.parse().ok()?, b.parse().ok()?),
+    };
+    if start == 0 || (end < start && !b.is_empty()) {
         return None;
     }
     Some((start, end))
@@ -327,8 +568,42 @@ fn list_directory(path: &Path) -> Result<String, GleanError> {
     Ok(format!("{header}\n\n{}", entries.join("\n")))
 }
 
-/// Detect file type by extension, then by name.
+/// `lang` as a code file when the caller forced one (`--lang`), otherwise
+/// whatever `sniff_file_type` makes of the path and its content. For a file
+/// the caller named; walks use `file_type_or`.
+pub fn file_type_with(path: &Path, lang: Option<Lang>, buf: &[u8]) -> FileType {
+    lang.map_or_else(|| sniff_file_type(path, buf), FileType::Code)
+}
+
+/// `sniff_file_type`, falling back to `lang` only for files it can't place.
+/// A search walk given `--lang` still parses `main.py` as Python.
+pub fn file_type_or(path: &Path, lang: Option<Lang>, buf: &[u8]) -> FileType {
+    match (sniff_file_type(path, buf), lang) {
+        (FileType::Other, Some(lang)) => FileType::Code(lang),
+        (file_type, _) => file_type,
+    }
+}
+
+/// `detect_file_type`, plus extensionless scripts recognised by the shebang
+/// at the start of `buf`. For callers that already hold the content.
+pub fn sniff_file_type(path: &Path, buf: &[u8]) -> FileType {
+    match detect_file_type(path) {
+        FileType::Other if path.extension().is_none() && is_shell_shebang(buf) => {
+            FileType::Code(Lang::Shell)
+        }
+        file_type => file_type,
+    }
+}
+
+/// Detect file type by extension, then by name. Never touches the file, so
+/// it's cheap on every path a walk visits; `sniff_file_type` also looks at
+/// the content. Lockfiles are matched by name first — their extensions
+/// (`.lock`, `.json`) lie.
 pub fn detect_file_type(path: &Path) -> FileType {
+    if outline::lockfile::is_lockfile(path) {
+        return FileType::Lockfile;
+    }
+
     match path.extension().and_then(|e| e.to_str()) {
         Some("ts") => FileType::Code(Lang::TypeScript),
         Some("tsx") => FileType::Code(Lang::Tsx),
@@ -344,9 +619,14 @@ pub fn detect_file_type(path: &Path) -> FileType {
         Some("kt" | "kts") => FileType::Code(Lang::Kotlin),
         Some("cs") => FileType::Code(Lang::CSharp),
         Some("zig") => FileType::Code(Lang::Zig),
+        Some("php") => FileType::Code(Lang::Php),
+        Some("sh" | "bash" | "zsh") => FileType::Code(Lang::Shell),
 
         Some("md" | "mdx" | "rst") => FileType::Markdown,
-        Some("json" | "yaml" | "yml" | "toml" | "xml" | "ini") => FileType::StructuredData,
+        Some("ipynb") => FileType::Notebook,
+        Some("json" | "jsonl" | "ndjson" | "yaml" | "yml" | "toml" | "xml" | "ini") => {
+            FileType::StructuredData
+        }
         Some("csv" | "tsv") => FileType::Tabular,
         Some("log") => FileType::Log,
 
@@ -360,15 +640,33 @@ fn file_type_from_name(path: &Path) -> FileType {
         Some("Dockerfile" | "Containerfile") => FileType::Code(Lang::Dockerfile),
         Some("Makefile" | "GNUmakefile") => FileType::Code(Lang::Make),
         Some("Vagrantfile" | "Rakefile") => FileType::Code(Lang::Ruby),
+        Some(".bashrc" | ".bash_profile" | ".zshrc" | ".profile") => FileType::Code(Lang::Shell),
         Some(n) if n.starts_with(".env") => FileType::StructuredData,
         _ => FileType::Other,
     }
 }
 
+/// Whether `buf` starts with a shebang that runs a shell:
+/// `#!/bin/sh`, `#!/usr/bin/env bash`, `#!/bin/zsh -e`.
+fn is_shell_shebang(buf: &[u8]) -> bool {
+    let Some(line) = buf.strip_prefix(b"#!") else {
+        return false;
+    };
+    let line = line.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next().unwrap_or_default();
+    if interpreter.ends_with("/env") {
+        interpreter = words.find(|w| !w.starts_with('-')).unwrap_or_default();
+    }
+    let name = interpreter.rsplit('/').next().unwrap_or_default();
+    matches!(name, "sh" | "bash" | "zsh" | "dash" | "ksh")
+}
+
 /// Public entry point for did-you-mean on path-like fallthrough queries.
 /// Resolves the query relative to scope and checks the parent directory.
 pub fn suggest_similar_file(scope: &Path, query: &str) -> Option<String> {
-    let resolved = scope.join(query);
+    let resolved = crate::classify::resolve_path(scope, query);
     suggest_similar(&resolved)
 }
 
@@ -437,6 +735,7 @@ fn mime_from_ext(path: &Path) -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fmt::Write as _;
 
     #[test]
     fn heading_found() {
@@ -446,6 +745,28 @@ mod tests {
         assert_eq!(result, Some((3, 4)));
     }
 
+    #[test]
+    fn closed_heading_found() {
+        let input = b"# Title #\nSome content\n## Section ##  \nSection content\n";
+        assert_eq!(resolve_heading(input, "## Section"), Some((3, 4)));
+        assert_eq!(resolve_heading(input, "## Section ##"), Some((3, 4)));
+        assert_eq!(resolve_heading(input, "# Title  "), Some((1, 4)));
+    }
+
+    #[test]
+    fn setext_heading_found() {
+        let input = b"Guide\n=====\n\nArchitecture\n------------\ntext\n\nUsage\n-----\nmore\n";
+        assert_eq!(resolve_heading(input, "## Architecture"), Some((4, 7)));
+        assert_eq!(resolve_heading(input, "# Guide"), Some((1, 10)));
+    }
+
+    #[test]
+    fn setext_underline_in_code_block_ignored() {
+        let input = b"# Doc\n```\nFake\n----\n```\n## Real\n";
+        assert_eq!(resolve_heading(input, "## Fake"), None);
+        assert_eq!(resolve_heading(input, "## Real"), Some((6, 6)));
+    }
+
     #[test]
     fn heading_not_found() {
         let input = b"# Title\nContent\n";
@@ -491,6 +812,61 @@ mod tests {
         assert_eq!(result, Some((1, 4)));
     }
 
+    #[test]
+    fn multiple_sections_each_get_a_slice() {
+        assert_eq!(
+            split_sections("1-2, 5-5,## Install, configure"),
+            ["1-2", "5-5", "## Install, configure"]
+        );
+
+        let input = b"# Doc\nintro\n## Install, configure\nsteps\n## Usage\nrun it\n";
+        let path = Path::new("doc.md");
+        let out = section_view(path, input, "1-2,## Install, configure,40-50", false).unwrap();
+        let headers: Vec<&str> = out.lines().filter(|l| l.starts_with("# doc.md")).collect();
+        assert_eq!(headers.len(), 3, "{out}");
+        assert!(out.contains("intro"), "{out}");
+        assert!(out.contains("steps") && !out.contains("run it"), "{out}");
+        assert!(
+            out.contains("section \"40-50\": range out of bounds"),
+            "{out}"
+        );
+
+        // Hashlines in every slice in edit mode
+        let edit = section_view(path, input, "1-1,5-5", true).unwrap();
+        let anchored: Vec<&str> = edit
+            .lines()
+            .filter(|l| l.contains('|'))
+            .map(|l| l.split_once(':').unwrap().0)
+            .collect();
+        assert_eq!(anchored, ["1", "5"], "{edit}");
+
+        let err = section_view(path, input, "40-50,## Missing", false).unwrap_err();
+        assert!(err.to_string().contains("heading not found"), "{err}");
+    }
+
+    #[test]
+    fn open_ended_ranges() {
+        assert_eq!(parse_range("-50", 200), Some((151, 200)));
+        assert_eq!(parse_range("-50", 20), Some((1, 20)));
+        assert_eq!(parse_range("100-", 200), Some((100, 200)));
+        assert_eq!(parse_range("45-89", 200), Some((45, 89)));
+        assert_eq!(parse_range("-", 200), None);
+        assert_eq!(parse_range("-0", 200), None);
+        assert_eq!(parse_range("0-", 200), None);
+        assert_eq!(parse_range("9-3", 200), None);
+
+        let input = b"one\ntwo\nthree\nfour\n";
+        let path = Path::new("app.log");
+        let tail = section_view(path, input, "-2", false).unwrap();
+        assert!(tail.contains("three") && tail.contains("four"), "{tail}");
+        assert!(!tail.contains("two"), "{tail}");
+        let rest = section_view(path, input, "2-", false).unwrap();
+        assert!(rest.contains("two") && rest.contains("four"), "{rest}");
+        let err = section_view(path, input, "9-", false).unwrap_err();
+        assert!(err.to_string().contains("out of bounds"), "{err}");
+        assert_eq!(split_sections("1-2,-10"), ["1-2", "-10"]);
+    }
+
     #[test]
     fn no_hashes() {
         let input = b"# Heading\ntext\n";
@@ -501,4 +877,205 @@ mod tests {
         // String without hashes
         assert_eq!(resolve_heading(input, "hello"), None);
     }
+
+    #[test]
+    fn lang_override_outlines_extensionless_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("build-script");
+        let mut source = String::new();
+        for i in 0..300 {
+            let _ = writeln!(
+                source,
+                "pub fn step_{i}(input: &str) -> String {{\n    input.repeat({i})\n}}\n"
+            );
+        }
+        fs::write(&path, &source).unwrap();
+        let cache = OutlineCache::new();
+
+        let lang = Lang::parse("Rust");
+        assert_eq!(lang, Some(Lang::Rust));
+        let out = read_file(&path, None, false, lang, &cache, false, None, None, false).unwrap();
+        assert!(out.contains("[outline]"), "{out}");
+        assert!(out.contains("fn step_0("), "{out}");
+        assert!(out.contains("fn step_299("), "{out}");
+        assert!(!out.contains("input.repeat"), "{out}");
+
+        // Without the override the name says nothing: raw lines, not an outline
+        let plain = read_file(&path, None, false, None, &cache, false, None, None, false).unwrap();
+        assert!(plain.contains("input.repeat(0)"), "{plain}");
+        assert_eq!(Lang::parse("rustlang"), None);
+    }
+
+    #[test]
+    fn revision_read_shows_the_old_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, "fn before() {}\n").unwrap();
+        git(&["add", "lib.rs"]);
+        git(&["commit", "-q", "-m", "first"]);
+        fs::write(&path, "fn after() {}\n").unwrap();
+
+        let old = read_revision(&path, "HEAD", None, false, None, false, None, false).unwrap();
+        assert!(old.contains("fn before()"), "{old}");
+        assert!(
+            old.starts_with(&format!("# {} @ HEAD (", path.display())),
+            "{old}"
+        );
+
+        let section =
+            read_revision(&path, "HEAD", Some("1-1"), false, None, false, None, false).unwrap();
+        assert!(section.contains("fn before()"), "{section}");
+
+        let missing = read_revision(
+            &dir.path().join("gone.rs"),
+            "HEAD",
+            None,
+            false,
+            None,
+            false,
+            None,
+            false,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(missing.contains("gone.rs"), "{missing}");
+        let bad_rev = read_revision(&path, "HEAD~5", None, false, None, false, None, false);
+        assert!(bad_rev.is_err());
+        assert!(read_revision(&path, "--output=x", None, false, None, false, None, false).is_err());
+    }
+
+    #[test]
+    fn generated_overrides_and_full_bypass() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.rs");
+        fs::write(
+            &path,
+            "// Auto-generated once, maintained by hand since\nfn schema() {}\n",
+        )
+        .unwrap();
+        let cache = OutlineCache::new();
+        let read = |full, overrides| {
+            read_file(
+                &path, None, full, None, &cache, false, None, overrides, false,
+            )
+            .unwrap()
+        };
+        assert!(read(false, None).contains("[generated — skipped]"));
+        assert!(read(true, None).contains("fn schema()"));
+
+        fs::write(
+            dir.path().join(generated::CONFIG_FILE),
+            "[generated]\nnever = [\"schema.rs\"]\n",
+        )
+        .unwrap();
+        let overrides = generated::Overrides::find(dir.path()).unwrap();
+        assert!(read(false, overrides.as_ref()).contains("fn schema()"));
+    }
+
+    #[test]
+    fn minified_bundle_summarised_unless_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.min.js");
+        let bundle = "var a=function(){return 1};".repeat(4000);
+        fs::write(&path, &bundle).unwrap();
+        let cache = OutlineCache::new();
+
+        let view = read_file(&path, None, false, None, &cache, false, None, None, false).unwrap();
+        assert!(view.contains("[minified]"), "{view}");
+        assert!(view.contains("~4000 declarations"), "{view}");
+        assert!(view.contains("first: a, a, a"), "{view}");
+        assert!(view.len() < 500, "{view}");
+
+        let full = read_file(&path, None, true, None, &cache, false, None, None, false).unwrap();
+        assert!(full.contains(&bundle));
+    }
+
+    #[test]
+    fn budget_shapes_outline_instead_of_cutting_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shapes.rs");
+        let mut source = String::new();
+        for i in 0..60 {
+            let _ = writeln!(
+                source,
+                "/// Shape number {i}.\npub struct Shape{i} {{\n    side: f64,\n}}\n\n\
+                 impl Shape{i} {{\n    pub fn area(&self, scale: f64) -> f64 {{\n        \
+                 self.side * self.side * scale * {i}.0\n    }}\n\n    \
+                 pub fn perimeter(&self) -> f64 {{\n        self.side * 4.0\n    }}\n}}\n"
+            );
+        }
+        fs::write(&path, &source).unwrap();
+        let cache = OutlineCache::new();
+        let unbudgeted =
+            read_file(&path, None, false, None, &cache, false, None, None, false).unwrap();
+        assert!(
+            unbudgeted.contains("pub fn perimeter(&self)"),
+            "{unbudgeted}"
+        );
+
+        // Room for everything: the same outline
+        let roomy = read_file(
+            &path,
+            None,
+            false,
+            None,
+            &cache,
+            false,
+            Some(100_000),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(roomy, unbudgeted);
+
+        // Members fold into counts, signatures drop
+        let folded = read_file(
+            &path,
+            None,
+            false,
+            None,
+            &cache,
+            false,
+            Some(2_000),
+            None,
+            false,
+        )
+        .unwrap();
+        assert!(count_tokens(&folded) <= 2_000, "{folded}");
+        assert!(folded.contains("impl Shape0  (+2 members)"), "{folded}");
+        assert!(folded.contains("Shape59"), "{folded}");
+        assert!(!folded.contains("perimeter"), "{folded}");
+
+        // Too tight even for that: whole entries, then a count of the rest
+        let tight = read_file(
+            &path,
+            None,
+            false,
+            None,
+            &cache,
+            false,
+            Some(200),
+            None,
+            false,
+        )
+        .unwrap();
+        assert!(count_tokens(&tight) <= 200, "{tight}");
+        assert!(
+            tight.contains("more entries (outline trimmed to budget)"),
+            "{tight}"
+        );
+        assert!(crate::budget::apply(&tight, 200).output == tight);
+    }
 }
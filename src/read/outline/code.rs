@@ -1,10 +1,24 @@
-use crate::types::{Lang, OutlineEntry, OutlineKind};
+use std::fmt::Write as _;
+
+use crate::types::{Lang, OutlineEntry, OutlineKind, count_tokens};
 
 /// Generate a code outline using tree-sitter. Walks top-level AST nodes,
-/// emitting signatures without bodies.
-pub fn outline(content: &str, lang: Lang, max_lines: usize) -> String {
+/// emitting signatures without bodies, shrunk to fit `budget` tokens when one
+/// is given. With `dedup_variants`, identical `#[cfg]`/`#ifdef` copies of a
+/// declaration collapse into one entry.
+pub fn outline_within(
+    content: &str,
+    lang: Lang,
+    max_lines: usize,
+    budget: Option<u64>,
+    dedup_variants: bool,
+) -> String {
     let Some(language) = outline_language(lang) else {
-        return fallback_outline(content, max_lines);
+        return match lang {
+            Lang::Make => super::makefile::outline(content, max_lines),
+            Lang::Dockerfile => super::dockerfile::outline(content, max_lines),
+            _ => fallback_outline(content, max_lines),
+        };
     };
 
     let Some(tree) = crate::search::treesitter::parse_tree(content, &language) else {
@@ -15,7 +29,10 @@ pub fn outline(content: &str, lang: Lang, max_lines: usize) -> String {
     let lines: Vec<&str> = content.lines().collect();
     let entries = walk_top_level(root, &lines, lang);
 
-    format_entries(&entries, &lines, max_lines)
+    match budget {
+        Some(budget) => format_entries_within(&entries, &lines, max_lines, budget, dedup_variants),
+        None => format_entries(&entries, &lines, max_lines, dedup_variants),
+    }
 }
 
 /// Get the tree-sitter Language for a given Lang variant.
@@ -32,9 +49,13 @@ pub fn outline_language(lang: Lang) -> Option<tree_sitter::Language> {
         Lang::Cpp => tree_sitter_cpp::LANGUAGE,
         Lang::Ruby => tree_sitter_ruby::LANGUAGE,
         Lang::Swift => tree_sitter_swift::LANGUAGE,
+        Lang::Kotlin => tree_sitter_kotlin_ng::LANGUAGE,
+        Lang::CSharp => tree_sitter_c_sharp::LANGUAGE,
         Lang::Zig => tree_sitter_zig::LANGUAGE,
+        Lang::Php => tree_sitter_php::LANGUAGE_PHP,
+        Lang::Shell => tree_sitter_bash::LANGUAGE,
         // Languages without shipped grammars — fall back
-        Lang::Kotlin | Lang::CSharp | Lang::Dockerfile | Lang::Make => {
+        Lang::Dockerfile | Lang::Make => {
             return None;
         }
     };
@@ -51,7 +72,19 @@ pub(crate) fn walk_top_level(
     let mut cursor = root.walk();
 
     for child in root.children(&mut cursor) {
-        if let Some(entry) = node_to_entry(child, lines, lang, 0) {
+        if matches!(lang, Lang::C | Lang::Cpp) && is_preproc_conditional(child.kind()) {
+            // Include guards and `#ifdef` blocks wrap top-level declarations
+            entries.extend(walk_top_level(child, lines, lang));
+        } else if matches!(
+            child.kind(),
+            "namespace_declaration" | "namespace_definition"
+        ) && let Some(body) = child.child_by_field_name("body")
+        {
+            // `namespace X { ... }` (C#, C++, PHP) wraps the whole file: list
+            // it, then its types at top level so their members still show
+            entries.extend(node_to_entry(child, lines, lang, 0));
+            entries.extend(walk_top_level(body, lines, lang));
+        } else if let Some(entry) = node_to_entry(child, lines, lang, 0) {
             entries.push(entry);
         }
     }
@@ -59,6 +92,13 @@ pub(crate) fn walk_top_level(
     entries
 }
 
+fn is_preproc_conditional(kind: &str) -> bool {
+    matches!(
+        kind,
+        "preproc_if" | "preproc_ifdef" | "preproc_else" | "preproc_elif" | "preproc_elifdef"
+    )
+}
+
 /// Convert a tree-sitter node to an `OutlineEntry` based on its kind.
 fn node_to_entry(
     node: tree_sitter::Node,
@@ -68,7 +108,16 @@ fn node_to_entry(
 ) -> Option<OutlineEntry> {
     let kind_str = node.kind();
     let start_line = node.start_position().row as u32 + 1;
-    let end_line = node.end_position().row as u32 + 1;
+    // Preprocessor nodes own their trailing newline, ending at column 0 below
+    let end = node.end_position();
+    let end_line = if kind_str.starts_with("preproc_")
+        && end.column == 0
+        && end.row > node.start_position().row
+    {
+        end.row as u32
+    } else {
+        end.row as u32 + 1
+    };
 
     let (kind, name, signature) = match kind_str {
         // Functions
@@ -76,7 +125,11 @@ fn node_to_entry(
         | "function_definition"
         | "function_item"
         | "method_definition"
-        | "method_declaration" => {
+        | "method_declaration"
+        | "method_signature"
+        | "abstract_method_signature"
+        | "method"
+        | "singleton_method" => {
             let name = find_child_text(node, "name", lines)
                 .or_else(|| find_child_text(node, "identifier", lines))
                 .unwrap_or_else(|| "<anonymous>".into());
@@ -91,23 +144,51 @@ fn node_to_entry(
                 .unwrap_or_else(|| "<anonymous>".into());
             // Swift uses class_declaration for class, struct, enum, extension, actor.
             // Disambiguate by checking the first keyword child.
-            let kind = if lang == Lang::Swift {
-                swift_class_kind(node)
-            } else {
-                OutlineKind::Class
+            let kind = match lang {
+                Lang::Swift => swift_class_kind(node),
+                Lang::Kotlin => kotlin_class_kind(node),
+                _ => OutlineKind::Class,
             };
             (kind, name, None)
         }
+        // Ruby classes (in other grammars `class` is only a keyword token)
+        "class" if lang == Lang::Ruby => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<anonymous>".into());
+            (OutlineKind::Class, name, None)
+        }
+        // Kotlin singletons: `object Registry`, `companion object`
+        "object_declaration" => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<object>".into());
+            (OutlineKind::Class, name, None)
+        }
+        "companion_object" => {
+            let name =
+                find_child_text(node, "name", lines).unwrap_or_else(|| "companion object".into());
+            (OutlineKind::Class, name, None)
+        }
+        // C# records are classes with value equality
+        "record_declaration" => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<anonymous>".into());
+            (OutlineKind::Class, name, None)
+        }
         "struct_item" | "struct_declaration" => {
             let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<anonymous>".into());
             (OutlineKind::Struct, name, None)
         }
 
         // Interfaces & types
-        "interface_declaration" | "type_alias_declaration" | "protocol_declaration" => {
+        "interface_declaration"
+        | "type_alias_declaration"
+        | "protocol_declaration"
+        | "trait_declaration" => {
             let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<anonymous>".into());
             (OutlineKind::Interface, name, None)
         }
+        // Kotlin `typealias Handler = ...` names the alias in its `type` field
+        "type_alias" => {
+            let name = find_child_text(node, "type", lines).unwrap_or_else(|| "<anonymous>".into());
+            (OutlineKind::TypeAlias, name, None)
+        }
         "type_item" | "typealias_declaration" => {
             let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<anonymous>".into());
             (OutlineKind::TypeAlias, name, None)
@@ -126,13 +207,23 @@ fn node_to_entry(
         }
 
         // Constants and variables
+        // PHP class constants: `const PER_PAGE = 20;`
+        "const_declaration" => {
+            let name = crate::search::treesitter::extract_definition_name(node, lines)
+                .unwrap_or_else(|| "<const>".into());
+            (OutlineKind::Constant, name, None)
+        }
         "const_item" | "static_item" => {
             let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<const>".into());
             (OutlineKind::Constant, name, None)
         }
         "lexical_declaration" => {
-            let name = first_identifier_text(node, lines).unwrap_or_else(|| "<var>".into());
-            (OutlineKind::Variable, name, None)
+            if let Some((name, sig)) = arrow_function(node, node, lines) {
+                (OutlineKind::Function, name, Some(sig))
+            } else {
+                let name = first_identifier_text(node, lines).unwrap_or_else(|| "<var>".into());
+                (OutlineKind::Variable, name, None)
+            }
         }
         "variable_declaration" => {
             let name = first_identifier_text(node, lines).unwrap_or_else(|| "<var>".into());
@@ -161,15 +252,37 @@ fn node_to_entry(
             (OutlineKind::Variable, name, None)
         }
 
+        // C# constructors and delegates
+        "constructor_declaration" => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<ctor>".into());
+            let sig = extract_signature(node, lines);
+            (OutlineKind::Function, name, Some(sig))
+        }
+        "delegate_declaration" => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<delegate>".into());
+            let sig = extract_signature(node, lines);
+            (OutlineKind::TypeAlias, name, Some(sig))
+        }
+        "field_declaration" if lang == Lang::CSharp => {
+            let name = crate::search::treesitter::extract_definition_name(node, lines)
+                .unwrap_or_else(|| "<field>".into());
+            (OutlineKind::Variable, name, None)
+        }
+
         // Swift init declarations
         "init_declaration" => {
             let sig = extract_signature(node, lines);
             (OutlineKind::Function, "init".into(), Some(sig))
         }
 
-        // Swift property declarations
+        // Swift, Kotlin, C# and PHP property declarations
         "property_declaration" => {
-            let name = first_identifier_text(node, lines).unwrap_or_else(|| "<prop>".into());
+            let name = if matches!(lang, Lang::Kotlin | Lang::CSharp | Lang::Php) {
+                crate::search::treesitter::extract_definition_name(node, lines)
+            } else {
+                first_identifier_text(node, lines)
+            }
+            .unwrap_or_else(|| "<prop>".into());
             (OutlineKind::Variable, name, None)
         }
 
@@ -182,20 +295,63 @@ fn node_to_entry(
             (OutlineKind::TestCase, name, Some(sig))
         }
 
+        // C/C++ macros: `#define FOO 1`, `#define MAX(a, b) ...`
+        "preproc_def" => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<macro>".into());
+            (OutlineKind::Macro, name, None)
+        }
+        "preproc_function_def" => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<macro>".into());
+            let params = find_child_text(node, "parameters", lines).unwrap_or_default();
+            let sig = format!("#define {name}{params}");
+            (OutlineKind::Macro, name, Some(sig))
+        }
+
+        // C/C++ `typedef struct { ... } Bar;`, `typedef int (*cb_t)(int);`
+        "type_definition" => {
+            let name = crate::search::treesitter::typedef_name(node, lines)
+                .unwrap_or_else(|| "<typedef>".into());
+            (OutlineKind::TypeAlias, name, None)
+        }
+
         // Imports — collect as a group
         "import_statement"
         | "import_declaration"
         | "use_declaration"
         | "use_item"
-        | "using_namespace_declaration" => {
+        | "using_namespace_declaration"
+        | "using_directive"
+        | "namespace_use_declaration"
+        | "import" => {
             let text = node_text(node, lines);
             (OutlineKind::Import, text, None)
         }
 
-        // Exports
+        // Exports — `export const f = (...) => ...` is a function, not a bare export
         "export_statement" => {
-            let name = node_text(node, lines);
-            (OutlineKind::Export, name, None)
+            if let Some(decl) = node.child_by_field_name("declaration")
+                && decl.kind() == "lexical_declaration"
+                && let Some((name, sig)) = arrow_function(decl, node, lines)
+            {
+                (OutlineKind::Function, name, Some(sig))
+            } else {
+                let name = node_text(node, lines);
+                (OutlineKind::Export, name, None)
+            }
+        }
+
+        // C#, C++ and PHP namespaces; their members are listed by `walk_top_level`
+        "namespace_declaration" | "file_scoped_namespace_declaration" | "namespace_definition" => {
+            let name = find_child_text(node, "name", lines).unwrap_or_else(|| "<namespace>".into());
+            return Some(OutlineEntry {
+                kind: OutlineKind::Module,
+                name,
+                start_line,
+                end_line,
+                signature: None,
+                children: Vec::new(),
+                doc: None,
+            });
         }
 
         // Module declarations
@@ -246,10 +402,14 @@ fn collect_children(
     let mut children = Vec::new();
     let mut cursor = node.walk();
 
-    // Look for a body node first
-    let body = node
-        .children(&mut cursor)
-        .find(|c| c.kind().contains("body") || c.kind().contains("block"));
+    // Look for a body node first. Rust impl/trait/mod, C# namespace/class and
+    // PHP class bodies are `declaration_list`; elsewhere that kind is no body
+    let body = node.children(&mut cursor).find(|c| {
+        c.kind().contains("body")
+            || c.kind().contains("block")
+            || (c.kind() == "declaration_list"
+                && matches!(lang, Lang::Rust | Lang::CSharp | Lang::Php))
+    });
 
     let parent = body.unwrap_or(node);
     let mut cursor2 = parent.walk();
@@ -263,8 +423,30 @@ fn collect_children(
     children
 }
 
-/// Extract the first line as a function signature (name + params + return type).
+/// Longest signature kept before truncating with `...`.
+const MAX_SIGNATURE_LEN: usize = 200;
+
+/// Extract a function signature (name + params + return type).
+///
+/// Nodes with a `body` field take everything before the body, so parameter
+/// lists, generics and return types that wrap across lines are kept whole.
+/// Body-less nodes (interface method signatures) take their full text.
 fn extract_signature(node: tree_sitter::Node, lines: &[&str]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map_or_else(|| node.end_position(), |body| body.start_position());
+    if end.row > node.start_position().row {
+        let sig = span_text(node.start_position(), end, lines);
+        let sig = sig.trim_end_matches(['{', ':', ';']).trim_end();
+        if !sig.is_empty() {
+            return cap_signature(sig);
+        }
+    }
+    first_line_signature(node, lines)
+}
+
+/// Single-line signature: the node's first line, cut at `{` or a trailing `:`.
+fn first_line_signature(node: tree_sitter::Node, lines: &[&str]) -> String {
     let start_row = node.start_position().row;
     if start_row < lines.len() {
         let line = lines[start_row].trim();
@@ -289,6 +471,73 @@ fn extract_signature(node: tree_sitter::Node, lines: &[&str]) -> String {
     }
 }
 
+/// Name and signature for a `const f = (...) => ...` declaration (arrow function
+/// or function expression). The signature runs from `sig_start` (the `export`
+/// keyword, if any) up to the function body, including type annotations.
+fn arrow_function(
+    decl: tree_sitter::Node,
+    sig_start: tree_sitter::Node,
+    lines: &[&str],
+) -> Option<(String, String)> {
+    let mut cursor = decl.walk();
+    let declarator = decl
+        .children(&mut cursor)
+        .find(|c| c.kind() == "variable_declarator")?;
+    let value = declarator.child_by_field_name("value")?;
+    if !matches!(
+        value.kind(),
+        "arrow_function" | "function_expression" | "function"
+    ) {
+        return None;
+    }
+    let name = find_child_text(declarator, "name", lines)?;
+    let end = value
+        .child_by_field_name("body")
+        .map_or_else(|| value.end_position(), |body| body.start_position());
+    let sig = span_text(sig_start.start_position(), end, lines);
+    let sig = sig.trim_end_matches(['{', ';']).trim_end();
+    Some((name, cap_signature(sig)))
+}
+
+/// Source text between two positions, whitespace-collapsed onto one line.
+fn span_text(start: tree_sitter::Point, end: tree_sitter::Point, lines: &[&str]) -> String {
+    let mut parts = Vec::new();
+    for (row, line) in lines.iter().enumerate().take(end.row + 1).skip(start.row) {
+        let from = if row == start.row {
+            start.column.min(line.len())
+        } else {
+            0
+        };
+        let to = if row == end.row {
+            end.column.min(line.len())
+        } else {
+            line.len()
+        };
+        if let Some(part) = line.get(from..to.max(from)) {
+            parts.push(part);
+        }
+    }
+    parts
+        .iter()
+        .flat_map(|p| p.split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ")
+        // Undo the space introduced where a parameter list was wrapped
+        .replace("( ", "(")
+        .replace(" )", ")")
+}
+
+fn cap_signature(sig: &str) -> String {
+    if sig.len() > MAX_SIGNATURE_LEN {
+        format!(
+            "{}...",
+            crate::types::truncate_str(sig, MAX_SIGNATURE_LEN - 3)
+        )
+    } else {
+        sig.to_string()
+    }
+}
+
 /// Find a named child and return its text.
 fn find_child_text(node: tree_sitter::Node, field: &str, lines: &[&str]) -> Option<String> {
     node.child_by_field_name(field).map(|n| node_text(n, lines))
@@ -363,6 +612,10 @@ fn extract_doc(node: tree_sitter::Node, lines: &[&str]) -> Option<String> {
     let kind = prev.kind();
     if kind.contains("comment") || kind.contains("doc") {
         let text = node_text(prev, lines);
+        // A script's `#!` line is a comment to the grammar, not documentation
+        if text.starts_with("#!") {
+            return None;
+        }
         let trimmed = text
             .trim_start_matches("///")
             .trim_start_matches("//!")
@@ -380,11 +633,16 @@ fn extract_doc(node: tree_sitter::Node, lines: &[&str]) -> Option<String> {
 }
 
 /// Format outline entries into the spec'd output format.
-fn format_entries(entries: &[OutlineEntry], _lines: &[&str], max_lines: usize) -> String {
+fn format_entries(
+    entries: &[OutlineEntry],
+    _lines: &[&str],
+    max_lines: usize,
+    dedup_variants: bool,
+) -> String {
     let mut out = Vec::new();
     let mut import_groups: Vec<&str> = Vec::new();
 
-    for entry in entries {
+    for (entry, also) in collapse_variants(entries, dedup_variants) {
         if out.len() >= max_lines {
             break;
         }
@@ -403,13 +661,13 @@ fn format_entries(entries: &[OutlineEntry], _lines: &[&str], max_lines: usize) -
             }
         }
 
-        out.push(format_entry(entry, 0));
+        out.push(format_entry(entry, 0, &also));
 
-        for child in &entry.children {
+        for (child, child_also) in collapse_variants(&entry.children, dedup_variants) {
             if out.len() >= max_lines {
                 break;
             }
-            out.push(format_entry(child, 1));
+            out.push(format_entry(child, 1, &child_also));
         }
     }
 
@@ -421,6 +679,113 @@ fn format_entries(entries: &[OutlineEntry], _lines: &[&str], max_lines: usize) -
     out.join("\n")
 }
 
+/// Format entries within `budget` tokens, dropping detail rather than cutting
+/// mid-entry: the full outline if it fits, else top-level entries only — no
+/// signatures or docs, members folded into a count — else as many of those as
+/// fit and a count of the rest.
+fn format_entries_within(
+    entries: &[OutlineEntry],
+    lines: &[&str],
+    max_lines: usize,
+    budget: u64,
+    dedup_variants: bool,
+) -> String {
+    let full = format_entries(entries, lines, max_lines, dedup_variants);
+    if count_tokens(&full) <= budget {
+        return full;
+    }
+
+    let mut brief = Vec::new();
+    let mut import_groups: Vec<&str> = Vec::new();
+    for (entry, also) in collapse_variants(entries, dedup_variants) {
+        if entry.kind == OutlineKind::Import {
+            import_groups.push(&entry.name);
+            continue;
+        }
+        if !import_groups.is_empty() {
+            brief.push(format_imports(&import_groups, entries.first()));
+            import_groups.clear();
+        }
+        brief.push(format_entry_brief(entry, &also));
+    }
+    if !import_groups.is_empty() {
+        brief.push(format_imports(&import_groups, entries.first()));
+    }
+    brief.truncate(max_lines);
+
+    let joined = brief.join("\n");
+    if count_tokens(&joined) <= budget {
+        return joined;
+    }
+
+    // Room for the closing `... N more entries` line
+    let mut used = 16;
+    let mut kept = Vec::new();
+    for line in &brief {
+        let tokens = count_tokens(line) + 1;
+        if used + tokens > budget {
+            break;
+        }
+        used += tokens;
+        kept.push(line.as_str());
+    }
+    let omitted = brief.len() - kept.len();
+    kept.push("");
+    let mut out = kept.join("\n");
+    let _ = write!(
+        out,
+        "... {omitted} more entries (outline trimmed to budget)"
+    );
+    out
+}
+
+/// One line per entry: range, kind and name, with members folded into a count.
+fn format_entry_brief(entry: &OutlineEntry, also: &[(u32, u32)]) -> String {
+    let range = super::fmt_range(entry.start_line, entry.end_line);
+    let also = super::also_suffix(also);
+    let members = match entry.children.len() {
+        0 => String::new(),
+        1 => "  (+1 member)".to_string(),
+        n => format!("  (+{n} members)"),
+    };
+    format!(
+        "{range:<12} {} {}{also}{members}",
+        kind_label(entry.kind),
+        entry.name
+    )
+}
+
+/// Collapse entries that declare the same thing twice — `#[cfg]`/`#ifdef`
+/// variants with identical name, kind and signature. Each survivor carries the
+/// line ranges of the copies it absorbed. Overloads whose signatures differ
+/// stay separate, and so does anything with members: two `impl Foo` blocks
+/// share a signature but not their methods. Off unless `enabled`, every entry
+/// is its own.
+fn collapse_variants(
+    entries: &[OutlineEntry],
+    enabled: bool,
+) -> Vec<(&OutlineEntry, Vec<(u32, u32)>)> {
+    let mut out: Vec<(&OutlineEntry, Vec<(u32, u32)>)> = Vec::with_capacity(entries.len());
+    let is_leaf = |e: &OutlineEntry| e.kind != OutlineKind::Import && e.children.is_empty();
+    for entry in entries {
+        let original = (enabled && is_leaf(entry))
+            .then(|| {
+                out.iter_mut().find(|(e, _)| {
+                    is_leaf(e)
+                        && e.kind == entry.kind
+                        && e.name == entry.name
+                        && e.signature == entry.signature
+                })
+            })
+            .flatten();
+        match original {
+            Some((_, also)) => also.push((entry.start_line, entry.end_line)),
+            None => out.push((entry, Vec::new())),
+        }
+    }
+    out
+}
+
 /// Format a collapsed import summary grouped by source with counts.
 /// Spec format: `imports: react(4), express(2), @/lib(3)`
 fn format_imports(imports: &[&str], first_entry: Option<&OutlineEntry>) -> String {
@@ -515,32 +880,14 @@ pub(crate) fn extract_import_source(text: &str) -> String {
         .to_string()
 }
 
-/// Format a single outline entry with optional indentation.
-fn format_entry(entry: &OutlineEntry, indent: usize) -> String {
+/// Format a single outline entry with optional indentation. `also` lists the
+/// ranges of collapsed duplicates (see `collapse_variants`).
+fn format_entry(entry: &OutlineEntry, indent: usize, also: &[(u32, u32)]) -> String {
     let prefix = "  ".repeat(indent);
-    let range = if entry.start_line == entry.end_line {
-        format!("[{}]", entry.start_line)
-    } else {
-        format!("[{}-{}]", entry.start_line, entry.end_line)
-    };
+    let range = super::fmt_range(entry.start_line, entry.end_line);
+    let also = super::also_suffix(also);
 
-    let kind_label = match entry.kind {
-        OutlineKind::Function => "fn",
-        OutlineKind::Method => "method",
-        OutlineKind::Class => "class",
-        OutlineKind::Struct => "struct",
-        OutlineKind::Interface => "interface",
-        OutlineKind::TypeAlias => "type",
-        OutlineKind::Enum => "enum",
-        OutlineKind::Constant => "const",
-        OutlineKind::Variable => "let",
-        OutlineKind::Export => "export",
-        OutlineKind::Property => "prop",
-        OutlineKind::Module => "mod",
-        OutlineKind::Import => "import",
-        OutlineKind::TestSuite => "suite",
-        OutlineKind::TestCase => "test",
-    };
+    let kind_label = kind_label(entry.kind);
 
     let sig = match &entry.signature {
         Some(s) => format!("\n{prefix}           {s}"),
@@ -559,7 +906,31 @@ fn format_entry(entry: &OutlineEntry, indent: usize) -> String {
         None => String::new(),
     };
 
-    format!("{prefix}{range:<12} {kind_label} {}{sig}{doc}", entry.name)
+    format!(
+        "{prefix}{range:<12} {kind_label} {}{also}{sig}{doc}",
+        entry.name
+    )
+}
+
+fn kind_label(kind: OutlineKind) -> &'static str {
+    match kind {
+        OutlineKind::Function => "fn",
+        OutlineKind::Method => "method",
+        OutlineKind::Class => "class",
+        OutlineKind::Struct => "struct",
+        OutlineKind::Interface => "interface",
+        OutlineKind::TypeAlias => "type",
+        OutlineKind::Enum => "enum",
+        OutlineKind::Constant => "const",
+        OutlineKind::Variable => "let",
+        OutlineKind::Macro => "macro",
+        OutlineKind::Export => "export",
+        OutlineKind::Property => "prop",
+        OutlineKind::Module => "mod",
+        OutlineKind::Import => "import",
+        OutlineKind::TestSuite => "suite",
+        OutlineKind::TestCase => "test",
+    }
 }
 
 /// Determine the `OutlineKind` for a Swift `class_declaration` node.
@@ -580,6 +951,20 @@ fn swift_class_kind(node: tree_sitter::Node) -> OutlineKind {
     OutlineKind::Class
 }
 
+/// Determine the `OutlineKind` for a Kotlin `class_declaration` node, which
+/// covers `class`, `interface`, `enum class` and `data class`.
+fn kotlin_class_kind(node: tree_sitter::Node) -> OutlineKind {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "interface" => return OutlineKind::Interface,
+            "enum_class_body" => return OutlineKind::Enum,
+            _ => {}
+        }
+    }
+    OutlineKind::Class
+}
+
 /// Determine the `OutlineKind` for a Zig `variable_declaration` node.
 ///
 /// In Zig, types are anonymous: `const Point = struct { ... };` is a
@@ -611,6 +996,95 @@ fn fallback_outline(content: &str, _max_lines: usize) -> String {
 mod tests {
     use super::*;
 
+    fn outline(content: &str, lang: Lang, max_lines: usize) -> String {
+        outline_within(content, lang, max_lines, None, false)
+    }
+
+    /// With `dedup_variants`, `#[cfg(unix)]`/`#[cfg(windows)]` copies of one
+    /// function collapse into a single entry listing both ranges; same-named
+    /// functions whose signatures differ per platform stay separate.
+    #[test]
+    fn cfg_variants_collapse_to_one_entry() {
+        let content = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/cfg-outline/platform.rs"
+        ))
+        .unwrap();
+        let plain = outline(&content, Lang::Rust, 100);
+        assert!(!plain.contains("(also"), "off by default: {plain}");
+
+        let result = outline_within(&content, Lang::Rust, 100, None, true);
+        let entries = |name: &str| {
+            result
+                .lines()
+                .filter(|l| l.starts_with('[') && l.contains(&format!(" fn {name}")))
+                .count()
+        };
+
+        assert_eq!(entries("home_dir"), 1, "{result}");
+        assert!(
+            result.contains("[8-10]       fn home_dir  (also [14-16])"),
+            "{result}"
+        );
+        assert_eq!(
+            entries("open_raw"),
+            2,
+            "distinct signatures must not collapse: {result}"
+        );
+        assert!(!result.contains("open_raw  (also"), "{result}");
+    }
+
+    /// Two `impl` blocks for one type share a signature but not their methods;
+    /// collapsing them would hide the second block's members.
+    #[test]
+    fn dedup_keeps_separate_impl_blocks() {
+        let content = "struct Foo;\n\n\
+                       impl Foo {\n    fn first(&self) {}\n}\n\n\
+                       impl Foo {\n    fn second(&self) {}\n}\n";
+        let result = outline_within(content, Lang::Rust, 100, None, true);
+        assert!(result.contains("fn first"), "{result}");
+        assert!(result.contains("fn second"), "{result}");
+        assert!(!result.contains("(also"), "{result}");
+    }
+
+    /// Arrow functions assigned to consts are functions in the outline, and
+    /// their signature keeps the full multi-line generics, parameter types and
+    /// return type — not just `export const withRetry = async <TInput extends object, TOutput>(`.
+    #[test]
+    fn ts_arrow_function_full_signature() {
+        let content = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/mini-ts/src/handlers.ts"
+        ))
+        .unwrap();
+        let result = outline(&content, Lang::TypeScript, 200);
+
+        assert!(result.contains("fn withRetry"), "{result}");
+        assert!(
+            result.contains(
+                "export const withRetry = async <TInput extends object, TOutput>(handler: \
+                 (input: TInput) => Promise<TOutput>, attempts: number = 3,): \
+                 Promise<TOutput | undefined> =>"
+            ),
+            "full arrow signature: {result}"
+        );
+        assert!(
+            result.contains(
+                "const isString = (schema: ZodString, value: unknown): value is string =>"
+            ),
+            "non-exported arrow with type predicate: {result}"
+        );
+        // Plain consts stay variables
+        assert!(!result.contains("fn DEFAULT_ATTEMPTS"), "{result}");
+        // Interface method signatures spanning lines are captured whole
+        assert!(
+            result.contains(
+                "register<T extends object>(name: string, handler: Handler<T>,): () => void"
+            ),
+            "interface method signature: {result}"
+        );
+    }
+
     #[test]
     fn swift_outline_covers_all_declaration_types() {
         let swift_code = r#"import Foundation
@@ -709,6 +1183,134 @@ func globalFunction(name: String) -> Bool {
         );
     }
 
+    #[test]
+    fn kotlin_outline_covers_declaration_types() {
+        let kotlin_code = r#"package com.example.app
+
+import androidx.lifecycle.ViewModel
+
+class LoginViewModel(private val repo: AuthRepository) : ViewModel() {
+    val state = repo.state
+
+    fun login(user: String) {
+        repo.signIn(user)
+    }
+
+    companion object {
+        const val TAG = "Login"
+    }
+}
+
+interface AuthRepository {
+    fun signIn(user: String)
+}
+
+object Registry {
+    fun lookup(name: String): Any? = null
+}
+
+enum class Mode { LIGHT, DARK }
+
+typealias Handler = (String) -> Unit
+
+val defaultMode = Mode.LIGHT
+"#;
+        let result = outline(kotlin_code, Lang::Kotlin, 100);
+
+        assert!(result.contains("imports:"), "{result}");
+        assert!(result.contains("class LoginViewModel"), "{result}");
+        assert!(result.contains("let state"), "{result}");
+        assert!(result.contains("fn login"), "{result}");
+        assert!(result.contains("class companion object"), "{result}");
+        assert!(result.contains("interface AuthRepository"), "{result}");
+        assert!(result.contains("class Registry"), "{result}");
+        assert!(result.contains("enum Mode"), "{result}");
+        assert!(result.contains("type Handler"), "{result}");
+        // The name, not the initializer's identifier
+        assert!(result.contains("let defaultMode"), "{result}");
+    }
+
+    #[test]
+    fn csharp_outline_lists_namespace_members() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/mini-csharp/Orders/OrderStore.cs"
+        );
+        let code = std::fs::read_to_string(path).unwrap();
+        let result = outline(&code, Lang::CSharp, 100);
+
+        assert!(result.contains("imports:"), "{result}");
+        assert!(result.contains("mod Shop.Orders"), "{result}");
+        assert!(result.contains("interface IOrderStore"), "{result}");
+        assert!(result.contains("class SqlOrderStore"), "{result}");
+        // Members of a class inside the namespace
+        assert!(result.contains("fn SqlOrderStore"), "{result}");
+        assert!(result.contains("fn Find"), "{result}");
+        assert!(result.contains("let Name"), "{result}");
+        assert!(result.contains("let _conn"), "{result}");
+        assert!(result.contains("struct Money"), "{result}");
+        assert!(result.contains("class Order"), "{result}");
+        assert!(result.contains("enum Status"), "{result}");
+        assert!(result.contains("type Changed"), "{result}");
+    }
+
+    #[test]
+    fn php_outline_covers_declaration_types() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/mini-php/app/Http/Controllers/UserController.php"
+        );
+        let code = std::fs::read_to_string(path).unwrap();
+        let result = outline(&code, Lang::Php, 100);
+        assert!(result.contains("mod App\\Http\\Controllers"), "{result}");
+        assert!(result.contains("imports:"), "{result}");
+        assert!(result.contains("class UserController"), "{result}");
+        assert!(result.contains("let $service"), "{result}");
+        assert!(result.contains("const PER_PAGE"), "{result}");
+        assert!(result.contains("fn __construct"), "{result}");
+        assert!(result.contains("fn show"), "{result}");
+
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/mini-php/app/Services/UserService.php"
+        );
+        let code = std::fs::read_to_string(path).unwrap();
+        let result = outline(&code, Lang::Php, 100);
+        assert!(result.contains("interface Finder"), "{result}");
+        assert!(result.contains("interface Loggable"), "{result}");
+        assert!(result.contains("fn log"), "{result}");
+        assert!(result.contains("class UserService"), "{result}");
+        assert!(result.contains("fn normalize_id"), "{result}");
+    }
+
+    #[test]
+    fn shell_functions_outlined_both_forms() {
+        let code = r#"#!/bin/sh
+
+log() {
+    echo "$*" >&2
+}
+
+# Push the build.
+function upload_artifacts {
+    log "uploading"
+}
+
+cat <<EOF
+fake() { not a function }
+EOF
+"#;
+        let entries = crate::search::callees::get_outline_entries(code, Lang::Shell);
+        let names: Vec<(&str, Option<&str>)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.doc.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            [("log", None), ("upload_artifacts", Some("Push the build."))]
+        );
+    }
+
     #[test]
     fn swift_callee_extraction() {
         let swift_code = r"func example() {
@@ -918,4 +1520,26 @@ pub fn main() !void {}
         );
         assert!(!defs.is_empty(), "should find 'main' definition");
     }
+
+    #[test]
+    fn c_macros_and_typedefs_outlined() {
+        let content = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/mini-c/buffer.h"
+        ))
+        .unwrap();
+        let result = outline(&content, Lang::C, 100);
+
+        assert!(result.contains("macro BUFFER_DEFAULT_CAPACITY"), "{result}");
+        assert!(result.contains("macro BUFFER_MAX"), "{result}");
+        assert!(result.contains("#define BUFFER_MAX(a, b)"), "{result}");
+        assert!(result.contains("[9-13]"), "{result}");
+        assert!(result.contains("type Buffer"), "{result}");
+        assert!(result.contains("type buffer_free_fn"), "{result}");
+        // Everything above sits inside the include guard; each macro is one line
+        assert!(
+            result.contains("[6]          macro BUFFER_DEFAULT_CAPACITY"),
+            "{result}"
+        );
+    }
 }
@@ -0,0 +1,171 @@
+//! Dockerfile outline: one entry per build stage (`FROM ... AS name`) with its
+//! line range, and under it the instructions that shape the image — `RUN`,
+//! `COPY`, `ENV`, `EXPOSE`, `ENTRYPOINT`, `CMD`. Continuation lines (`\`)
+//! belong to the instruction they continue.
+
+use std::fmt::Write;
+
+use super::fmt_range;
+
+/// Instructions listed under their stage.
+const SHOWN: &[&str] = &["RUN", "COPY", "ENV", "EXPOSE", "ENTRYPOINT", "CMD"];
+
+/// Longest instruction argument shown before it's cut.
+const ARGS_CAP: usize = 60;
+
+/// One instruction, possibly spanning continuation lines.
+struct Instruction {
+    keyword: String,
+    args: String,
+    start: u32,
+    end: u32,
+}
+
+/// A build stage: the `AS` name if given, its base image, line range.
+pub(crate) struct Stage {
+    pub name: Option<String>,
+    pub image: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+fn instructions(content: &str) -> Vec<Instruction> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.is_empty() || line.starts_with('#') {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut text = line.trim_end_matches('\\').trim().to_string();
+        while lines[i].trim_end().ends_with('\\') && i + 1 < lines.len() {
+            i += 1;
+            let next = lines[i].trim();
+            if !next.starts_with('#') {
+                text.push(' ');
+                text.push_str(next.trim_end_matches('\\').trim());
+            }
+        }
+        let (keyword, args) = text.split_once(char::is_whitespace).unwrap_or((&text, ""));
+        out.push(Instruction {
+            keyword: keyword.to_ascii_uppercase(),
+            args: args.trim().to_string(),
+            start: (start + 1) as u32,
+            end: (i + 1) as u32,
+        });
+        i += 1;
+    }
+    out
+}
+
+/// Every `FROM` stage in `content`, each running until the next `FROM`.
+pub(crate) fn stages(content: &str) -> Vec<Stage> {
+    let instructions = instructions(content);
+    let mut out: Vec<Stage> = Vec::new();
+    for ins in &instructions {
+        if ins.keyword == "FROM" {
+            let words: Vec<&str> = ins.args.split_whitespace().collect();
+            // `FROM [--platform=...] image [AS name]`
+            let image = words.iter().find(|w| !w.starts_with("--")).copied();
+            let name = words
+                .iter()
+                .position(|w| w.eq_ignore_ascii_case("as"))
+                .and_then(|i| words.get(i + 1))
+                .map(|n| (*n).to_string());
+            out.push(Stage {
+                name,
+                image: image.unwrap_or("").to_string(),
+                start: ins.start,
+                end: ins.end,
+            });
+        } else if let Some(stage) = out.last_mut() {
+            stage.end = ins.end;
+        }
+    }
+    out
+}
+
+pub fn outline(content: &str, max_lines: usize) -> String {
+    let instructions = instructions(content);
+    let stages = stages(content);
+    if stages.is_empty() {
+        return super::fallback::head_tail(content);
+    }
+
+    let mut lines = Vec::new();
+    for stage in &stages {
+        let name = stage.name.as_deref().unwrap_or("(unnamed)");
+        lines.push(format!(
+            "{:<12} stage {name}  FROM {}",
+            fmt_range(stage.start, stage.end),
+            stage.image
+        ));
+        for ins in instructions
+            .iter()
+            .filter(|ins| ins.start > stage.start && ins.end <= stage.end)
+            .filter(|ins| SHOWN.contains(&ins.keyword.as_str()))
+        {
+            let args = if ins.args.len() > ARGS_CAP {
+                format!("{}...", crate::types::truncate_str(&ins.args, ARGS_CAP - 3))
+            } else {
+                ins.args.clone()
+            };
+            lines.push(format!(
+                "  {:<12} {} {args}",
+                fmt_range(ins.start, ins.end),
+                ins.keyword
+            ));
+        }
+    }
+
+    let mut out = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i >= max_lines {
+            let _ = write!(out, "\n... {} more lines", lines.len() - i);
+            break;
+        }
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_and_their_instructions() {
+        let dockerfile = "# syntax=docker/dockerfile:1\n\
+                          FROM --platform=$BUILDPLATFORM golang:1.22 AS builder\n\
+                          WORKDIR /src\n\
+                          ENV CGO_ENABLED=0\n\
+                          COPY . .\n\
+                          RUN go mod download && \\\n\
+                          \x20   go build -o /out/app ./cmd/app\n\
+                          \n\
+                          FROM alpine:3.19\n\
+                          COPY --from=builder /out/app /app\n\
+                          EXPOSE 8080\n\
+                          ENTRYPOINT [\"/app\"]\n";
+        let names: Vec<Option<String>> = stages(dockerfile).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, [Some("builder".to_string()), None]);
+
+        assert_eq!(
+            outline(dockerfile, usize::MAX),
+            "[2-7]        stage builder  FROM golang:1.22\n\
+             \x20 [4]          ENV CGO_ENABLED=0\n\
+             \x20 [5]          COPY . .\n\
+             \x20 [6-7]        RUN go mod download && go build -o /out/app ./cmd/app\n\
+             [9-12]       stage (unnamed)  FROM alpine:3.19\n\
+             \x20 [10]         COPY --from=builder /out/app /app\n\
+             \x20 [11]         EXPOSE 8080\n\
+             \x20 [12]         ENTRYPOINT [\"/app\"]"
+        );
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::Path;
+
+const NOTABLE_CAP: usize = 10;
+
+/// Lockfile formats we can summarize. Detected by file name, never by extension —
+/// `Cargo.lock` is TOML and `package-lock.json` is JSON, but neither should get
+/// the generic structured-data outline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Cargo,
+    Npm,
+    Yarn,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Cargo.lock" => Some(Self::Cargo),
+            "package-lock.json" | "npm-shrinkwrap.json" => Some(Self::Npm),
+            "yarn.lock" => Some(Self::Yarn),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cargo => "cargo",
+            Self::Npm => "npm",
+            Self::Yarn => "yarn",
+        }
+    }
+}
+
+/// Is this a lockfile we know how to summarize?
+pub fn is_lockfile(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(Format::from_name)
+        .is_some()
+}
+
+/// Resolved dependency graph summary, independent of lockfile format.
+#[derive(Debug, Default)]
+struct Summary {
+    /// `name → versions` for every locked third-party package.
+    packages: BTreeMap<String, BTreeSet<String>>,
+    /// `name → versions` a workspace member or the root manifest depends on
+    /// directly, when the lockfile (or its manifest) records them.
+    direct: Option<BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl Summary {
+    fn add(&mut self, name: &str, version: &str) {
+        self.packages
+            .entry(name.to_string())
+            .or_default()
+            .insert(version.to_string());
+    }
+
+    fn add_direct(&mut self, name: &str, version: &str) {
+        self.direct
+            .get_or_insert_default()
+            .entry(name.to_string())
+            .or_default()
+            .insert(version.to_string());
+    }
+
+    fn total(&self) -> usize {
+        self.packages.values().map(BTreeSet::len).sum()
+    }
+
+    /// Direct dependencies that are locked, each version counted once — the
+    /// same unit as [`Summary::total`].
+    fn direct_count(&self) -> Option<usize> {
+        let direct = self.direct.as_ref()?;
+        Some(
+            direct
+                .iter()
+                .filter_map(|(name, versions)| {
+                    let locked = self.packages.get(name)?;
+                    Some(versions.intersection(locked).count())
+                })
+                .sum(),
+        )
+    }
+}
+
+/// Compact dependency summary: package count, direct vs transitive split,
+/// direct dependencies with versions, and packages locked at multiple versions.
+pub fn outline(path: &Path, content: &str) -> String {
+    let Some(format) = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(Format::from_name)
+    else {
+        return super::fallback::head_tail(content);
+    };
+
+    let summary = match format {
+        Format::Cargo => cargo_summary(content),
+        Format::Npm => npm_summary(content),
+        Format::Yarn => Ok(yarn_summary(path, content)),
+    };
+    let summary = match summary {
+        Ok(s) => s,
+        Err(e) => return format!("[parse error: {e}]"),
+    };
+
+    format_summary(format, &summary)
+}
+
+fn format_summary(format: Format, summary: &Summary) -> String {
+    let total = summary.total();
+    let mut out = format!("lockfile: {}\n", format.label());
+
+    match summary.direct_count() {
+        Some(direct_count) => {
+            let _ = write!(
+                out,
+                "packages: {total} ({direct_count} direct, {} transitive)",
+                total.saturating_sub(direct_count)
+            );
+        }
+        None => {
+            let _ = write!(out, "packages: {total}");
+        }
+    }
+
+    // Notable: direct deps when known, otherwise the first few locked packages.
+    let notable: Vec<String> = match &summary.direct {
+        Some(direct) => direct
+            .iter()
+            .filter_map(|(name, versions)| {
+                let locked = summary.packages.get(name)?;
+                let versions: BTreeSet<String> = versions.intersection(locked).cloned().collect();
+                (!versions.is_empty()).then(|| format_versions(name, &versions))
+            })
+            .collect(),
+        None => summary
+            .packages
+            .iter()
+            .map(|(name, versions)| format_versions(name, versions))
+            .collect(),
+    };
+    if !notable.is_empty() {
+        let label = if summary.direct.is_some() {
+            "direct"
+        } else {
+            "packages"
+        };
+        let shown = notable.len().min(NOTABLE_CAP);
+        let _ = write!(out, "\n{label}: {}", notable[..shown].join(", "));
+        if notable.len() > shown {
+            let _ = write!(out, ", ... ({} more)", notable.len() - shown);
+        }
+    }
+
+    let duplicated: Vec<String> = summary
+        .packages
+        .iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(name, versions)| format!("{name}({})", versions.len()))
+        .collect();
+    if !duplicated.is_empty() {
+        let shown = duplicated.len().min(NOTABLE_CAP);
+        let _ = write!(
+            out,
+            "\nmultiple versions: {}",
+            duplicated[..shown].join(", ")
+        );
+        if duplicated.len() > shown {
+            let _ = write!(out, ", ... ({} more)", duplicated.len() - shown);
+        }
+    }
+
+    out
+}
+
+fn format_versions(name: &str, versions: &BTreeSet<String>) -> String {
+    let joined = versions.iter().map(String::as_str).collect::<Vec<_>>();
+    format!("{name} {}", joined.join("/"))
+}
+
+// --- Cargo ---
+
+/// `Cargo.lock`: packages without a `source` are workspace members. Their
+/// `dependencies` lists are the direct dependencies; everything else is transitive.
+/// An entry names its version only when several are locked, so a bare name is
+/// resolved once every package has been seen.
+fn cargo_summary(content: &str) -> Result<Summary, String> {
+    let value: toml::Value = content
+        .parse()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    let packages = value
+        .get("package")
+        .and_then(toml::Value::as_array)
+        .map_or(&[][..], Vec::as_slice);
+
+    let mut summary = Summary::default();
+    let mut members = BTreeSet::new();
+    let mut direct: Vec<(String, Option<String>)> = Vec::new();
+
+    for pkg in packages {
+        let Some(name) = pkg.get("name").and_then(toml::Value::as_str) else {
+            continue;
+        };
+        let version = pkg
+            .get("version")
+            .and_then(toml::Value::as_str)
+            .unwrap_or("?");
+
+        if pkg.get("source").is_some() {
+            summary.add(name, version);
+            continue;
+        }
+
+        members.insert(name.to_string());
+        let deps = pkg
+            .get("dependencies")
+            .and_then(toml::Value::as_array)
+            .map_or(&[][..], Vec::as_slice);
+        for dep in deps.iter().filter_map(toml::Value::as_str) {
+            // Entries are "name", "name version" or "name version (source)"
+            let mut words = dep.split_whitespace();
+            let dep_name = words.next().unwrap_or(dep);
+            direct.push((dep_name.to_string(), words.next().map(str::to_string)));
+        }
+    }
+
+    summary.direct = Some(BTreeMap::new());
+    for (name, version) in direct {
+        // Path dependencies between workspace members are not third-party deps.
+        if members.contains(&name) {
+            continue;
+        }
+        let versions = match version {
+            Some(v) => vec![v],
+            None => summary
+                .packages
+                .get(&name)
+                .map(|locked| locked.iter().cloned().collect())
+                .unwrap_or_default(),
+        };
+        for v in versions {
+            summary.add_direct(&name, &v);
+        }
+    }
+    Ok(summary)
+}
+
+// --- npm ---
+
+/// `package-lock.json`: v2/v3 lockfiles carry a flat `packages` map whose `""`
+/// entry is the root manifest. v1 lockfiles only have the nested `dependencies`
+/// tree, where direct vs hoisted transitive deps can't be told apart.
+fn npm_summary(content: &str) -> Result<Summary, String> {
+    let value: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut summary = Summary::default();
+
+    if let Some(packages) = value.get("packages").and_then(|v| v.as_object()) {
+        for (key, pkg) in packages {
+            // Only installed packages live under node_modules/; other keys are
+            // the root ("") and workspace member directories.
+            let Some(idx) = key.rfind("node_modules/") else {
+                continue;
+            };
+            if pkg.get("link").and_then(serde_json::Value::as_bool) == Some(true) {
+                continue;
+            }
+            let name = pkg
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or(&key[idx + "node_modules/".len()..]);
+            let version = pkg.get("version").and_then(|v| v.as_str()).unwrap_or("?");
+            summary.add(name, version);
+        }
+
+        // A direct dependency is installed at the top of node_modules/
+        if let Some(root) = packages.get("") {
+            summary.direct = Some(BTreeMap::new());
+            for name in manifest_dependencies(root).keys() {
+                if let Some(version) = packages
+                    .get(&format!("node_modules/{name}"))
+                    .and_then(|pkg| pkg.get("version"))
+                    .and_then(|v| v.as_str())
+                {
+                    summary.add_direct(name, version);
+                }
+            }
+        }
+        return Ok(summary);
+    }
+
+    if let Some(deps) = value.get("dependencies").and_then(|v| v.as_object()) {
+        walk_npm_v1(deps, &mut summary);
+    }
+    Ok(summary)
+}
+
+fn walk_npm_v1(deps: &serde_json::Map<String, serde_json::Value>, summary: &mut Summary) {
+    for (name, dep) in deps {
+        let version = dep.get("version").and_then(|v| v.as_str()).unwrap_or("?");
+        summary.add(name, version);
+        if let Some(nested) = dep.get("dependencies").and_then(|v| v.as_object()) {
+            walk_npm_v1(nested, summary);
+        }
+    }
+}
+
+/// `name → requested range` declared by a `package.json`-shaped object.
+fn manifest_dependencies(manifest: &serde_json::Value) -> BTreeMap<String, String> {
+    let mut names = BTreeMap::new();
+    for field in [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "peerDependencies",
+    ] {
+        if let Some(map) = manifest.get(field).and_then(|v| v.as_object()) {
+            names.extend(
+                map.iter()
+                    .map(|(name, range)| (name.clone(), range.as_str().unwrap_or("").to_string())),
+            );
+        }
+    }
+    names
+}
+
+// --- Yarn ---
+
+/// `yarn.lock`: one block per resolved package. Block headers start at column 0
+/// and list the requested specifiers (`"lodash@^4.17.0", lodash@^4.17.21:`);
+/// the indented `version` line holds the resolved version. Direct deps come from
+/// a sibling `package.json` when one exists, each matched to the block that
+/// lists its `name@range` specifier.
+fn yarn_summary(path: &Path, content: &str) -> Summary {
+    let mut summary = Summary::default();
+    let mut current: Option<String> = None;
+    let mut specs: Vec<String> = Vec::new();
+    let mut resolved: BTreeMap<String, String> = BTreeMap::new();
+
+    for line in content.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            specs = line
+                .trim_end_matches(':')
+                .split(',')
+                .map(|spec| spec.trim().trim_matches('"').to_string())
+                .collect();
+            current = specs
+                .first()
+                .map(|spec| yarn_package_name(spec).to_string());
+            continue;
+        }
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed
+            .strip_prefix("version ")
+            .or_else(|| trimmed.strip_prefix("version: "))
+            && let Some(name) = current.take()
+        {
+            let version = rest.trim().trim_matches('"');
+            summary.add(&name, version);
+            for spec in specs.drain(..) {
+                resolved.insert(spec, version.to_string());
+            }
+        }
+    }
+
+    let manifest = path
+        .parent()
+        .map(|dir| dir.join("package.json"))
+        .and_then(|manifest| std::fs::read_to_string(manifest).ok())
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok());
+    if let Some(manifest) = manifest {
+        summary.direct = Some(BTreeMap::new());
+        for (name, range) in manifest_dependencies(&manifest) {
+            // Berry writes specifiers with their protocol: `lodash@npm:^4.17.21`
+            if let Some(version) = resolved
+                .get(&format!("{name}@{range}"))
+                .or_else(|| resolved.get(&format!("{name}@npm:{range}")))
+            {
+                summary.add_direct(&name, version);
+            }
+        }
+    }
+
+    summary
+}
+
+/// `@scope/name@^1.0.0` → `@scope/name`, `lodash@npm:^4.0.0` → `lodash`.
+fn yarn_package_name(spec: &str) -> &str {
+    let search_from = usize::from(spec.starts_with('@'));
+    match spec[search_from..].find('@') {
+        Some(at) => &spec[..search_from + at],
+        None => spec,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/lockfiles")
+            .join(name)
+    }
+
+    #[test]
+    fn detects_lockfiles_by_name_only() {
+        assert!(is_lockfile(Path::new("Cargo.lock")));
+        assert!(is_lockfile(Path::new("web/package-lock.json")));
+        assert!(is_lockfile(Path::new("yarn.lock")));
+        assert!(!is_lockfile(Path::new("package.json")));
+        assert!(!is_lockfile(Path::new("other.lock")));
+    }
+
+    #[test]
+    fn cargo_lock_counts_direct_and_transitive() {
+        let path = fixture("Cargo.lock");
+        let content = std::fs::read_to_string(&path).unwrap();
+        let result = outline(&path, &content);
+
+        // 6 third-party packages: serde, serde_derive, proc-macro2, quote, syn x2
+        assert!(
+            result.contains("packages: 6 (2 direct, 4 transitive)"),
+            "should summarize dependency counts: {result}"
+        );
+        assert!(
+            result.contains("direct: serde 1.0.200, syn 2.0.60\n"),
+            "should list direct deps with versions: {result}"
+        );
+        assert!(
+            result.contains("multiple versions: syn(2)"),
+            "should flag duplicated packages: {result}"
+        );
+        // Workspace members are not dependencies
+        assert!(!result.contains("demo-app"), "members excluded: {result}");
+    }
+
+    #[test]
+    fn package_lock_counts_direct_and_transitive() {
+        let path = fixture("package-lock.json");
+        let content = std::fs::read_to_string(&path).unwrap();
+        let result = outline(&path, &content);
+
+        assert!(
+            result.contains("packages: 5 (2 direct, 3 transitive)"),
+            "should summarize dependency counts: {result}"
+        );
+        assert!(
+            result.contains("direct: express 4.19.2, typescript 5.4.5"),
+            "should list direct deps with versions: {result}"
+        );
+    }
+
+    #[test]
+    fn yarn_direct_deps_match_the_requested_range() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{ "dependencies": { "lodash": "^4.17.21" } }"#,
+        )
+        .unwrap();
+        let lock = "lodash@^3.10.0:\n  version \"3.10.1\"\n\n\
+                    lodash@^4.17.21:\n  version \"4.17.21\"\n";
+        let result = outline(&dir.path().join("yarn.lock"), lock);
+        assert!(
+            result.contains("packages: 2 (1 direct, 1 transitive)"),
+            "{result}"
+        );
+        assert!(result.contains("direct: lodash 4.17.21\n"), "{result}");
+    }
+
+    #[test]
+    fn yarn_package_names() {
+        assert_eq!(yarn_package_name("lodash@^4.17.21"), "lodash");
+        assert_eq!(yarn_package_name("@babel/core@^7.0.0"), "@babel/core");
+        assert_eq!(yarn_package_name("lodash"), "lodash");
+    }
+}
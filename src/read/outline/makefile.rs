@@ -0,0 +1,109 @@
+//! Makefile outline: one line per target with its line range and
+//! prerequisites. A target is a line matching `^[A-Za-z0-9_.-]+:` — not an
+//! assignment (`FOO := x`) and not a special target (`.PHONY:`). Its range runs
+//! through the tab-indented recipe below it.
+
+use std::fmt::Write;
+
+/// A rule: target name, line range (1-based, inclusive), prerequisites.
+pub(crate) struct Target<'a> {
+    pub name: &'a str,
+    pub start: u32,
+    pub end: u32,
+    pub prerequisites: &'a str,
+}
+
+/// Every target in `content`, in file order.
+pub(crate) fn targets(content: &str) -> Vec<Target<'_>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some((name, rest)) = rule(line) else {
+            continue;
+        };
+        // Recipe: tab-indented lines, and lines continuing one with `\`
+        let mut end = i;
+        while let Some(next) = lines.get(end + 1) {
+            if next.starts_with('\t') || lines[end].ends_with('\\') {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+        let prerequisites = rest.split('#').next().unwrap_or("").trim();
+        out.push(Target {
+            name,
+            start: (i + 1) as u32,
+            end: (end + 1) as u32,
+            prerequisites,
+        });
+    }
+    out
+}
+
+/// `name: prerequisites` → `(name, prerequisites)`.
+fn rule(line: &str) -> Option<(&str, &str)> {
+    let colon = line.find(':')?;
+    let name = &line[..colon];
+    let rest = &line[colon + 1..];
+    let is_name = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'-'));
+    // `FOO := x`, `FOO ::= x` are assignments; `.PHONY:` and friends are directives
+    if !is_name || rest.starts_with('=') || rest.starts_with(":=") || name.starts_with('.') {
+        return None;
+    }
+    Some((name, rest))
+}
+
+pub fn outline(content: &str, max_lines: usize) -> String {
+    let targets = targets(content);
+    let mut out = String::new();
+    for (i, target) in targets.iter().enumerate() {
+        if i >= max_lines {
+            let _ = write!(out, "\n... {} more targets", targets.len() - i);
+            break;
+        }
+        let range = super::fmt_range(target.start, target.end);
+        let _ = write!(out, "\n{range:<12} target {}", target.name);
+        if !target.prerequisites.is_empty() {
+            let _ = write!(out, "  ← {}", target.prerequisites);
+        }
+    }
+    if targets.is_empty() {
+        return super::fallback::head_tail(content);
+    }
+    out.trim_start().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_with_recipes_and_prerequisites() {
+        let make = "GO := go\n\
+                    VERSION ?= dev\n\
+                    .PHONY: build test\n\
+                    \n\
+                    build: fmt lint ## compile\n\
+                    \t$(GO) build \\\n\
+                    \t  -o bin/app .\n\
+                    \n\
+                    test:\n\
+                    \t$(GO) test ./...\n\
+                    \n\
+                    fmt:\n\
+                    \tgofmt -w .\n";
+        let names: Vec<&str> = targets(make).iter().map(|t| t.name).collect();
+        assert_eq!(names, ["build", "test", "fmt"]);
+
+        assert_eq!(
+            outline(make, usize::MAX),
+            "[5-7]        target build  ← fmt lint\n\
+             [9-10]       target test\n\
+             [12-13]      target fmt"
+        );
+    }
+}
@@ -2,49 +2,34 @@
 /// Find lines starting with `#`, extract heading level and text,
 /// count code blocks per section. Shows line ranges for each heading.
 pub fn outline(buf: &[u8], max_lines: usize) -> String {
-    // First pass: collect all headings and count total lines
-    let mut headings = Vec::new();
-    let mut pos = 0;
-    let mut line_num = 0u32;
-    let mut code_block_count = 0u32;
-    let mut in_code_block = false;
-
-    while pos < buf.len() && headings.len() < max_lines {
-        line_num += 1;
-
-        // Find end of current line
-        let line_end = memchr::memchr(b'\n', &buf[pos..]).map_or(buf.len(), |i| pos + i);
+    outline_to_level(buf, max_lines, 6)
+}
 
-        let line = &buf[pos..line_end];
+/// `outline` keeping only headings up to `max_level` (`2` → `#` and `##`).
+/// Ranges still span the hidden subsections, and each entry notes how many
+/// deeper headings it holds.
+pub fn outline_to_level(buf: &[u8], max_lines: usize, max_level: usize) -> String {
+    let scan = scan(buf);
+    let code_block_count = scan.code_blocks;
+    let total_lines = scan.total_lines;
 
-        // Track code blocks
-        if line.starts_with(b"```") {
-            if in_code_block {
-                in_code_block = false;
-            } else {
-                in_code_block = true;
-                code_block_count += 1;
-            }
-            pos = line_end + 1;
-            continue;
+    let mut headings = Vec::new();
+    // Deeper headings skipped after each kept one
+    let mut hidden: Vec<usize> = Vec::new();
+    for h in scan.headings {
+        if headings.len() >= max_lines {
+            break;
         }
-
-        if !in_code_block && !line.is_empty() && line[0] == b'#' {
-            // Count heading level
-            let level = line.iter().take_while(|&&b| b == b'#').count();
-            if level <= 6 {
-                let text_start = level + usize::from(line.get(level) == Some(&b' '));
-                if let Ok(text) = std::str::from_utf8(&line[text_start..]) {
-                    headings.push((line_num, level, text.to_string()));
-                }
+        if h.level > max_level {
+            if let Some(last) = hidden.last_mut() {
+                *last += 1;
             }
+        } else {
+            headings.push((h.line, h.level, h.text));
+            hidden.push(0);
         }
-
-        pos = line_end + 1;
     }
 
-    let total_lines = line_num;
-
     // Second pass: compute end lines for each heading and format output
     let mut entries = Vec::new();
     let num_headings = headings.len();
@@ -70,8 +55,13 @@ pub fn outline(buf: &[u8], max_lines: usize) -> String {
             text.clone()
         };
 
+        let subsections = match hidden[i] {
+            0 => String::new(),
+            1 => " (1 subsection)".to_string(),
+            n => format!(" ({n} subsections)"),
+        };
         entries.push(format!(
-            "[{start_line}-{end_line}] {indent}{hashes} {truncated}"
+            "[{start_line}-{end_line}] {indent}{hashes} {truncated}{subsections}"
         ));
     }
 
@@ -82,10 +72,145 @@ pub fn outline(buf: &[u8], max_lines: usize) -> String {
     entries.join("\n")
 }
 
+/// A heading: 1-indexed line of its text, level 1-6, and the text itself.
+pub(crate) struct Heading {
+    pub line: usize,
+    pub level: usize,
+    pub text: String,
+}
+
+pub(crate) struct Scan {
+    pub headings: Vec<Heading>,
+    pub code_blocks: u32,
+    /// Lines in the file, not counting the empty one after a trailing newline.
+    pub total_lines: usize,
+}
+
+/// Collect ATX (`## Title`) and setext (`Title` underlined with `===` for
+/// level 1 or `---` for level 2) headings outside fenced code blocks. A YAML
+/// front matter block is skipped so its closing `---` isn't an underline.
+pub(crate) fn scan(buf: &[u8]) -> Scan {
+    let mut headings = Vec::new();
+    let mut code_blocks = 0u32;
+    let mut in_code_block = false;
+    let mut in_front_matter = false;
+    // The previous line, when it could still become a setext heading's text
+    let mut paragraph: Option<(usize, &[u8])> = None;
+    let mut pos = 0;
+    let mut line_num = 0;
+
+    while pos < buf.len() {
+        line_num += 1;
+        let line_end = memchr::memchr(b'\n', &buf[pos..]).map_or(buf.len(), |i| pos + i);
+        let line = &buf[pos..line_end];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        pos = line_end + 1;
+
+        if line_num == 1 && line == b"---" {
+            in_front_matter = true;
+            continue;
+        }
+        if in_front_matter {
+            in_front_matter = !matches!(line, b"---" | b"...");
+            continue;
+        }
+
+        if line.starts_with(b"```") {
+            if !in_code_block {
+                code_blocks += 1;
+            }
+            in_code_block = !in_code_block;
+            paragraph = None;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if let Some(level) = setext_level(line)
+            && let Some((text_line, text)) = paragraph.take()
+        {
+            if let Ok(text) = std::str::from_utf8(text) {
+                headings.push(Heading {
+                    line: text_line,
+                    level,
+                    text: text.trim().to_string(),
+                });
+            }
+            continue;
+        }
+
+        if line.first() == Some(&b'#') {
+            let level = line.iter().take_while(|&&b| b == b'#').count();
+            if level <= 6 {
+                let text_start = level + usize::from(line.get(level) == Some(&b' '));
+                if let Ok(text) = std::str::from_utf8(&line[text_start..]) {
+                    headings.push(Heading {
+                        line: line_num,
+                        level,
+                        text: atx_text(text).to_string(),
+                    });
+                }
+            }
+            paragraph = None;
+            continue;
+        }
+
+        paragraph = (!line.trim_ascii().is_empty()).then_some((line_num, line));
+    }
+
+    Scan {
+        headings,
+        code_blocks,
+        total_lines: line_num,
+    }
+}
+
+/// ATX heading text without surrounding whitespace or a closing `#` run
+/// (`Architecture ##` → `Architecture`). The run must follow a space, so
+/// `C#` keeps its hash.
+pub(crate) fn atx_text(text: &str) -> &str {
+    let text = text.trim();
+    let open = text.trim_end_matches('#');
+    if open.is_empty() {
+        ""
+    } else if open.ends_with([' ', '\t']) {
+        open.trim_end()
+    } else {
+        text
+    }
+}
+
+/// `1` for a `===` underline, `2` for `---`, allowing up to three spaces of
+/// indent and trailing whitespace.
+fn setext_level(line: &[u8]) -> Option<usize> {
+    let indent = line.iter().take_while(|&&b| b == b' ').count();
+    if indent > 3 {
+        return None;
+    }
+    let marks = line[indent..].trim_ascii_end();
+    let level = match marks.first()? {
+        b'=' => 1,
+        b'-' => 2,
+        _ => return None,
+    };
+    (marks.len() >= 2 && marks.iter().all(|&b| b == marks[0])).then_some(level)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn max_level_keeps_top_structure() {
+        let input = b"# Guide\n## Install\n### Linux\n### macOS\n## Usage\n#### Flags\ntext\n";
+        let result = outline_to_level(input, 100, 2);
+        assert_eq!(
+            result,
+            "[1-7] # Guide\n[2-4]   ## Install (2 subsections)\n[5-7]   ## Usage (1 subsection)"
+        );
+    }
+
     #[test]
     fn basic_headings() {
         let input = b"# H1\nSome text\n## H2\nMore text\n";
@@ -144,6 +269,36 @@ mod tests {
         assert_eq!(result, "[1-4] # Heading");
     }
 
+    #[test]
+    fn closed_atx_headings() {
+        let input = b"# Guide #\n## C# ##\n## C#\n";
+        assert_eq!(
+            outline(input, 100),
+            "[1-3] # Guide\n[2-2]   ## C#\n[3-3]   ## C#"
+        );
+    }
+
+    #[test]
+    fn setext_headings() {
+        let input = b"Guide\n=====\nintro\n\nArchitecture\n------------\ntext\n\n---\n\n## Notes\n";
+        assert_eq!(
+            outline(input, 100),
+            "[1-11] # Guide\n[5-10]   ## Architecture\n[11-11]   ## Notes"
+        );
+    }
+
+    #[test]
+    fn setext_underline_in_fence_ignored() {
+        let input = b"# Doc\n```\nnot a heading\n---\n```\n";
+        assert_eq!(outline(input, 100), "[1-5] # Doc\n\n(1 code blocks)");
+    }
+
+    #[test]
+    fn front_matter_is_not_a_heading() {
+        let input = b"---\ntitle: x\n---\n# Real\n";
+        assert_eq!(outline(input, 100), "[4-4] # Real");
+    }
+
     #[test]
     fn empty_file() {
         let input = b"";
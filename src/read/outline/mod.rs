@@ -1,6 +1,10 @@
 pub mod code;
+pub mod dockerfile;
 pub mod fallback;
+pub mod lockfile;
+pub mod makefile;
 pub mod markdown;
+pub mod notebook;
 pub mod structured;
 pub mod tabular;
 pub mod test_file;
@@ -11,13 +15,15 @@ use crate::types::FileType;
 
 const OUTLINE_CAP: usize = 100; // max outline lines for huge files
 
-/// Generate a smart view based on file type.
+/// Generate a smart view based on file type. `dedup_variants` collapses
+/// identical conditional-compilation copies in code outlines.
 pub fn generate(
     path: &Path,
     file_type: FileType,
     content: &str,
     buf: &[u8],
     capped: bool,
+    dedup_variants: bool,
 ) -> String {
     let max_lines = if capped { OUTLINE_CAP } else { usize::MAX };
 
@@ -30,15 +36,58 @@ pub fn generate(
     }
 
     match file_type {
-        FileType::Code(lang) => code::outline(content, lang, max_lines),
+        FileType::Code(lang) => {
+            code::outline_within(content, lang, max_lines, None, dedup_variants)
+        }
         FileType::Markdown => markdown::outline(buf, max_lines),
         FileType::StructuredData => structured::outline(path, content, max_lines),
         FileType::Tabular => tabular::outline(content, max_lines),
         FileType::Log => fallback::log_view(content),
+        FileType::Lockfile => lockfile::outline(path, content),
+        FileType::Notebook => notebook::outline(path, content, max_lines),
         FileType::Other => fallback::head_tail(content),
     }
 }
 
+/// [`generate`] within `budget` tokens. Code outlines drop detail to fit
+/// rather than being cut mid-entry; other views come back whole for
+/// `budget::apply` to trim.
+pub fn generate_within(
+    path: &Path,
+    file_type: FileType,
+    content: &str,
+    buf: &[u8],
+    capped: bool,
+    budget: u64,
+    dedup_variants: bool,
+) -> String {
+    if let FileType::Code(lang) = file_type
+        && !is_test_file(path)
+    {
+        let max_lines = if capped { OUTLINE_CAP } else { usize::MAX };
+        return code::outline_within(content, lang, max_lines, Some(budget), dedup_variants);
+    }
+    generate(path, file_type, content, buf, capped, dedup_variants)
+}
+
+/// Line range label for outline entries: `[7]` for one line, `[7-12]` otherwise.
+pub(crate) fn fmt_range(start: u32, end: u32) -> String {
+    if start == end {
+        format!("[{start}]")
+    } else {
+        format!("[{start}-{end}]")
+    }
+}
+
+/// `  (also [8-10], [14])` for the ranges of collapsed duplicates, or nothing.
+pub(crate) fn also_suffix(also: &[(u32, u32)]) -> String {
+    if also.is_empty() {
+        return String::new();
+    }
+    let ranges: Vec<String> = also.iter().map(|&(s, e)| fmt_range(s, e)).collect();
+    format!("  (also {})", ranges.join(", "))
+}
+
 /// Detect test files by path patterns.
 fn is_test_file(path: &Path) -> bool {
     let s = path.to_string_lossy();
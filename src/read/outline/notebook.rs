@@ -0,0 +1,223 @@
+//! Jupyter notebook outline: one line per cell instead of the JSON key tree.
+//! Markdown cells show their headings (or first line), code cells their
+//! `def`/`class` signatures (or first line) and length.
+
+use std::fmt::Write;
+
+use serde_json::Value;
+
+/// Longest cell text shown before it's cut.
+const TEXT_CAP: usize = 80;
+
+pub fn outline(path: &std::path::Path, content: &str, max_lines: usize) -> String {
+    let Ok(notebook) = serde_json::from_str::<Value>(content) else {
+        return super::structured::outline(path, content, max_lines);
+    };
+    let Some(cells) = notebook.get("cells").and_then(Value::as_array) else {
+        return super::structured::outline(path, content, max_lines);
+    };
+
+    let language = notebook
+        .pointer("/metadata/kernelspec/language")
+        .or_else(|| notebook.pointer("/metadata/language_info/name"))
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let code = cells.iter().filter(|c| cell_type(c) == "code").count();
+    let markdown = cells.iter().filter(|c| cell_type(c) == "markdown").count();
+    let mut out = format!(
+        "{language} notebook — {} cells: {code} code, {markdown} markdown\n",
+        cells.len()
+    );
+
+    // Line ranges in the file, so a cell can be read with a section; the
+    // cell number stands in if the raw JSON doesn't scan as expected
+    let spans = cell_spans(content);
+    let spans = if spans.len() == cells.len() {
+        spans
+    } else {
+        Vec::new()
+    };
+    for (i, cell) in cells.iter().enumerate() {
+        if i >= max_lines {
+            let _ = write!(out, "\n... {} more cells", cells.len() - i);
+            break;
+        }
+        let source = source(cell);
+        let lines: Vec<&str> = source.lines().collect();
+        let range = match spans.get(i) {
+            Some(&(start, end)) => format!("[{start}-{end}]"),
+            None => format!("[cell {}]", i + 1),
+        };
+        let range = format!("{range:<10}");
+        match cell_type(cell) {
+            "markdown" => {
+                let headings = headings(&lines);
+                if headings.is_empty() {
+                    let _ = write!(out, "\n{range} md   {}", first_line(&lines));
+                }
+                for heading in headings {
+                    let level = heading.chars().take_while(|&c| c == '#').count();
+                    let indent = "  ".repeat(level.saturating_sub(1));
+                    let _ = write!(out, "\n{range} md   {indent}{}", cap(heading));
+                }
+            }
+            "code" => {
+                let signatures: Vec<String> = lines
+                    .iter()
+                    .filter(|l| is_signature(l))
+                    .map(|l| cap(l.trim_end().trim_end_matches(':')))
+                    .collect();
+                let summary = if signatures.is_empty() {
+                    first_line(&lines)
+                } else {
+                    signatures.join(" · ")
+                };
+                let _ = write!(out, "\n{range} code {summary}  ({} lines)", lines.len());
+            }
+            other => {
+                let _ = write!(out, "\n{range} {other}  ({} lines)", lines.len());
+            }
+        }
+    }
+    out
+}
+
+fn cell_type(cell: &Value) -> &str {
+    cell.get("cell_type").and_then(Value::as_str).unwrap_or("")
+}
+
+/// The 1-based line range of each object in the top-level `cells` array,
+/// found by scanning the raw JSON: `serde_json` keeps no positions.
+fn cell_spans(content: &str) -> Vec<(u32, u32)> {
+    let Some(key) = content.find("\"cells\"") else {
+        return Vec::new();
+    };
+    let mut line = 1 + content[..key].matches('\n').count() as u32;
+    let mut chars = content[key + "\"cells\"".len()..].chars();
+    // Up to the array's opening bracket
+    for c in chars.by_ref() {
+        match c {
+            '[' => break,
+            '\n' => line += 1,
+            _ => {}
+        }
+    }
+
+    let mut spans = Vec::new();
+    let mut depth = 0u32;
+    let mut start = 0;
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '{' | '[' => {
+                if depth == 0 {
+                    start = line;
+                }
+                depth += 1;
+            }
+            '}' | ']' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    spans.push((start, line));
+                }
+            }
+            ']' => break,
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Markdown heading lines, skipping `#` lines inside fenced code blocks.
+fn headings<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let mut fence: Option<&str> = None;
+    let mut out = Vec::new();
+    for line in lines {
+        let trimmed = line.trim();
+        let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+        match (fence, marker) {
+            (None, Some(marker)) => fence = Some(marker),
+            (Some(open), Some(marker)) if open == marker => fence = None,
+            (None, None) if line.starts_with('#') => out.push(line.trim_end()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Cell source is a string or, more often, a list of lines.
+fn source(cell: &Value) -> String {
+    match cell.get("source") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Top-level `def`, `async def` and `class` lines.
+fn is_signature(line: &str) -> bool {
+    line.starts_with("def ") || line.starts_with("async def ") || line.starts_with("class ")
+}
+
+/// First line with content, skipping blank lines and `#` comments.
+fn first_line(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with('#'))
+        .map_or_else(|| "(empty)".to_string(), cap)
+}
+
+fn cap(text: &str) -> String {
+    if text.len() > TEXT_CAP {
+        format!("{}...", crate::types::truncate_str(text, TEXT_CAP - 3))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn cells_outlined_in_order() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/notebook/analysis.ipynb");
+        let content = std::fs::read_to_string(&path).unwrap();
+        let out = outline(&path, &content, usize::MAX);
+        assert_eq!(
+            out,
+            "python notebook — 5 cells: 3 code, 2 markdown\n\
+             \n[3-11]     md   # Sales analysis\
+             \n[3-11]     md     ## Loading data\
+             \n[12-22]    code import pandas as pd  (3 lines)\
+             \n[23-37]    code def load(path) · class Cleaner  (7 lines)\
+             \n[38-42]    md   Totals per region, largest first.\
+             \n[43-59]    code totals = df.groupby(\"region\").sum()  (2 lines)"
+        );
+
+        let capped = outline(&path, &content, 2);
+        assert!(capped.ends_with("\n... 3 more cells"), "{capped}");
+    }
+
+    #[test]
+    fn fenced_comments_are_not_headings() {
+        let lines = [
+            "# Setup",
+            "```bash",
+            "# install first",
+            "pip install pandas",
+            "```",
+            "## Usage",
+        ];
+        assert_eq!(headings(&lines), ["# Setup", "## Usage"]);
+    }
+}
@@ -1,9 +1,16 @@
+use std::fmt::Write as _;
 use std::path::Path;
 
-/// Depth-limited outline for JSON, YAML, TOML.
+use crate::format::plural;
+
+/// Records of a JSON Lines file parsed for keys, types and samples.
+const SAMPLE_RECORDS: usize = 1000;
+
+/// Depth-limited outline for JSON, YAML, TOML; a record summary for JSON Lines.
 pub fn outline(path: &Path, content: &str, max_lines: usize) -> String {
     match path.extension().and_then(|e| e.to_str()) {
         Some("json") => json_outline(content, max_lines),
+        Some("jsonl" | "ndjson") => json_lines_outline(content, max_lines),
         Some("yaml" | "yml") => yaml_outline(content, max_lines),
         Some("toml") => toml_outline(content, max_lines),
         _ => key_value_outline(content, max_lines),
@@ -105,6 +112,102 @@ fn json_type_name(v: &serde_json::Value) -> &'static str {
     }
 }
 
+/// One JSON value per line: the record count, then each top-level key seen in
+/// the first `SAMPLE_RECORDS` records with its types, how often it's present,
+/// and a sample value.
+fn json_lines_outline(content: &str, max_lines: usize) -> String {
+    struct Key {
+        name: String,
+        types: Vec<&'static str>,
+        present: usize,
+        sample: Option<String>,
+    }
+
+    let records: Vec<(usize, &str)> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .collect();
+    let mut keys: Vec<Key> = Vec::new();
+    let mut sampled = 0;
+    let mut not_objects = 0;
+    let mut bad_lines = Vec::new();
+
+    for &(i, line) in records.iter().take(SAMPLE_RECORDS) {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+            bad_lines.push(i + 1);
+            continue;
+        };
+        sampled += 1;
+        let serde_json::Value::Object(map) = value else {
+            not_objects += 1;
+            continue;
+        };
+        for (name, val) in map {
+            let idx = keys.iter().position(|k| k.name == name).unwrap_or_else(|| {
+                keys.push(Key {
+                    name: name.clone(),
+                    types: Vec::new(),
+                    present: 0,
+                    sample: None,
+                });
+                keys.len() - 1
+            });
+            let key = &mut keys[idx];
+            key.present += 1;
+            let ty = json_type_name(&val);
+            if !key.types.contains(&ty) {
+                key.types.push(ty);
+            }
+            if key.sample.is_none() && !val.is_null() {
+                key.sample = Some(truncate_json_value(&val, 40));
+            }
+        }
+    }
+
+    let mut out = format!(
+        "{}, {}",
+        plural(records.len(), "record", "records"),
+        plural(keys.len(), "key", "keys")
+    );
+    if records.len() > sampled + bad_lines.len() {
+        let _ = write!(out, " (from the first {SAMPLE_RECORDS})");
+    }
+    if not_objects > 0 {
+        let _ = write!(
+            out,
+            "\n{} not an object",
+            plural(not_objects, "record is", "records are")
+        );
+    }
+    if let Some(first) = bad_lines.first() {
+        let _ = write!(
+            out,
+            "\n{} not valid JSON (first: line {first})",
+            plural(bad_lines.len(), "line is", "lines are")
+        );
+    }
+
+    let types: Vec<String> = keys.iter().map(|k| k.types.join("|")).collect();
+    let width = keys.iter().map(|k| k.name.len()).max().unwrap_or(0);
+    let type_width = types.iter().map(String::len).max().unwrap_or(0);
+    for (i, (key, types)) in keys.iter().zip(&types).enumerate() {
+        if i >= max_lines {
+            let _ = write!(out, "\n... {} more keys", keys.len() - i);
+            break;
+        }
+        let _ = write!(out, "\n{:<width$}  {types:<type_width$}", key.name);
+        let objects = sampled - not_objects;
+        if key.present < objects {
+            let _ = write!(out, "  in {}/{objects}", key.present);
+        }
+        if let Some(sample) = &key.sample {
+            let _ = write!(out, "  e.g. {sample}");
+        }
+    }
+    out.trim_end().to_string()
+}
+
 fn truncate_json_value(v: &serde_json::Value, max: usize) -> String {
     let s = match v {
         serde_json::Value::String(s) => format!("\"{s}\""),
@@ -222,3 +325,31 @@ fn key_value_outline(content: &str, max_lines: usize) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lines_report_records_and_key_union() {
+        let jsonl = "{\"ts\":\"2024-01-31T09:30:00Z\",\"level\":\"info\",\"code\":200}\n\
+                     {\"ts\":\"2024-01-31T09:30:01Z\",\"level\":\"warn\",\"code\":null,\"retry\":true}\n\
+                     \n\
+                     not json\n\
+                     {\"ts\":\"2024-01-31T09:30:02Z\",\"level\":\"error\",\"code\":1.5,\"ctx\":{\"id\":7}}\n";
+        let out = outline(Path::new("events.jsonl"), jsonl, usize::MAX);
+        assert_eq!(
+            out,
+            "4 records, 5 keys\n\
+             1 line is not valid JSON (first: line 4)\n\
+             ts     string       e.g. \"2024-01-31T09:30:00Z\"\n\
+             level  string       e.g. \"info\"\n\
+             code   number|null  e.g. 200\n\
+             retry  boolean      in 1/3  e.g. true\n\
+             ctx    object       in 1/3  e.g. {\"id\":7}"
+        );
+        // Same routing for .ndjson; a single JSON document still parses whole
+        assert!(outline(Path::new("e.ndjson"), jsonl, usize::MAX).starts_with("4 records"));
+        assert!(outline(Path::new("e.json"), jsonl, usize::MAX).starts_with("[parse error"));
+    }
+}
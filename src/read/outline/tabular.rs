@@ -1,45 +1,219 @@
-/// CSV/TSV outline: column headers + row count + first 5 + last 3 rows.
-/// Uses memchr for line counting on the raw bytes, then only collects
-/// the head/tail slices needed for display.
-pub fn outline(content: &str, _max_lines: usize) -> String {
+/// CSV/TSV outline: column count, row count, then one line per column with
+/// its inferred type and a couple of sample values. Types come from the first
+/// `SAMPLE_ROWS` rows so huge files stay fast; rows are counted with memchr.
+use std::fmt::Write;
+
+/// Rows read for type inference and samples.
+const SAMPLE_ROWS: usize = 1000;
+
+/// Distinct sample values shown per column.
+const SAMPLES: usize = 2;
+
+/// Longest sample value shown before it's cut.
+const SAMPLE_CAP: usize = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Empty,
+    Bool,
+    Int,
+    Float,
+    Date,
+    String,
+}
+
+impl ColumnType {
+    fn of(value: &str) -> Self {
+        if value.is_empty() {
+            Self::Empty
+        } else if matches!(
+            value,
+            "true" | "false" | "TRUE" | "FALSE" | "True" | "False"
+        ) {
+            Self::Bool
+        } else if value.parse::<i64>().is_ok() {
+            Self::Int
+        } else if value.parse::<f64>().is_ok_and(f64::is_finite) {
+            Self::Float
+        } else if is_date(value) {
+            Self::Date
+        } else {
+            Self::String
+        }
+    }
+
+    /// The narrowest type holding both; empty cells don't widen anything.
+    fn join(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Empty, t) | (t, Self::Empty) => t,
+            (Self::Int, Self::Float) | (Self::Float, Self::Int) => Self::Float,
+            _ => Self::String,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Empty => "empty",
+            Self::Bool => "bool",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Date => "date",
+            Self::String => "string",
+        }
+    }
+}
+
+/// `2024-01-31`, `2024/01/31`, optionally followed by a time (`T09:30:00Z`).
+fn is_date(value: &str) -> bool {
+    let b = value.as_bytes();
+    b.len() >= 10
+        && b[..4].iter().all(u8::is_ascii_digit)
+        && matches!(b[4], b'-' | b'/')
+        && b[5..7].iter().all(u8::is_ascii_digit)
+        && b[7] == b[4]
+        && b[8..10].iter().all(u8::is_ascii_digit)
+        && (b.len() == 10 || matches!(b[10], b'T' | b' '))
+}
+
+pub fn outline(content: &str, max_lines: usize) -> String {
     let buf = content.as_bytes();
     if buf.is_empty() {
         return "(empty)".to_string();
     }
 
     // Count lines via memchr — O(n) SIMD scan, no Vec allocation
-    let total = memchr::memchr_iter(b'\n', buf).count() + 1;
+    let newlines = memchr::memchr_iter(b'\n', buf).count();
+    let lines_total = newlines + usize::from(!content.ends_with('\n'));
+    let rows = lines_total.saturating_sub(1);
 
-    // We still need to index into lines for head/tail display,
-    // but only collect offsets, not full line slices
-    let lines: Vec<&str> = content.lines().collect();
+    let mut lines = content.lines();
+    let header_line = lines.next().unwrap_or_default();
+    let delimiter = if header_line.matches('\t').count() > header_line.matches(',').count() {
+        '\t'
+    } else {
+        ','
+    };
+    let header = split_row(header_line, delimiter);
 
-    let mut out = Vec::new();
+    let mut types = vec![ColumnType::Empty; header.len()];
+    let mut samples: Vec<Vec<String>> = vec![Vec::new(); header.len()];
+    for line in lines.take(SAMPLE_ROWS) {
+        for (i, value) in split_row(line, delimiter).into_iter().enumerate() {
+            let Some(ty) = types.get_mut(i) else {
+                break;
+            };
+            *ty = ty.join(ColumnType::of(&value));
+            if !value.is_empty() && samples[i].len() < SAMPLES && !samples[i].contains(&value) {
+                samples[i].push(value);
+            }
+        }
+    }
 
-    // Header
-    out.push(format!("columns: {}", lines[0]));
-    out.push(format!("rows: {}", total.saturating_sub(1)));
-    out.push(String::new());
+    let mut out = format!(
+        "{} columns, {} rows\ncolumns: {}\n",
+        header.len(),
+        thousands(rows),
+        header
+            .iter()
+            .zip(&types)
+            .map(|(name, ty)| format!("{name}({})", ty.label()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
 
-    // First 5 data rows
-    let head_end = 6.min(lines.len()); // header + 5 rows
-    for line in &lines[1..head_end] {
-        out.push((*line).to_string());
+    let width = header.iter().map(String::len).max().unwrap_or(0);
+    for (i, name) in header.iter().enumerate() {
+        if i >= max_lines {
+            let _ = write!(out, "\n... {} more columns", header.len() - i);
+            break;
+        }
+        let _ = write!(out, "\n{name:<width$}  {:<6}", types[i].label());
+        if !samples[i].is_empty() {
+            let shown: Vec<String> = samples[i].iter().map(|s| sample(s)).collect();
+            let _ = write!(out, "  e.g. {}", shown.join(", "));
+        }
     }
+    out.trim_end().to_string()
+}
 
-    // Gap indicator + last 3 rows
-    if total > 9 {
-        out.push(format!("... {} rows omitted", total - 9));
-        out.push(String::new());
-        let tail_start = lines.len().saturating_sub(3);
-        for line in &lines[tail_start..] {
-            out.push((*line).to_string());
+/// Split one row on `delimiter`, honouring double quotes (`"a, b"`, `""`).
+/// A quoted field spanning lines is cut at the line end — fine for sampling.
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
         }
-    } else if lines.len() > head_end {
-        for line in &lines[head_end..] {
-            out.push((*line).to_string());
+    }
+    fields.push(field);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+fn sample(value: &str) -> String {
+    if value.len() > SAMPLE_CAP {
+        format!("{}...", crate::types::truncate_str(value, SAMPLE_CAP - 3))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `203118` → `203,118`.
+fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
         }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_typed_from_sampled_rows() {
+        let csv = "id,email,score,active,created_at,note\n\
+                   1,a@example.com,3.5,true,2024-01-31,\"hello, world\"\n\
+                   2,b@example.com,4,false,2024-02-01T09:30:00Z,\n\
+                   3,a@example.com,,TRUE,2024-02-02,\"say \"\"hi\"\"\"\n";
+        let out = outline(csv, usize::MAX);
+        assert_eq!(
+            out,
+            "6 columns, 3 rows\n\
+             columns: id(int), email(string), score(float), active(bool), created_at(date), note(string)\n\
+             \nid          int     e.g. 1, 2\
+             \nemail       string  e.g. a@example.com, b@example.com\
+             \nscore       float   e.g. 3.5, 4\
+             \nactive      bool    e.g. true, false\
+             \ncreated_at  date    e.g. 2024-01-31, 2024-02-01T09:30:00Z\
+             \nnote        string  e.g. hello, world, say \"hi\""
+        );
     }
 
-    out.join("\n")
+    #[test]
+    fn tsv_and_large_counts() {
+        let mut tsv = String::from("name\tqty\n");
+        for i in 0..203_118 {
+            let _ = writeln!(tsv, "item{i}\t{i}");
+        }
+        let out = outline(&tsv, usize::MAX);
+        assert!(
+            out.starts_with("2 columns, 203,118 rows\ncolumns: name(string), qty(int)"),
+            "{out}"
+        );
+    }
 }
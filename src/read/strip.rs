@@ -0,0 +1,139 @@
+//! Full file content with comments removed, for feeding minimal code to a model.
+//!
+//! Comments are blanked rather than deleted so line numbers still match the
+//! file on disk: a line holding only a comment becomes empty, a trailing
+//! comment is cut along with the whitespace before it, and a block comment
+//! spanning lines leaves that many empty lines. Python docstrings count as
+//! comments. String literals are left alone — only tree-sitter comment nodes
+//! are removed.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use super::file_type_with;
+use crate::cache::OutlineCache;
+use crate::error::GleanError;
+use crate::format;
+use crate::read::outline::code::outline_language;
+use crate::search::treesitter::{comment_ranges, parse_tree};
+use crate::types::{FileType, Lang, ViewMode};
+
+/// `content` with every comment blanked, or `None` when `lang` has no grammar.
+pub(crate) fn strip_comments(content: &str, lang: Lang) -> Option<String> {
+    let ts_lang = outline_language(lang)?;
+    let tree = parse_tree(content, &ts_lang)?;
+
+    // Drop comment bytes but keep their newlines, so lines stay aligned
+    let mut kept = String::with_capacity(content.len());
+    let mut pos = 0;
+    for range in comment_ranges(&tree) {
+        kept.push_str(&content[pos..range.start]);
+        kept.extend(content[range.clone()].chars().filter(|&c| c == '\n'));
+        pos = range.end;
+    }
+    kept.push_str(&content[pos..]);
+
+    let mut out = String::with_capacity(kept.len());
+    for (original, stripped) in content.split('\n').zip(kept.split('\n')) {
+        let line = if stripped == original {
+            stripped
+        } else {
+            stripped.trim_end()
+        };
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.pop();
+    Some(out)
+}
+
+/// `glean_read` with `full` + `strip_comments`: the whole file, comments
+/// blanked. Files without a grammar fall back to the plain full read.
+pub fn read_stripped(
+    path: &Path,
+    lang: Option<Lang>,
+    cache: &OutlineCache,
+    edit_mode: bool,
+) -> Result<String, GleanError> {
+    let content = path
+        .is_file()
+        .then(|| fs::read_to_string(path).ok())
+        .flatten();
+    let stripped = content.and_then(|content| {
+        let FileType::Code(lang) = file_type_with(path, lang, content.as_bytes()) else {
+            return None;
+        };
+        strip_comments(&content, lang).map(|s| (content, s))
+    });
+    let Some((content, stripped)) = stripped else {
+        return super::read_file(path, None, true, lang, cache, edit_mode, None, None, false);
+    };
+
+    let byte_len = content.len() as u64;
+    let line_count = content.matches('\n').count() as u32 + 1;
+    let header = format::file_header(path, byte_len, line_count, ViewMode::Stripped);
+    if !edit_mode {
+        return Ok(format!("{header}\n\n{stripped}"));
+    }
+
+    // Anchors hash the line on disk, so edits against them still apply
+    let mut numbered = String::with_capacity(stripped.len() + line_count as usize * 8);
+    for (i, (original, shown)) in content.lines().zip(stripped.lines()).enumerate() {
+        let hash = format::line_hash(original.as_bytes());
+        let _ = writeln!(numbered, "{}:{hash:03x}|{shown}", i + 1);
+    }
+    Ok(format!("{header}\n\n{numbered}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"// Package header comment
+const URL: &str = "https://example.com"; // trailing
+
+/* block
+   comment */
+fn main() {
+    let s = "// not a comment"; /* inline */ let t = 1;
+    /// doc on a statement
+    println!("{s} {t}");
+}
+"#;
+
+    #[test]
+    fn comments_blanked_code_and_lines_kept() {
+        let out = strip_comments(SOURCE, Lang::Rust).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines.len(), SOURCE.lines().count(), "{out}");
+        assert_eq!(lines[0], "");
+        assert_eq!(lines[1], r#"const URL: &str = "https://example.com";"#);
+        assert_eq!(lines[3], "");
+        assert_eq!(lines[4], "");
+        assert_eq!(lines[5], "fn main() {");
+        assert_eq!(lines[6], r#"    let s = "// not a comment";  let t = 1;"#);
+        assert_eq!(lines[7], "");
+        assert_eq!(lines[8], r#"    println!("{s} {t}");"#);
+        assert!(!out.contains("trailing") && !out.contains("block"), "{out}");
+    }
+
+    #[test]
+    fn edit_mode_anchors_hash_original_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.rs");
+        fs::write(&path, SOURCE).unwrap();
+
+        let out = read_stripped(&path, None, &OutlineCache::new(), true).unwrap();
+        let original = SOURCE.lines().nth(1).unwrap();
+        let anchor = format!("2:{:03x}|", format::line_hash(original.as_bytes()));
+        assert!(
+            out.contains(&format!(
+                "{anchor}const URL: &str = \"https://example.com\";\n"
+            )),
+            "{out}"
+        );
+        assert!(out.contains("[full, comments stripped]"), "{out}");
+    }
+}
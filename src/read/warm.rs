@@ -0,0 +1,116 @@
+//! Prefetch: outline every file in a scope up front, in parallel, so the
+//! first read or search of each one is a cache hit.
+
+use std::fs;
+use std::path::Path;
+
+use crate::cache::{OutlineCache, View};
+use crate::read::{FILE_SIZE_CAP, binary, detect_file_type, generated, outline, sniff_file_type};
+use crate::search::SearchFilter;
+use crate::types::FileType;
+
+/// What a warm-up pass did.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WarmStats {
+    /// Outlines computed and stored by this pass.
+    pub cached: usize,
+    /// Outlines that were already in the cache.
+    pub already_cached: usize,
+    /// Files with no outline view: unknown types, binaries, generated code.
+    pub skipped: usize,
+}
+
+enum Outcome {
+    Cached,
+    AlreadyCached,
+    Skipped,
+}
+
+/// Outline every file under `scope` into `cache`. Walks like a search —
+/// skip dirs and `filter` apply — and leaves out files over `FILE_SIZE_CAP`,
+/// which reads never outline in full anyway.
+pub fn warm(scope: &Path, filter: &SearchFilter, cache: &OutlineCache) -> WarmStats {
+    let outcomes = crate::search::walk_collect(scope, filter, None, Some(FILE_SIZE_CAP), |entry| {
+        vec![warm_file(entry.path(), cache)]
+    });
+
+    let mut stats = WarmStats::default();
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Cached => stats.cached += 1,
+            Outcome::AlreadyCached => stats.already_cached += 1,
+            Outcome::Skipped => stats.skipped += 1,
+        }
+    }
+    stats
+}
+
+fn warm_file(path: &Path, cache: &OutlineCache) -> Outcome {
+    let mut file_type = detect_file_type(path);
+    // Only an extensionless file may turn out to be a script
+    if file_type == FileType::Other && path.extension().is_some() {
+        return Outcome::Skipped;
+    }
+    let Ok(meta) = fs::metadata(path) else {
+        return Outcome::Skipped;
+    };
+    let mut read = None;
+    if file_type == FileType::Other {
+        let Ok(buf) = fs::read(path) else {
+            return Outcome::Skipped;
+        };
+        file_type = sniff_file_type(path, &buf);
+        if file_type == FileType::Other {
+            return Outcome::Skipped;
+        }
+        read = Some(buf);
+    }
+    let mtime = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+    let view = View::outline(file_type, false);
+    if cache.contains(path, mtime, view) {
+        return Outcome::AlreadyCached;
+    }
+    let Some(buf) = read.or_else(|| fs::read(path).ok()) else {
+        return Outcome::Skipped;
+    };
+    // Reads return early for these without an outline — nothing to cache
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    if buf.is_empty()
+        || binary::is_binary(&buf)
+        || (file_type != FileType::Lockfile
+            && (generated::is_generated_by_name(name) || generated::is_generated_by_content(&buf)))
+    {
+        return Outcome::Skipped;
+    }
+
+    let content = String::from_utf8_lossy(&buf);
+    cache.get_or_compute(path, mtime, view, || {
+        outline::generate(path, file_type, &content, &buf, false, false)
+    });
+    Outcome::Cached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn warmed_outlines_are_not_recomputed() {
+        let scope = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini-rust");
+        let cache = OutlineCache::new();
+        let stats = warm(&scope, &SearchFilter::default(), &cache);
+        assert!(stats.cached >= 2, "{stats:?}");
+
+        let path = scope.join("src/searcher.rs");
+        let mtime = fs::metadata(&path).unwrap().modified().unwrap();
+        let view = View::outline(detect_file_type(&path), false);
+        let outline = cache.get_or_compute(&path, mtime, view, || panic!("outline recomputed"));
+        assert!(outline.contains("Searcher"), "{outline}");
+
+        // A second pass finds everything in place
+        let again = warm(&scope, &SearchFilter::default(), &cache);
+        assert_eq!(again.cached, 0);
+        assert_eq!(again.already_cached, stats.cached);
+    }
+}
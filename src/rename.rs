@@ -0,0 +1,339 @@
+//! Rename a symbol across a scope: every whole-word occurrence of the old
+//! name becomes the new one, as hashline-anchored `Edit`s — one per line, so
+//! several occurrences on a line are a single replacement.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::edit::{self, Edit, EditMode, EditOptions, EditResult};
+use crate::error::{GleanError, io_err};
+use crate::format;
+use crate::search::{SearchFilter, symbol};
+
+/// The edits renaming one file, with each edited line as it was.
+#[derive(Debug)]
+pub struct FileRename {
+    pub path: PathBuf,
+    /// Ascending by line; `start_line == end_line` for each.
+    pub edits: Vec<Edit>,
+    /// The original text of each edited line, parallel to `edits`.
+    pub before: Vec<String>,
+}
+
+/// Edits renaming `old` to `new` in every file under `scope`. Lines are found
+/// by the same whole-word matcher symbol search uses, so comments and strings
+/// that mention the name are renamed too — the preview shows them.
+pub fn rename_edits(
+    old: &str,
+    new: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<Vec<FileRename>, GleanError> {
+    for name in [old, new] {
+        if !is_identifier(name) {
+            return Err(GleanError::InvalidQuery {
+                query: name.to_string(),
+                reason: "rename takes plain identifiers (letters, digits, _)".into(),
+            });
+        }
+    }
+    if old == new {
+        return Err(GleanError::InvalidQuery {
+            query: old.to_string(),
+            reason: "old and new names are the same".into(),
+        });
+    }
+
+    let mut by_file: BTreeMap<PathBuf, Vec<u32>> = BTreeMap::new();
+    for m in symbol::word_lines(old, scope, filter)? {
+        by_file.entry(m.path).or_default().push(m.line);
+    }
+
+    let mut renames = Vec::new();
+    for (path, line_numbers) in by_file {
+        let content = fs::read_to_string(&path).map_err(io_err(&path))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let mut edits = Vec::new();
+        let mut before = Vec::new();
+        for n in line_numbers {
+            let Some(&line) = lines.get(n as usize - 1) else {
+                continue;
+            };
+            let Some(replaced) = replace_word(line, old, new) else {
+                continue;
+            };
+            let hash = format::line_hash(line.as_bytes());
+            edits.push(Edit {
+                start_line: n as usize,
+                start_hash: hash,
+                end_line: n as usize,
+                end_hash: hash,
+                content: replaced,
+                mode: EditMode::Replace,
+            });
+            before.push(line.to_string());
+        }
+        if !edits.is_empty() {
+            renames.push(FileRename {
+                path,
+                edits,
+                before,
+            });
+        }
+    }
+    Ok(renames)
+}
+
+/// `line` with every whole-word `old` replaced, or `None` when `old` only
+/// appears inside longer identifiers.
+fn replace_word(line: &str, old: &str, new: &str) -> Option<String> {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    let mut replaced = false;
+    while let Some(pos) = rest.find(old) {
+        let before = rest[..pos].chars().next_back();
+        let after = rest[pos + old.len()..].chars().next();
+        out.push_str(&rest[..pos]);
+        if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+            out.push_str(old);
+        } else {
+            out.push_str(new);
+            replaced = true;
+        }
+        rest = &rest[pos + old.len()..];
+    }
+    out.push_str(rest);
+    replaced.then_some(out)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| !c.is_ascii_digit()) && name.chars().all(is_word_char)
+}
+
+/// The rename as a diff-like listing: each line before and after.
+#[must_use]
+pub fn format_preview(
+    old: &str,
+    new: &str,
+    scope: &Path,
+    renames: &[FileRename],
+    absolute: bool,
+) -> String {
+    let lines: usize = renames.iter().map(|r| r.edits.len()).sum();
+    let mut out = format!(
+        "# Rename \"{old}\" → \"{new}\" in {} — {lines} line{} in {} file{} (preview; --apply to write)",
+        scope.display(),
+        if lines == 1 { "" } else { "s" },
+        renames.len(),
+        if renames.len() == 1 { "" } else { "s" },
+    );
+    if renames.is_empty() {
+        let _ = write!(out, "\n\nNo whole-word occurrences of \"{old}\".");
+        return out;
+    }
+    for r in renames {
+        let _ = write!(out, "\n\n## {}", format::rel(&r.path, scope, absolute));
+        for (e, before) in r.edits.iter().zip(&r.before) {
+            let _ = write!(
+                out,
+                "\n  [{}] - {}\n  [{}] + {}",
+                e.start_line,
+                before.trim(),
+                e.start_line,
+                e.content.trim()
+            );
+        }
+    }
+    out
+}
+
+/// Write every file's edits. All anchors are checked against the files on
+/// disk first; if any line changed since the edits were computed, nothing is
+/// written. Each file is replaced whole through a temp file and a rename.
+pub fn apply(scope: &Path, renames: &[FileRename], absolute: bool) -> Result<String, GleanError> {
+    let groups: Vec<(PathBuf, Vec<Edit>)> = renames
+        .iter()
+        .map(|r| (r.path.clone(), r.edits.clone()))
+        .collect();
+    let results = edit::apply_edits_atomic(&groups, EditOptions::default())?;
+
+    let stale: Vec<String> = results
+        .iter()
+        .filter_map(|(path, result)| match result {
+            EditResult::HashMismatch { stale_anchors, .. } => Some(
+                stale_anchors
+                    .iter()
+                    .map(|a| {
+                        let line = a.split(':').next().unwrap_or(a);
+                        format!("{}:{line}", format::rel(path, scope, absolute))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            _ => None,
+        })
+        .collect();
+    if !stale.is_empty() {
+        return Err(GleanError::InvalidQuery {
+            query: stale.join(", "),
+            reason: "lines changed since the rename was computed; nothing written".into(),
+        });
+    }
+
+    let mut out = String::new();
+    let mut total = 0;
+    for (path, result) in &results {
+        if let EditResult::Applied { edits_applied, .. } = result {
+            total += edits_applied;
+            let _ = write!(
+                out,
+                "\n  {} ({edits_applied} line{})",
+                format::rel(path, scope, absolute),
+                if *edits_applied == 1 { "" } else { "s" }
+            );
+        }
+    }
+    Ok(format!(
+        "# Renamed {total} line{} in {} file{}{out}",
+        if total == 1 { "" } else { "s" },
+        renames.len(),
+        if renames.len() == 1 { "" } else { "s" },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn whole_words_only_every_occurrence() {
+        assert_eq!(
+            replace_word("parse(parse_all(x), parse)", "parse", "load").as_deref(),
+            Some("load(parse_all(x), load)")
+        );
+        assert_eq!(replace_word("reparse(x)", "parse", "load"), None);
+    }
+
+    #[test]
+    fn rename_across_two_files() {
+        let scope = fixture("rename");
+        let renames = rename_edits(
+            "parse_config",
+            "load_config",
+            &scope,
+            &SearchFilter::default(),
+        )
+        .unwrap();
+
+        let edits: Vec<(String, usize, &str)> = renames
+            .iter()
+            .flat_map(|r| {
+                let name = r.path.file_name().unwrap().to_string_lossy().into_owned();
+                r.edits
+                    .iter()
+                    .map(move |e| (name.clone(), e.start_line, e.content.as_str()))
+            })
+            .collect();
+        assert_eq!(
+            edits,
+            [
+                (
+                    "config.rs".to_string(),
+                    1,
+                    "/// Reads settings. Call load_config once; `parse_config_path` names the file."
+                ),
+                (
+                    "config.rs".to_string(),
+                    2,
+                    "pub fn load_config(text: &str) -> Config {"
+                ),
+                (
+                    "main.rs".to_string(),
+                    4,
+                    "    let a = load_config(&read()); let b = load_config(\"\");"
+                ),
+            ]
+        );
+
+        // Anchors hash the line on disk
+        let config = fs::read_to_string(scope.join("config.rs")).unwrap();
+        let line2 = config.lines().nth(1).unwrap();
+        assert_eq!(
+            renames[0].edits[1].start_hash,
+            format::line_hash(line2.as_bytes())
+        );
+
+        let preview = format_preview("parse_config", "load_config", &scope, &renames, false);
+        assert!(
+            preview.contains("— 3 lines in 2 files (preview"),
+            "{preview}"
+        );
+        assert!(
+            preview.contains("\n  [2] - pub fn parse_config(text: &str) -> Config {"),
+            "{preview}"
+        );
+    }
+
+    #[test]
+    fn apply_writes_every_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["config.rs", "main.rs"] {
+            fs::copy(fixture("rename").join(name), dir.path().join(name)).unwrap();
+        }
+        let renames = rename_edits(
+            "parse_config",
+            "load_config",
+            dir.path(),
+            &SearchFilter::default(),
+        )
+        .unwrap();
+        let out = apply(dir.path(), &renames, false).unwrap();
+        assert!(out.starts_with("# Renamed 3 lines in 2 files"), "{out}");
+
+        let main = fs::read_to_string(dir.path().join("main.rs")).unwrap();
+        assert!(!main.contains("parse_config("), "{main}");
+        assert!(main.contains("parse_config_path"), "{main}");
+    }
+
+    #[test]
+    fn large_files_are_renamed_too() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = format!(
+            "{}fn main() {{ parse_config(); }}\n",
+            "// padding\n".repeat(60_000)
+        );
+        fs::write(dir.path().join("big.rs"), &big).unwrap();
+        let renames = rename_edits(
+            "parse_config",
+            "load_config",
+            dir.path(),
+            &SearchFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            renames.len(),
+            1,
+            "a file over the search size cap is still renamed"
+        );
+        apply(dir.path(), &renames, false).unwrap();
+        let after = fs::read_to_string(dir.path().join("big.rs")).unwrap();
+        assert!(after.ends_with("fn main() { load_config(); }\n"));
+        assert_eq!(
+            fs::read_dir(dir.path()).unwrap().count(),
+            1,
+            "no temp file left"
+        );
+    }
+}
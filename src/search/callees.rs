@@ -1,4 +1,5 @@
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use streaming_iterator::StreamingIterator;
 
@@ -18,7 +19,7 @@ pub struct ResolvedCallee {
 
 /// Return the tree-sitter query string for extracting callee names in the given language.
 /// Each language has patterns targeting `@callee` captures on call-like expressions.
-pub(crate) fn callee_query_str(lang: Lang) -> Option<&'static str> {
+fn callee_query_str(lang: Lang) -> Option<&'static str> {
     match lang {
         Lang::Rust => Some(concat!(
             "(call_expression function: (identifier) @callee)\n",
@@ -43,11 +44,38 @@ pub(crate) fn callee_query_str(lang: Lang) -> Option<&'static str> {
             "(call_expression function: (identifier) @callee)\n",
             "(call_expression function: (field_expression field: (field_identifier) @callee))\n",
         )),
-        Lang::Ruby => Some("(call method: (identifier) @callee)\n"),
+        Lang::Ruby => Some(concat!(
+            // `a.b`, `b(x)` and paren-less `b x` are all `call` nodes
+            "(call method: (identifier) @callee)\n",
+            // A bare `b` in statement position is a call without arguments
+            // (a trailing local variable read matches too, but won't resolve)
+            "(body_statement (identifier) @callee)\n",
+            "(block_body (identifier) @callee)\n",
+            "(then (identifier) @callee)\n",
+            "(else (identifier) @callee)\n",
+            "(if_modifier body: (identifier) @callee)\n",
+            "(unless_modifier body: (identifier) @callee)\n",
+            "(yield \"yield\" @callee)\n",
+        )),
         Lang::Swift => Some(concat!(
             "(call_expression (simple_identifier) @callee)\n",
             "(call_expression (navigation_expression (navigation_suffix (simple_identifier) @callee)))\n",
         )),
+        Lang::CSharp => Some(concat!(
+            "(invocation_expression function: (identifier) @callee)\n",
+            "(invocation_expression function: (member_access_expression name: (identifier) @callee))\n",
+        )),
+        Lang::Kotlin => Some(concat!(
+            "(call_expression (identifier) @callee)\n",
+            "(call_expression (navigation_expression (_) (identifier) @callee))\n",
+        )),
+        Lang::Php => Some(concat!(
+            "(function_call_expression function: (name) @callee)\n",
+            "(member_call_expression name: (name) @callee)\n",
+            "(nullsafe_member_call_expression name: (name) @callee)\n",
+            "(scoped_call_expression name: (name) @callee)\n",
+        )),
+        Lang::Shell => Some("(command name: (command_name (word) @callee))\n"),
         Lang::Zig => Some(concat!(
             "(call_expression function: (identifier) @callee)\n",
             "(call_expression function: (field_expression member: (identifier) @callee))\n",
@@ -56,6 +84,41 @@ pub(crate) fn callee_query_str(lang: Lang) -> Option<&'static str> {
     }
 }
 
+/// One slot per `Lang` variant, indexed by discriminant (`NAMES` lists them
+/// in declaration order).
+static CALLEE_QUERIES: [OnceLock<Option<tree_sitter::Query>>; Lang::NAMES.len()] =
+    [const { OnceLock::new() }; Lang::NAMES.len()];
+
+// A variant added without a name would index past the end of the slots
+const _: () = assert!(
+    Lang::Make as usize + 1 == Lang::NAMES.len(),
+    "Lang::NAMES must name every Lang variant, last one last"
+);
+
+/// Compiles per language, so tests can check each query is built once.
+#[cfg(test)]
+static CALLEE_QUERY_COMPILES: [std::sync::atomic::AtomicUsize; Lang::NAMES.len()] =
+    [const { std::sync::atomic::AtomicUsize::new(0) }; Lang::NAMES.len()];
+
+/// The compiled callee query for `lang`, built on first use and shared by every
+/// file and thread after that. `None` when the language has no query or its
+/// grammar rejects the patterns.
+pub(crate) fn callee_query(lang: Lang) -> Option<&'static tree_sitter::Query> {
+    CALLEE_QUERIES[lang as usize]
+        .get_or_init(|| {
+            #[cfg(test)]
+            CALLEE_QUERY_COMPILES[lang as usize].fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let ts_lang = outline_language(lang)?;
+            tree_sitter::Query::new(&ts_lang, callee_query_str(lang)?).ok()
+        })
+        .as_ref()
+}
+
+#[cfg(test)]
+pub(crate) fn compiles(lang: Lang) -> usize {
+    CALLEE_QUERY_COMPILES[lang as usize].load(std::sync::atomic::Ordering::Relaxed)
+}
+
 /// Extract names of functions/methods called within a given line range.
 /// Uses tree-sitter query patterns to find call expressions.
 ///
@@ -71,12 +134,7 @@ pub fn extract_callee_names(
         return Vec::new();
     };
 
-    let Some(query_str) = callee_query_str(lang) else {
-        return Vec::new();
-    };
-
-    // Compile the query — if the grammar doesn't support these patterns, bail gracefully.
-    let Ok(query) = tree_sitter::Query::new(&ts_lang, query_str) else {
+    let Some(query) = callee_query(lang) else {
         return Vec::new();
     };
 
@@ -90,7 +148,7 @@ pub fn extract_callee_names(
 
     let content_bytes = content.as_bytes();
     let mut cursor = tree_sitter::QueryCursor::new();
-    let mut matches = cursor.matches(&query, tree.root_node(), content_bytes);
+    let mut matches = cursor.matches(query, tree.root_node(), content_bytes);
 
     let mut names: Vec<String> = Vec::new();
 
@@ -352,6 +410,32 @@ fn process(input: &str) -> Result<Output, Error> {
         );
     }
 
+    /// Ruby calls come in many shapes: with a receiver, without parens, with
+    /// no arguments at all, and `yield` to the block.
+    #[test]
+    fn ruby_callees_cover_call_forms() {
+        let code = r"
+def checkout(cart)
+  prepare
+  cart.total
+  charge cart.total, currency: :usd
+  notify(cart.owner) if paid?
+  cart.items.each do |item|
+    restock
+  end
+  yield cart
+end
+";
+        let names = extract_callee_names(code, Lang::Ruby, None);
+        assert_eq!(
+            names,
+            vec![
+                "charge", "each", "items", "notify", "owner", "paid?", "prepare", "restock",
+                "total", "yield"
+            ]
+        );
+    }
+
     /// Benchmark analog: zod_parse_flow — TS callee extraction in a function
     /// that calls imported functions. The agent expands safeParse and needs
     /// to see that it calls parse() (pointing to the next file).
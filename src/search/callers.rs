@@ -4,12 +4,13 @@ use std::path::{Path, PathBuf};
 
 use streaming_iterator::StreamingIterator;
 
-use super::treesitter::{DEFINITION_KINDS, extract_definition_name};
+use super::treesitter::enclosing_symbol;
 
 use crate::cache::OutlineCache;
 use crate::error::GleanError;
-use crate::read::detect_file_type;
 use crate::read::outline::code::outline_language;
+use crate::read::sniff_file_type;
+use crate::search::SearchFilter;
 use crate::session::Session;
 use crate::types::FileType;
 
@@ -17,6 +18,12 @@ const MAX_MATCHES: usize = 10;
 /// Stop walking once we have this many raw matches. Generous headroom for dedup + ranking.
 const EARLY_QUIT_THRESHOLD: usize = 30;
 
+/// Deepest caller level a call tree follows.
+const MAX_TREE_DEPTH: u32 = 5;
+
+/// Call sites shown in one call tree; the rest are cut and the output says so.
+const MAX_TREE_NODES: usize = 50;
+
 /// A single caller match — a call site of a target symbol.
 #[derive(Debug)]
 pub struct CallerMatch {
@@ -31,11 +38,16 @@ pub struct CallerMatch {
 }
 
 /// Find all call sites of a target symbol across the codebase using tree-sitter.
-pub fn find_callers(target: &str, scope: &Path) -> Result<Vec<CallerMatch>, GleanError> {
+pub fn find_callers(
+    target: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<Vec<CallerMatch>, GleanError> {
     let needle = target.as_bytes();
 
     Ok(super::walk_collect(
         scope,
+        filter,
         Some(EARLY_QUIT_THRESHOLD),
         Some(500_000),
         |entry| {
@@ -52,7 +64,7 @@ pub fn find_callers(target: &str, scope: &Path) -> Result<Vec<CallerMatch>, Glea
             }
 
             // Only process files with tree-sitter grammars
-            let file_type = detect_file_type(path);
+            let file_type = sniff_file_type(path, content.as_bytes());
             let FileType::Code(lang) = file_type else {
                 return Vec::new();
             };
@@ -74,13 +86,7 @@ fn find_callers_treesitter(
     content: &str,
     lang: crate::types::Lang,
 ) -> Vec<CallerMatch> {
-    // Get the query string for this language
-    let Some(query_str) = super::callees::callee_query_str(lang) else {
-        return Vec::new();
-    };
-
-    // Compile the query
-    let Ok(query) = tree_sitter::Query::new(ts_lang, query_str) else {
+    let Some(query) = super::callees::callee_query(lang) else {
         return Vec::new();
     };
 
@@ -95,7 +101,7 @@ fn find_callers_treesitter(
     let content_bytes = content.as_bytes();
     let lines: Vec<&str> = content.lines().collect();
     let mut cursor = tree_sitter::QueryCursor::new();
-    let mut matches = cursor.matches(&query, tree.root_node(), content_bytes);
+    let mut matches = cursor.matches(query, tree.root_node(), content_bytes);
 
     let mut callers = Vec::new();
 
@@ -154,40 +160,30 @@ fn find_enclosing_function(
     node: tree_sitter::Node,
     lines: &[&str],
 ) -> (String, Option<(u32, u32)>) {
-    // Walk up the tree until we find a definition node
-    let mut current = Some(node);
-
-    while let Some(n) = current {
-        let kind = n.kind();
-
-        if DEFINITION_KINDS.contains(&kind) {
-            // Extract the function name
-            let name =
-                extract_definition_name(n, lines).unwrap_or_else(|| "<anonymous>".to_string());
-            let range = Some((
-                n.start_position().row as u32 + 1,
-                n.end_position().row as u32 + 1,
-            ));
-            return (name, range);
-        }
-
-        current = n.parent();
+    match enclosing_symbol(node, lines) {
+        Some((name, range)) => (name, Some(range)),
+        // No enclosing function found — top-level call
+        None => ("<top-level>".to_string(), None),
     }
-
-    // No enclosing function found — top-level call
-    ("<top-level>".to_string(), None)
 }
 
 /// Format and rank caller search results with optional expand.
+/// `depth` above 1 follows callers of callers and renders a call tree instead.
 pub fn search_callers_expanded(
     target: &str,
     scope: &Path,
+    filter: &SearchFilter,
     _cache: &OutlineCache,
     _session: &Session,
     expand: usize,
     context: Option<&Path>,
+    depth: u32,
 ) -> Result<String, GleanError> {
-    let callers = find_callers(target, scope)?;
+    if depth > 1 {
+        return search_call_tree(target, scope, filter, depth, context);
+    }
+
+    let callers = find_callers(target, scope, filter)?;
 
     if callers.is_empty() {
         return Ok(format!(
@@ -218,11 +214,7 @@ pub fn search_callers_expanded(
         let _ = write!(
             output,
             "\n## {}:{} [caller: {}]\n",
-            caller
-                .path
-                .strip_prefix(scope)
-                .unwrap_or(&caller.path)
-                .display(),
+            crate::format::rel(&caller.path, scope, filter.absolute_paths),
             caller.line,
             caller.calling_function
         );
@@ -263,6 +255,119 @@ pub fn search_callers_expanded(
     Ok(output)
 }
 
+/// One call site in a call tree; `parent` is the call site inside the function
+/// this one calls, `None` for direct callers of the target.
+struct TreeNode {
+    caller: CallerMatch,
+    parent: Option<usize>,
+}
+
+/// Reverse call graph of `target`, `depth` levels deep, as an indented tree.
+///
+/// Each level runs `find_callers` on the calling functions found at the level
+/// above — matched by name, so common names pull in unrelated call sites.
+/// Call sites appear once (by file and line) and each function is followed
+/// once, which also stops recursion.
+fn search_call_tree(
+    target: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    depth: u32,
+    context: Option<&Path>,
+) -> Result<String, GleanError> {
+    use std::collections::HashSet;
+
+    let depth = depth.min(MAX_TREE_DEPTH);
+    let mut nodes: Vec<TreeNode> = Vec::new();
+    let mut seen: HashSet<(PathBuf, u32)> = HashSet::new();
+    let mut followed: HashSet<String> = HashSet::from([target.to_string()]);
+    // (name to find callers of, node calling into it)
+    let mut frontier: Vec<(String, Option<usize>)> = vec![(target.to_string(), None)];
+    let mut truncated = false;
+
+    'levels: for _ in 0..depth {
+        let mut next = Vec::new();
+        for (name, parent) in &frontier {
+            let mut callers = find_callers(name, scope, filter)?;
+            rank_callers(&mut callers, scope, context);
+            for caller in callers {
+                if !seen.insert((caller.path.clone(), caller.line)) {
+                    continue;
+                }
+                if nodes.len() >= MAX_TREE_NODES {
+                    truncated = true;
+                    break 'levels;
+                }
+                if caller.caller_range.is_some() && followed.insert(caller.calling_function.clone())
+                {
+                    next.push((caller.calling_function.clone(), Some(nodes.len())));
+                }
+                nodes.push(TreeNode {
+                    caller,
+                    parent: *parent,
+                });
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    if nodes.is_empty() {
+        return Ok(format!(
+            "# Callers of \"{}\" in {} — no call sites found",
+            target,
+            scope.display()
+        ));
+    }
+
+    let total = nodes.len();
+    let mut output = format!(
+        "# Call tree of \"{}\" in {} — {} call site{}, depth ≤ {depth}\n\n{target}\n",
+        target,
+        scope.display(),
+        total,
+        if total == 1 { "" } else { "s" }
+    );
+    write_tree(&mut output, &nodes, None, 1, scope, filter.absolute_paths);
+    if truncated {
+        let _ = writeln!(
+            output,
+            "\n... truncated at {MAX_TREE_NODES} call sites — lower depth or narrow scope"
+        );
+    }
+
+    let token_est = crate::types::estimate_tokens(output.len() as u64);
+    let _ = writeln!(output, "\n[~{token_est} tokens]");
+    Ok(output)
+}
+
+/// Children of `parent`, two spaces of indent per level:
+/// `← caller  path:line  call text`.
+fn write_tree(
+    out: &mut String,
+    nodes: &[TreeNode],
+    parent: Option<usize>,
+    level: usize,
+    scope: &Path,
+    absolute: bool,
+) {
+    for (i, node) in nodes.iter().enumerate().filter(|(_, n)| n.parent == parent) {
+        let c = &node.caller;
+        let _ = writeln!(
+            out,
+            "{}← {}  {}:{}  {}",
+            "  ".repeat(level),
+            c.calling_function,
+            crate::format::rel(&c.path, scope, absolute),
+            c.line,
+            c.call_text
+        );
+        write_tree(out, nodes, Some(i), level + 1, scope, absolute);
+    }
+}
+
 /// Simple ranking: context file first, then by path length (proximity heuristic).
 fn rank_callers(callers: &mut [CallerMatch], scope: &Path, context: Option<&Path>) {
     callers.sort_by(|a, b| {
@@ -308,7 +413,8 @@ mod tests {
     /// Without these, the agent needs extra tool calls to understand call chains.
     #[test]
     fn callers_provide_full_navigation_context() {
-        let callers = find_callers("Continue", &fixture("mini-go")).unwrap();
+        let callers =
+            find_callers("Continue", &fixture("mini-go"), &SearchFilter::default()).unwrap();
         assert!(!callers.is_empty(), "should find call sites for Next");
 
         // Must find the middleware call site
@@ -345,7 +451,8 @@ mod tests {
     /// This tests that multiple call sites across files are all found.
     #[test]
     fn finds_callers_across_multiple_files() {
-        let callers = find_callers("Continue", &fixture("mini-go")).unwrap();
+        let callers =
+            find_callers("Continue", &fixture("mini-go"), &SearchFilter::default()).unwrap();
         let files: std::collections::HashSet<_> = callers
             .iter()
             .map(|c| c.path.file_name().unwrap().to_string_lossy().to_string())
@@ -357,9 +464,48 @@ mod tests {
         );
     }
 
+    /// The callee query is compiled once per language and shared by every
+    /// file and rayon worker, however many searches run.
+    #[test]
+    fn callee_query_compiled_once_per_language() {
+        use crate::search::callees::{callee_query, compiles};
+        use crate::types::Lang;
+
+        for _ in 0..3 {
+            find_callers("Continue", &fixture("mini-go"), &SearchFilter::default()).unwrap();
+        }
+        let first = callee_query(Lang::Go).unwrap();
+        let again = callee_query(Lang::Go).unwrap();
+        assert!(std::ptr::eq(first, again));
+        assert_eq!(compiles(Lang::Go), 1);
+    }
+
+    /// depth 2 follows the caller's callers and nests them under it.
+    #[test]
+    fn call_tree_follows_callers_of_callers() {
+        let scope = fixture("mini-ruby");
+        let out = search_call_tree("validate", &scope, &SearchFilter::default(), 2, None).unwrap();
+        let save = out.find("\n  ← save  app/models/user.rb:").expect(&out);
+        let show = out
+            .find("\n    ← show  app/controllers/users_controller.rb:")
+            .expect(&out);
+        assert!(save < show, "{out}");
+        assert!(out.contains("depth ≤ 2"), "{out}");
+        assert!(!out.contains("truncated"), "{out}");
+
+        let direct =
+            search_call_tree("validate", &scope, &SearchFilter::default(), 1, None).unwrap();
+        assert!(!direct.contains("← show"), "{direct}");
+    }
+
     #[test]
     fn no_callers_returns_empty() {
-        let callers = find_callers("nonexistent_function_xyz", &fixture("mini-go")).unwrap();
+        let callers = find_callers(
+            "nonexistent_function_xyz",
+            &fixture("mini-go"),
+            &SearchFilter::default(),
+        )
+        .unwrap();
         assert!(callers.is_empty());
     }
 }
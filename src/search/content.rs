@@ -1,10 +1,17 @@
+use std::fs;
+use std::ops::Range;
 use std::path::Path;
+use std::time::SystemTime;
 
-use super::file_metadata;
+use super::{MAX_SEARCH_FILE_SIZE, file_metadata};
 
 use crate::error::GleanError;
-use crate::search::rank;
-use crate::types::{Match, SearchResult};
+use crate::read::detect_file_type;
+use crate::read::outline::code::outline_language;
+use crate::search::{SearchFilter, rank};
+use crate::source::FileSource;
+use crate::types::{FileType, Match, SearchResult};
+use grep_matcher::Matcher;
 use grep_regex::RegexMatcher;
 use grep_searcher::BinaryDetection;
 use grep_searcher::SearcherBuilder;
@@ -12,27 +19,20 @@ use grep_searcher::sinks::UTF8;
 
 const MAX_MATCHES: usize = 10;
 const EARLY_QUIT_THRESHOLD: usize = MAX_MATCHES * 3;
-const MAX_SEARCH_FILE_SIZE: u64 = 500_000;
 
 /// Content search using ripgrep crates. Literal by default, regex if `is_regex`.
 pub fn search(
     pattern: &str,
     scope: &Path,
+    filter: &SearchFilter,
     is_regex: bool,
     context: Option<&Path>,
 ) -> Result<SearchResult, GleanError> {
-    let matcher = if is_regex {
-        RegexMatcher::new(pattern)
-    } else {
-        RegexMatcher::new(&regex_syntax::escape(pattern))
-    }
-    .map_err(|e| GleanError::InvalidQuery {
-        query: pattern.to_string(),
-        reason: e.to_string(),
-    })?;
+    let matcher = build_matcher(pattern, is_regex)?;
 
     let mut all_matches = super::walk_collect(
         scope,
+        filter,
         Some(EARLY_QUIT_THRESHOLD),
         Some(MAX_SEARCH_FILE_SIZE),
         |entry| {
@@ -54,6 +54,7 @@ pub fn search(
                         column: 0,
                         text: line.trim_end().to_string(),
                         is_definition: false,
+                        implementation: false,
                         exact: false,
                         file_lines,
                         mtime,
@@ -70,6 +71,184 @@ pub fn search(
 
     let total = all_matches.len();
 
+    if filter.git_recency {
+        rank::use_commit_times(&mut all_matches);
+    }
+    rank::sort(&mut all_matches, pattern, scope, context);
+    all_matches.truncate(MAX_MATCHES);
+
+    Ok(SearchResult {
+        query: pattern.to_string(),
+        scope: scope.to_path_buf(),
+        matches: all_matches,
+        total_found: total,
+        definitions: 0,
+        usages: total,
+    })
+}
+
+/// `search` over the files of a `FileSource` rather than a disk walk, such as
+/// piped stdin held in memory. Sequential, like `symbol::search_source`.
+pub fn search_source(
+    source: &impl FileSource,
+    pattern: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    is_regex: bool,
+) -> Result<SearchResult, GleanError> {
+    let matcher = build_matcher(pattern, is_regex)?;
+
+    let mut all_matches = Vec::new();
+    for path in source.files(scope) {
+        let rel = path.strip_prefix(scope).unwrap_or(&path);
+        if filter.excludes(rel) {
+            continue;
+        }
+        let Ok(bytes) = source.read(&path) else {
+            continue;
+        };
+        let mtime = source.modified(&path).unwrap_or(SystemTime::UNIX_EPOCH);
+        let file_lines = memchr::memchr_iter(b'\n', &bytes).count() as u32;
+
+        let mut searcher = SearcherBuilder::new()
+            .binary_detection(BinaryDetection::convert(b'\x00'))
+            .build();
+        let _ = searcher.search_slice(
+            &matcher,
+            &bytes,
+            UTF8(|line_num, line| {
+                all_matches.push(Match {
+                    path: path.clone(),
+                    line: line_num as u32,
+                    column: 0,
+                    text: line.trim_end().to_string(),
+                    is_definition: false,
+                    implementation: false,
+                    exact: false,
+                    file_lines,
+                    mtime,
+                    def_range: None,
+                    def_name: None,
+                });
+                Ok(true)
+            }),
+        );
+    }
+
+    let total = all_matches.len();
+    rank::sort(&mut all_matches, pattern, scope, None);
+    all_matches.truncate(MAX_MATCHES);
+
+    Ok(SearchResult {
+        query: pattern.to_string(),
+        scope: scope.to_path_buf(),
+        matches: all_matches,
+        total_found: total,
+        definitions: 0,
+        usages: total,
+    })
+}
+
+/// Which part of a file a content search looks in (`scope_to`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    /// Comments and docstrings.
+    Comments,
+    /// String literals, docstrings excluded.
+    Strings,
+    /// Everything that is neither a comment nor a string.
+    Code,
+}
+
+impl Region {
+    pub const NAMES: [&'static str; 3] = ["comments", "strings", "code"];
+
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "comments" => Some(Self::Comments),
+            "strings" => Some(Self::Strings),
+            "code" => Some(Self::Code),
+            _ => None,
+        }
+    }
+}
+
+/// Content search restricted to one `Region` of each file, classified by
+/// parsing the file. For comments, grammar-less code and config files keep
+/// matches after a `//` or `#` on the line, and other files are skipped. For
+/// strings and code, files without a grammar keep every match, as in plain
+/// content search.
+pub fn search_region(
+    pattern: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    is_regex: bool,
+    context: Option<&Path>,
+    region: Region,
+) -> Result<SearchResult, GleanError> {
+    let matcher = build_matcher(pattern, is_regex)?;
+
+    let mut all_matches = super::walk_collect(
+        scope,
+        filter,
+        Some(EARLY_QUIT_THRESHOLD),
+        Some(MAX_SEARCH_FILE_SIZE),
+        |entry| {
+            let path = entry.path();
+            // Prose and data files have no comments worth searching
+            let file_type = detect_file_type(path);
+            if region == Region::Comments
+                && !matches!(
+                    file_type,
+                    FileType::Code(_) | FileType::StructuredData | FileType::Notebook
+                )
+            {
+                return Vec::new();
+            }
+            let Ok(content) = fs::read_to_string(path) else {
+                return Vec::new();
+            };
+            if matcher.find(content.as_bytes()).ok().flatten().is_none() {
+                return Vec::new();
+            }
+            let comments = CommentMap::new(file_type, &content);
+            let (file_lines, mtime) = file_metadata(path);
+
+            let mut offset = 0;
+            let mut file_matches = Vec::new();
+            for (i, line) in content.split_inclusive('\n').enumerate() {
+                let mut hit = false;
+                let _ = matcher.find_iter(line.as_bytes(), |m| {
+                    hit = comments.in_region(region, line, offset, m.start());
+                    !hit
+                });
+                if hit {
+                    file_matches.push(Match {
+                        path: path.to_path_buf(),
+                        line: (i + 1) as u32,
+                        column: 0,
+                        text: line.trim_end().to_string(),
+                        is_definition: false,
+                        implementation: false,
+                        exact: false,
+                        file_lines,
+                        mtime,
+                        def_range: None,
+                        def_name: None,
+                    });
+                }
+                offset += line.len();
+            }
+            file_matches
+        },
+    );
+
+    let total = all_matches.len();
+
+    if filter.git_recency {
+        rank::use_commit_times(&mut all_matches);
+    }
     rank::sort(&mut all_matches, pattern, scope, context);
     all_matches.truncate(MAX_MATCHES);
 
@@ -83,6 +262,92 @@ pub fn search(
     })
 }
 
+pub(super) fn build_matcher(pattern: &str, is_regex: bool) -> Result<RegexMatcher, GleanError> {
+    if is_regex {
+        RegexMatcher::new(pattern)
+    } else {
+        RegexMatcher::new(&regex_syntax::escape(pattern))
+    }
+    .map_err(|e| GleanError::InvalidQuery {
+        query: pattern.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Where a file's comments (and strings) are: parsed nodes, or a per-line
+/// prefix guess for comments.
+pub(super) enum CommentMap {
+    Parsed {
+        tree: tree_sitter::Tree,
+        ranges: Vec<Range<usize>>,
+        strings: Vec<Range<usize>>,
+    },
+    Heuristic,
+}
+
+impl CommentMap {
+    pub(super) fn new(file_type: FileType, content: &str) -> Self {
+        let ts_lang = match file_type {
+            FileType::Code(lang) => outline_language(lang),
+            _ => None,
+        };
+        ts_lang
+            .and_then(|l| super::treesitter::parse_tree(content, &l))
+            .map_or(CommentMap::Heuristic, |tree| CommentMap::Parsed {
+                ranges: super::treesitter::comment_ranges(&tree),
+                strings: super::treesitter::string_ranges(&tree),
+                tree,
+            })
+    }
+
+    /// Name and line range of the definition around byte `pos`. Only known
+    /// when the file was parsed.
+    pub(super) fn enclosing_symbol(
+        &self,
+        pos: usize,
+        lines: &[&str],
+    ) -> Option<(String, (u32, u32))> {
+        let CommentMap::Parsed { tree, .. } = self else {
+            return None;
+        };
+        let node = tree.root_node().descendant_for_byte_range(pos, pos)?;
+        super::treesitter::enclosing_symbol(node, lines)
+    }
+
+    /// Whether the match at byte `col` of `line` (starting at byte `line_start`
+    /// of the file) is inside a comment.
+    pub(super) fn contains(&self, line: &str, line_start: usize, col: usize) -> bool {
+        self.in_region(Region::Comments, line, line_start, col)
+    }
+
+    /// Whether the match at byte `col` of `line` falls in `region`. Without a
+    /// parse, only comments are guessed; strings and code take every match.
+    fn in_region(&self, region: Region, line: &str, line_start: usize, col: usize) -> bool {
+        match self {
+            CommentMap::Parsed {
+                ranges, strings, ..
+            } => {
+                let pos = line_start + col;
+                let inside = |ranges: &[Range<usize>]| {
+                    let i = ranges.partition_point(|r| r.end <= pos);
+                    ranges.get(i).is_some_and(|r| r.start <= pos)
+                };
+                match region {
+                    Region::Comments => inside(ranges),
+                    Region::Strings => inside(strings),
+                    Region::Code => !inside(ranges) && !inside(strings),
+                }
+            }
+            CommentMap::Heuristic if region == Region::Comments => ["//", "#"]
+                .iter()
+                .filter_map(|marker| line.find(marker))
+                .min()
+                .is_some_and(|start| start < col),
+            CommentMap::Heuristic => true,
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::doc_markdown)]
 mod tests {
@@ -101,7 +366,14 @@ mod tests {
     /// avoids a follow-up search.
     #[test]
     fn top_result_is_most_relevant_file() {
-        let result = search("X-Forwarded-For", &fixture("mini-go"), false, None).unwrap();
+        let result = search(
+            "X-Forwarded-For",
+            &fixture("mini-go"),
+            &SearchFilter::default(),
+            false,
+            None,
+        )
+        .unwrap();
         assert!(result.total_found > 0, "should find X-Forwarded-For");
         let first = &result.matches[0];
         assert!(
@@ -115,7 +387,14 @@ mod tests {
     /// any line mentioning "Continue". The matched text should be the func signature.
     #[test]
     fn regex_search_finds_method_signature() {
-        let result = search(r"func \(.*\) Continue", &fixture("mini-go"), true, None).unwrap();
+        let result = search(
+            r"func \(.*\) Continue",
+            &fixture("mini-go"),
+            &SearchFilter::default(),
+            true,
+            None,
+        )
+        .unwrap();
         assert!(
             result.total_found > 0,
             "should find Continue method via regex"
@@ -134,7 +413,14 @@ mod tests {
     #[test]
     fn unique_string_returns_tight_count() {
         // "X-Forwarded-For" appears in exactly one file
-        let result = search("X-Forwarded-For", &fixture("mini-go"), false, None).unwrap();
+        let result = search(
+            "X-Forwarded-For",
+            &fixture("mini-go"),
+            &SearchFilter::default(),
+            false,
+            None,
+        )
+        .unwrap();
         assert!(
             result.total_found <= 3,
             "unique string should have tight result count, got {}",
@@ -147,10 +433,89 @@ mod tests {
         let result = search(
             "xyzzy_nonexistent_string_42",
             &fixture("mini-go"),
+            &SearchFilter::default(),
             false,
             None,
         )
         .unwrap();
         assert_eq!(result.total_found, 0);
     }
+
+    /// The same `TODO` text appears in a comment and a string literal; only
+    /// the comment counts. Python docstrings count as comments.
+    #[test]
+    fn comment_search_ignores_string_literals() {
+        let scope = fixture("todos");
+        let lines = |pattern: &str| {
+            let result = search_region(
+                pattern,
+                &scope,
+                &SearchFilter::default(),
+                false,
+                None,
+                Region::Comments,
+            )
+            .unwrap();
+            let mut hits: Vec<String> = result
+                .matches
+                .iter()
+                .map(|m| {
+                    format!(
+                        "{}:{}",
+                        m.path.file_name().unwrap().to_string_lossy(),
+                        m.line
+                    )
+                })
+                .collect();
+            hits.sort();
+            hits
+        };
+
+        // worker.ts:5, queue.py:13 and build.kts:6 hold "TODO" in strings
+        assert_eq!(lines("TODO"), ["build.kts:5", "worker.ts:4"]);
+        assert_eq!(lines("HACK"), ["queue.py:4"]);
+        assert_eq!(lines("FIXME"), ["worker.ts:13"]);
+
+        let plain = search("TODO", &scope, &SearchFilter::default(), false, None).unwrap();
+        assert_eq!(plain.total_found, 5, "plain content search sees every TODO");
+    }
+
+    /// String literals exclude docstrings; code excludes both comments and
+    /// strings.
+    #[test]
+    fn string_and_code_regions() {
+        let scope = fixture("todos");
+        let lines = |pattern: &str, region: Region| {
+            let result = search_region(
+                pattern,
+                &scope,
+                &SearchFilter::default(),
+                false,
+                None,
+                region,
+            )
+            .unwrap();
+            let mut hits: Vec<String> = result
+                .matches
+                .iter()
+                .map(|m| {
+                    format!(
+                        "{}:{}",
+                        m.path.file_name().unwrap().to_string_lossy(),
+                        m.line
+                    )
+                })
+                .collect();
+            hits.sort();
+            hits
+        };
+
+        assert_eq!(
+            lines("TODO", Region::Strings),
+            ["build.kts:6", "queue.py:13", "worker.ts:5"]
+        );
+        assert!(lines("HACK", Region::Strings).is_empty());
+        assert!(lines("TODO", Region::Code).is_empty());
+        assert_eq!(lines("items", Region::Code), ["queue.py:12", "queue.py:8"]);
+    }
 }
@@ -0,0 +1,211 @@
+//! Count-only search: per-file match counts without listing or expansion.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use grep_regex::RegexMatcher;
+use grep_searcher::sinks::UTF8;
+use grep_searcher::{BinaryDetection, SearcherBuilder};
+
+use super::symbol::NameCase;
+use super::{MAX_SEARCH_FILE_SIZE, SearchFilter};
+use crate::error::GleanError;
+use crate::format::{self, plural};
+
+/// Matching lines in one file, split by definition vs usage.
+#[derive(Debug)]
+pub struct FileCount {
+    pub path: PathBuf,
+    pub definitions: usize,
+    pub usages: usize,
+}
+
+impl FileCount {
+    fn total(&self) -> usize {
+        self.definitions + self.usages
+    }
+}
+
+/// Per-file counts for a symbol: definition lines via tree-sitter, usage lines
+/// via word-boundary regex. Walks the whole scope — no early quit, no expand.
+pub fn symbol_counts(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<Vec<FileCount>, GleanError> {
+    let matcher = super::symbol::symbol_matcher(query, NameCase::of(filter))?;
+    let mode = filter.definitions;
+
+    let counts = super::walk_collect(scope, filter, None, Some(MAX_SEARCH_FILE_SIZE), |entry| {
+        let path = entry.path();
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        if !super::symbol::may_contain(&content, query, NameCase::of(filter)) {
+            return Vec::new();
+        }
+
+        let def_lines: HashSet<u32> = super::symbol::file_definitions(
+            path,
+            query,
+            &content,
+            super::file_metadata(path),
+            mode,
+            filter,
+        )
+        .iter()
+        .map(|d| d.line)
+        .collect();
+        let usages = matching_lines(&matcher, &content)
+            .into_iter()
+            .filter(|line| !def_lines.contains(line))
+            .count();
+
+        file_count(path, def_lines.len(), usages)
+    });
+
+    Ok(sorted(counts))
+}
+
+/// Per-file counts of lines matching a literal or regex pattern.
+pub fn content_counts(
+    pattern: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    is_regex: bool,
+) -> Result<Vec<FileCount>, GleanError> {
+    let matcher = super::content::build_matcher(pattern, is_regex)?;
+
+    let counts = super::walk_collect(scope, filter, None, Some(MAX_SEARCH_FILE_SIZE), |entry| {
+        let path = entry.path();
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        file_count(path, 0, matching_lines(&matcher, &content).len())
+    });
+
+    Ok(sorted(counts))
+}
+
+/// Render counts as `N  path  (D definitions, U usages)`, highest first.
+pub fn format_counts(query: &str, scope: &Path, counts: &[FileCount], absolute: bool) -> String {
+    let total: usize = counts.iter().map(FileCount::total).sum();
+    let defs: usize = counts.iter().map(|c| c.definitions).sum();
+    let mut out = format!(
+        "# Count: \"{query}\" in {} — {} in {} ({}, {})\n",
+        scope.display(),
+        plural(total, "match", "matches"),
+        plural(counts.len(), "file", "files"),
+        plural(defs, "definition", "definitions"),
+        plural(total - defs, "usage", "usages"),
+    );
+
+    let width = counts.first().map_or(1, |c| c.total().to_string().len());
+    for c in counts {
+        let _ = write!(
+            out,
+            "\n{:>width$}  {}",
+            c.total(),
+            format::rel(&c.path, scope, absolute)
+        );
+        if c.definitions > 0 {
+            let _ = write!(
+                out,
+                "  ({}, {})",
+                plural(c.definitions, "definition", "definitions"),
+                plural(c.usages, "usage", "usages")
+            );
+        }
+    }
+    out
+}
+
+fn matching_lines(matcher: &RegexMatcher, content: &str) -> Vec<u32> {
+    let mut lines = Vec::new();
+    let mut searcher = SearcherBuilder::new()
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+    let _ = searcher.search_slice(
+        matcher,
+        content.as_bytes(),
+        UTF8(|line_num, _| {
+            lines.push(line_num as u32);
+            Ok(true)
+        }),
+    );
+    lines
+}
+
+fn file_count(path: &Path, definitions: usize, usages: usize) -> Vec<FileCount> {
+    if definitions + usages == 0 {
+        return Vec::new();
+    }
+    vec![FileCount {
+        path: path.to_path_buf(),
+        definitions,
+        usages,
+    }]
+}
+
+/// Highest count first; path breaks ties so output is deterministic.
+fn sorted(mut counts: Vec<FileCount>) -> Vec<FileCount> {
+    counts.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.path.cmp(&b.path)));
+    counts
+}
+
+#[cfg(test)]
+#[allow(clippy::doc_markdown)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    /// Counts must equal the real occurrences: router.go defines ServeHTTP once
+    /// and mentions it again in its doc comment; the test calls it once.
+    #[test]
+    fn symbol_counts_match_occurrences() {
+        let scope = fixture("mini-go");
+        let counts = symbol_counts("ServeHTTP", &scope, &SearchFilter::default()).unwrap();
+
+        let router = counts
+            .iter()
+            .find(|c| c.path.ends_with("router.go"))
+            .expect("router.go counted");
+        let router_lines = fs::read_to_string(scope.join("router.go"))
+            .unwrap()
+            .lines()
+            .filter(|l| l.contains("ServeHTTP"))
+            .count();
+        assert_eq!(router.definitions, 1);
+        assert_eq!(router.total(), router_lines);
+
+        let test = counts
+            .iter()
+            .find(|c| c.path.ends_with("router_test.go"))
+            .expect("router_test.go counted");
+        assert_eq!((test.definitions, test.usages), (0, 1));
+
+        // Sorted by count, highest first
+        assert!(counts.windows(2).all(|w| w[0].total() >= w[1].total()));
+
+        let output = format_counts("ServeHTTP", &scope, &counts, false);
+        assert!(output.contains("router.go  (1 definition, "), "{output}");
+
+        let single = [FileCount {
+            path: scope.join("router.go"),
+            definitions: 1,
+            usages: 0,
+        }];
+        let output = format_counts("ServeHTTP", &scope, &single, false);
+        assert!(
+            output.contains("— 1 match in 1 file (1 definition, 0 usages)"),
+            "{output}"
+        );
+    }
+}
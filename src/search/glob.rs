@@ -1,10 +1,11 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use globset::Glob;
 
 use crate::error::GleanError;
-use crate::types::estimate_tokens;
+use crate::search::SearchFilter;
+use crate::types::file_tokens;
 
 const MAX_FILES: usize = 20;
 
@@ -13,15 +14,29 @@ pub struct GlobFileEntry {
     pub preview: Option<String>,
 }
 
+/// Matches under one top-level directory of the scope (`.` for files
+/// directly in it), summed over every match — not just the listed ones.
+pub struct GlobDirEntry {
+    pub path: PathBuf,
+    pub files: usize,
+    pub tokens: u64,
+}
+
 pub struct GlobResult {
     pub pattern: String,
     pub files: Vec<GlobFileEntry>,
     pub total_found: usize,
     pub available_extensions: Vec<String>,
+    /// Largest token total first.
+    pub dirs: Vec<GlobDirEntry>,
 }
 
 /// Glob search using `ignore::WalkBuilder` (parallel, .gitignore-aware).
-pub fn search(pattern: &str, scope: &Path) -> Result<GlobResult, GleanError> {
+pub fn search(
+    pattern: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<GlobResult, GleanError> {
     let glob = Glob::new(pattern).map_err(|e| GleanError::InvalidQuery {
         query: pattern.to_string(),
         reason: e.to_string(),
@@ -31,14 +46,17 @@ pub fn search(pattern: &str, scope: &Path) -> Result<GlobResult, GleanError> {
     let files: std::sync::Mutex<Vec<GlobFileEntry>> = std::sync::Mutex::new(Vec::new());
     let total_found = std::sync::atomic::AtomicUsize::new(0);
     let extensions: std::sync::Mutex<HashSet<String>> = std::sync::Mutex::new(HashSet::new());
+    let dirs: std::sync::Mutex<HashMap<PathBuf, (usize, u64)>> =
+        std::sync::Mutex::new(HashMap::new());
 
-    let walker = super::walker(scope);
+    let walker = super::walker(scope, filter);
 
     walker.run(|| {
         let matcher = &matcher;
         let files = &files;
         let total_found = &total_found;
         let extensions = &extensions;
+        let dirs = &dirs;
 
         Box::new(move |entry| {
             let Ok(entry) = entry else {
@@ -48,6 +66,9 @@ pub fn search(pattern: &str, scope: &Path) -> Result<GlobResult, GleanError> {
             if !entry.file_type().is_some_and(|ft| ft.is_file()) {
                 return ignore::WalkState::Continue;
             }
+            if let Some(progress) = &filter.progress {
+                progress.tick();
+            }
 
             let path = entry.path();
 
@@ -65,8 +86,18 @@ pub fn search(pattern: &str, scope: &Path) -> Result<GlobResult, GleanError> {
 
             if matcher.is_match(name) || matcher.is_match(rel) {
                 total_found.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                // Counted once: with the tokenizer feature this reads the file
+                let tokens = entry.metadata().ok().map(|m| file_tokens(path, m.len()));
+                {
+                    let mut dirs = dirs
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner);
+                    let slot = dirs.entry(top_dir(rel)).or_default();
+                    slot.0 += 1;
+                    slot.1 += tokens.unwrap_or(0);
+                }
                 // Compute preview outside the lock, then check-and-push in one acquisition
-                let preview = file_preview(path);
+                let preview = tokens.map(|t| format!("~{t} tokens"));
                 let mut locked = files
                     .lock()
                     .unwrap_or_else(std::sync::PoisonError::into_inner);
@@ -90,6 +121,18 @@ pub fn search(pattern: &str, scope: &Path) -> Result<GlobResult, GleanError> {
         .into_inner()
         .unwrap_or_else(std::sync::PoisonError::into_inner);
 
+    let mut dirs: Vec<GlobDirEntry> = dirs
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .into_iter()
+        .map(|(path, (files, tokens))| GlobDirEntry {
+            path,
+            files,
+            tokens,
+        })
+        .collect();
+    dirs.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+
     let available_extensions: Vec<String> = if files.is_empty() {
         let mut exts: Vec<String> = extensions.into_iter().collect();
         exts.sort();
@@ -104,12 +147,15 @@ pub fn search(pattern: &str, scope: &Path) -> Result<GlobResult, GleanError> {
         files,
         total_found: total,
         available_extensions,
+        dirs,
     })
 }
 
-/// Quick preview: token estimate, or "test file", or "module" based on exports.
-fn file_preview(path: &Path) -> Option<String> {
-    let meta = std::fs::metadata(path).ok()?;
-    let tokens = estimate_tokens(meta.len());
-    Some(format!("~{tokens} tokens"))
+/// First component of a scope-relative path, or `.` for a file at the top.
+fn top_dir(rel: &Path) -> PathBuf {
+    let mut components = rel.components();
+    match (components.next(), components.next()) {
+        (Some(first), Some(_)) => PathBuf::from(first.as_os_str()),
+        _ => PathBuf::from("."),
+    }
 }
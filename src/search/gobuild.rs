@@ -0,0 +1,325 @@
+//! Go build constraints: skip `.go` files that wouldn't compile for a target
+//! platform, judged by `_GOOS`/`_GOARCH` filename suffixes and `//go:build` lines.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Values accepted by `GOOS`. Filename suffixes only count when they name one.
+const KNOWN_OS: &[&str] = &[
+    "aix",
+    "android",
+    "darwin",
+    "dragonfly",
+    "freebsd",
+    "hurd",
+    "illumos",
+    "ios",
+    "js",
+    "linux",
+    "nacl",
+    "netbsd",
+    "openbsd",
+    "plan9",
+    "solaris",
+    "wasip1",
+    "windows",
+    "zos",
+];
+
+/// Values accepted by `GOARCH`.
+const KNOWN_ARCH: &[&str] = &[
+    "386", "amd64", "arm", "arm64", "loong64", "mips", "mips64", "mips64le", "mipsle", "ppc64",
+    "ppc64le", "riscv64", "s390x", "wasm",
+];
+
+/// Operating systems satisfying the `unix` build tag.
+const UNIX_OS: &[&str] = &[
+    "aix",
+    "android",
+    "darwin",
+    "dragonfly",
+    "freebsd",
+    "hurd",
+    "illumos",
+    "ios",
+    "linux",
+    "netbsd",
+    "openbsd",
+    "solaris",
+];
+
+/// Build constraints live above the package clause; don't read further.
+const HEADER_BYTES: usize = 4096;
+
+/// A `GOOS`/`GOARCH` pair to evaluate build constraints against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoTarget {
+    pub os: String,
+    pub arch: String,
+}
+
+impl GoTarget {
+    /// The platform glean is running on, in Go's naming.
+    #[must_use]
+    pub fn host() -> Self {
+        let os = match std::env::consts::OS {
+            "macos" => "darwin",
+            other => other,
+        };
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => "amd64",
+            "aarch64" => "arm64",
+            "x86" => "386",
+            "powerpc64" => "ppc64",
+            "loongarch64" => "loong64",
+            other => other,
+        };
+        GoTarget {
+            os: os.to_string(),
+            arch: arch.to_string(),
+        }
+    }
+
+    /// Parse `"os/arch"`, or `"host"` for the current platform.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        if s == "host" {
+            return Some(Self::host());
+        }
+        let (os, arch) = s.split_once('/')?;
+        if !KNOWN_OS.contains(&os) || !KNOWN_ARCH.contains(&arch) {
+            return None;
+        }
+        Some(GoTarget {
+            os: os.to_string(),
+            arch: arch.to_string(),
+        })
+    }
+
+    /// Whether a file would be built for this target. Non-Go files always pass.
+    #[must_use]
+    pub fn includes(&self, path: &Path) -> bool {
+        if path.extension().is_none_or(|e| e != "go") {
+            return true;
+        }
+        if !self.filename_matches(path) {
+            return false;
+        }
+        build_line(path).is_none_or(|expr| self.eval(&expr))
+    }
+
+    /// `name_GOOS.go`, `name_GOARCH.go`, `name_GOOS_GOARCH.go` (before `_test`).
+    fn filename_matches(&self, path: &Path) -> bool {
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            return true;
+        };
+        let stem = stem.strip_suffix("_test").unwrap_or(stem);
+        let parts: Vec<&str> = stem.split('_').collect();
+        // The first element is the name proper: `linux.go` is unconstrained
+        let n = parts.len();
+        if n >= 3 && KNOWN_OS.contains(&parts[n - 2]) && KNOWN_ARCH.contains(&parts[n - 1]) {
+            return self.has_tag(parts[n - 2]) && parts[n - 1] == self.arch;
+        }
+        if n >= 2 {
+            let last = parts[n - 1];
+            if KNOWN_OS.contains(&last) {
+                return self.has_tag(last);
+            }
+            if KNOWN_ARCH.contains(&last) {
+                return last == self.arch;
+            }
+        }
+        true
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        tag == self.os
+            || tag == self.arch
+            || (tag == "unix" && UNIX_OS.contains(&self.os.as_str()))
+            // GOOS=android implies linux, GOOS=ios implies darwin, illumos implies solaris
+            || (tag == "linux" && self.os == "android")
+            || (tag == "darwin" && self.os == "ios")
+            || (tag == "solaris" && self.os == "illumos")
+            || tag == "gc"
+            || tag.starts_with("go1.")
+    }
+
+    /// Evaluate a `//go:build` expression. Unknown tags (`cgo`, custom tags)
+    /// are false, matching a plain `go build`.
+    fn eval(&self, expr: &str) -> bool {
+        let tokens = tokenize(expr);
+        let mut pos = 0;
+        let result = self.eval_or(&tokens, &mut pos);
+        // Malformed expression: keep the file rather than hide it
+        result.unwrap_or(true)
+    }
+
+    fn eval_or(&self, tokens: &[Token], pos: &mut usize) -> Option<bool> {
+        let mut value = self.eval_and(tokens, pos)?;
+        while tokens.get(*pos) == Some(&Token::Or) {
+            *pos += 1;
+            value |= self.eval_and(tokens, pos)?;
+        }
+        Some(value)
+    }
+
+    fn eval_and(&self, tokens: &[Token], pos: &mut usize) -> Option<bool> {
+        let mut value = self.eval_not(tokens, pos)?;
+        while tokens.get(*pos) == Some(&Token::And) {
+            *pos += 1;
+            value &= self.eval_not(tokens, pos)?;
+        }
+        Some(value)
+    }
+
+    fn eval_not(&self, tokens: &[Token], pos: &mut usize) -> Option<bool> {
+        let token = tokens.get(*pos)?;
+        *pos += 1;
+        match token {
+            Token::Not => self.eval_not(tokens, pos).map(|v| !v),
+            Token::Open => {
+                let value = self.eval_or(tokens, pos)?;
+                (tokens.get(*pos) == Some(&Token::Close)).then(|| {
+                    *pos += 1;
+                    value
+                })
+            }
+            Token::Tag(tag) => Some(self.has_tag(tag)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String),
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+fn tokenize(expr: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '!' => tokens.push(Token::Not),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '&' if chars.next_if_eq(&'&').is_some() => tokens.push(Token::And),
+            '|' if chars.next_if_eq(&'|').is_some() => tokens.push(Token::Or),
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut tag = c.to_string();
+                while let Some(next) =
+                    chars.next_if(|n| n.is_alphanumeric() || *n == '_' || *n == '.')
+                {
+                    tag.push(next);
+                }
+                tokens.push(Token::Tag(tag));
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// The `//go:build` expression from a file's header, if any.
+fn build_line(path: &Path) -> Option<String> {
+    let mut buf = Vec::with_capacity(HEADER_BYTES);
+    File::open(path)
+        .ok()?
+        .take(HEADER_BYTES as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+    let header = String::from_utf8_lossy(&buf);
+    for line in header.lines() {
+        let line = line.trim();
+        if let Some(expr) = line.strip_prefix("//go:build") {
+            return Some(expr.trim().to_string());
+        }
+        if line.starts_with("package ") {
+            break;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchFilter;
+    use std::path::PathBuf;
+
+    fn target(s: &str) -> GoTarget {
+        GoTarget::parse(s).unwrap()
+    }
+
+    #[test]
+    fn filename_suffixes() {
+        let linux = target("linux/amd64");
+        assert!(linux.filename_matches(Path::new("poll_linux.go")));
+        assert!(linux.filename_matches(Path::new("poll_linux_test.go")));
+        assert!(linux.filename_matches(Path::new("poll_amd64.go")));
+        assert!(linux.filename_matches(Path::new("linux.go")));
+        assert!(!linux.filename_matches(Path::new("poll_windows.go")));
+        assert!(!linux.filename_matches(Path::new("poll_linux_arm64.go")));
+        assert!(!linux.filename_matches(Path::new("poll_darwin_test.go")));
+    }
+
+    #[test]
+    fn build_expressions() {
+        let darwin = target("darwin/arm64");
+        assert!(darwin.eval("darwin && (arm64 || amd64)"));
+        assert!(darwin.eval("unix"));
+        assert!(darwin.eval("!windows && !cgo"));
+        assert!(!darwin.eval("linux || windows"));
+        assert!(!darwin.eval("integration"));
+    }
+
+    #[test]
+    fn host_uses_go_names() {
+        let host = GoTarget::host();
+        assert!(KNOWN_OS.contains(&host.os.as_str()), "{host:?}");
+        assert_ne!(host.os, "macos");
+        assert_ne!(host.arch, "x86_64");
+    }
+
+    /// Each target searches the portable files plus only its own platform's.
+    #[test]
+    fn search_skips_other_platform_files() {
+        let scope = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/go-build");
+        let searched = |t: Option<&str>| {
+            let filter = SearchFilter {
+                go_target: t.map(target),
+                ..SearchFilter::default()
+            };
+            let result =
+                crate::search::content::search("pollWait", &scope, &filter, false, None).unwrap();
+            let mut files: Vec<String> = result
+                .matches
+                .iter()
+                .map(|m| m.path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            files.sort();
+            files.dedup();
+            files
+        };
+
+        assert_eq!(
+            searched(Some("linux/amd64")),
+            ["poll_linux.go", "poll_nocgo.go", "poller.go"]
+        );
+        assert_eq!(
+            searched(Some("windows/amd64")),
+            ["poll_windows_amd64.go", "poller.go"]
+        );
+        assert_eq!(
+            searched(Some("darwin/arm64")),
+            ["poll_kqueue.go", "poll_nocgo.go", "poller.go"]
+        );
+        assert_eq!(searched(None).len(), 5, "no target: every file");
+    }
+}
@@ -0,0 +1,282 @@
+//! Blast radius of changing a symbol: where it's defined, plus every function
+//! that reaches it through a chain of calls, grouped by package.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::SearchFilter;
+use super::{callers, rank, symbol};
+use crate::error::GleanError;
+use crate::format;
+
+/// Caller levels followed when the caller doesn't say.
+pub const DEFAULT_DEPTH: u32 = 3;
+
+/// Deepest caller level ever followed.
+const MAX_DEPTH: u32 = 6;
+
+/// Stop collecting callers past this many; the report says it was cut short.
+const MAX_CALLERS: usize = 100;
+
+/// Where the target symbol is defined.
+#[derive(Debug)]
+pub struct Definition {
+    pub path: PathBuf,
+    pub line: u32,
+    pub range: Option<(u32, u32)>,
+}
+
+/// A function that calls the target, directly (depth 1) or through others.
+#[derive(Debug)]
+pub struct ImpactCaller {
+    pub path: PathBuf,
+    pub line: u32,
+    pub function: String,
+    /// The name it calls: the target itself, or a caller from the level above.
+    pub calls: String,
+    pub depth: u32,
+}
+
+#[derive(Debug)]
+pub struct Impact {
+    pub definitions: Vec<Definition>,
+    /// Ordered by path, then line.
+    pub callers: Vec<ImpactCaller>,
+    pub depth: u32,
+    /// Hit `MAX_CALLERS` before the walk finished.
+    pub truncated: bool,
+}
+
+impl Impact {
+    /// Distinct files holding at least one caller.
+    #[must_use]
+    pub fn affected_files(&self) -> usize {
+        self.callers
+            .iter()
+            .map(|c| &c.path)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+/// Definitions of `target` and its transitive callers, up to `depth` levels.
+///
+/// Callers are matched by name at each level, so a common function name can
+/// pull in unrelated call sites — the same trade-off `kind: "callers"` makes.
+/// Each (file, function) pair is reported once, at the shallowest depth.
+pub fn find_impact(
+    target: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    depth: u32,
+) -> Result<Impact, GleanError> {
+    let depth = depth.clamp(1, MAX_DEPTH);
+    let definitions = symbol::search(target, scope, filter, None)?
+        .matches
+        .into_iter()
+        .filter(|m| m.is_definition && m.exact)
+        .map(|m| Definition {
+            path: m.path,
+            line: m.line,
+            range: m.def_range,
+        })
+        .collect();
+
+    let mut found: Vec<ImpactCaller> = Vec::new();
+    let mut seen: HashSet<(PathBuf, String)> = HashSet::new();
+    let mut followed: HashSet<String> = HashSet::from([target.to_string()]);
+    let mut frontier = vec![target.to_string()];
+    let mut truncated = false;
+
+    'levels: for level in 1..=depth {
+        let mut next = Vec::new();
+        for name in &frontier {
+            for c in callers::find_callers(name, scope, filter)? {
+                if !seen.insert((c.path.clone(), c.calling_function.clone())) {
+                    continue;
+                }
+                if found.len() >= MAX_CALLERS {
+                    truncated = true;
+                    break 'levels;
+                }
+                let top_level = c.caller_range.is_none();
+                if !top_level && followed.insert(c.calling_function.clone()) {
+                    next.push(c.calling_function.clone());
+                }
+                found.push(ImpactCaller {
+                    path: c.path,
+                    line: c.line,
+                    function: c.calling_function,
+                    calls: name.clone(),
+                    depth: level,
+                });
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    found.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    Ok(Impact {
+        definitions,
+        callers: found,
+        depth,
+        truncated,
+    })
+}
+
+/// Render the report: affected-file count up front, then callers grouped by
+/// package root (nearest `Cargo.toml`, `go.mod`, ...) and file.
+pub fn format_impact(target: &str, scope: &Path, impact: &Impact, absolute: bool) -> String {
+    let files = impact.affected_files();
+    let n = impact.callers.len();
+    let mut out = format!(
+        "# Impact of changing \"{target}\" in {} — {files} file{} affected, {n} caller{} (depth ≤ {})",
+        scope.display(),
+        if files == 1 { "" } else { "s" },
+        if n == 1 { "" } else { "s" },
+        impact.depth,
+    );
+
+    out.push_str("\n\n");
+    if impact.definitions.is_empty() {
+        out.push_str("Definition: not found in scope");
+    } else {
+        let locations: Vec<String> = impact
+            .definitions
+            .iter()
+            .map(|d| match d.range {
+                Some((start, end)) => {
+                    format!("{}:{start}-{end}", format::rel(&d.path, scope, absolute))
+                }
+                None => format!("{}:{}", format::rel(&d.path, scope, absolute), d.line),
+            })
+            .collect();
+        let _ = write!(out, "Defined at {}", locations.join(", "));
+    }
+
+    if impact.callers.is_empty() {
+        out.push_str("\n\nNo callers found — changing it affects only its definition.");
+        return out;
+    }
+
+    // package label → file → callers; callers are already in path order
+    let mut groups: BTreeMap<String, BTreeMap<&Path, Vec<&ImpactCaller>>> = BTreeMap::new();
+    for c in &impact.callers {
+        let package =
+            rank::package_root(&c.path).map_or_else(|| scope.to_path_buf(), Path::to_path_buf);
+        let label = match format::rel(&package, scope, absolute) {
+            rel if rel.is_empty() => ".".to_string(),
+            rel => rel,
+        };
+        groups
+            .entry(label)
+            .or_default()
+            .entry(&c.path)
+            .or_default()
+            .push(c);
+    }
+
+    for (package, by_file) in &groups {
+        let _ = write!(
+            out,
+            "\n\n## {package} ({} file{})",
+            by_file.len(),
+            if by_file.len() == 1 { "" } else { "s" }
+        );
+        for (path, callers) in by_file {
+            let _ = write!(out, "\n  {}", format::rel(path, scope, absolute));
+            for c in callers {
+                let _ = write!(
+                    out,
+                    "\n    [{}] {} → {}  (depth {})",
+                    c.line, c.function, c.calls, c.depth
+                );
+            }
+        }
+    }
+
+    if impact.truncated {
+        let _ = write!(
+            out,
+            "\n\n... stopped after {MAX_CALLERS} callers. Lower the depth or narrow the scope."
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn impact_follows_callers_transitively() {
+        let scope = fixture("mini-go");
+        let impact = find_impact("Next", &scope, &SearchFilter::default(), DEFAULT_DEPTH).unwrap();
+
+        assert_eq!(impact.definitions.len(), 1);
+        assert!(impact.definitions[0].path.ends_with("context.go"));
+
+        let chain: Vec<(&str, &str, u32)> = impact
+            .callers
+            .iter()
+            .map(|c| (c.function.as_str(), c.calls.as_str(), c.depth))
+            .collect();
+        assert!(chain.contains(&("handleRequest", "Next", 1)), "{chain:?}");
+        assert!(
+            chain.contains(&("ServeHTTP", "handleRequest", 2)),
+            "{chain:?}"
+        );
+        assert!(
+            chain.contains(&("TestServeHTTP", "ServeHTTP", 3)),
+            "{chain:?}"
+        );
+
+        let files: BTreeSet<String> = impact
+            .callers
+            .iter()
+            .map(|c| c.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            files.into_iter().collect::<Vec<_>>(),
+            ["middleware.go", "router.go", "router_test.go"]
+        );
+
+        let output = format_impact("Next", &scope, &impact, false);
+        assert!(output.contains("— 3 files affected"), "{output}");
+        assert!(output.contains("Defined at context.go:"), "{output}");
+        assert!(output.contains("\n## . (3 files)"), "{output}");
+    }
+
+    #[test]
+    fn depth_bounds_the_walk() {
+        let scope = fixture("mini-go");
+        let impact = find_impact("Next", &scope, &SearchFilter::default(), 1).unwrap();
+        assert!(impact.callers.iter().all(|c| c.depth == 1));
+        assert!(
+            impact.callers.iter().all(|c| c.function != "ServeHTTP"),
+            "{:?}",
+            impact.callers
+        );
+    }
+
+    #[test]
+    fn caller_without_callers_ends_the_chain() {
+        let scope = fixture("mini-go");
+        let impact =
+            find_impact("ClientIP", &scope, &SearchFilter::default(), DEFAULT_DEPTH).unwrap();
+        let output = format_impact("ClientIP", &scope, &impact, false);
+        assert!(output.contains("— 1 file affected"), "{output}");
+        assert!(output.contains("[11] Logger → ClientIP"), "{output}");
+    }
+}
@@ -0,0 +1,135 @@
+//! `kind: "implementations"`: a trait or interface together with everything
+//! that implements it — `impl Trait for Type`, `class Foo implements Bar`,
+//! Python `class Child(Base)` — each body expanded, in one call.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use super::{SearchFilter, code_block, doc_start, symbol};
+use crate::error::GleanError;
+use crate::format;
+use crate::read::detect_file_type;
+use crate::types::{FileType, Match};
+
+/// Most definitions expanded; the rest are listed by location.
+const MAX_IMPLEMENTATIONS: usize = 8;
+
+/// Longest body shown per definition before it's cut.
+const MAX_BODY_LINES: u32 = 60;
+
+/// The definitions of `query` first, then its implementations, each with its
+/// label (`impl Matcher for RegexMatcher`) and body.
+pub fn search_implementations(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<String, GleanError> {
+    let mut defs: Vec<Match> = symbol::definitions(query, scope, filter)?
+        .into_iter()
+        .filter(|m| m.exact && m.def_range.is_some())
+        .collect();
+    defs.sort_by(|a, b| {
+        a.implementation
+            .cmp(&b.implementation)
+            .then_with(|| a.path.cmp(&b.path))
+            .then(a.line.cmp(&b.line))
+    });
+    defs.dedup_by(|a, b| a.path == b.path && a.line == b.line);
+
+    let implementations = defs.iter().filter(|m| m.implementation).count();
+    let declarations = defs.len() - implementations;
+    let mut out = format!(
+        "# Implementations of \"{query}\" in {} — {declarations} definition{}, {implementations} implementation{}",
+        scope.display(),
+        if declarations == 1 { "" } else { "s" },
+        if implementations == 1 { "" } else { "s" },
+    );
+    if defs.is_empty() {
+        out.push_str("\n\nNo definitions found.");
+        return Ok(out);
+    }
+
+    let mut files: HashMap<&Path, String> = HashMap::new();
+    for m in defs.iter().take(MAX_IMPLEMENTATIONS) {
+        let Some((start, end)) = m.def_range else {
+            continue;
+        };
+        let content = files
+            .entry(&m.path)
+            .or_insert_with(|| fs::read_to_string(&m.path).unwrap_or_default());
+        let lines: Vec<&str> = content.lines().collect();
+        let total = lines.len() as u32;
+        // A stale range can point past the end of a file that has since shrunk
+        let start = start.clamp(1, total.max(1));
+        let start = match detect_file_type(&m.path) {
+            FileType::Code(lang) => doc_start(&lines, start, lang),
+            _ => start,
+        };
+        let end = end.min(total);
+        let shown_end = end.min(start + MAX_BODY_LINES - 1);
+
+        let rel = format::rel(&m.path, scope, filter.absolute_paths);
+        let _ = write!(out, "\n\n## {rel}:{start}-{end} — {}", label(m, query));
+        out.push_str(&code_block(&rel, &lines, start, shown_end));
+        if shown_end < end {
+            let _ = write!(
+                out,
+                "\n... {} more lines (section {}-{end})",
+                end - shown_end,
+                shown_end + 1
+            );
+        }
+    }
+
+    if defs.len() > MAX_IMPLEMENTATIONS {
+        out.push_str("\n\n... not expanded:");
+        for m in &defs[MAX_IMPLEMENTATIONS..] {
+            let _ = write!(
+                out,
+                "\n  {}:{} — {}",
+                format::rel(&m.path, scope, filter.absolute_paths),
+                m.line,
+                label(m, query)
+            );
+        }
+    }
+    Ok(out)
+}
+
+/// `impl Matcher for RegexMatcher`, `Foo implements Bar`, or for the
+/// declaration its first line up to the body: `pub trait Matcher`.
+fn label(m: &Match, query: &str) -> String {
+    if m.implementation {
+        return m.def_name.clone().unwrap_or_else(|| query.to_string());
+    }
+    let head = m.text.split('{').next().unwrap_or(&m.text);
+    head.trim().trim_end_matches(':').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn trait_and_impls_expanded_together() {
+        let scope = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini-rust");
+        let out = search_implementations("Matcher", &scope, &SearchFilter::default()).unwrap();
+
+        assert!(out.contains("— 1 definition, 1 implementation"), "{out}");
+        let trait_at = out.find("— pub trait Matcher").expect(&out);
+        let impl_at = out.find("— impl Matcher for RegexMatcher").expect(&out);
+        assert!(trait_at < impl_at, "declaration first: {out}");
+
+        // Bodies, doc comments included
+        assert!(
+            out.contains("/// Matcher trait for pattern matching"),
+            "{out}"
+        );
+        assert!(out.contains("fn find(&self, _haystack"), "{out}");
+        // The generic bound in searcher.rs is not an implementation
+        assert!(!out.contains("Searcher"), "{out}");
+    }
+}
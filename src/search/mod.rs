@@ -1,15 +1,24 @@
 pub mod callees;
 pub mod callers;
 pub mod content;
+pub mod count;
 pub mod glob;
+pub mod gobuild;
+pub mod impact;
+pub mod implementations;
+pub mod pack;
 pub mod rank;
+mod related;
 pub mod symbol;
+pub mod todos;
 pub mod treesitter;
 
 use std::collections::HashSet;
 use std::fmt::Write;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::SystemTime;
 
 use ignore::WalkBuilder;
@@ -19,7 +28,7 @@ use crate::error::GleanError;
 use crate::format;
 use crate::read;
 use crate::session::Session;
-use crate::types::{FileType, Match, SearchResult, estimate_tokens};
+use crate::types::{FileType, Lang, Match, SearchResult, estimate_tokens};
 
 // Directories that are always skipped — build artifacts, dependencies, VCS internals.
 // We skip these explicitly instead of relying on .gitignore so that locally-relevant
@@ -59,23 +68,226 @@ pub(crate) const SKIP_DIRS: &[&str] = &[
 
 const EXPAND_FULL_FILE_THRESHOLD: u64 = 800;
 
+/// Files larger than this are skipped by content, count and TODO searches.
+const MAX_SEARCH_FILE_SIZE: u64 = 500_000;
+
+/// How the `── calls ──` footer under an expanded definition lists callees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CallsFooter {
+    /// One line per resolved callee: name, location and signature.
+    #[default]
+    Full,
+    /// Names only, wrapped onto as few lines as fit: resolved callees carry
+    /// `(file:line)`, unresolved ones are bare.
+    Compact,
+}
+
+impl CallsFooter {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "full" => Some(CallsFooter::Full),
+            "compact" => Some(CallsFooter::Compact),
+            _ => None,
+        }
+    }
+}
+
+/// Caller-selected search options: which files a walk visits and how loosely
+/// symbol names match. The default walks everything except `SKIP_DIRS` and
+/// matches names exactly.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// Drop test files and test directories (`is_test_file`) entirely.
+    pub exclude_tests: bool,
+    /// Match symbol names regardless of case (`HttpClient` finds `HTTPClient`).
+    pub ignore_case: bool,
+    /// Match symbol names across `snake_case`/`camelCase` conventions.
+    pub loose_case: bool,
+    /// Skip Go files whose build constraints exclude this platform.
+    pub go_target: Option<gobuild::GoTarget>,
+    /// Parse files detection can't place as this language (`--lang`). A read
+    /// of one named file is forced to it whatever its name.
+    pub lang: Option<Lang>,
+    /// Paths to skip on top of `SKIP_DIRS` (`exclude`).
+    pub exclude: Option<ExcludeGlobs>,
+    /// Skip what `.gitignore`, `.git/info/exclude` and `.ignore` files ignore.
+    pub respect_gitignore: bool,
+    /// Only visit code files detected as one of these languages (`languages`).
+    pub languages: Option<Vec<Lang>>,
+    /// Rank recency by each file's last commit time instead of its mtime.
+    pub git_recency: bool,
+    /// Told how many files the walks have scanned, for long searches.
+    pub progress: Option<Progress>,
+    /// Which declarations count as definitions (`--strict-definitions`,
+    /// `--loose-definitions`).
+    pub definitions: treesitter::DefinitionMode,
+    /// Collapse identical `#[cfg]`/`#ifdef` copies of a declaration in code
+    /// outlines read through this query (`--dedup-variants`).
+    pub dedup_variants: bool,
+    /// Show result paths in full instead of relative to the scope
+    /// (`--absolute-paths`).
+    pub absolute_paths: bool,
+}
+
+/// Files-scanned reporting for slow walks (MCP `notifications/progress`).
+/// Clones share one count, so a search that walks several times reports a
+/// number that only grows.
+#[derive(Clone)]
+pub struct Progress(Arc<ProgressState>);
+
+struct ProgressState {
+    scanned: AtomicUsize,
+    report: Box<dyn Fn(usize) + Send + Sync>,
+}
+
+impl Progress {
+    /// Files between reports.
+    pub const EVERY: usize = 500;
+
+    /// `report` gets the running total every `EVERY` files, from the walker
+    /// threads.
+    pub fn new(report: impl Fn(usize) + Send + Sync + 'static) -> Self {
+        Progress(Arc::new(ProgressState {
+            scanned: AtomicUsize::new(0),
+            report: Box::new(report),
+        }))
+    }
+
+    fn tick(&self) {
+        let scanned = self.0.scanned.fetch_add(1, Ordering::Relaxed) + 1;
+        if scanned.is_multiple_of(Self::EVERY) {
+            (self.0.report)(scanned);
+        }
+    }
+}
+
+impl std::fmt::Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress")
+            .field("scanned", &self.0.scanned.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl SearchFilter {
+    /// Whether a walked path (relative to scope) is filtered out.
+    fn excludes(&self, rel: &Path) -> bool {
+        if rel.as_os_str().is_empty() {
+            return false;
+        }
+        (self.exclude_tests && rank::is_test_file(rel))
+            || self
+                .exclude
+                .as_ref()
+                .is_some_and(|globs| globs.matches(rel))
+    }
+}
+
+/// Caller-supplied exclude globs, compiled once and shared by every walker
+/// thread. A glob matches a path relative to the scope or its file name, so
+/// `migrations` skips every `migrations/` directory and `*.pb.go` every
+/// generated Go file.
+#[derive(Debug, Clone)]
+pub struct ExcludeGlobs(std::sync::Arc<globset::GlobSet>);
+
+impl ExcludeGlobs {
+    /// Parse a comma-separated list like `"**/generated/**,*.pb.go"`.
+    /// Blank entries are ignored.
+    pub fn parse(spec: &str) -> Result<Self, GleanError> {
+        let mut builder = globset::GlobSetBuilder::new();
+        for pattern in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let glob = globset::Glob::new(pattern).map_err(|e| GleanError::InvalidQuery {
+                query: pattern.to_string(),
+                reason: format!("invalid exclude glob: {e}"),
+            })?;
+            builder.add(glob);
+        }
+        let set = builder.build().map_err(|e| GleanError::InvalidQuery {
+            query: spec.to_string(),
+            reason: format!("invalid exclude glob: {e}"),
+        })?;
+        Ok(Self(std::sync::Arc::new(set)))
+    }
+
+    fn matches(&self, rel: &Path) -> bool {
+        self.0.is_match(rel) || rel.file_name().is_some_and(|name| self.0.is_match(name))
+    }
+}
+
+/// Parse a comma-separated language list like `"rust,go"` for
+/// `SearchFilter::languages`. `typescript` also admits `.tsx` files.
+pub fn parse_languages(spec: &str) -> Result<Vec<Lang>, GleanError> {
+    let mut langs = Vec::new();
+    for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let lang = Lang::parse(name).ok_or_else(|| GleanError::InvalidQuery {
+            query: name.to_string(),
+            reason: format!(
+                "unknown language. Expected one of {}",
+                Lang::NAMES.join(", ")
+            ),
+        })?;
+        langs.push(lang);
+        if lang == Lang::TypeScript {
+            langs.push(Lang::Tsx);
+        }
+    }
+    if langs.is_empty() {
+        return Err(GleanError::InvalidQuery {
+            query: spec.to_string(),
+            reason: format!(
+                "no languages given. Expected some of {}",
+                Lang::NAMES.join(", ")
+            ),
+        });
+    }
+    Ok(langs)
+}
+
 /// Build a parallel directory walker that searches ALL files except known junk directories.
-/// Does NOT respect .gitignore — ensures gitignored but locally-relevant files are found.
-pub(crate) fn walker(scope: &Path) -> ignore::WalkParallel {
+/// Does NOT respect .gitignore unless `respect_gitignore` is set — ensures gitignored but
+/// locally-relevant files are found.
+pub(crate) fn walker(scope: &Path, filter: &SearchFilter) -> ignore::WalkParallel {
+    let scope_root = scope.to_path_buf();
+    let filter = filter.clone();
+    let gitignore = filter.respect_gitignore;
     WalkBuilder::new(scope)
         .hidden(false)
-        .git_ignore(false)
+        .git_ignore(gitignore)
         .git_global(false)
-        .git_exclude(false)
-        .ignore(false)
-        .parents(false)
-        .filter_entry(|entry| {
+        .git_exclude(gitignore)
+        .ignore(gitignore)
+        // A scope inside the repo still needs the root's ignore files
+        .parents(gitignore)
+        // Never follow symlinks: a link to an ancestor (common in vendored
+        // dependencies) would revisit the tree. Linked files are skipped too.
+        .follow_links(false)
+        .filter_entry(move |entry| {
             if entry.file_type().is_some_and(|ft| ft.is_dir())
                 && let Some(name) = entry.file_name().to_str()
+                && SKIP_DIRS.contains(&name)
+            {
+                return false;
+            }
+            // Filters see scope-relative paths so a scope under `tests/` still works
+            let rel = entry
+                .path()
+                .strip_prefix(&scope_root)
+                .unwrap_or(entry.path());
+            if filter.excludes(rel) {
+                return false;
+            }
+            let is_file = entry.file_type().is_some_and(|ft| ft.is_file());
+            if is_file
+                && let Some(langs) = &filter.languages
+                && !matches!(read::detect_file_type(entry.path()), FileType::Code(l) if langs.contains(&l))
             {
-                return !SKIP_DIRS.contains(&name);
+                return false;
             }
-            true
+            // Build constraints need the file header — only files are read
+            filter
+                .go_target
+                .as_ref()
+                .is_none_or(|target| !is_file || target.includes(entry.path()))
         })
         .build_parallel()
 }
@@ -86,27 +298,47 @@ pub(crate) fn walker(scope: &Path) -> ignore::WalkParallel {
 /// and mutex accumulation with poison-safe extraction.
 pub(crate) fn walk_collect<T: Send>(
     scope: &Path,
+    filter: &SearchFilter,
     early_quit_threshold: Option<usize>,
     max_file_size: Option<u64>,
     process: impl Fn(&ignore::DirEntry) -> Vec<T> + Send + Sync,
+) -> Vec<T> {
+    let found_count = AtomicUsize::new(0);
+    walk_collect_until(
+        scope,
+        filter,
+        max_file_size,
+        || early_quit_threshold.is_some_and(|t| found_count.load(Ordering::Relaxed) >= t),
+        |entry| {
+            let items = process(entry);
+            found_count.fetch_add(items.len(), Ordering::Relaxed);
+            items
+        },
+    )
+}
+
+/// `walk_collect` with the stopping rule left to the caller: the walk quits
+/// once `done` returns true, checked before and after each file.
+pub(crate) fn walk_collect_until<T: Send>(
+    scope: &Path,
+    filter: &SearchFilter,
+    max_file_size: Option<u64>,
+    done: impl Fn() -> bool + Send + Sync,
+    process: impl Fn(&ignore::DirEntry) -> Vec<T> + Send + Sync,
 ) -> Vec<T> {
     use std::sync::Mutex;
-    use std::sync::atomic::{AtomicUsize, Ordering};
 
     let results: Mutex<Vec<T>> = Mutex::new(Vec::new());
-    let found_count = AtomicUsize::new(0);
 
-    let w = walker(scope);
+    let w = walker(scope, filter);
 
     w.run(|| {
         let results = &results;
-        let found_count = &found_count;
+        let done = &done;
         let process = &process;
 
         Box::new(move |entry| {
-            if let Some(threshold) = early_quit_threshold
-                && found_count.load(Ordering::Relaxed) >= threshold
-            {
+            if done() {
                 return ignore::WalkState::Quit;
             }
 
@@ -117,6 +349,9 @@ pub(crate) fn walk_collect<T: Send>(
             if !entry.file_type().is_some_and(|ft| ft.is_file()) {
                 return ignore::WalkState::Continue;
             }
+            if let Some(progress) = &filter.progress {
+                progress.tick();
+            }
 
             if let Some(max_size) = max_file_size
                 && let Ok(meta) = std::fs::metadata(entry.path())
@@ -128,16 +363,13 @@ pub(crate) fn walk_collect<T: Send>(
             let items = process(&entry);
 
             if !items.is_empty() {
-                found_count.fetch_add(items.len(), Ordering::Relaxed);
                 let mut all = results
                     .lock()
                     .unwrap_or_else(std::sync::PoisonError::into_inner);
                 all.extend(items);
             }
 
-            if let Some(threshold) = early_quit_threshold
-                && found_count.load(Ordering::Relaxed) >= threshold
-            {
+            if done() {
                 return ignore::WalkState::Quit;
             }
 
@@ -151,7 +383,7 @@ pub(crate) fn walk_collect<T: Send>(
 }
 
 /// Parse `/pattern/` regex syntax. Returns (pattern, `is_regex`).
-fn parse_pattern(query: &str) -> (&str, bool) {
+pub(crate) fn parse_pattern(query: &str) -> (&str, bool) {
     if query.starts_with('/') && query.ends_with('/') && query.len() > 2 {
         (&query[1..query.len() - 1], true)
     } else {
@@ -175,31 +407,61 @@ pub(crate) fn file_metadata(path: &Path) -> (u32, SystemTime) {
 pub fn search_symbol(
     query: &str,
     scope: &Path,
+    filter: &SearchFilter,
     cache: &OutlineCache,
 ) -> Result<String, GleanError> {
-    let result = symbol::search(query, scope, None)?;
-    format_search_result(&result, cache, None, 0)
+    let result = symbol::search(query, scope, filter, None)?;
+    format_search_result(
+        &result,
+        cache,
+        None,
+        0,
+        CallsFooter::Full,
+        filter.absolute_paths,
+    )
 }
 
 pub fn search_symbol_expanded(
     query: &str,
     scope: &Path,
+    filter: &SearchFilter,
     cache: &OutlineCache,
     session: &Session,
     expand: usize,
     context: Option<&Path>,
+    calls: CallsFooter,
+    related: bool,
 ) -> Result<String, GleanError> {
-    let result = symbol::search(query, scope, context)?;
-    format_search_result(&result, cache, Some(session), expand)
+    let result = symbol::search(query, scope, filter, context)?;
+    let mut out = format_search_result(
+        &result,
+        cache,
+        Some(session),
+        expand,
+        calls,
+        filter.absolute_paths,
+    )?;
+    if related {
+        related::write_related(
+            &mut out,
+            query,
+            &result.matches,
+            scope,
+            filter.absolute_paths,
+        );
+    }
+    Ok(out)
 }
 
 pub fn search_multi_symbol_expanded(
     queries: &[&str],
     scope: &Path,
+    filter: &SearchFilter,
     cache: &OutlineCache,
     session: &Session,
     expand: usize,
     context: Option<&Path>,
+    calls: CallsFooter,
 ) -> Result<String, GleanError> {
     // Shared expand budget: at least 1 slot per query, or explicit expand if higher.
     // expand=0 means no expansion at all.
@@ -212,7 +474,7 @@ pub fn search_multi_symbol_expanded(
     let mut sections = Vec::with_capacity(queries.len());
 
     for query in queries {
-        let result = symbol::search(query, scope, context)?;
+        let result = symbol::search(query, scope, filter, context)?;
         let mut out = format::search_header(
             &result.query,
             &result.scope,
@@ -227,6 +489,8 @@ pub fn search_multi_symbol_expanded(
             Some(session),
             &mut expand_remaining,
             &mut expanded_files,
+            calls,
+            filter.absolute_paths,
             &mut out,
         );
         if result.total_found > result.matches.len() {
@@ -245,60 +509,423 @@ pub fn search_multi_symbol_expanded(
 pub fn search_content(
     query: &str,
     scope: &Path,
+    filter: &SearchFilter,
     cache: &OutlineCache,
 ) -> Result<String, GleanError> {
     let (pattern, is_regex) = parse_pattern(query);
-    let result = content::search(pattern, scope, is_regex, None)?;
-    format_search_result(&result, cache, None, 0)
+    let result = content::search(pattern, scope, filter, is_regex, None)?;
+    format_search_result(
+        &result,
+        cache,
+        None,
+        0,
+        CallsFooter::Full,
+        filter.absolute_paths,
+    )
 }
 
 pub fn search_content_expanded(
     query: &str,
     scope: &Path,
+    filter: &SearchFilter,
     cache: &OutlineCache,
     session: &Session,
     expand: usize,
     context: Option<&Path>,
 ) -> Result<String, GleanError> {
     let (pattern, is_regex) = parse_pattern(query);
-    let result = content::search(pattern, scope, is_regex, context)?;
-    format_search_result(&result, cache, Some(session), expand)
+    let result = content::search(pattern, scope, filter, is_regex, context)?;
+    format_search_result(
+        &result,
+        cache,
+        Some(session),
+        expand,
+        CallsFooter::Full,
+        filter.absolute_paths,
+    )
+}
+
+/// Most context lines shown on each side of a match.
+const MAX_CONTEXT_LINES: u32 = 20;
+
+/// Content or regex search showing `lines` lines of context around each match
+/// (like `rg -C`), in place of outline context and expansion, optionally
+/// limited to one `region` of each file. A regex `query`
+/// is used as-is; otherwise `/.../` marks one, as in `search_content_expanded`.
+pub fn search_content_context(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    context: Option<&Path>,
+    regex: bool,
+    region: Option<content::Region>,
+    lines: u32,
+) -> Result<String, GleanError> {
+    let (pattern, is_regex) = if regex {
+        (query, true)
+    } else {
+        parse_pattern(query)
+    };
+    let result = match region {
+        Some(region) => content::search_region(pattern, scope, filter, is_regex, context, region)?,
+        None => content::search(pattern, scope, filter, is_regex, context)?,
+    };
+    Ok(format_context_result(
+        &result,
+        lines.min(MAX_CONTEXT_LINES),
+        filter.absolute_paths,
+    ))
+}
+
+/// Each match with `around` lines either side, matched lines marked `→`.
+/// Matches in the same file whose windows touch share one block.
+fn format_context_result(result: &SearchResult, around: u32, absolute: bool) -> String {
+    let mut out = format::search_header(
+        &result.query,
+        &result.scope,
+        result.matches.len(),
+        result.definitions,
+        result.usages,
+    );
+
+    let mut i = 0;
+    while i < result.matches.len() {
+        let m = &result.matches[i];
+        // Gather following matches from this file that overlap the window
+        let mut hits = vec![m.line];
+        let mut j = i + 1;
+        while let Some(next) = result.matches.get(j)
+            && next.path == m.path
+            && next.line <= hits[hits.len() - 1] + 2 * around + 1
+            && next.line > hits[hits.len() - 1]
+        {
+            hits.push(next.line);
+            j += 1;
+        }
+
+        let rel = format::rel(&m.path, &result.scope, absolute);
+        let _ = write!(out, "\n\n## {rel}:{}", m.line);
+        if let Ok(content) = fs::read_to_string(&m.path) {
+            let lines: Vec<&str> = content.lines().collect();
+            let start = hits[0].saturating_sub(around).max(1);
+            let end = (hits[hits.len() - 1] + around).min(lines.len() as u32);
+            let _ = write!(out, "\n```{rel}:{start}-{end}");
+            for n in start..=end {
+                let marker = if hits.contains(&n) { '→' } else { ' ' };
+                let _ = write!(out, "\n{marker}{n:>4} │ {}", lines[(n - 1) as usize]);
+            }
+            out.push_str("\n```");
+        } else {
+            let _ = write!(out, "\n→ [{}]   {}", m.line, m.text);
+        }
+        i = j;
+    }
+
+    if result.total_found > result.matches.len() {
+        let omitted = result.total_found - result.matches.len();
+        let _ = write!(
+            out,
+            "\n\n... and {omitted} more matches. Narrow with scope."
+        );
+    }
+    out
+}
+
+/// Content search limited to comments and docstrings (`TODO`, `@deprecated`).
+pub fn search_comments_expanded(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+    session: &Session,
+    expand: usize,
+    context: Option<&Path>,
+) -> Result<String, GleanError> {
+    search_region_expanded(
+        query,
+        scope,
+        filter,
+        cache,
+        session,
+        expand,
+        context,
+        content::Region::Comments,
+    )
+}
+
+/// Content search limited to comments, string literals or code (`scope_to`).
+pub fn search_region_expanded(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+    session: &Session,
+    expand: usize,
+    context: Option<&Path>,
+    region: content::Region,
+) -> Result<String, GleanError> {
+    let (pattern, is_regex) = parse_pattern(query);
+    let result = content::search_region(pattern, scope, filter, is_regex, context, region)?;
+    format_search_result(
+        &result,
+        cache,
+        Some(session),
+        expand,
+        CallsFooter::Full,
+        filter.absolute_paths,
+    )
+}
+
+/// TODO/FIXME-style markers in comments, grouped by file with enclosing symbol.
+/// Empty `markers` means `todos::DEFAULT_MARKERS`.
+pub fn search_todos(
+    scope: &Path,
+    filter: &SearchFilter,
+    markers: &[&str],
+) -> Result<String, GleanError> {
+    let todos = todos::find_todos(scope, filter, markers)?;
+    Ok(todos::format_todos(scope, &todos, filter.absolute_paths))
+}
+
+/// Definition plus transitive callers of `target`, grouped by package.
+pub fn search_impact(
+    target: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    depth: u32,
+) -> Result<String, GleanError> {
+    let impact = impact::find_impact(target, scope, filter, depth)?;
+    Ok(impact::format_impact(
+        target,
+        scope,
+        &impact,
+        filter.absolute_paths,
+    ))
+}
+
+/// Count-only search: per-file definition/usage counts, no listing or expand.
+/// `/regex/` and content queries count matching lines.
+pub fn search_count(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    content_search: bool,
+) -> Result<String, GleanError> {
+    let counts = if content_search {
+        let (pattern, is_regex) = parse_pattern(query);
+        count::content_counts(pattern, scope, filter, is_regex)?
+    } else {
+        count::symbol_counts(query, scope, filter)?
+    };
+    Ok(count::format_counts(
+        query,
+        scope,
+        &counts,
+        filter.absolute_paths,
+    ))
 }
 
 /// Raw symbol search — returns structured result for programmatic inspection.
-pub fn search_symbol_raw(query: &str, scope: &Path) -> Result<SearchResult, GleanError> {
-    symbol::search(query, scope, None)
+pub fn search_symbol_raw(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<SearchResult, GleanError> {
+    symbol::search(query, scope, filter, None)
 }
 
 /// Raw content search — returns structured result for programmatic inspection.
-pub fn search_content_raw(query: &str, scope: &Path) -> Result<SearchResult, GleanError> {
+pub fn search_content_raw(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<SearchResult, GleanError> {
     let (pattern, is_regex) = parse_pattern(query);
-    content::search(pattern, scope, is_regex, None)
+    content::search(pattern, scope, filter, is_regex, None)
+}
+
+/// What a JSON search carries beyond the matches themselves.
+#[derive(Clone, Copy, Default)]
+pub struct JsonOptions {
+    /// Definitions, in order, that carry their source.
+    pub expand: usize,
+    /// Lines either side of each match to include, capped as for text output.
+    pub context_lines: Option<u32>,
+    /// Restrict content and regex search to one region of each file.
+    pub region: Option<content::Region>,
+}
+
+/// A search result as `search_json` returns it.
+#[derive(Clone, serde::Serialize)]
+pub struct JsonResult {
+    pub query: String,
+    pub total_found: usize,
+    pub definitions: usize,
+    pub usages: usize,
+    pub matches: Vec<JsonMatch>,
+}
+
+/// One match in a `JsonResult`. `source` and `context` appear only when asked
+/// for and readable.
+#[derive(Clone, serde::Serialize)]
+pub struct JsonMatch {
+    pub path: String,
+    pub line: u32,
+    pub text: String,
+    pub is_definition: bool,
+    pub def_range: Option<[u32; 2]>,
+    pub def_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<JsonContext>,
+}
+
+/// Lines `start..=end` of the file around a match.
+#[derive(Clone, serde::Serialize)]
+pub struct JsonContext {
+    pub start: u32,
+    pub end: u32,
+    pub lines: Vec<String>,
+}
+
+/// Symbol, content or regex search as structured results, for callers that
+/// render them themselves. `kind` picks the search as in `glean_search`.
+pub fn search_json(
+    query: &str,
+    kind: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    context: Option<&Path>,
+    opts: JsonOptions,
+) -> Result<JsonResult, GleanError> {
+    let result = match (kind, opts.region) {
+        ("symbol", None) => symbol::search(query, scope, filter, context)?,
+        ("content", region) => {
+            let (pattern, is_regex) = parse_pattern(query);
+            match region {
+                Some(region) => {
+                    content::search_region(pattern, scope, filter, is_regex, context, region)?
+                }
+                None => content::search(pattern, scope, filter, is_regex, context)?,
+            }
+        }
+        ("regex", Some(region)) => {
+            content::search_region(query, scope, filter, true, context, region)?
+        }
+        ("regex", None) => content::search(query, scope, filter, true, context)?,
+        ("symbol", Some(_)) => {
+            return Err(GleanError::InvalidQuery {
+                query: query.to_string(),
+                reason: "scope_to applies to content and regex search".into(),
+            });
+        }
+        _ => {
+            return Err(GleanError::InvalidQuery {
+                query: kind.to_string(),
+                reason: "JSON output supports symbol, content and regex search".into(),
+            });
+        }
+    };
+    Ok(result_json(&result, opts, filter.absolute_paths))
+}
+
+fn result_json(result: &SearchResult, opts: JsonOptions, absolute: bool) -> JsonResult {
+    let around = opts.context_lines.map(|n| n.min(MAX_CONTEXT_LINES));
+    let mut expand_remaining = opts.expand;
+    let mut file: Option<(&Path, Vec<String>)> = None;
+    let matches = result
+        .matches
+        .iter()
+        .map(|m| {
+            let wants_source = m.def_range.is_some() && expand_remaining > 0;
+            if (wants_source || around.is_some())
+                && file.as_ref().is_none_or(|(path, _)| *path != m.path)
+            {
+                let lines = fs::read_to_string(&m.path)
+                    .map(|c| c.lines().map(str::to_string).collect())
+                    .unwrap_or_default();
+                file = Some((&m.path, lines));
+            }
+            let lines = file.as_ref().map_or(&[][..], |(_, lines)| lines.as_slice());
+
+            let source = m
+                .def_range
+                .filter(|_| wants_source)
+                .and_then(|(start, end)| line_span(lines, start, end))
+                .map(|span| span.join("\n"));
+            if source.is_some() {
+                expand_remaining -= 1;
+            }
+            let context = around.and_then(|around| {
+                let start = m.line.saturating_sub(around).max(1);
+                let end = m.line.saturating_add(around).min(lines.len() as u32);
+                line_span(lines, start, end).map(|span| JsonContext {
+                    start,
+                    end,
+                    lines: span.to_vec(),
+                })
+            });
+            JsonMatch {
+                path: format::rel(&m.path, &result.scope, absolute),
+                line: m.line,
+                text: m.text.clone(),
+                is_definition: m.is_definition,
+                def_range: m.def_range.map(|(s, e)| [s, e]),
+                def_name: m.def_name.clone(),
+                source,
+                context,
+            }
+        })
+        .collect();
+    JsonResult {
+        query: result.query.clone(),
+        total_found: result.total_found,
+        definitions: result.definitions,
+        usages: result.usages,
+        matches,
+    }
+}
+
+/// Lines `start..=end` (1-based), or `None` for a range that is empty or runs
+/// past the end — a stale index or an edited file.
+fn line_span(lines: &[String], start: u32, end: u32) -> Option<&[String]> {
+    let start = (start as usize).checked_sub(1)?;
+    lines
+        .get(start..end as usize)
+        .filter(|span| !span.is_empty())
 }
 
 /// Format a symbol search result (public for Fallthrough path in lib.rs).
 pub fn format_symbol_result(
     result: &SearchResult,
     cache: &OutlineCache,
+    absolute: bool,
 ) -> Result<String, GleanError> {
-    format_search_result(result, cache, None, 0)
+    format_search_result(result, cache, None, 0, CallsFooter::Full, absolute)
 }
 
 /// Format a content search result (public for Fallthrough path in lib.rs).
 pub fn format_content_result(
     result: &SearchResult,
     cache: &OutlineCache,
+    absolute: bool,
 ) -> Result<String, GleanError> {
-    format_search_result(result, cache, None, 0)
+    format_search_result(result, cache, None, 0, CallsFooter::Full, absolute)
 }
 
 pub fn search_glob(
     pattern: &str,
     scope: &Path,
+    filter: &SearchFilter,
+    group_dirs: bool,
     _cache: &OutlineCache,
 ) -> Result<String, GleanError> {
-    let result = glob::search(pattern, scope)?;
-    format_glob_result(&result, scope)
+    let result = glob::search(pattern, scope, filter)?;
+    if group_dirs && result.total_found > 0 {
+        return Ok(format_glob_dirs(&result, scope));
+    }
+    format_glob_result(&result, scope, filter.absolute_paths)
 }
 
 /// Facet categories for grouping search results.
@@ -313,10 +940,7 @@ enum Facet {
 impl Facet {
     fn classify(m: &Match) -> Self {
         if m.is_definition {
-            if m.def_name
-                .as_ref()
-                .is_some_and(|n| n.starts_with("impl ") || n.contains(" implements "))
-            {
+            if m.implementation {
                 Facet::Implementation
             } else {
                 Facet::Definition
@@ -347,6 +971,8 @@ fn format_matches(
     session: Option<&Session>,
     expand_remaining: &mut usize,
     expanded_files: &mut HashSet<PathBuf>,
+    calls: CallsFooter,
+    absolute: bool,
     out: &mut String,
 ) {
     // Multi-file: one expand per unique file. Single-file: sequential per-match.
@@ -370,9 +996,16 @@ fn format_matches(
             }
         }
 
-        let rel = format::rel(&m.path, scope);
+        let rel = format::rel(&m.path, scope, absolute);
+        let reexport = if m.is_definition {
+            None
+        } else {
+            crate::read::imports::reexport_source(&m.text)
+        };
         let kind = if m.is_definition {
             "definition"
+        } else if reexport.is_some() {
+            "re-export"
         } else {
             "usage"
         };
@@ -388,6 +1021,13 @@ fn format_matches(
             let _ = write!(out, "\n\n## {rel}:{} [{kind}]", m.line);
         }
 
+        // Point re-exports at the module that holds the real definition
+        if reexport.is_some()
+            && let Some(target) = crate::read::imports::resolve_reexport(&m.path, &m.text)
+        {
+            let _ = write!(out, " \u{2192} {}", format::rel(&target, scope, absolute));
+        }
+
         if let Some(context) = outline_context_for_match(&m.path, m.line, cache) {
             out.push_str(&context);
         } else {
@@ -415,7 +1055,7 @@ fn format_matches(
                 // Multi-file or cross-query: skip files already expanded.
                 // Single-file within one query: expand sequentially (no per-file dedup).
                 let skip = multi_file && expanded_files.contains(&m.path);
-                if !skip && let Some((code, content)) = expand_match(m, scope) {
+                if !skip && let Some((code, content)) = expand_match(m, scope, absolute) {
                     // Record expansion for future dedup
                     if m.is_definition
                         && m.def_range.is_some()
@@ -446,24 +1086,34 @@ fn format_matches(
                                     resolved.retain(|c| c.name != *name);
                                 }
 
+                                // Classify before the cap, so a resolved callee that
+                                // doesn't make the cut isn't listed as unresolved
+                                let unresolved: Vec<&String> = callee_names
+                                    .iter()
+                                    .filter(|n| {
+                                        m.def_name.as_ref() != Some(*n)
+                                            && !resolved.iter().any(|c| c.name == **n)
+                                    })
+                                    .collect();
+
                                 // Cap at 8, prioritize cross-file over same-file
                                 if resolved.len() > 8 {
                                     resolved.sort_by_key(|c| i32::from(c.file == m.path));
                                     resolved.truncate(8);
                                 }
 
-                                if !resolved.is_empty() {
-                                    out.push_str("\n\n\u{2500}\u{2500} calls \u{2500}\u{2500}");
-                                    for c in &resolved {
-                                        let crel = format::rel(&c.file, scope);
-                                        let _ = write!(
+                                match calls {
+                                    CallsFooter::Full => {
+                                        write_calls_full(out, &resolved, scope, absolute);
+                                    }
+                                    CallsFooter::Compact => {
+                                        write_calls_compact(
                                             out,
-                                            "\n  {}  {crel}:{}-{}",
-                                            c.name, c.start_line, c.end_line
+                                            &resolved,
+                                            &unresolved,
+                                            scope,
+                                            absolute,
                                         );
-                                        if let Some(ref sig) = c.signature {
-                                            let _ = write!(out, "  {sig}");
-                                        }
                                     }
                                 }
                             }
@@ -479,7 +1129,7 @@ fn format_matches(
                                 if i > 0 {
                                     out.push_str(", ");
                                 }
-                                let _ = write!(out, "{}", format::rel(p, scope));
+                                let _ = write!(out, "{}", format::rel(p, scope, absolute));
                             }
                         }
                     }
@@ -496,6 +1146,71 @@ fn format_matches(
     }
 }
 
+const CALLS_HEADER: &str = "\n\n\u{2500}\u{2500} calls \u{2500}\u{2500}";
+
+/// Compact footer lines wrap at this width.
+const COMPACT_CALLS_WIDTH: usize = 100;
+
+fn write_calls_full(
+    out: &mut String,
+    resolved: &[callees::ResolvedCallee],
+    scope: &Path,
+    absolute: bool,
+) {
+    if resolved.is_empty() {
+        return;
+    }
+    out.push_str(CALLS_HEADER);
+    for c in resolved {
+        let crel = format::rel(&c.file, scope, absolute);
+        let _ = write!(
+            out,
+            "\n  {}  {crel}:{}-{}",
+            c.name, c.start_line, c.end_line
+        );
+        if let Some(ref sig) = c.signature {
+            let _ = write!(out, "  {sig}");
+        }
+    }
+}
+
+fn write_calls_compact(
+    out: &mut String,
+    resolved: &[callees::ResolvedCallee],
+    unresolved: &[&String],
+    scope: &Path,
+    absolute: bool,
+) {
+    let items: Vec<String> = resolved
+        .iter()
+        .map(|c| {
+            format!(
+                "{} ({}:{})",
+                c.name,
+                format::rel(&c.file, scope, absolute),
+                c.start_line
+            )
+        })
+        .chain(unresolved.iter().map(|n| (*n).clone()))
+        .collect();
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(CALLS_HEADER);
+    let mut line = String::new();
+    for item in items {
+        if !line.is_empty() && line.len() + item.len() + 2 > COMPACT_CALLS_WIDTH {
+            let _ = write!(out, "\n  {line}");
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push_str(", ");
+        }
+        line.push_str(&item);
+    }
+    let _ = write!(out, "\n  {line}");
+}
+
 /// Format a symbol/content search result.
 /// When an outline cache is available, wraps each match in the file's outline context.
 /// When `expand > 0`, the top N matches inline actual code (def body or ±10 lines).
@@ -504,6 +1219,8 @@ fn format_search_result(
     cache: &OutlineCache,
     session: Option<&Session>,
     expand: usize,
+    calls: CallsFooter,
+    absolute: bool,
 ) -> Result<String, GleanError> {
     let header = format::search_header(
         &result.query,
@@ -522,6 +1239,8 @@ fn format_search_result(
         session,
         &mut expand_remaining,
         &mut expanded_files,
+        calls,
+        absolute,
         &mut out,
     );
 
@@ -539,23 +1258,34 @@ fn format_search_result(
 /// The raw content is returned so the caller can reuse it (e.g. for related-file hints)
 /// without a redundant file read.
 ///
-/// For definitions: use tree-sitter node range (`def_range`).
+/// For definitions: use tree-sitter node range (`def_range`), widened upward
+/// to take in the doc comment and attributes directly above it.
 /// For usages: ±10 lines around the match.
-fn expand_match(m: &Match, scope: &Path) -> Option<(String, String)> {
+fn expand_match(m: &Match, scope: &Path, absolute: bool) -> Option<(String, String)> {
     let content = fs::read_to_string(&m.path).ok()?;
     let lines: Vec<&str> = content.lines().collect();
     let total = lines.len() as u32;
 
     let (start, end) = if estimate_tokens(content.len() as u64) < EXPAND_FULL_FILE_THRESHOLD {
         (1, total)
+    } else if let Some((s, e)) = m.def_range {
+        let s = s.clamp(1, total.max(1));
+        let s = match read::detect_file_type(&m.path) {
+            FileType::Code(lang) => doc_start(&lines, s, lang),
+            _ => s,
+        };
+        (s, e.min(total))
     } else {
-        let (s, e) = m
-            .def_range
-            .unwrap_or((m.line.saturating_sub(10), m.line.saturating_add(10)));
+        let (s, e) = (m.line.saturating_sub(10), m.line.saturating_add(10));
         (s.max(1), e.min(total))
     };
 
-    let rel = format::rel(&m.path, scope);
+    let out = code_block(&format::rel(&m.path, scope, absolute), &lines, start, end);
+    Some((out, content))
+}
+
+/// Lines `start..=end` (1-based) as a fenced, line-numbered block.
+fn code_block(rel: &str, lines: &[&str], start: u32, end: u32) -> String {
     let mut out = String::new();
     let _ = write!(out, "\n```{rel}:{start}-{end}");
     for i in start..=end {
@@ -565,7 +1295,68 @@ fn expand_match(m: &Match, scope: &Path) -> Option<(String, String)> {
         }
     }
     out.push_str("\n```");
-    Some((out, content))
+    out
+}
+
+/// First line of the doc comment, attributes and decorators sitting directly
+/// above a definition that starts on line `start` (1-based). Returns `start`
+/// when there are none. Stops at a blank line or at anything that isn't the
+/// language's doc syntax, so the code before a definition never comes along.
+fn doc_start(lines: &[&str], start: u32, lang: Lang) -> u32 {
+    let mut first = start as usize;
+    while first > 1 {
+        let line = lines[first - 2].trim();
+        if line.ends_with("*/") {
+            // Walk up the block to its opener; every line between is ` * ...`
+            let mut open = first - 2;
+            while !lines[open].trim_start().starts_with("/*") {
+                if open == 0 || !lines[open].trim_start().starts_with('*') {
+                    return first as u32;
+                }
+                open -= 1;
+            }
+            if !is_doc_block(lines[open].trim_start(), lang) {
+                break;
+            }
+            first = open + 1;
+        } else if is_doc_line(line, lang) {
+            first -= 1;
+        } else {
+            break;
+        }
+    }
+    first as u32
+}
+
+/// A single line of doc comment or attribute in `lang`'s conventions. Go and
+/// C document with ordinary `//` comments; Ruby with `#` (`RDoc`/YARD).
+/// Python's docstring is inside the body already — only decorators sit above.
+fn is_doc_line(line: &str, lang: Lang) -> bool {
+    match lang {
+        Lang::Rust => line.starts_with("///") || line.starts_with("#["),
+        Lang::Go | Lang::C | Lang::Cpp => line.starts_with("//"),
+        Lang::Zig => line.starts_with("///"),
+        Lang::Swift => line.starts_with("///") || line.starts_with('@'),
+        Lang::CSharp => line.starts_with("///") || line.starts_with('['),
+        Lang::Php => line.starts_with("#["),
+        Lang::TypeScript
+        | Lang::Tsx
+        | Lang::JavaScript
+        | Lang::Python
+        | Lang::Java
+        | Lang::Kotlin => line.starts_with('@'),
+        Lang::Ruby | Lang::Shell | Lang::Dockerfile | Lang::Make => line.starts_with('#'),
+    }
+}
+
+/// A `/* ... */` block that counts as documentation: `/**` (`JSDoc`,
+/// Javadoc, `KDoc`, rustdoc) everywhere, any block comment in Go and C.
+fn is_doc_block(opener: &str, lang: Lang) -> bool {
+    match lang {
+        Lang::Go | Lang::C | Lang::Cpp => true,
+        Lang::Python | Lang::Ruby | Lang::Shell | Lang::Dockerfile | Lang::Make => false,
+        _ => opener.starts_with("/**") || opener.starts_with("/*!"),
+    }
 }
 
 /// Generate outline context for a search match: show nearby outline entries
@@ -590,10 +1381,11 @@ fn outline_context_for_match(
         return None;
     }
 
-    let outline_str = cache.get_or_compute(path, mtime, || {
+    let view = crate::cache::View::outline(file_type, false);
+    let outline_str = cache.get_or_compute(path, mtime, view, || {
         let content = std::fs::read_to_string(path).unwrap_or_default();
         let buf = content.as_bytes();
-        read::outline::generate(path, file_type, &content, buf, false)
+        read::outline::generate(path, file_type, &content, buf, false, false)
     });
 
     // Parse the outline to find entries near the match line
@@ -645,8 +1437,39 @@ fn extract_line_range(line: &str) -> Option<(u32, u32)> {
     }
 }
 
+/// Format glob results as per-directory totals, biggest first, for drilling
+/// down a tree before listing files.
+fn format_glob_dirs(result: &glob::GlobResult, scope: &Path) -> String {
+    let mut out = format!(
+        "# Glob: \"{}\" in {} — {} files in {} directories",
+        result.pattern,
+        scope.display(),
+        result.total_found,
+        result.dirs.len()
+    );
+    for dir in &result.dirs {
+        let name = if dir.path == Path::new(".") {
+            ".".to_string()
+        } else {
+            format!("{}/", dir.path.display())
+        };
+        let _ = write!(
+            out,
+            "\n  {name}  ({} file{}, ~{} tokens)",
+            dir.files,
+            if dir.files == 1 { "" } else { "s" },
+            dir.tokens
+        );
+    }
+    out
+}
+
 /// Format glob search results (file list with previews).
-fn format_glob_result(result: &glob::GlobResult, scope: &Path) -> Result<String, GleanError> {
+fn format_glob_result(
+    result: &glob::GlobResult,
+    scope: &Path,
+    absolute: bool,
+) -> Result<String, GleanError> {
     let header = format!(
         "# Glob: \"{}\" in {} — {} files",
         result.pattern,
@@ -656,7 +1479,7 @@ fn format_glob_result(result: &glob::GlobResult, scope: &Path) -> Result<String,
 
     let mut out = header;
     for file in &result.files {
-        let _ = write!(out, "\n  {}", format::rel(&file.path, scope));
+        let _ = write!(out, "\n  {}", format::rel(&file.path, scope, absolute));
         if let Some(ref preview) = file.preview {
             let _ = write!(out, "  ({preview})");
         }
@@ -695,8 +1518,15 @@ mod tests {
     fn small_file_always_expanded() {
         let cache = OutlineCache::new();
         // expand=0 would normally prevent any expansion
-        let result = symbol::search("request", &fixture("mini-swift"), None).unwrap();
-        let output = format_search_result(&result, &cache, None, 0).unwrap();
+        let result = symbol::search(
+            "request",
+            &fixture("mini-swift"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
+        let output =
+            format_search_result(&result, &cache, None, 0, CallsFooter::Full, false).unwrap();
 
         // With small-file bypass, code blocks should appear even at expand=0
         assert!(
@@ -704,4 +1534,454 @@ mod tests {
             "small files should be expanded even with expand=0.\nOutput:\n{output}"
         );
     }
+
+    #[test]
+    fn json_output_carries_match_structure() {
+        let scope = fixture("mini-ruby");
+        let opts = JsonOptions {
+            expand: 1,
+            ..JsonOptions::default()
+        };
+        let result = search_json(
+            "validate",
+            "symbol",
+            &scope,
+            &SearchFilter::default(),
+            None,
+            opts,
+        );
+        let json = serde_json::to_value(result.unwrap()).unwrap();
+        assert_eq!(json["query"], "validate");
+        let matches = json["matches"].as_array().unwrap();
+
+        let def = &matches[0];
+        assert_eq!(def["is_definition"], true);
+        assert_eq!(def["def_name"], "validate");
+        assert!(def["def_range"].as_array().is_some_and(|r| r.len() == 2));
+        assert!(def["source"].as_str().unwrap().contains("def validate"));
+        // Usages carry no definition info, and only one source is inlined
+        assert!(matches.iter().skip(1).all(|m| m.get("source").is_none()));
+        assert!(
+            matches
+                .iter()
+                .any(|m| m["is_definition"] == false && m["def_range"].is_null())
+        );
+
+        let filter = SearchFilter::default();
+        assert!(search_json("x", "callers", &scope, &filter, None, opts).is_err());
+
+        // Region and context lines apply as they do to text output
+        let opts = JsonOptions {
+            context_lines: Some(1),
+            region: Some(content::Region::Comments),
+            ..JsonOptions::default()
+        };
+        let result = search_json("account", "content", &scope, &filter, None, opts).unwrap();
+        assert_eq!(result.matches.len(), 1, "only the comment matches");
+        let opts = JsonOptions {
+            region: Some(content::Region::Code),
+            ..opts
+        };
+        let result = search_json("validate", "content", &scope, &filter, None, opts).unwrap();
+        assert!(!result.matches.is_empty());
+        let json = serde_json::to_value(&result).unwrap();
+        for m in json["matches"].as_array().unwrap() {
+            let context = &m["context"];
+            assert!(context["start"].as_u64() < m["line"].as_u64());
+            assert_eq!(context["lines"].as_array().unwrap().len(), 3);
+        }
+    }
+
+    #[test]
+    fn json_source_skips_bad_ranges() {
+        let lines: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+        assert_eq!(line_span(&lines, 2, 3), Some(&lines[1..]));
+        assert_eq!(line_span(&lines, 0, 2), None);
+        assert_eq!(line_span(&lines, 3, 2), None);
+        assert_eq!(line_span(&lines, 2, 9), None);
+    }
+
+    /// Barrel files: searching a class re-exported from `index.ts` must surface
+    /// the real definition first and label the barrel line as a re-export
+    /// pointing at the defining module.
+    #[test]
+    fn barrel_reexport_demoted_and_labeled() {
+        let cache = OutlineCache::new();
+        let result = symbol::search(
+            "ZodError",
+            &fixture("mini-ts"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
+
+        let first = &result.matches[0];
+        assert!(
+            first.is_definition,
+            "matches[0] must be the class definition"
+        );
+        assert!(
+            first.path.ends_with("src/errors.ts"),
+            "got {}",
+            first.path.display()
+        );
+
+        let barrel = result
+            .matches
+            .iter()
+            .position(|m| m.path.ends_with("src/index.ts"))
+            .expect("barrel re-export should still be listed");
+        let usage = result
+            .matches
+            .iter()
+            .position(|m| !m.is_definition && m.path.ends_with("src/parse.ts"))
+            .expect("parse.ts usage");
+        assert!(usage < barrel, "re-export should rank below real usages");
+
+        let output =
+            format_search_result(&result, &cache, None, 0, CallsFooter::Full, false).unwrap();
+        assert!(
+            output.contains("index.ts:1 [re-export] \u{2192} src/errors.ts"),
+            "re-export should point at the defining module:\n{output}"
+        );
+    }
+
+    /// Compact calls footer: same callees, names with `(file:line)` only,
+    /// shorter than the full footer's ranges and signatures.
+    #[test]
+    fn compact_calls_footer_is_shorter() {
+        let cache = OutlineCache::new();
+        let scope = fixture("mini-go");
+        let footer = |calls: CallsFooter| {
+            let output = search_symbol_expanded(
+                "ServeHTTP",
+                &scope,
+                &SearchFilter::default(),
+                &cache,
+                &Session::new(),
+                1,
+                None,
+                calls,
+                false,
+            )
+            .unwrap();
+            let start = output
+                .find("\u{2500}\u{2500} calls")
+                .unwrap_or_else(|| panic!("no calls footer:\n{output}"));
+            let rest = &output[start..];
+            rest[..rest.find("\n\n").unwrap_or(rest.len())].to_string()
+        };
+
+        let full = footer(CallsFooter::Full);
+        let compact = footer(CallsFooter::Compact);
+        assert!(full.contains("handleRequest  router.go:23-25"), "{full}");
+        assert!(
+            compact.contains("handleRequest (router.go:23)"),
+            "{compact}"
+        );
+        assert!(
+            compact.len() < full.len(),
+            "full:\n{full}\ncompact:\n{compact}"
+        );
+    }
+
+    /// Headers use scope-relative paths even though scope is canonicalized
+    /// (absolute) — shorter output, and paths can be pasted back into a read.
+    #[test]
+    fn symbol_headers_are_scope_relative() {
+        let cache = OutlineCache::new();
+        let scope = fixture("mini-go").canonicalize().unwrap();
+        let result = symbol::search("ServeHTTP", &scope, &SearchFilter::default(), None).unwrap();
+        let output =
+            format_search_result(&result, &cache, None, 0, CallsFooter::Full, false).unwrap();
+
+        let headers: Vec<&str> = output.lines().filter(|l| l.starts_with("## ")).collect();
+        assert!(!headers.is_empty(), "expected result headers:\n{output}");
+        assert!(
+            headers.iter().any(|h| h.starts_with("## router.go:")),
+            "headers: {headers:?}"
+        );
+        let abs = scope.display().to_string();
+        assert!(
+            headers.iter().all(|h| !h.contains(&abs)),
+            "headers should not contain the absolute scope: {headers:?}"
+        );
+    }
+
+    #[test]
+    fn exclude_tests_filter_covers_names_and_dirs() {
+        let filter = SearchFilter {
+            exclude_tests: true,
+            ..SearchFilter::default()
+        };
+        assert!(filter.excludes(Path::new("router_test.go")));
+        assert!(filter.excludes(Path::new("src/parse.spec.ts")));
+        assert!(filter.excludes(Path::new("tests")));
+        assert!(filter.excludes(Path::new("pkg/__tests__/a.js")));
+        assert!(!filter.excludes(Path::new("src/router.go")));
+        assert!(
+            !filter.excludes(Path::new("")),
+            "scope root is never excluded"
+        );
+        assert!(!SearchFilter::default().excludes(Path::new("router_test.go")));
+    }
+
+    #[test]
+    fn exclude_globs_match_paths_and_names() {
+        let filter = SearchFilter {
+            exclude: Some(ExcludeGlobs::parse("**/generated/**, *.pb.go,migrations,").unwrap()),
+            ..SearchFilter::default()
+        };
+        assert!(filter.excludes(Path::new("api/generated/client.ts")));
+        assert!(filter.excludes(Path::new("proto/user.pb.go")));
+        assert!(filter.excludes(Path::new("db/migrations")));
+        assert!(!filter.excludes(Path::new("proto/user.go")));
+        assert!(!filter.excludes(Path::new("")));
+
+        let err = ExcludeGlobs::parse("src/[a").unwrap_err();
+        assert!(err.to_string().contains("invalid exclude glob"), "{err}");
+    }
+
+    #[test]
+    fn respect_gitignore_skips_ignored_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::create_dir(dir.path().join("data")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "data/\n").unwrap();
+        fs::write(dir.path().join("main.py"), "def load_rows():\n    pass\n").unwrap();
+        fs::write(dir.path().join("data/dump.py"), "load_rows()\n").unwrap();
+
+        let found = |filter: &SearchFilter| {
+            let result = symbol::search("load_rows", dir.path(), filter, None).unwrap();
+            result
+                .matches
+                .iter()
+                .any(|m| m.path.ends_with("data/dump.py"))
+        };
+        assert!(
+            found(&SearchFilter::default()),
+            "gitignored files searched by default"
+        );
+        let filter = SearchFilter {
+            respect_gitignore: true,
+            ..SearchFilter::default()
+        };
+        assert!(!found(&filter));
+    }
+
+    #[test]
+    fn languages_filter_walks_only_those_files() {
+        let scope = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let filter = SearchFilter {
+            languages: Some(parse_languages("go, py").unwrap()),
+            ..SearchFilter::default()
+        };
+        let files = walk_collect(&scope, &filter, None, None, |entry| {
+            vec![entry.path().to_path_buf()]
+        });
+        assert!(!files.is_empty());
+        assert!(
+            files.iter().all(|p| {
+                matches!(
+                    read::detect_file_type(p),
+                    FileType::Code(Lang::Go | Lang::Python)
+                )
+            }),
+            "{files:?}"
+        );
+
+        assert_eq!(
+            parse_languages("typescript").unwrap(),
+            [Lang::TypeScript, Lang::Tsx]
+        );
+        let err = parse_languages("rust,cobol").unwrap_err().to_string();
+        assert!(
+            err.contains("cobol") && err.contains("rust, typescript"),
+            "{err}"
+        );
+        assert!(parse_languages(" , ").is_err());
+    }
+
+    #[test]
+    fn exclude_drops_files_from_symbol_search() {
+        let scope = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini-go");
+        let filter = SearchFilter {
+            exclude: Some(ExcludeGlobs::parse("router.go").unwrap()),
+            ..SearchFilter::default()
+        };
+        let result = symbol::search("HandlerFunc", &scope, &filter, None).unwrap();
+        assert!(!result.matches.is_empty());
+        assert!(
+            result
+                .matches
+                .iter()
+                .all(|m| !m.path.ends_with("router.go")),
+            "{:?}",
+            result.matches
+        );
+    }
+
+    #[test]
+    fn expanded_definition_includes_doc_comment() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        // Padding keeps the file over the expand-whole-file threshold
+        let padding = "// padding line to make the file large enough\n".repeat(100);
+        let source = format!(
+            "{padding}fn before() -> u32 {{\n    1\n}}\n/// Parses the header.\n///\n/// Fails on empty input.\n#[inline]\npub fn parse_header(input: &str) -> usize {{\n    input.len()\n}}\n{padding}"
+        );
+        fs::write(&path, source).unwrap();
+
+        let result =
+            symbol::search("parse_header", dir.path(), &SearchFilter::default(), None).unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        let (code, _) = expand_match(def, dir.path(), false).unwrap();
+
+        assert!(code.contains("```lib.rs:104-110"), "{code}");
+        assert!(code.contains("/// Parses the header."), "{code}");
+        assert!(code.contains("#[inline]"), "{code}");
+        assert!(!code.contains("fn before"), "{code}");
+        assert!(!code.contains("    1\n"), "{code}");
+    }
+
+    #[test]
+    fn doc_start_follows_language_conventions() {
+        let go = ["x := 1", "", "// Run starts the server.", "func Run() {}"];
+        assert_eq!(doc_start(&go, 4, Lang::Go), 3);
+
+        let ts = [
+            "let x = 1;",
+            "/**",
+            " * Run it.",
+            " */",
+            "@memo",
+            "run() {}",
+        ];
+        assert_eq!(doc_start(&ts, 6, Lang::TypeScript), 2);
+
+        // A plain comment isn't a doc comment outside Go and C
+        let ts_plain = ["// setup", "function run() {}"];
+        assert_eq!(doc_start(&ts_plain, 2, Lang::TypeScript), 2);
+        let rust_block = ["/* note */", "fn run() {}"];
+        assert_eq!(doc_start(&rust_block, 2, Lang::Rust), 2);
+
+        let py = ["import os", "@cache", "def run():"];
+        assert_eq!(doc_start(&py, 3, Lang::Python), 2);
+    }
+
+    /// Every walk of a search ticks the same counter; reports come only at
+    /// `Progress::EVERY` boundaries, so a small tree reports nothing.
+    #[test]
+    fn progress_counts_scanned_files() {
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let filter = SearchFilter {
+            progress: Some(Progress::new(move |n| sink.lock().unwrap().push(n))),
+            ..SearchFilter::default()
+        };
+        let progress = filter.progress.clone().unwrap();
+        let scope = fixture("mini-go");
+
+        content::search("c.index", &scope, &filter, false, None).unwrap();
+        let once = progress.0.scanned.load(Ordering::Relaxed);
+        assert_eq!(once, 5, "{progress:?}");
+        glob::search("*.go", &scope, &filter).unwrap();
+        assert_eq!(progress.0.scanned.load(Ordering::Relaxed), 2 * once);
+        assert!(reports.lock().unwrap().is_empty());
+
+        for _ in 2 * once..Progress::EVERY {
+            progress.tick();
+        }
+        assert_eq!(*reports.lock().unwrap(), [Progress::EVERY]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loops_terminate() {
+        use std::os::unix::fs::symlink;
+
+        // Built at test time: committed loops would trip cargo's own package walk.
+        // third_party/self -> . and third_party/dep/root -> ../.. loop back up the tree
+        let dir = tempfile::tempdir().unwrap();
+        let scope = dir.path();
+        fs::create_dir_all(scope.join("third_party/dep")).unwrap();
+        fs::write(
+            scope.join("lib.rs"),
+            "pub fn entry() -> u32 {\n    dep_value()\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            scope.join("third_party/dep/mod.rs"),
+            "pub fn dep_value() -> u32 {\n    42\n}\n",
+        )
+        .unwrap();
+        symlink(".", scope.join("third_party/self")).unwrap();
+        symlink("../..", scope.join("third_party/dep/root")).unwrap();
+
+        let result = glob::search("**/*", scope, &SearchFilter::default()).unwrap();
+        let mut paths: Vec<_> = result
+            .files
+            .iter()
+            .map(|f| f.path.strip_prefix(scope).unwrap().to_path_buf())
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("lib.rs"),
+                PathBuf::from("third_party/dep/mod.rs")
+            ]
+        );
+
+        let visited = walk_collect(scope, &SearchFilter::default(), None, None, |e| {
+            vec![e.path().to_path_buf()]
+        });
+        assert_eq!(visited.len(), 2, "walk revisited the tree: {visited:?}");
+    }
+
+    #[test]
+    fn glob_group_dirs_sums_per_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let scope = dir.path();
+        let files = [
+            ("api/handler.go", "// handles requests\n".repeat(200)),
+            ("api/v1/routes.go", "package v1\n".to_string()),
+            ("cmd/main.go", "package main\n".to_string()),
+            ("cmd/flags.go", "package main\n".to_string()),
+            ("cmd/util.go", "package main\n".to_string()),
+            ("docs/guide.md", "# Guide\n".to_string()),
+        ];
+        for (path, content) in &files {
+            let path = scope.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let result = glob::search("**/*.go", scope, &SearchFilter::default()).unwrap();
+        let totals: Vec<(String, usize)> = result
+            .dirs
+            .iter()
+            .map(|d| (d.path.display().to_string(), d.files))
+            .collect();
+        // Biggest first: api has fewer files but more tokens, nested ones included
+        assert_eq!(totals, [("api".to_string(), 2), ("cmd".to_string(), 3)]);
+
+        let tokens: u64 = ["api/handler.go", "api/v1/routes.go"]
+            .iter()
+            .map(|p| {
+                let path = scope.join(p);
+                crate::types::file_tokens(&path, fs::metadata(&path).unwrap().len())
+            })
+            .sum();
+        assert_eq!(result.dirs[0].tokens, tokens);
+
+        let cache = OutlineCache::new();
+        let out = search_glob("**/*.go", scope, &SearchFilter::default(), true, &cache).unwrap();
+        assert!(out.contains("— 5 files in 2 directories"), "{out}");
+        assert!(
+            out.contains(&format!("\n  api/  (2 files, ~{tokens} tokens)")),
+            "{out}"
+        );
+        assert!(!out.contains("handler.go"), "{out}");
+    }
 }
@@ -0,0 +1,346 @@
+//! Context pack: everything needed to reason about one symbol or file, as a
+//! single markdown document to hand to another agent or save. A symbol pack
+//! holds its definition, the bodies it calls, its direct callers and the files
+//! it imports; a file pack holds the file and the files it imports.
+//!
+//! Every code range appears once — a callee that is also a caller, or sits
+//! inside the definition, is referenced instead of repeated — and sections are
+//! ordered by path and line so the same tree always packs the same way.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{SearchFilter, callees, callers, code_block, doc_start, symbol};
+use crate::cache::OutlineCache;
+use crate::classify::resolve_path;
+use crate::error::GleanError;
+use crate::format;
+use crate::read::{self, detect_file_type, imports};
+use crate::types::FileType;
+
+/// Token cap when the caller doesn't give one.
+pub const DEFAULT_BUDGET: u64 = 8000;
+
+/// Definitions packed when a name is defined in several places.
+const MAX_DEFINITIONS: usize = 3;
+
+/// Callees and callers expanded; the rest are counted.
+const MAX_CALLEES: usize = 6;
+const MAX_CALLERS: usize = 6;
+
+/// Longest body shown before it's cut.
+const MAX_BODY_LINES: u32 = 80;
+
+/// Pack `root` — a file path under `scope`, or a symbol name.
+pub fn build_pack(
+    root: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    let path = resolve_path(scope, root);
+    if path.is_file() {
+        pack_file(&path, scope, filter, cache)
+    } else {
+        pack_symbol(root, scope, filter, cache)
+    }
+}
+
+fn pack_file(
+    path: &Path,
+    scope: &Path,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    let rel = format::rel(path, scope, filter.absolute_paths);
+    let mut out = format!("# Context pack: {rel}\n\n## File\n\n");
+    out.push_str(&read::read_file(
+        path,
+        None,
+        false,
+        filter.lang,
+        cache,
+        false,
+        None,
+        read::generated::Overrides::for_file(path).as_ref(),
+        false,
+    )?);
+
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let mut related = imports::resolve_related_files_with_content(path, &content);
+    related.retain(|p| p != path && p.starts_with(scope));
+    related.sort();
+    related.dedup();
+    if !related.is_empty() {
+        out.push_str("\n\n## Imported files");
+        for p in &related {
+            let _ = write!(
+                out,
+                "\n\n### {}\n\n",
+                format::rel(p, scope, filter.absolute_paths)
+            );
+            out.push_str(&read::read_file(
+                p,
+                None,
+                false,
+                None,
+                cache,
+                false,
+                None,
+                read::generated::Overrides::for_file(p).as_ref(),
+                false,
+            )?);
+        }
+    }
+    Ok(out)
+}
+
+fn pack_symbol(
+    name: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    cache: &OutlineCache,
+) -> Result<String, GleanError> {
+    let mut defs: Vec<_> = symbol::definitions(name, scope, filter)?
+        .into_iter()
+        .filter(|m| m.exact && m.def_range.is_some())
+        .collect();
+    defs.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    defs.dedup_by(|a, b| a.path == b.path && a.line == b.line);
+    if defs.is_empty() {
+        return Err(GleanError::InvalidQuery {
+            query: name.to_string(),
+            reason: "no definition in scope — pack takes a defined symbol or a file path".into(),
+        });
+    }
+    let omitted_defs = defs.len().saturating_sub(MAX_DEFINITIONS);
+    defs.truncate(MAX_DEFINITIONS);
+
+    let mut pack = Pack::new(scope, filter.absolute_paths);
+    let _ = write!(
+        pack.out,
+        "# Context pack: \"{name}\" in {}\n\n## Definition",
+        scope.display()
+    );
+    for m in &defs {
+        if let Some((start, end)) = m.def_range {
+            pack.snippet(&m.path, start, end, name, true);
+        }
+    }
+    if omitted_defs > 0 {
+        let _ = write!(pack.out, "\n\n... {omitted_defs} more definitions");
+    }
+
+    // Callees of every packed definition, resolved to where they're defined
+    let mut resolved = Vec::new();
+    for m in &defs {
+        let content = pack.content(&m.path).to_string();
+        let FileType::Code(lang) = detect_file_type(&m.path) else {
+            continue;
+        };
+        let mut names = callees::extract_callee_names(&content, lang, m.def_range);
+        names.retain(|n| n != name);
+        resolved.extend(callees::resolve_callees(&names, &m.path, &content, cache));
+    }
+    resolved.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.start_line.cmp(&b.start_line))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    resolved.dedup_by(|a, b| a.file == b.file && a.start_line == b.start_line);
+    pack.out.push_str("\n\n## Calls");
+    if resolved.is_empty() {
+        pack.out.push_str("\n\nNothing it calls resolves in scope.");
+    }
+    for c in resolved.iter().take(MAX_CALLEES) {
+        pack.snippet(&c.file, c.start_line, c.end_line, &c.name, false);
+    }
+    if resolved.len() > MAX_CALLEES {
+        let _ = write!(
+            pack.out,
+            "\n\n... {} more callees",
+            resolved.len() - MAX_CALLEES
+        );
+    }
+
+    let mut found = callers::find_callers(name, scope, filter)?;
+    found.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    found.dedup_by(|a, b| a.path == b.path && a.calling_function == b.calling_function);
+    pack.out.push_str("\n\n## Called by");
+    if found.is_empty() {
+        pack.out.push_str("\n\nNo callers in scope.");
+    }
+    for c in found.iter().take(MAX_CALLERS) {
+        if let Some((start, end)) = c.caller_range {
+            pack.snippet(&c.path, start, end, &c.calling_function, false);
+        } else {
+            pack.files.insert(c.path.clone());
+            let _ = write!(
+                pack.out,
+                "\n\n### {}:{} — {}",
+                format::rel(&c.path, scope, filter.absolute_paths),
+                c.line,
+                c.call_text.trim()
+            );
+        }
+    }
+    if found.len() > MAX_CALLERS {
+        let _ = write!(
+            pack.out,
+            "\n\n... {} more callers",
+            found.len() - MAX_CALLERS
+        );
+    }
+
+    // Imports of the definitions' files that nothing above already showed
+    let mut related = BTreeSet::new();
+    for m in &defs {
+        let content = pack.content(&m.path).to_string();
+        related.extend(imports::resolve_related_files_with_content(
+            &m.path, &content,
+        ));
+    }
+    let related: Vec<PathBuf> = related
+        .into_iter()
+        .filter(|p| p.starts_with(scope) && !pack.files.contains(p))
+        .collect();
+    if !related.is_empty() {
+        pack.out.push_str("\n\n## Related files");
+        for p in &related {
+            let _ = write!(
+                pack.out,
+                "\n- {}",
+                format::rel(p, scope, filter.absolute_paths)
+            );
+        }
+    }
+    Ok(pack.out)
+}
+
+/// The document being built, and what it already shows.
+struct Pack<'a> {
+    scope: &'a Path,
+    /// Show paths in full instead of relative to `scope`.
+    absolute: bool,
+    out: String,
+    /// Ranges written so far, to refer back to instead of repeating.
+    shown: Vec<(PathBuf, u32, u32)>,
+    /// Files any section has drawn from.
+    files: BTreeSet<PathBuf>,
+    contents: HashMap<PathBuf, String>,
+}
+
+impl<'a> Pack<'a> {
+    fn new(scope: &'a Path, absolute: bool) -> Self {
+        Self {
+            scope,
+            absolute,
+            out: String::new(),
+            shown: Vec::new(),
+            files: BTreeSet::new(),
+            contents: HashMap::new(),
+        }
+    }
+
+    fn content(&mut self, path: &Path) -> &str {
+        self.contents
+            .entry(path.to_path_buf())
+            .or_insert_with(|| fs::read_to_string(path).unwrap_or_default())
+    }
+
+    /// Write lines `start..=end` of `path` under a `### label` heading, or a
+    /// pointer when an earlier section already shows them. `docs` widens the
+    /// range up to the doc comment.
+    fn snippet(&mut self, path: &Path, start: u32, end: u32, label: &str, docs: bool) {
+        let rel = format::rel(path, self.scope, self.absolute);
+        if self
+            .shown
+            .iter()
+            .any(|(p, s, e)| p == path && *s <= start && end <= *e)
+        {
+            let _ = write!(
+                self.out,
+                "\n\n### {label} — {rel}:{start}-{end} (shown above)"
+            );
+            return;
+        }
+        let scope = self.scope;
+        let content = self.content(path).to_string();
+        let lines: Vec<&str> = content.lines().collect();
+        let total = lines.len() as u32;
+        let start = start.clamp(1, total.max(1));
+        let start = match detect_file_type(path) {
+            FileType::Code(lang) if docs => doc_start(&lines, start, lang),
+            _ => start,
+        };
+        let end = end.min(total);
+        let shown_end = end.min(start + MAX_BODY_LINES - 1);
+
+        let _ = write!(self.out, "\n\n### {label} — {rel}:{start}-{end}");
+        self.out.push_str(&code_block(
+            &format::rel(path, scope, self.absolute),
+            &lines,
+            start,
+            shown_end,
+        ));
+        if shown_end < end {
+            let _ = write!(self.out, "\n... {} more lines", end - shown_end);
+        }
+        self.shown.push((path.to_path_buf(), start, end));
+        self.files.insert(path.to_path_buf());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn symbol_pack_has_definition_callee_and_caller() {
+        let scope = fixture("mini-go");
+        let cache = OutlineCache::new();
+        let out = build_pack("handleRequest", &scope, &SearchFilter::default(), &cache).unwrap();
+
+        let definition = out.find("## Definition").expect(&out);
+        let calls = out.find("## Calls").expect(&out);
+        let called_by = out.find("## Called by").expect(&out);
+        assert!(definition < calls && calls < called_by, "{out}");
+
+        assert!(
+            out[definition..calls].contains("func (e *Engine) handleRequest(c *Context)"),
+            "{out}"
+        );
+        assert!(
+            out[calls..called_by].contains("### Next — context.go:"),
+            "{out}"
+        );
+        assert!(
+            out[called_by..].contains("### ServeHTTP — router.go:"),
+            "{out}"
+        );
+
+        // Same input, same document
+        let again = build_pack("handleRequest", &scope, &SearchFilter::default(), &cache).unwrap();
+        assert_eq!(out, again);
+    }
+
+    #[test]
+    fn range_already_shown_is_referenced() {
+        let scope = fixture("mini-go");
+        let mut pack = Pack::new(&scope, false);
+        let router = scope.join("router.go");
+        pack.snippet(&router, 12, 20, "ServeHTTP", false);
+        pack.snippet(&router, 12, 20, "ServeHTTP", false);
+        assert_eq!(pack.out.matches("```router.go").count(), 1, "{}", pack.out);
+        assert!(pack.out.contains("(shown above)"), "{}", pack.out);
+    }
+}
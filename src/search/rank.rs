@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
 
 use crate::types::Match;
 
@@ -29,6 +31,8 @@ pub fn sort(matches: &mut [Match], query: &str, scope: &Path, context: Option<&P
 
     // Cache package roots for match paths — avoids repeated stat walks
     let mut pkg_cache: HashMap<PathBuf, Option<PathBuf>> = HashMap::new();
+    // Context's imports resolved once; importers of it resolved per match file
+    let mut links = context.map(ImportLinks::new);
 
     matches.sort_by(|a, b| {
         let sa = score(
@@ -38,6 +42,7 @@ pub fn sort(matches: &mut [Match], query: &str, scope: &Path, context: Option<&P
             ctx_parent,
             ctx_pkg_root.as_ref(),
             &mut pkg_cache,
+            links.as_mut(),
         );
         let sb = score(
             b,
@@ -46,6 +51,7 @@ pub fn sort(matches: &mut [Match], query: &str, scope: &Path, context: Option<&P
             ctx_parent,
             ctx_pkg_root.as_ref(),
             &mut pkg_cache,
+            links.as_mut(),
         );
         sb.cmp(&sa)
             .then_with(|| a.path.cmp(&b.path))
@@ -61,6 +67,7 @@ fn score(
     ctx_parent: Option<&Path>,
     ctx_pkg_root: Option<&PathBuf>,
     pkg_cache: &mut HashMap<PathBuf, Option<PathBuf>>,
+    links: Option<&mut ImportLinks>,
 ) -> i32 {
     let mut s = 0i32;
 
@@ -80,7 +87,7 @@ fn score(
 
     // Context-aware boosts
     if ctx_parent.is_some() || ctx_pkg_root.is_some() {
-        s += context_proximity(&m.path, ctx_parent, ctx_pkg_root, pkg_cache);
+        s += context_proximity(&m.path, ctx_parent, ctx_pkg_root, pkg_cache, links);
     }
 
     // Vendor penalty (always active)
@@ -95,6 +102,12 @@ fn score(
         s -= 100;
     }
 
+    // Barrel re-export penalty — `export { Foo } from "./foo"` only forwards
+    // to the real definition, so it should sit below genuine usages.
+    if !m.is_definition && crate::read::imports::reexport_source(&m.text).is_some() {
+        s -= 150;
+    }
+
     s
 }
 
@@ -111,7 +124,14 @@ fn context_proximity(
     ctx_parent: Option<&Path>,
     ctx_pkg_root: Option<&PathBuf>,
     pkg_cache: &mut HashMap<PathBuf, Option<PathBuf>>,
+    links: Option<&mut ImportLinks>,
 ) -> i32 {
+    // Imported by the context file, or importing it: related by actual use,
+    // which beats merely sharing a directory
+    if links.is_some_and(|l| l.linked(match_path)) {
+        return 125;
+    }
+
     // Same directory as context file
     if let Some(cp) = ctx_parent
         && match_path.parent() == Some(cp)
@@ -138,8 +158,48 @@ fn context_proximity(
     0
 }
 
+/// Files one direct import away from the context file, in either direction.
+struct ImportLinks {
+    context: PathBuf,
+    /// What the context file imports (canonical paths).
+    imported: HashSet<PathBuf>,
+    /// Per match file: whether it's linked either way.
+    seen: HashMap<PathBuf, bool>,
+}
+
+impl ImportLinks {
+    fn new(context: &Path) -> Self {
+        let imported = crate::read::imports::resolve_related_files(context)
+            .iter()
+            .map(|p| canonical(p))
+            .collect();
+        ImportLinks {
+            context: canonical(context),
+            imported,
+            seen: HashMap::new(),
+        }
+    }
+
+    fn linked(&mut self, path: &Path) -> bool {
+        let (context, imported) = (&self.context, &self.imported);
+        *self
+            .seen
+            .entry(path.to_path_buf())
+            .or_insert_with_key(|path| {
+                imported.contains(&canonical(path))
+                    || crate::read::imports::resolve_related_files(path)
+                        .iter()
+                        .any(|p| canonical(p) == *context)
+            })
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
 /// Walk up to find the nearest Cargo.toml, package.json, pyproject.toml, go.mod, etc.
-fn package_root(path: &Path) -> Option<&Path> {
+pub(crate) fn package_root(path: &Path) -> Option<&Path> {
     const MANIFESTS: &[&str] = &[
         "Cargo.toml",
         "package.json",
@@ -180,6 +240,7 @@ pub(crate) fn is_test_file(path: &Path) -> bool {
     // JS/TS: foo.test.ts, foo.spec.ts, etc.
     // Java/Kotlin: FooTest.java, FooTest.kt
     // Swift: FooTests.swift
+    // PHP: FooTest.php (PHPUnit)
     name.ends_with("_test.go")
         || name.ends_with("_test.rs")
         || name.ends_with("_test.py")
@@ -189,6 +250,7 @@ pub(crate) fn is_test_file(path: &Path) -> bool {
         || name.ends_with("Test.java")
         || name.ends_with("Test.kt")
         || name.ends_with("Tests.swift")
+        || name.ends_with("Test.php")
         || path.components().any(|c| {
             c.as_os_str()
                 .to_str()
@@ -196,6 +258,111 @@ pub(crate) fn is_test_file(path: &Path) -> bool {
         })
 }
 
+/// Swap each match's mtime for its file's last commit time (`git_recency`),
+/// so a checkout that touched every file doesn't flatten recency. Files
+/// outside a git work tree, or not yet committed, keep their mtime.
+pub(crate) fn use_commit_times(matches: &mut [Match]) {
+    let paths: Vec<&Path> = matches.iter().map(|m| m.path.as_path()).collect();
+    let times = commit_times(&paths);
+    for m in matches {
+        if let Some(&time) = times.get(&m.path) {
+            m.mtime = time;
+        }
+    }
+}
+
+/// Last commit time of each of `paths` that has one, cached for the life of
+/// the process. Uncached files cost one `git log` per repository, not one
+/// per file.
+fn commit_times(paths: &[&Path]) -> HashMap<PathBuf, SystemTime> {
+    static TIMES: OnceLock<Mutex<HashMap<PathBuf, Option<SystemTime>>>> = OnceLock::new();
+    let Ok(mut times) = TIMES.get_or_init(Default::default).lock() else {
+        return HashMap::new();
+    };
+
+    // Uncached files, grouped by the work tree holding them
+    let mut by_repo: HashMap<PathBuf, Vec<(&Path, String)>> = HashMap::new();
+    for &path in paths {
+        if times.contains_key(path) {
+            continue;
+        }
+        match repo_relative(path) {
+            Some((root, rel)) => by_repo.entry(root).or_default().push((path, rel)),
+            None => {
+                times.insert(path.to_path_buf(), None);
+            }
+        }
+    }
+    for (root, files) in by_repo {
+        let rels: HashSet<&str> = files.iter().map(|(_, rel)| rel.as_str()).collect();
+        let found = last_commits(&root, &rels);
+        for (path, rel) in &files {
+            times.insert(path.to_path_buf(), found.get(rel.as_str()).copied());
+        }
+    }
+
+    paths
+        .iter()
+        .filter_map(|&p| Some((p.to_path_buf(), (*times.get(p)?)?)))
+        .collect()
+}
+
+/// The work tree root above `path` and `path` relative to it, `/`-separated
+/// as git prints it.
+fn repo_relative(path: &Path) -> Option<(PathBuf, String)> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let root = path.ancestors().skip(1).find(|d| d.join(".git").exists())?;
+    let rel = path.strip_prefix(root).ok()?.to_str()?.replace('\\', "/");
+    Some((root.to_path_buf(), rel))
+}
+
+/// One `git log --name-only` over the history of `rels`, newest first: the
+/// first commit naming a file is its last change. Stops reading once every
+/// file is found.
+fn last_commits<'a>(root: &Path, rels: &HashSet<&'a str>) -> HashMap<&'a str, SystemTime> {
+    use std::io::{BufRead, BufReader};
+
+    let mut found = HashMap::new();
+    let Ok(mut child) = Command::new("git")
+        .arg("--literal-pathspecs")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotePath=false", "log", "--name-only"])
+        // NUL can't start a file name, so it marks the commit lines
+        .arg("--format=%x00%ct")
+        .arg("--")
+        .args(rels)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return found;
+    };
+
+    let mut time = None;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(secs) = line.strip_prefix('\0') {
+                time = secs
+                    .parse()
+                    .ok()
+                    .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+            } else if let (Some(time), Some(&rel)) = (time, rels.get(line.as_str())) {
+                found.entry(rel).or_insert(time);
+                if found.len() == rels.len() {
+                    break;
+                }
+            }
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+    found
+}
+
 /// 0-100, newer = higher. Files modified within the last hour get max score.
 fn recency(mtime: SystemTime) -> u32 {
     let age = SystemTime::now()
@@ -226,6 +393,7 @@ mod tests {
             column: 0,
             text: "test".to_string(),
             is_definition,
+            implementation: false,
             exact,
             file_lines,
             mtime: SystemTime::now(),
@@ -340,6 +508,18 @@ mod tests {
         );
     }
 
+    /// Barrel files (`index.ts`) re-export symbols; the re-export line is a
+    /// pointer, not a call site, so it ranks below real usages.
+    #[test]
+    fn reexports_rank_below_usages() {
+        let mut barrel = make_match("src/index.ts", false, true, 10);
+        barrel.text = "export { ZodError } from \"./errors\";".to_string();
+        let mut matches = vec![barrel, make_match("src/parse.ts", false, true, 10)];
+        let scope = Path::new("/tmp/project");
+        sort(&mut matches, "ZodError", scope, None);
+        assert_eq!(matches[0].path, PathBuf::from("src/parse.ts"));
+    }
+
     /// Test file penalty should not override the definition bonus — a definition
     /// in a test file is still more valuable than a usage in source code.
     #[test]
@@ -379,4 +559,76 @@ mod tests {
         let paths_b: Vec<_> = b.iter().map(|m| &m.path).collect();
         assert_eq!(paths_a, paths_b, "same inputs must produce same order");
     }
+
+    /// A fresh checkout gives every file the same mtime; commit times keep
+    /// the recently changed file ahead. Untracked files keep their mtime.
+    #[test]
+    fn commit_times_replace_mtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str], date: &str| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .stdout(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"], "");
+        for (name, date) in [
+            ("old.rs", "2001-01-01T00:00:00Z"),
+            ("new.rs", "@1700000000"),
+        ] {
+            std::fs::write(dir.path().join(name), "fn f() {}\n").unwrap();
+            git(&["add", name], date);
+            git(&["commit", "-q", "-m", name], date);
+        }
+        std::fs::write(dir.path().join("draft.rs"), "fn f() {}\n").unwrap();
+
+        let mut matches: Vec<Match> = ["old.rs", "new.rs", "draft.rs"]
+            .iter()
+            .map(|name| {
+                let mut m = make_match(name, false, true, 10);
+                m.path = dir.path().join(name);
+                m
+            })
+            .collect();
+        let now = matches[2].mtime;
+        use_commit_times(&mut matches);
+
+        assert!(matches[0].mtime < matches[1].mtime);
+        assert_eq!(
+            matches[1].mtime,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+        assert_eq!(matches[2].mtime, now, "untracked file keeps its mtime");
+    }
+
+    /// Files the context imports, or that import it, outrank a same-directory
+    /// file with no import relation.
+    #[test]
+    fn import_links_outrank_same_directory() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/py-pkg");
+        let at = |rel: &str| {
+            let mut m = make_match(rel, false, true, 10);
+            m.path = root.join(rel);
+            m
+        };
+
+        // routes.py imports ..models; api/__init__.py merely shares its directory
+        let mut matches = vec![at("service/api/__init__.py"), at("service/models.py")];
+        let context = root.join("service/api/routes.py");
+        sort(&mut matches, "test", &root, Some(&context));
+        assert!(matches[0].path.ends_with("service/models.py"));
+
+        // routes.py imports service.db, so it outranks db.py's neighbour
+        let mut matches = vec![at("service/models.py"), at("service/api/routes.py")];
+        let context = root.join("service/db.py");
+        sort(&mut matches, "test", &root, Some(&context));
+        assert!(matches[0].path.ends_with("service/api/routes.py"));
+    }
 }
@@ -0,0 +1,121 @@
+//! "Related" footer for symbol search: definitions that sit next to the top
+//! match in its file's outline — the other methods of its impl/class, or the
+//! file's other top-level items when it isn't nested.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use super::callees::get_outline_entries;
+use crate::format;
+use crate::read::detect_file_type;
+use crate::types::{FileType, Match, OutlineEntry, OutlineKind};
+
+/// Most neighbours listed, nearest to the match first.
+const MAX_RELATED: usize = 5;
+
+/// Append the related-symbols footer for the first definition in `matches`.
+/// Writes nothing when there is no definition, no grammar, or no neighbour.
+pub(super) fn write_related(
+    out: &mut String,
+    query: &str,
+    matches: &[Match],
+    scope: &Path,
+    absolute: bool,
+) {
+    let Some(m) = matches.iter().find(|m| m.is_definition) else {
+        return;
+    };
+    let FileType::Code(lang) = detect_file_type(&m.path) else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&m.path) else {
+        return;
+    };
+    let entries = get_outline_entries(&content, lang);
+
+    // The method's impl/class when the match is nested, else the whole file
+    let container = entries
+        .iter()
+        .find(|e| contains(e, m.line) && e.children.iter().any(|c| contains(c, m.line)));
+    let (label, siblings) = match container {
+        Some(parent) => (parent.name.as_str(), parent.children.as_slice()),
+        None => ("same file", entries.as_slice()),
+    };
+
+    // `Type.method` queries name the method last
+    let name = query.rsplit(['.', ':']).next().unwrap_or(query);
+    let mut related: Vec<&OutlineEntry> = siblings
+        .iter()
+        .filter(|e| {
+            e.kind != OutlineKind::Import
+                && !contains(e, m.line)
+                && e.name != name
+                && m.def_name.as_deref() != Some(e.name.as_str())
+        })
+        .collect();
+    if related.is_empty() {
+        return;
+    }
+    related.sort_by_key(|e| e.start_line.abs_diff(m.line));
+    related.truncate(MAX_RELATED);
+    related.sort_by_key(|e| e.start_line);
+
+    let rel = format::rel(&m.path, scope, absolute);
+    let _ = write!(
+        out,
+        "\n\n\u{2500}\u{2500} related ({label}) \u{2500}\u{2500}"
+    );
+    for e in related {
+        let _ = write!(out, "\n  {}  {rel}:{}-{}", e.name, e.start_line, e.end_line);
+    }
+}
+
+fn contains(entry: &OutlineEntry, line: u32) -> bool {
+    (entry.start_line..=entry.end_line).contains(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{SearchFilter, symbol};
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn method_lists_siblings_from_same_impl() {
+        let scope = fixture("mini-rust");
+        let result =
+            symbol::search("set_max_count", &scope, &SearchFilter::default(), None).unwrap();
+        let mut out = String::new();
+        write_related(&mut out, "set_max_count", &result.matches, &scope, false);
+
+        assert!(out.contains("related (impl Searcher<M>)"), "{out}");
+        assert!(out.contains("\n  new  src/searcher.rs:"), "{out}");
+        assert!(out.contains("\n  search  src/searcher.rs:"), "{out}");
+        assert!(!out.contains("  set_max_count  "), "{out}");
+        // Siblings only — not the struct or the file's other items
+        assert!(!out.contains("Searcher  "), "{out}");
+    }
+
+    #[test]
+    fn top_level_definition_lists_file_neighbours() {
+        let scope = fixture("mini-rust");
+        let result =
+            symbol::search("RegexMatcher", &scope, &SearchFilter::default(), None).unwrap();
+        let mut out = String::new();
+        write_related(&mut out, "RegexMatcher", &result.matches, &scope, false);
+
+        assert!(out.contains("related (same file)"), "{out}");
+        assert!(
+            out.contains("\n  impl RegexMatcher  src/lib.rs:15-25"),
+            "{out}"
+        );
+        assert!(out.lines().count() <= MAX_RELATED + 3, "{out}");
+    }
+}
@@ -1,18 +1,22 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::SystemTime;
 
 use super::file_metadata;
 use super::treesitter::{
-    DEFINITION_KINDS, extract_definition_name, extract_impl_trait, extract_impl_type,
-    extract_implemented_interfaces,
+    DefinitionMode, extract_definition_name, extract_go_interface_methods, extract_impl_trait,
+    extract_impl_type, extract_implemented_interfaces, extract_python_bases, ruby_accessor_names,
 };
 
 use crate::error::GleanError;
-use crate::read::detect_file_type;
+use crate::read::file_type_or;
 use crate::read::outline::code::outline_language;
-use crate::search::rank;
-use crate::types::{FileType, Match, SearchResult};
+use crate::read::outline::{dockerfile, makefile};
+use crate::search::{SearchFilter, rank};
+use crate::source::FileSource;
+use crate::types::{FileType, Lang, Match, SearchResult};
 use grep_regex::RegexMatcher;
 use grep_searcher::BinaryDetection;
 use grep_searcher::SearcherBuilder;
@@ -23,18 +27,132 @@ const MAX_MATCHES: usize = 10;
 const EARLY_QUIT_THRESHOLD: usize = MAX_MATCHES * 3;
 
 /// Split a dotted query like `"Session.request"` into `("Session", "request")`.
-/// Returns `None` for plain identifiers, empty parts, or multiple dots.
+/// Longer paths split at the last dot: `"Outer.Inner.run"` gives
+/// `("Outer.Inner", "run")`. Returns `None` for plain identifiers or empty parts.
 fn split_dotted_query(query: &str) -> Option<(&str, &str)> {
-    let dot = query.find('.')?;
-    let type_name = &query[..dot];
-    let member_name = &query[dot + 1..];
-    // Reject empty parts or multiple dots
-    if type_name.is_empty() || member_name.is_empty() || member_name.contains('.') {
+    let (type_name, member_name) = query.rsplit_once('.')?;
+    if member_name.is_empty() || type_name.split('.').any(str::is_empty) {
         return None;
     }
     Some((type_name, member_name))
 }
 
+/// Split an identifier into lowercase words on `_`/`-` and case boundaries:
+/// `getUser`, `get_user`, `GetUser` and `GET_USER` all give `["get", "user"]`.
+/// Acronyms stay whole — `HTTPServer` gives `["http", "server"]`.
+pub(super) fn case_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if !prev.is_uppercase() || next_lower {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// How declared names are compared with the query: exactly by default,
+/// ignoring case, and/or across naming conventions.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct NameCase {
+    pub(super) ignore: bool,
+    pub(super) loose: bool,
+}
+
+impl NameCase {
+    pub(super) fn of(filter: &SearchFilter) -> Self {
+        Self {
+            ignore: filter.ignore_case,
+            loose: filter.loose_case,
+        }
+    }
+}
+
+/// The prefix of a wildcard query like `handle*`, which matches every name
+/// starting with `handle`. `None` for plain queries.
+pub(super) fn wildcard_prefix(query: &str) -> Option<&str> {
+    query.strip_suffix('*').filter(|prefix| !prefix.is_empty())
+}
+
+/// Whether a declared name matches the query. Ignore case compares lowercased
+/// names; loose case compares the word sequences, so `get_user` matches `getUser`.
+/// A wildcard query compares only the leading part of the name.
+fn names_match(name: &str, query: &str, case: NameCase) -> bool {
+    if let Some(prefix) = wildcard_prefix(query) {
+        return name.starts_with(prefix)
+            || (case.ignore && name.to_lowercase().starts_with(&prefix.to_lowercase()))
+            || (case.loose && case_words(name).starts_with(&case_words(prefix)));
+    }
+    name == query
+        || (case.ignore && name.to_lowercase() == query.to_lowercase())
+        || (case.loose && case_words(name) == case_words(query))
+}
+
+/// Whether a matched name is the query itself: the exact name, or for a
+/// wildcard the bare prefix (`handle` for `handle*`), so it ranks first.
+fn is_exact(name: &str, query: &str) -> bool {
+    name == wildcard_prefix(query).unwrap_or(query)
+}
+
+/// Cheap pre-parse check that a file can contain the symbol at all.
+pub(super) fn may_contain(content: &str, query: &str, case: NameCase) -> bool {
+    let query = wildcard_prefix(query).unwrap_or(query);
+    if memchr::memmem::find(content.as_bytes(), query.as_bytes()).is_some() {
+        return true;
+    }
+    if !case.ignore && !case.loose {
+        return false;
+    }
+    let lower = content.to_lowercase();
+    if case.ignore && lower.contains(&query.to_lowercase()) {
+        return true;
+    }
+    case.loose && case_words(query).iter().all(|w| lower.contains(w.as_str()))
+}
+
+/// Word-boundary matcher for usages. Ignore case matches the query in any
+/// case. Loose case matches the query's words in any case, joined by an
+/// optional underscore: `get_user` also finds `getUser`, `GetUser` and `GET_USER`.
+/// A wildcard query lets the word run on: `handle*` finds `handleAuth`.
+pub(super) fn symbol_matcher(query: &str, case: NameCase) -> Result<RegexMatcher, GleanError> {
+    let (query, tail) = match wildcard_prefix(query) {
+        Some(prefix) => (prefix, r"\w*"),
+        None => (query, ""),
+    };
+    let words = case_words(query);
+    let pattern = if case.loose && !words.is_empty() {
+        let words: Vec<String> = words
+            .iter()
+            .map(|w| format!("(?i:{})", regex_syntax::escape(w)))
+            .collect();
+        format!(r"\b{}{tail}\b", words.join("_?"))
+    } else if case.ignore {
+        format!(r"(?i)\b{}{tail}\b", regex_syntax::escape(query))
+    } else {
+        format!(r"\b{}{tail}\b", regex_syntax::escape(query))
+    };
+    RegexMatcher::new(&pattern).map_err(|e| GleanError::InvalidQuery {
+        query: query.to_string(),
+        reason: e.to_string(),
+    })
+}
+
 /// Container node kinds that represent types a member can belong to.
 const TYPE_CONTAINER_KINDS: &[&str] = &[
     // Classes
@@ -54,6 +172,20 @@ const TYPE_CONTAINER_KINDS: &[&str] = &[
     "trait_item",
     // Go type declarations
     "type_declaration",
+    // Kotlin singletons
+    "object_declaration",
+    // C# value types and records
+    "struct_declaration",
+    "record_declaration",
+    // PHP traits
+    "trait_declaration",
+    // Ruby classes and modules
+    "class",
+    "module",
+    // Swift extensions. tree-sitter-swift 0.7 parses `extension Foo` as a
+    // `class_declaration` named `Foo`, so this only matters for grammars
+    // that give extensions their own node.
+    "extension_declaration",
 ];
 
 /// Check if a node is inside a type container with the given name.
@@ -72,33 +204,182 @@ fn is_inside_type(node: tree_sitter::Node, type_name: &str, lines: &[&str]) -> b
     false
 }
 
-/// Symbol search: find definitions via tree-sitter, usages via ripgrep, concurrently.
-/// Merge results, deduplicate, definitions first.
+/// Check if a node sits inside the container chain `Outer.Inner` (innermost
+/// last). The enclosing containers, innermost first, must spell the chain
+/// from its end; a namespace contributes each part of its qualified name.
+/// Segments left once the containers run out may name the file's module path,
+/// as in `pkg.module.Class`.
+fn is_inside_chain(node: tree_sitter::Node, chain: &str, path: &Path, lines: &[&str]) -> bool {
+    let mut wanted = chain.rsplit('.').peekable();
+    let mut outermost = None;
+    let mut current = node.parent();
+    while let Some(n) = current {
+        current = n.parent();
+        let is_namespace = matches!(n.kind(), "namespace_declaration" | "namespace_definition");
+        if n.parent().is_none() || !(is_namespace || TYPE_CONTAINER_KINDS.contains(&n.kind())) {
+            continue;
+        }
+        let Some(name) = extract_definition_name(n, lines) else {
+            continue;
+        };
+        for part in name.rsplit('.') {
+            match wanted.next() {
+                None => return true,
+                Some(w) if w == part => {}
+                Some(_) => return false,
+            }
+        }
+        if wanted.peek().is_none() {
+            return true;
+        }
+        outermost = Some(name);
+    }
+    // The innermost segment must be a real container, as for `Type.member`
+    if outermost.is_none() {
+        return false;
+    }
+
+    // Skip a file stem named after its class (Java, Kotlin, C#)
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .filter(|&s| Some(s) != outermost.as_deref());
+    let dirs = path
+        .parent()
+        .into_iter()
+        .flat_map(|p| p.components().rev())
+        .filter_map(|c| c.as_os_str().to_str());
+    let mut parts = stem.into_iter().chain(dirs);
+    wanted.all(|w| parts.next() == Some(w))
+}
+
+/// `is_inside_type` for a single container name, `is_inside_chain` for a
+/// dotted container path.
+fn is_inside_container(
+    node: tree_sitter::Node,
+    type_name: &str,
+    path: &Path,
+    lines: &[&str],
+) -> bool {
+    if type_name.contains('.') {
+        is_inside_chain(node, type_name, path, lines)
+    } else {
+        is_inside_type(node, type_name, lines)
+    }
+}
+
+/// Symbol search: find definitions via tree-sitter and usages via ripgrep in
+/// one walk. Merge results, deduplicate, definitions first.
 pub fn search(
     query: &str,
     scope: &Path,
+    filter: &SearchFilter,
     context: Option<&Path>,
 ) -> Result<SearchResult, GleanError> {
     // Dotted query: branch to specialized search
     if let Some((type_name, member_name)) = split_dotted_query(query) {
-        return search_dotted(query, type_name, member_name, scope, context);
+        return search_dotted(query, type_name, member_name, scope, filter, context);
     }
 
     // Compile regex once, share across both arms
-    let word_pattern = format!(r"\b{}\b", regex_syntax::escape(query));
-    let matcher = RegexMatcher::new(&word_pattern).map_err(|e| GleanError::InvalidQuery {
-        query: query.to_string(),
-        reason: e.to_string(),
-    })?;
+    let matcher = symbol_matcher(query, NameCase::of(filter))?;
+
+    let mode = filter.definitions;
+    let case = NameCase::of(filter);
+    let go_methods = GoMethods::default();
+    let (mut defs, usages) =
+        walk_defs_and_usages(scope, filter, query, &matcher, |path, content, meta| {
+            go_methods.collect(path, content);
+            if may_contain(content, query, case) {
+                file_definitions(path, query, content, meta, mode, filter)
+            } else {
+                Vec::new()
+            }
+        });
+    defs.extend(go_implementors(query, &defs, go_methods, scope, filter));
+
+    Ok(merge(query, scope, filter, context, defs, usages))
+}
+
+/// The definition and usage passes of a symbol search in one parallel walk:
+/// each file is read once, handed to `file_defs` and to the usage `matcher`
+/// as the same buffer. Each pass stops looking once it has
+/// `EARLY_QUIT_THRESHOLD` matches, and the walk quits when both have.
+fn walk_defs_and_usages(
+    scope: &Path,
+    filter: &SearchFilter,
+    usage_name: &str,
+    matcher: &RegexMatcher,
+    file_defs: impl Fn(&Path, &str, (u32, SystemTime)) -> Vec<Match> + Sync,
+) -> (Vec<Match>, Vec<Match>) {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let def_count = AtomicUsize::new(0);
+    let usage_count = AtomicUsize::new(0);
+    let full = |count: &AtomicUsize| count.load(Ordering::Relaxed) >= EARLY_QUIT_THRESHOLD;
+
+    let per_file = super::walk_collect_until(
+        scope,
+        filter,
+        Some(500_000),
+        || full(&def_count) && full(&usage_count),
+        |entry| {
+            let path = entry.path();
+            let Ok(bytes) = fs::read(path) else {
+                return Vec::new();
+            };
+
+            // Definitions need the whole file as text; usages go line by line
+            let meta = file_metadata(path);
+            let defs = match std::str::from_utf8(&bytes) {
+                Ok(content) if !full(&def_count) => file_defs(path, content, meta),
+                _ => Vec::new(),
+            };
 
-    let (defs, usages) = rayon::join(
-        || find_definitions(query, scope),
-        || find_usages(query, &matcher, scope),
+            let mut usages = Vec::new();
+            if !full(&usage_count) {
+                let (file_lines, mtime) = meta;
+                let mut searcher = SearcherBuilder::new()
+                    .binary_detection(BinaryDetection::convert(b'\x00'))
+                    .build();
+                let _ = searcher.search_slice(
+                    matcher,
+                    &bytes,
+                    UTF8(|line_num, line| {
+                        usages.push(usage(path, line_num, line, usage_name, file_lines, mtime));
+                        Ok(true)
+                    }),
+                );
+            }
+
+            if defs.is_empty() && usages.is_empty() {
+                return Vec::new();
+            }
+            def_count.fetch_add(defs.len(), Ordering::Relaxed);
+            usage_count.fetch_add(usages.len(), Ordering::Relaxed);
+            vec![(defs, usages)]
+        },
     );
 
-    let defs = defs?;
-    let usages = usages?;
+    let mut defs = Vec::new();
+    let mut usages = Vec::new();
+    for (file_defs, file_usages) in per_file {
+        defs.extend(file_defs);
+        usages.extend(file_usages);
+    }
+    (defs, usages)
+}
 
+/// Definitions first, then usages not on a definition's line; ranked and cut
+/// to `MAX_MATCHES`.
+fn merge(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    context: Option<&Path>,
+    defs: Vec<Match>,
+    usages: Vec<Match>,
+) -> SearchResult {
     // Deduplicate: remove usage matches that overlap with definition matches.
     // Linear scan — max ~30 defs from EARLY_QUIT_THRESHOLD, no allocation needed.
     let mut merged: Vec<Match> = defs;
@@ -116,17 +397,82 @@ pub fn search(
     let total = merged.len();
     let usage_count = total - def_count;
 
+    if filter.git_recency {
+        rank::use_commit_times(&mut merged);
+    }
     rank::sort(&mut merged, query, scope, context);
     merged.truncate(MAX_MATCHES);
 
-    Ok(SearchResult {
+    SearchResult {
         query: query.to_string(),
         scope: scope.to_path_buf(),
         matches: merged,
         total_found: total,
         definitions: def_count,
         usages: usage_count,
-    })
+    }
+}
+
+/// `search` over the files of a `FileSource` rather than a disk walk.
+/// Sequential: sources are small — a handful of open buffers.
+pub fn search_source(
+    source: &impl FileSource,
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<SearchResult, GleanError> {
+    let dotted = split_dotted_query(query);
+    let usage_name = dotted.map_or(query, |(_, member)| member);
+    let case = if dotted.is_none() {
+        NameCase::of(filter)
+    } else {
+        NameCase::default()
+    };
+    let matcher = symbol_matcher(usage_name, case)?;
+    let mode = filter.definitions;
+
+    let mut defs = Vec::new();
+    let mut usages = Vec::new();
+    for path in source.files(scope) {
+        let rel = path.strip_prefix(scope).unwrap_or(&path);
+        if filter.excludes(rel) {
+            continue;
+        }
+        let Ok(bytes) = source.read(&path) else {
+            continue;
+        };
+        let Ok(content) = String::from_utf8(bytes) else {
+            continue;
+        };
+        // Size and mtime come from the source; the path may not exist on disk
+        let mtime = source.modified(&path).unwrap_or(SystemTime::UNIX_EPOCH);
+        let file_lines = content.lines().count() as u32;
+        let meta = (file_lines, mtime);
+
+        defs.extend(match dotted {
+            Some((type_name, member)) => {
+                file_definitions_dotted(&path, type_name, member, &content, meta, mode, filter)
+            }
+            None if may_contain(&content, query, NameCase::of(filter)) => {
+                file_definitions(&path, query, &content, meta, mode, filter)
+            }
+            None => Vec::new(),
+        });
+
+        let mut searcher = SearcherBuilder::new()
+            .binary_detection(BinaryDetection::convert(b'\x00'))
+            .build();
+        let _ = searcher.search_slice(
+            &matcher,
+            content.as_bytes(),
+            UTF8(|line_num, line| {
+                usages.push(usage(&path, line_num, line, usage_name, file_lines, mtime));
+                Ok(true)
+            }),
+        );
+    }
+
+    Ok(merge(query, scope, filter, None, defs, usages))
 }
 
 /// Dotted symbol search: `Type.member` — find member definitions inside Type,
@@ -136,6 +482,7 @@ fn search_dotted(
     type_name: &str,
     member_name: &str,
     scope: &Path,
+    filter: &SearchFilter,
     context: Option<&Path>,
 ) -> Result<SearchResult, GleanError> {
     let word_pattern = format!(r"\b{}\b", regex_syntax::escape(member_name));
@@ -144,40 +491,28 @@ fn search_dotted(
         reason: e.to_string(),
     })?;
 
-    let (defs, usages) = rayon::join(
-        || find_definitions_dotted(type_name, member_name, scope),
-        || find_usages(member_name, &matcher, scope),
+    let mode = filter.definitions;
+    let (defs, usages) = walk_defs_and_usages(
+        scope,
+        filter,
+        member_name,
+        &matcher,
+        |path, content, meta| {
+            file_definitions_dotted(path, type_name, member_name, content, meta, mode, filter)
+        },
     );
 
-    let defs = defs?;
-    let usages = usages?;
-
-    let mut merged: Vec<Match> = defs;
-    let def_count = merged.len();
-
-    for m in usages {
-        let dominated = merged[..def_count]
-            .iter()
-            .any(|d| d.path == m.path && d.line == m.line);
-        if !dominated {
-            merged.push(m);
-        }
-    }
-
-    let total = merged.len();
-    let usage_count = total - def_count;
-
-    rank::sort(&mut merged, original_query, scope, context);
-    merged.truncate(MAX_MATCHES);
+    Ok(merge(original_query, scope, filter, context, defs, usages))
+}
 
-    Ok(SearchResult {
-        query: original_query.to_string(),
-        scope: scope.to_path_buf(),
-        matches: merged,
-        total_found: total,
-        definitions: def_count,
-        usages: usage_count,
-    })
+/// Every definition of `query` in scope — including `impl Trait for Type`
+/// and `class Foo implements Bar` — unranked, without usages.
+pub(crate) fn definitions(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<Vec<Match>, GleanError> {
+    find_definitions(query, scope, filter, filter.definitions)
 }
 
 /// Find definitions using tree-sitter structural detection.
@@ -188,11 +523,16 @@ fn search_dotted(
 /// Single-read design: reads each file once, checks for symbol via
 /// `memchr::memmem` (SIMD), then reuses the buffer for tree-sitter parsing.
 /// Early termination: quits the parallel walker once enough defs are found.
-fn find_definitions(query: &str, scope: &Path) -> Result<Vec<Match>, GleanError> {
-    let needle = query.as_bytes();
-
-    Ok(super::walk_collect(
+fn find_definitions(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+    mode: DefinitionMode,
+) -> Result<Vec<Match>, GleanError> {
+    let go_methods = GoMethods::default();
+    let mut defs = super::walk_collect(
         scope,
+        filter,
         Some(EARLY_QUIT_THRESHOLD),
         Some(500_000),
         |entry| {
@@ -202,88 +542,275 @@ fn find_definitions(query: &str, scope: &Path) -> Result<Vec<Match>, GleanError>
             let Ok(content) = fs::read_to_string(path) else {
                 return Vec::new();
             };
+            go_methods.collect(path, &content);
 
             // Fast byte check via memchr::memmem (SIMD) — skip files without the symbol
-            if memchr::memmem::find(content.as_bytes(), needle).is_none() {
+            if !may_contain(&content, query, NameCase::of(filter)) {
                 return Vec::new();
             }
 
-            // Get file metadata once per file
-            let (file_lines, mtime) = file_metadata(path);
+            file_definitions(path, query, &content, file_metadata(path), mode, filter)
+        },
+    );
+    defs.extend(go_implementors(query, &defs, go_methods, scope, filter));
+    Ok(defs)
+}
 
-            // Try tree-sitter structural detection
-            let file_type = detect_file_type(path);
-            let is_code = matches!(file_type, FileType::Code(_));
-            let ts_language = match file_type {
-                FileType::Code(l) => outline_language(l),
-                _ => None,
-            };
+/// Go methods seen while a search walks the scope, so implementors can be
+/// found without walking it again: `(file, receiver type, method name)`.
+#[derive(Default)]
+struct GoMethods(Mutex<Vec<(PathBuf, String, String)>>);
+
+impl GoMethods {
+    /// Record the methods declared in `content` if it is a Go file. A line
+    /// scan, not a parse — most files searched will never be needed.
+    fn collect(&self, path: &Path, content: &str) {
+        if path.extension().is_none_or(|e| e != "go") {
+            return;
+        }
+        let methods: Vec<_> = content
+            .lines()
+            .filter_map(go_method_line)
+            .map(|(receiver, name)| (path.to_path_buf(), receiver.to_string(), name.to_string()))
+            .collect();
+        if !methods.is_empty() {
+            self.0
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .extend(methods);
+        }
+    }
+}
 
-            let mut file_defs = if let Some(ref ts_lang) = ts_language {
-                find_defs_treesitter(path, query, ts_lang, &content, file_lines, mtime)
-            } else {
-                Vec::new()
-            };
+/// Receiver type and method name of a method declaration line:
+/// `func (e *Engine[T]) Get(` gives `("Engine", "Get")`.
+fn go_method_line(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix("func (")?;
+    let close = rest.find(')')?;
+    let receiver = rest[..close].split('[').next()?.split_whitespace().last()?;
+    let receiver = receiver.trim_start_matches('*');
+    let after = rest[close + 1..].trim_start();
+    let name_len = after
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(after.len());
+    (!receiver.is_empty() && name_len > 0).then(|| (receiver, &after[..name_len]))
+}
+
+/// Go has no `implements` clause: when `query` is a Go interface, report the
+/// types whose method sets cover its methods as `Type implements Query`.
+/// Best-effort and name-based — signatures are not compared. Methods are
+/// grouped per package directory, so they may be spread across files; only
+/// the directories of matching receivers are parsed for their type
+/// declarations.
+fn go_implementors(
+    query: &str,
+    defs: &[Match],
+    go_methods: GoMethods,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Vec<Match> {
+    let Some(required) = defs
+        .iter()
+        .filter(|d| d.is_definition && d.exact && d.def_name.as_deref() == Some(query))
+        .filter(|d| d.path.extension().is_some_and(|e| e == "go"))
+        .find_map(|d| go_interface_methods_at(&d.path, d.line, query))
+        .filter(|methods| !methods.is_empty())
+    else {
+        return Vec::new();
+    };
 
-            // Fallback: keyword heuristic for code files without tree-sitter grammars.
-            // Only for Code files — Markdown fenced code blocks, structured data, etc.
-            // must not produce definitions (they're examples, not declarations).
-            if file_defs.is_empty() && ts_language.is_none() && is_code {
-                file_defs = find_defs_heuristic_buf(path, query, &content, file_lines, mtime);
+    // Method sets keyed by (package dir, receiver type)
+    let methods = go_methods
+        .0
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let mut method_sets: HashMap<(&Path, &str), HashSet<&str>> = HashMap::new();
+    for (path, receiver, method) in &methods {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        method_sets
+            .entry((dir, receiver))
+            .or_default()
+            .insert(method);
+    }
+    let implementors: HashSet<(&Path, &str)> = method_sets
+        .into_iter()
+        .filter(|((_, receiver), set)| {
+            *receiver != query && required.iter().all(|m| set.contains(m.as_str()))
+        })
+        .map(|(key, _)| key)
+        .collect();
+    let dirs: HashSet<&Path> = implementors.iter().map(|(dir, _)| *dir).collect();
+
+    let mut found = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| Some(e.ok()?.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "go"))
+            .filter(|p| !filter.excludes(p.strip_prefix(scope).unwrap_or(p)))
+            .collect();
+        files.sort();
+        for path in files {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            for (name, start, end, text) in go_types(&content) {
+                if !implementors.contains(&(dir, name.as_str())) {
+                    continue;
+                }
+                let (file_lines, mtime) = file_metadata(&path);
+                found.push(Match {
+                    path: path.clone(),
+                    line: start,
+                    column: 0,
+                    text,
+                    is_definition: true,
+                    implementation: true,
+                    exact: true,
+                    file_lines,
+                    mtime,
+                    def_range: Some((start, end)),
+                    def_name: Some(format!("{name} implements {query}")),
+                });
             }
+        }
+    }
+    found
+}
 
-            file_defs
-        },
-    ))
+/// Methods of the Go interface `name` declared at `line` in `path`, if it is
+/// one. The declaration may be a group: `type ( A interface{...}; B ... )`.
+fn go_interface_methods_at(path: &Path, line: u32, name: &str) -> Option<Vec<String>> {
+    let content = fs::read_to_string(path).ok()?;
+    let ts_lang = outline_language(Lang::Go)?;
+    let tree = super::treesitter::parse_tree(&content, &ts_lang)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let root = tree.root_node();
+    let row = line as usize - 1;
+    let mut cursor = root.walk();
+    let decl = root.named_children(&mut cursor).find(|n| {
+        n.kind() == "type_declaration"
+            && (n.start_position().row..=n.end_position().row).contains(&row)
+    })?;
+    let mut specs = decl.walk();
+    decl.named_children(&mut specs)
+        .filter(|spec| spec.kind() == "type_spec")
+        .find(|spec| extract_definition_name(*spec, &lines).as_deref() == Some(name))
+        .and_then(|spec| extract_go_interface_methods(spec, &lines))
 }
 
-/// Find definitions for dotted queries: search for `member_name` in files
-/// containing `member_name`, then post-filter by `is_inside_type(type_name)`.
-fn find_definitions_dotted(
-    type_name: &str,
-    member_name: &str,
-    scope: &Path,
-) -> Result<Vec<Match>, GleanError> {
-    let needle = member_name.as_bytes();
+/// Top-level non-interface types declared in one Go file:
+/// (name, start line, end line, declaration line).
+fn go_types(content: &str) -> Vec<(String, u32, u32, String)> {
+    let Some(tree) =
+        outline_language(Lang::Go).and_then(|l| super::treesitter::parse_tree(content, &l))
+    else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut types = Vec::new();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for node in root.named_children(&mut cursor) {
+        if node.kind() != "type_declaration" {
+            continue;
+        }
+        let mut specs = node.walk();
+        for spec in node.named_children(&mut specs) {
+            if spec.kind() != "type_spec" || extract_go_interface_methods(spec, &lines).is_some() {
+                continue;
+            }
+            let Some(name) = extract_definition_name(spec, &lines) else {
+                continue;
+            };
+            let row = spec.start_position().row;
+            let text = lines.get(row).unwrap_or(&"").trim_end().to_string();
+            types.push((
+                name,
+                row as u32 + 1,
+                spec.end_position().row as u32 + 1,
+                text,
+            ));
+        }
+    }
+    types
+}
 
-    Ok(super::walk_collect(
-        scope,
-        Some(EARLY_QUIT_THRESHOLD),
-        Some(500_000),
-        |entry| {
-            let path = entry.path();
+/// Definitions of `query` in one already-read file: tree-sitter when a grammar
+/// exists, keyword heuristic for other code files, nothing for non-code.
+pub(super) fn file_definitions(
+    path: &Path,
+    query: &str,
+    content: &str,
+    (file_lines, mtime): (u32, SystemTime),
+    mode: DefinitionMode,
+    filter: &SearchFilter,
+) -> Vec<Match> {
+    let case = NameCase::of(filter);
+
+    // Try tree-sitter structural detection
+    let file_type = file_type_or(path, filter.lang, content.as_bytes());
+    let is_code = matches!(file_type, FileType::Code(_));
+    let ts_language = match file_type {
+        FileType::Code(l) => outline_language(l),
+        _ => None,
+    };
 
-            let Ok(content) = fs::read_to_string(path) else {
-                return Vec::new();
-            };
+    let mut file_defs = if let Some(ref ts_lang) = ts_language {
+        find_defs_treesitter(path, query, ts_lang, content, file_lines, mtime, mode, case)
+    } else if let FileType::Code(lang @ (Lang::Make | Lang::Dockerfile)) = file_type {
+        return find_defs_line_scan(path, query, lang, content, file_lines, mtime, case);
+    } else {
+        Vec::new()
+    };
 
-            if memchr::memmem::find(content.as_bytes(), needle).is_none() {
-                return Vec::new();
-            }
+    // Fallback: keyword heuristic for code files without tree-sitter grammars.
+    // Only for Code files — Markdown fenced code blocks, structured data, etc.
+    // must not produce definitions (they're examples, not declarations).
+    if file_defs.is_empty() && ts_language.is_none() && is_code {
+        file_defs = find_defs_heuristic_buf(path, query, content, file_lines, mtime);
+    }
 
-            let (file_lines, mtime) = file_metadata(path);
+    file_defs
+}
 
-            let file_type = detect_file_type(path);
-            let ts_language = match file_type {
-                FileType::Code(l) => outline_language(l),
-                _ => None,
-            };
+/// Definitions of `member_name` inside `type_name` in one already-read file.
+/// Tree-sitter only — the keyword heuristic can't tell containers apart.
+fn file_definitions_dotted(
+    path: &Path,
+    type_name: &str,
+    member_name: &str,
+    content: &str,
+    (file_lines, mtime): (u32, SystemTime),
+    mode: DefinitionMode,
+    filter: &SearchFilter,
+) -> Vec<Match> {
+    if memchr::memmem::find(content.as_bytes(), member_name.as_bytes()).is_none() {
+        return Vec::new();
+    }
 
-            if let Some(ref ts_lang) = ts_language {
-                find_defs_treesitter_dotted(
-                    path,
-                    type_name,
-                    member_name,
-                    ts_lang,
-                    &content,
-                    file_lines,
-                    mtime,
-                )
-            } else {
-                Vec::new()
-            }
-        },
-    ))
+    let file_type = file_type_or(path, filter.lang, content.as_bytes());
+    let ts_language = match file_type {
+        FileType::Code(l) => outline_language(l),
+        _ => None,
+    };
+
+    if let Some(ref ts_lang) = ts_language {
+        find_defs_treesitter_dotted(
+            path,
+            type_name,
+            member_name,
+            ts_lang,
+            content,
+            file_lines,
+            mtime,
+            mode,
+        )
+    } else {
+        Vec::new()
+    }
 }
 
 /// Tree-sitter dotted definition detection: find `member_name` definitions
@@ -296,6 +823,7 @@ fn find_defs_treesitter_dotted(
     content: &str,
     file_lines: u32,
     mtime: SystemTime,
+    mode: DefinitionMode,
 ) -> Vec<Match> {
     let Some(tree) = super::treesitter::parse_tree(content, ts_lang) else {
         return Vec::new();
@@ -313,6 +841,7 @@ fn find_defs_treesitter_dotted(
         &lines,
         file_lines,
         mtime,
+        mode,
         &mut defs,
         0,
     );
@@ -321,7 +850,8 @@ fn find_defs_treesitter_dotted(
 }
 
 /// Recursively walk AST looking for definitions of `member_name` inside `type_name`.
-/// Depth limit 4 (vs 3 for plain search) to handle deeper nesting.
+/// Depth limit 4 (vs 3 for plain search) to handle deeper nesting, plus a
+/// container and its body for each extra segment of a dotted `type_name`.
 fn walk_for_definitions_dotted(
     node: tree_sitter::Node,
     type_name: &str,
@@ -330,19 +860,18 @@ fn walk_for_definitions_dotted(
     lines: &[&str],
     file_lines: u32,
     mtime: SystemTime,
+    mode: DefinitionMode,
     defs: &mut Vec<Match>,
     depth: usize,
 ) {
-    if depth > 4 {
+    if depth > 4 + 2 * type_name.matches('.').count() {
         return;
     }
 
-    let kind = node.kind();
-
-    if DEFINITION_KINDS.contains(&kind)
+    if mode.is_definition(node)
         && let Some(name) = extract_definition_name(node, lines)
         && name == member_name
-        && is_inside_type(node, type_name, lines)
+        && is_inside_container(node, type_name, path, lines)
     {
         let line_num = node.start_position().row as u32 + 1;
         let line_text = lines
@@ -355,6 +884,7 @@ fn walk_for_definitions_dotted(
             column: node.start_position().column as u32,
             text: line_text.to_string(),
             is_definition: true,
+            implementation: false,
             exact: true,
             file_lines,
             mtime,
@@ -366,6 +896,22 @@ fn walk_for_definitions_dotted(
         });
     }
 
+    if ruby_accessor_names(node, lines)
+        .iter()
+        .any(|n| n == member_name)
+        && is_inside_container(node, type_name, path, lines)
+    {
+        defs.push(accessor_match(
+            node,
+            path,
+            lines,
+            file_lines,
+            mtime,
+            format!("{type_name}.{member_name}"),
+            true,
+        ));
+    }
+
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         walk_for_definitions_dotted(
@@ -376,12 +922,56 @@ fn walk_for_definitions_dotted(
             lines,
             file_lines,
             mtime,
+            mode,
             defs,
-            depth + 1,
+            child_depth(node, depth),
         );
     }
 }
 
+/// Definition match for a Ruby `attr_*` line, ranged to the line itself.
+fn accessor_match(
+    node: tree_sitter::Node,
+    path: &Path,
+    lines: &[&str],
+    file_lines: u32,
+    mtime: SystemTime,
+    def_name: String,
+    exact: bool,
+) -> Match {
+    let row = node.start_position().row;
+    Match {
+        path: path.to_path_buf(),
+        line: row as u32 + 1,
+        column: node.start_position().column as u32,
+        text: lines.get(row).unwrap_or(&"").trim_end().to_string(),
+        is_definition: true,
+        implementation: false,
+        exact,
+        file_lines,
+        mtime,
+        def_range: Some((row as u32 + 1, node.end_position().row as u32 + 1)),
+        def_name: Some(def_name),
+    }
+}
+
+/// Depth of `node`'s children in the definition walks. Namespaces and their
+/// bodies don't count, so a C# method inside `namespace` + `class` sits as
+/// deep as a Rust method inside an `impl`. Ruby modules nest like namespaces.
+fn child_depth(node: tree_sitter::Node, depth: usize) -> usize {
+    let is_namespace = |n: tree_sitter::Node| match n.kind() {
+        "namespace_declaration" | "namespace_definition" => true,
+        // Python's root is also a `module`
+        "module" => n.parent().is_some(),
+        _ => false,
+    };
+    if is_namespace(node) || node.parent().is_some_and(is_namespace) {
+        depth
+    } else {
+        depth + 1
+    }
+}
+
 /// Tree-sitter structural definition detection.
 /// Accepts pre-read content — no redundant file read.
 fn find_defs_treesitter(
@@ -391,6 +981,8 @@ fn find_defs_treesitter(
     content: &str,
     file_lines: u32,
     mtime: SystemTime,
+    mode: DefinitionMode,
+    case: NameCase,
 ) -> Vec<Match> {
     let Some(tree) = super::treesitter::parse_tree(content, ts_lang) else {
         return Vec::new();
@@ -400,7 +992,9 @@ fn find_defs_treesitter(
     let root = tree.root_node();
     let mut defs = Vec::new();
 
-    walk_for_definitions(root, query, path, &lines, file_lines, mtime, &mut defs, 0);
+    walk_for_definitions(
+        root, query, path, &lines, file_lines, mtime, mode, case, &mut defs, 0,
+    );
 
     defs
 }
@@ -413,6 +1007,8 @@ fn walk_for_definitions(
     lines: &[&str],
     file_lines: u32,
     mtime: SystemTime,
+    mode: DefinitionMode,
+    case: NameCase,
     defs: &mut Vec<Match>,
     depth: usize,
 ) {
@@ -422,10 +1018,11 @@ fn walk_for_definitions(
 
     let kind = node.kind();
 
-    if DEFINITION_KINDS.contains(&kind) {
+    if mode.is_definition(node) {
         // Standard definition check: name matches query directly
+        // (or by word sequence under loose case — ranked below exact names)
         if let Some(name) = extract_definition_name(node, lines)
-            && name == query
+            && names_match(&name, query, case)
         {
             let line_num = node.start_position().row as u32 + 1;
             let line_text = lines
@@ -438,14 +1035,15 @@ fn walk_for_definitions(
                 column: node.start_position().column as u32,
                 text: line_text.to_string(),
                 is_definition: true,
-                exact: true,
+                implementation: false,
+                exact: is_exact(&name, query),
                 file_lines,
                 mtime,
                 def_range: Some((
                     node.start_position().row as u32 + 1,
                     node.end_position().row as u32 + 1,
                 )),
-                def_name: Some(query.to_string()),
+                def_name: Some(name),
             });
         }
 
@@ -466,6 +1064,7 @@ fn walk_for_definitions(
                 column: node.start_position().column as u32,
                 text: line_text.to_string(),
                 is_definition: true,
+                implementation: true,
                 exact: true,
                 file_lines,
                 mtime,
@@ -494,6 +1093,7 @@ fn walk_for_definitions(
                     column: node.start_position().column as u32,
                     text: line_text.to_string(),
                     is_definition: true,
+                    implementation: true,
                     exact: true,
                     file_lines,
                     mtime,
@@ -505,6 +1105,45 @@ fn walk_for_definitions(
                 });
             }
         }
+
+        // Python subclass: `class Child(Base)` — surface when searching for the base
+        if kind == "class_definition"
+            && extract_python_bases(node, lines).iter().any(|b| b == query)
+        {
+            let class_name =
+                extract_definition_name(node, lines).unwrap_or_else(|| "<class>".into());
+            let line_num = node.start_position().row as u32 + 1;
+            let line_text = lines
+                .get(node.start_position().row)
+                .unwrap_or(&"")
+                .trim_end();
+            defs.push(Match {
+                path: path.to_path_buf(),
+                line: line_num,
+                column: node.start_position().column as u32,
+                text: line_text.to_string(),
+                is_definition: true,
+                implementation: true,
+                exact: true,
+                file_lines,
+                mtime,
+                def_range: Some((
+                    node.start_position().row as u32 + 1,
+                    node.end_position().row as u32 + 1,
+                )),
+                def_name: Some(format!("{class_name}({query})")),
+            });
+        }
+    }
+
+    // Ruby `attr_accessor :name` defines `name` without a `def`
+    for name in ruby_accessor_names(node, lines) {
+        if names_match(&name, query, case) {
+            let exact = is_exact(&name, query);
+            defs.push(accessor_match(
+                node, path, lines, file_lines, mtime, name, exact,
+            ));
+        }
     }
 
     // Recurse into children (for nested definitions, class bodies, impl blocks, etc.)
@@ -517,12 +1156,59 @@ fn walk_for_definitions(
             lines,
             file_lines,
             mtime,
+            mode,
+            case,
             defs,
-            depth + 1,
+            child_depth(node, depth),
         );
     }
 }
 
+/// Makefile targets and Dockerfile stage names (`FROM ... AS name`) found by
+/// the outline scanners, with the rule or stage as the definition range.
+fn find_defs_line_scan(
+    path: &Path,
+    query: &str,
+    lang: Lang,
+    content: &str,
+    file_lines: u32,
+    mtime: SystemTime,
+    case: NameCase,
+) -> Vec<Match> {
+    let defs: Vec<(String, u32, u32)> = if lang == Lang::Make {
+        makefile::targets(content)
+            .into_iter()
+            .map(|t| (t.name.to_string(), t.start, t.end))
+            .collect()
+    } else {
+        dockerfile::stages(content)
+            .into_iter()
+            .filter_map(|s| Some((s.name?, s.start, s.end)))
+            .collect()
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    defs.into_iter()
+        .filter(|(name, _, _)| names_match(name, query, case))
+        .map(|(name, start, end)| Match {
+            path: path.to_path_buf(),
+            line: start,
+            column: 0,
+            text: lines
+                .get(start as usize - 1)
+                .unwrap_or(&"")
+                .trim_end()
+                .to_string(),
+            is_definition: true,
+            implementation: false,
+            exact: is_exact(&name, query),
+            file_lines,
+            mtime,
+            def_range: Some((start, end)),
+            def_name: Some(name),
+        })
+        .collect()
+}
+
 /// Keyword heuristic fallback for files without tree-sitter grammars.
 /// Operates on pre-read buffer — no redundant file read.
 fn find_defs_heuristic_buf(
@@ -533,15 +1219,17 @@ fn find_defs_heuristic_buf(
     mtime: SystemTime,
 ) -> Vec<Match> {
     let mut defs = Vec::new();
+    let needle = wildcard_prefix(query).unwrap_or(query);
 
     for (i, line) in content.lines().enumerate() {
-        if line.contains(query) && is_definition_line(line) {
+        if line.contains(needle) && is_definition_line(line) {
             defs.push(Match {
                 path: path.to_path_buf(),
                 line: (i + 1) as u32,
                 column: 0,
                 text: line.trim_end().to_string(),
                 is_definition: true,
+                implementation: false,
                 exact: true,
                 file_lines,
                 mtime,
@@ -554,18 +1242,36 @@ fn find_defs_heuristic_buf(
     defs
 }
 
+/// Every line in scope where `query` appears as a whole word — definitions
+/// and usages alike, unranked and uncapped, in files of any size. For tools
+/// that must touch every occurrence, like rename.
+pub(crate) fn word_lines(
+    query: &str,
+    scope: &Path,
+    filter: &SearchFilter,
+) -> Result<Vec<Match>, GleanError> {
+    let matcher = symbol_matcher(query, NameCase::default())?;
+    let mut lines = find_usages(query, &matcher, scope, filter, None)?;
+    lines.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    Ok(lines)
+}
+
 /// Find all usages via ripgrep (word-boundary matching).
 /// Collects per-file, locks once per file (not per line).
-/// Early termination once enough usages found.
+/// Early termination once `early_quit` usages are found. No size cap, so
+/// no file is silently left out.
 fn find_usages(
     query: &str,
     matcher: &RegexMatcher,
     scope: &Path,
+    filter: &SearchFilter,
+    early_quit: Option<usize>,
 ) -> Result<Vec<Match>, GleanError> {
     Ok(super::walk_collect(
         scope,
-        Some(EARLY_QUIT_THRESHOLD),
-        Some(500_000),
+        filter,
+        early_quit,
+        None,
         |entry| {
             let path = entry.path();
             let (file_lines, mtime) = file_metadata(path);
@@ -579,18 +1285,7 @@ fn find_usages(
                 matcher,
                 path,
                 UTF8(|line_num, line| {
-                    file_matches.push(Match {
-                        path: path.to_path_buf(),
-                        line: line_num as u32,
-                        column: 0,
-                        text: line.trim_end().to_string(),
-                        is_definition: false,
-                        exact: line.contains(query),
-                        file_lines,
-                        mtime,
-                        def_range: None,
-                        def_name: None,
-                    });
+                    file_matches.push(usage(path, line_num, line, query, file_lines, mtime));
                     Ok(true)
                 }),
             );
@@ -600,6 +1295,30 @@ fn find_usages(
     ))
 }
 
+/// A usage match for one line the word matcher hit.
+fn usage(
+    path: &Path,
+    line_num: u64,
+    line: &str,
+    query: &str,
+    file_lines: u32,
+    mtime: SystemTime,
+) -> Match {
+    Match {
+        path: path.to_path_buf(),
+        line: line_num as u32,
+        column: 0,
+        text: line.trim_end().to_string(),
+        is_definition: false,
+        implementation: false,
+        exact: line.contains(query),
+        file_lines,
+        mtime,
+        def_range: None,
+        def_name: None,
+    }
+}
+
 /// Keyword heuristic fallback — only used when tree-sitter grammar unavailable.
 fn is_definition_line(line: &str) -> bool {
     let trimmed = line.trim();
@@ -650,7 +1369,47 @@ pub(crate) mod tests {
         ts_lang: &tree_sitter::Language,
         content: &str,
     ) -> Vec<Match> {
-        find_defs_treesitter(path, query, ts_lang, content, 100, SystemTime::now())
+        find_defs_treesitter(
+            path,
+            query,
+            ts_lang,
+            content,
+            100,
+            SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
+        )
+    }
+
+    #[test]
+    fn makefile_targets_and_docker_stages_are_definitions() {
+        let make = "BUILD := build\n.PHONY: build\n\nbuild: deps\n\tgo build ./...\n\ndeps:\n\tgo mod download\n";
+        let filter = SearchFilter::default();
+        let defs = file_definitions(
+            Path::new("Makefile"),
+            "build",
+            make,
+            (8, SystemTime::UNIX_EPOCH),
+            DefinitionMode::Standard,
+            &filter,
+        );
+        assert_eq!(defs.len(), 1, "only the rule, not the variable or .PHONY");
+        assert_eq!(defs[0].line, 4);
+        assert_eq!(defs[0].def_range, Some((4, 5)));
+        assert_eq!(defs[0].text, "build: deps");
+
+        let docker =
+            "FROM rust:1 AS build\nRUN cargo build\n\nFROM debian\nCOPY --from=build /app /app\n";
+        let defs = file_definitions(
+            Path::new("Dockerfile"),
+            "build",
+            docker,
+            (5, SystemTime::UNIX_EPOCH),
+            DefinitionMode::Standard,
+            &filter,
+        );
+        assert_eq!(defs.len(), 1);
+        assert_eq!(defs[0].def_range, Some((1, 2)));
     }
 
     #[test]
@@ -680,6 +1439,8 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
             code,
             15,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(!defs.is_empty(), "should find 'hello' definition");
         assert!(defs[0].is_definition);
@@ -692,6 +1453,8 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
             code,
             15,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(!defs.is_empty(), "should find 'Foo' definition");
 
@@ -702,6 +1465,8 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
             code,
             15,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(!defs.is_empty(), "should find 'dispatch_tool' definition");
     }
@@ -718,7 +1483,13 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
     /// instead of wading through usages.
     #[test]
     fn definition_ranks_first_go() {
-        let result = search("ServeHTTP", &fixture("mini-go"), None).unwrap();
+        let result = search(
+            "ServeHTTP",
+            &fixture("mini-go"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
         assert!(result.definitions > 0, "should find ServeHTTP definition");
         let first = &result.matches[0];
         assert!(first.is_definition, "matches[0] must be a definition");
@@ -741,7 +1512,13 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
     /// 3. def_range is populated so expand can show the full trait body
     #[test]
     fn definition_first_with_cross_file_usages() {
-        let result = search("PatternMatcher", &fixture("mini-rust"), None).unwrap();
+        let result = search(
+            "PatternMatcher",
+            &fixture("mini-rust"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
         let first = &result.matches[0];
         assert!(first.is_definition, "matches[0] must be the definition");
         assert!(
@@ -770,7 +1547,13 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
     /// 3. Result count is not inflated (small codebase = small result set)
     #[test]
     fn results_deduped_and_balanced() {
-        let result = search("Continue", &fixture("mini-go"), None).unwrap();
+        let result = search(
+            "Continue",
+            &fixture("mini-go"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
 
         // No duplicates
         let mut seen = std::collections::HashSet::new();
@@ -801,7 +1584,13 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
     #[test]
     fn markdown_code_examples_not_classified_as_definitions() {
         // mini-rust has a README.md with ```rust code blocks mentioning PatternMatcher and RegexMatcher
-        let result = search("PatternMatcher", &fixture("mini-rust"), None).unwrap();
+        let result = search(
+            "PatternMatcher",
+            &fixture("mini-rust"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
 
         for m in &result.matches {
             if m.is_definition {
@@ -833,7 +1622,13 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
     fn context_does_not_demote_definitions() {
         let scope = fixture("mini-rust");
         let context = scope.join("src/searcher.rs");
-        let result = search("PatternMatcher", &scope, Some(&context)).unwrap();
+        let result = search(
+            "PatternMatcher",
+            &scope,
+            &SearchFilter::default(),
+            Some(&context),
+        )
+        .unwrap();
 
         // Even with context pointing at searcher.rs, definitions must still be first
         // (definition +1000 > context +100)
@@ -843,6 +1638,203 @@ pub(crate) fn dispatch_tool(tool: &str) -> Result<String, String> {
         );
     }
 
+    /// JS/TS `const`/`let` bindings are definitions in loose mode only — the
+    /// default leaves them out as it always has, and strict keeps just the
+    /// ones bound to functions. Functions count in every mode.
+    #[test]
+    fn definition_modes_for_top_level_consts() {
+        let code = r"export const MAX_RETRIES = 3;
+const timeout = 500;
+export const backoff = (attempt: number) => attempt * timeout;
+
+export function retry() {
+  return MAX_RETRIES;
+}
+";
+        let ts_lang =
+            crate::read::outline::code::outline_language(crate::types::Lang::TypeScript).unwrap();
+        let defs = |query: &str, mode: DefinitionMode| {
+            find_defs_treesitter(
+                std::path::Path::new("retry.ts"),
+                query,
+                &ts_lang,
+                code,
+                7,
+                SystemTime::now(),
+                mode,
+                NameCase::default(),
+            )
+        };
+
+        assert_eq!(DefinitionMode::default(), DefinitionMode::Standard);
+        assert!(defs("MAX_RETRIES", DefinitionMode::Standard).is_empty());
+        assert!(defs("backoff", DefinitionMode::Standard).is_empty());
+        assert!(!defs("retry", DefinitionMode::Standard).is_empty());
+
+        assert!(!defs("MAX_RETRIES", DefinitionMode::Loose).is_empty());
+        assert!(!defs("timeout", DefinitionMode::Loose).is_empty());
+        assert!(!defs("backoff", DefinitionMode::Loose).is_empty());
+
+        assert!(defs("MAX_RETRIES", DefinitionMode::Strict).is_empty());
+        assert!(defs("timeout", DefinitionMode::Strict).is_empty());
+        assert!(!defs("backoff", DefinitionMode::Strict).is_empty());
+        assert!(!defs("retry", DefinitionMode::Strict).is_empty());
+    }
+
+    #[test]
+    fn rust_strict_mode_skips_const_items() {
+        let code = "pub const LIMIT: usize = 10;\n\npub fn limit() -> usize {\n    LIMIT\n}\n";
+        let ts_lang =
+            crate::read::outline::code::outline_language(crate::types::Lang::Rust).unwrap();
+        let path = std::path::Path::new("lib.rs");
+        let now = SystemTime::now();
+
+        let loose = find_defs_treesitter(
+            path,
+            "LIMIT",
+            &ts_lang,
+            code,
+            5,
+            now,
+            DefinitionMode::Loose,
+            NameCase::default(),
+        );
+        let strict = find_defs_treesitter(
+            path,
+            "LIMIT",
+            &ts_lang,
+            code,
+            5,
+            now,
+            DefinitionMode::Strict,
+            NameCase::default(),
+        );
+        assert_eq!(loose.len(), 1);
+        assert!(strict.is_empty());
+    }
+
+    /// `exclude_tests` drops test files outright — the `_test.go` usage that
+    /// normally shows (ranked low) must not appear at all.
+    #[test]
+    fn exclude_tests_drops_test_file_usages() {
+        let scope = fixture("mini-go");
+        let in_test = |m: &Match| m.path.ends_with("router_test.go");
+
+        let result = search("ServeHTTP", &scope, &SearchFilter::default(), None).unwrap();
+        assert!(result.matches.iter().any(in_test), "default keeps tests");
+
+        let filter = SearchFilter {
+            exclude_tests: true,
+            ..SearchFilter::default()
+        };
+        let result = search("ServeHTTP", &scope, &filter, None).unwrap();
+        assert!(!result.matches.iter().any(in_test), "tests excluded");
+        assert!(result.matches[0].is_definition, "definition still found");
+    }
+
+    #[test]
+    fn case_words_split_conventions() {
+        for name in ["getUser", "get_user", "GetUser", "GET_USER", "get-user"] {
+            assert_eq!(case_words(name), ["get", "user"], "{name}");
+        }
+        assert_eq!(case_words("HTTPServer"), ["http", "server"]);
+        assert_eq!(case_words("parseJSON2"), ["parse", "json2"]);
+    }
+
+    /// `safe_parse` only reaches the camelCase `safeParse` definition when
+    /// loose_case is on, and the loose hit is not marked exact.
+    #[test]
+    fn loose_case_matches_across_conventions() {
+        let scope = fixture("mini-ts");
+        let is_def = |r: &SearchResult| r.matches.iter().any(|m| m.is_definition);
+
+        let strict = search("safe_parse", &scope, &SearchFilter::default(), None).unwrap();
+        assert!(strict.matches.is_empty(), "exact by default");
+
+        let filter = SearchFilter {
+            loose_case: true,
+            ..SearchFilter::default()
+        };
+        let loose = search("safe_parse", &scope, &filter, None).unwrap();
+        assert!(is_def(&loose), "loose_case finds safeParse");
+        let def = loose.matches.iter().find(|m| m.is_definition).unwrap();
+        assert!(def.path.ends_with("parse.ts"));
+        assert!(!def.exact);
+        assert_eq!(def.def_name.as_deref(), Some("safeParse"));
+    }
+
+    /// `safeparse` reaches `safeParse` only with ignore_case, where the
+    /// definition and its usages are found but not marked exact.
+    #[test]
+    fn ignore_case_matches_any_case() {
+        let scope = fixture("mini-ts");
+        let strict = search("safeparse", &scope, &SearchFilter::default(), None).unwrap();
+        assert!(strict.matches.is_empty(), "exact by default");
+
+        let filter = SearchFilter {
+            ignore_case: true,
+            ..SearchFilter::default()
+        };
+        let result = search("safeparse", &scope, &filter, None).unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        assert!(def.path.ends_with("parse.ts"));
+        assert!(!def.exact);
+        assert_eq!(def.def_name.as_deref(), Some("safeParse"));
+        assert!(result.usages > 0, "usages matched case-insensitively too");
+    }
+
+    /// `get*` lists every definition starting with `get`, and `get` itself
+    /// (the bare prefix) ranks above the longer names.
+    #[test]
+    fn wildcard_lists_definitions_by_prefix() {
+        let code = "function getUser() {}\n\nfunction get() {}\n\nfunction setUser() {}\n\nfunction getUserName() {}\n";
+        let ts_lang =
+            crate::read::outline::code::outline_language(crate::types::Lang::TypeScript).unwrap();
+        let mut defs = find_defs_treesitter(
+            Path::new("users.ts"),
+            "get*",
+            &ts_lang,
+            code,
+            7,
+            SystemTime::now(),
+            DefinitionMode::Loose,
+            NameCase::default(),
+        );
+        rank::sort(&mut defs, "get*", Path::new("."), None);
+        let names: Vec<_> = defs.iter().filter_map(|d| d.def_name.as_deref()).collect();
+        assert_eq!(names, ["get", "getUser", "getUserName"]);
+        assert!(defs[0].exact && !defs[1].exact);
+
+        let matcher = symbol_matcher("get*", NameCase::default()).unwrap();
+        let hits = |line: &str| grep_matcher::Matcher::is_match(&matcher, line.as_bytes()).unwrap();
+        assert!(hits("const u = getUserName(id);"));
+        assert!(!hits("const u = forgetUser(id);"));
+    }
+
+    #[test]
+    fn exact_convention_ranks_above_loose_match() {
+        let code = "function get_user() {}\n\nfunction getUser() {}\n";
+        let ts_lang =
+            crate::read::outline::code::outline_language(crate::types::Lang::TypeScript).unwrap();
+        let mut defs = find_defs_treesitter(
+            Path::new("users.ts"),
+            "getUser",
+            &ts_lang,
+            code,
+            3,
+            SystemTime::now(),
+            DefinitionMode::Loose,
+            NameCase {
+                loose: true,
+                ..NameCase::default()
+            },
+        );
+        assert_eq!(defs.len(), 2);
+        rank::sort(&mut defs, "getUser", Path::new("."), None);
+        assert_eq!(defs[0].def_name.as_deref(), Some("getUser"));
+        assert!(defs[0].exact && !defs[1].exact);
+    }
+
     #[test]
     fn swift_definitions_detected() {
         let code = r"protocol Drawable {
@@ -871,6 +1863,8 @@ func globalHelper() -> Bool {
             code,
             15,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(!defs.is_empty(), "should find 'Shape' definition");
         assert!(defs[0].is_definition);
@@ -883,6 +1877,8 @@ func globalHelper() -> Bool {
             code,
             15,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(!defs.is_empty(), "should find 'Drawable' definition");
 
@@ -893,6 +1889,8 @@ func globalHelper() -> Bool {
             code,
             15,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(!defs.is_empty(), "should find 'globalHelper' definition");
     }
@@ -901,7 +1899,7 @@ func globalHelper() -> Bool {
     /// as definitions, so agents can discover all implementors.
     #[test]
     fn rust_impl_trait_detected_by_trait_name() {
-        let code = r#"pub trait PatternMatcher {
+        let code = r"pub trait PatternMatcher {
     fn find(&self) -> bool;
 }
 
@@ -920,7 +1918,7 @@ impl Regex {
         Regex { pattern: p.to_string() }
     }
 }
-"#;
+";
         let ts_lang =
             crate::read::outline::code::outline_language(crate::types::Lang::Rust).unwrap();
 
@@ -932,6 +1930,8 @@ impl Regex {
             code,
             20,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(
             defs.len() >= 2,
@@ -957,7 +1957,7 @@ impl Regex {
     /// Searching for a type name should find bare `impl Type` blocks.
     #[test]
     fn rust_bare_impl_detected_by_type_name() {
-        let code = r#"pub struct Foo {
+        let code = r"pub struct Foo {
     x: i32,
 }
 
@@ -966,7 +1966,7 @@ impl Foo {
         Foo { x: 0 }
     }
 }
-"#;
+";
         let ts_lang =
             crate::read::outline::code::outline_language(crate::types::Lang::Rust).unwrap();
 
@@ -977,6 +1977,8 @@ impl Foo {
             code,
             20,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         // Should find both the struct and the bare impl
         assert!(
@@ -1013,6 +2015,8 @@ class User implements Serializable, Loggable {
             code,
             20,
             SystemTime::now(),
+            DefinitionMode::Standard,
+            NameCase::default(),
         );
         assert!(
             defs.len() >= 2,
@@ -1029,11 +2033,61 @@ class User implements Serializable, Loggable {
         );
     }
 
+    /// Python subclasses surface as `Child(Base)` definitions, through dotted
+    /// and subscripted bases; keyword arguments are not bases.
+    #[test]
+    fn python_subclasses_of_base() {
+        let code = r"from django.db import models
+
+
+class Model:
+    pass
+
+
+class Article(models.Model):
+    title = models.CharField(max_length=200)
+
+
+class Tagged(Generic[T], Model, metaclass=Meta):
+    pass
+
+
+class Meta(type):
+    pass
+";
+        let py = crate::read::outline::code::outline_language(crate::types::Lang::Python).unwrap();
+        let names = |query: &str| -> Vec<String> {
+            find_defs_treesitter(
+                std::path::Path::new("models.py"),
+                query,
+                &py,
+                code,
+                20,
+                SystemTime::now(),
+                DefinitionMode::Loose,
+                NameCase::default(),
+            )
+            .into_iter()
+            .filter_map(|d| d.def_name)
+            .collect()
+        };
+
+        assert_eq!(names("Model"), ["Model", "Article(Model)", "Tagged(Model)"]);
+        assert_eq!(names("Generic"), ["Tagged(Generic)"]);
+        assert_eq!(names("Meta"), ["Meta"]);
+    }
+
     /// Integration test: searching "PatternMatcher" in mini-rust should now find
     /// both the trait definition AND the impl block as definitions.
     #[test]
     fn impl_trait_surfaces_in_symbol_search() {
-        let result = search("PatternMatcher", &fixture("mini-rust"), None).unwrap();
+        let result = search(
+            "PatternMatcher",
+            &fixture("mini-rust"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
         assert!(
             result.definitions >= 2,
             "should find trait + impl as definitions, got {}",
@@ -1074,15 +2128,312 @@ class User implements Serializable, Loggable {
     }
 
     #[test]
-    fn split_dotted_rejects_multi_dot() {
-        assert_eq!(split_dotted_query("a.b.c"), None);
+    fn split_dotted_multi_part() {
+        assert_eq!(split_dotted_query("a.b.c"), Some(("a.b", "c")));
+        assert_eq!(split_dotted_query("a..c"), None);
+    }
+
+    /// A Kotlin class search puts the AST definition ahead of the fragment
+    /// that uses it; members of an `object` resolve through dotted queries.
+    #[test]
+    fn kotlin_definition_first() {
+        let scope = fixture("mini-kotlin");
+        let result = search("LoginViewModel", &scope, &SearchFilter::default(), None).unwrap();
+        let first = &result.matches[0];
+        assert!(first.is_definition, "{:?}", result.matches);
+        assert!(first.path.ends_with("LoginViewModel.kt"));
+        assert_eq!(first.def_range, Some((6, 12)));
+        assert!(result.usages > 0);
+
+        let result = search("Session.clear", &scope, &SearchFilter::default(), None).unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        assert_eq!(def.def_name.as_deref(), Some("Session.clear"));
+        assert_eq!(def.def_range, Some((6, 8)));
+
+        let callers =
+            crate::search::callers::find_callers("login", &scope, &SearchFilter::default())
+                .unwrap();
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].calling_function, "bind");
+
+        // `val state` in the class and the interface
+        let result = search("state", &scope, &SearchFilter::default(), None).unwrap();
+        assert_eq!(result.definitions, 2, "{:?}", result.matches);
+    }
+
+    /// C# methods sit under namespace + class; classes listing an interface in
+    /// their base list surface as its implementors.
+    #[test]
+    fn csharp_definitions_inside_namespaces() {
+        let scope = fixture("mini-csharp");
+        let filter = SearchFilter::default();
+
+        let result = search("Find", &scope, &filter, None).unwrap();
+        let mut found: Vec<(String, u32)> = result
+            .matches
+            .iter()
+            .filter(|m| m.is_definition)
+            .map(|m| {
+                let file = m.path.file_name().unwrap().to_string_lossy().into_owned();
+                (file, m.line)
+            })
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("CachedOrderStore.cs".to_string(), 9),
+                ("OrderStore.cs".to_string(), 9),
+                ("OrderStore.cs".to_string(), 18),
+            ]
+        );
+
+        let result = search("IOrderStore", &scope, &filter, None).unwrap();
+        let mut implementors: Vec<&str> = result
+            .matches
+            .iter()
+            .filter_map(|m| m.def_name.as_deref())
+            .filter(|n| n.contains(" implements "))
+            .collect();
+        implementors.sort_unstable();
+        assert_eq!(
+            implementors,
+            [
+                "CachedOrderStore implements IOrderStore",
+                "SqlOrderStore implements IOrderStore"
+            ]
+        );
+
+        let result = search("SqlOrderStore.Dispose", &scope, &filter, None).unwrap();
+        assert_eq!(result.definitions, 1, "{:?}", result.matches);
+
+        // Namespace-qualified, in full or by its last part
+        for query in [
+            "Shop.Orders.SqlOrderStore.Dispose",
+            "Orders.SqlOrderStore.Dispose",
+        ] {
+            let result = search(query, &scope, &filter, None).unwrap();
+            assert_eq!(result.definitions, 1, "{query}: {:?}", result.matches);
+        }
+        let result = search("Billing.SqlOrderStore.Dispose", &scope, &filter, None).unwrap();
+        assert_eq!(result.definitions, 0, "{:?}", result.matches);
+    }
+
+    /// `Outer.Inner.member` checks the whole container chain; leading segments
+    /// past the outermost class can name the module.
+    #[test]
+    fn dotted_chain_of_nested_containers() {
+        let scope = fixture("py-pkg");
+        let filter = SearchFilter::default();
+        let defs = |query: &str| -> Vec<u32> {
+            let result = search(query, &scope, &filter, None).unwrap();
+            let mut lines: Vec<u32> = result
+                .matches
+                .iter()
+                .filter(|m| m.is_definition)
+                .map(|m| m.line)
+                .collect();
+            lines.sort_unstable();
+            lines
+        };
+
+        assert_eq!(defs("User.Meta.table"), [3]);
+        assert_eq!(defs("models.User.table"), [6]);
+        assert_eq!(defs("service.models.User.table"), [6]);
+        // Two parts keep the shallower fast path
+        assert_eq!(defs("User.table"), [6]);
+        assert!(defs("Meta.User.table").is_empty());
+        assert!(defs("routes.User.table").is_empty());
+    }
+
+    /// Go: types whose method sets cover an interface's methods are reported as
+    /// implementors, even with methods split across files of the package.
+    #[test]
+    fn go_interface_implementors_by_method_set() {
+        let scope = fixture("go-iface");
+        let filter = SearchFilter::default();
+
+        let result = search("Store", &scope, &filter, None).unwrap();
+        let implementors: Vec<(&str, u32)> = result
+            .matches
+            .iter()
+            .filter(|m| {
+                m.def_name
+                    .as_deref()
+                    .is_some_and(|n| n.contains(" implements "))
+            })
+            .map(|m| (m.def_name.as_deref().unwrap(), m.line))
+            .collect();
+        assert_eq!(
+            implementors,
+            [("MemoryStore implements Store", 6)],
+            "{:?}",
+            result.matches
+        );
+
+        // Interfaces inside a grouped `type ( ... )` declaration
+        let result = search("Closer", &scope, &filter, None).unwrap();
+        assert!(
+            result
+                .matches
+                .iter()
+                .any(|m| m.def_name.as_deref() == Some("MemoryStore implements Closer")),
+            "{:?}",
+            result.matches
+        );
+        let result = search("Flusher", &scope, &filter, None).unwrap();
+        assert!(
+            result.matches.iter().all(|m| m
+                .def_name
+                .as_deref()
+                .is_none_or(|n| !n.contains(" implements "))),
+            "{:?}",
+            result.matches
+        );
+
+        let result = search("ReadOnly", &scope, &filter, None).unwrap();
+        assert!(
+            result.matches.iter().all(|m| m
+                .def_name
+                .as_deref()
+                .is_none_or(|n| !n.contains(" implements "))),
+            "{:?}",
+            result.matches
+        );
+    }
+
+    /// PHP: interface implementors, trait members through dotted queries, and
+    /// callers through `->`, `::` and plain function calls.
+    #[test]
+    fn php_implementors_and_callers() {
+        let scope = fixture("mini-php");
+        let filter = SearchFilter::default();
+
+        let result = search("Finder", &scope, &filter, None).unwrap();
+        assert!(
+            result
+                .matches
+                .iter()
+                .any(|m| m.def_name.as_deref() == Some("UserService implements Finder")),
+            "{:?}",
+            result.matches
+        );
+
+        let result = search("Loggable.log", &scope, &filter, None).unwrap();
+        assert_eq!(result.definitions, 1, "{:?}", result.matches);
+
+        for (target, caller) in [
+            ("find", "show"),
+            ("normalize_id", "find"),
+            ("format", "show"),
+        ] {
+            let callers = crate::search::callers::find_callers(target, &scope, &filter).unwrap();
+            assert!(
+                callers.iter().any(|c| c.calling_function == caller),
+                "{target}: {callers:?}"
+            );
+        }
+    }
+
+    /// Ruby: `def` methods are definitions, and `attr_accessor :name` stands in
+    /// for the `name` method it generates. Callers see through paren-less calls.
+    #[test]
+    fn ruby_methods_and_accessors_are_definitions() {
+        let scope = fixture("mini-ruby");
+        let filter = SearchFilter::default();
+
+        let result = search("name", &scope, &filter, None).unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        assert!(def.path.ends_with("app/models/user.rb"));
+        assert_eq!(def.def_range, Some((3, 3)));
+        assert_eq!(def.text.trim(), "attr_accessor :name, :email");
+
+        let result = search("User.display_name", &scope, &filter, None).unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        assert_eq!(def.def_range, Some((11, 13)));
+
+        let callers = crate::search::callers::find_callers("save", &scope, &filter).unwrap();
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].calling_function, "show");
+
+        let callers = crate::search::callers::find_callers("validate", &scope, &filter).unwrap();
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].calling_function, "save");
+    }
+
+    /// Shell: an extensionless script is detected by its shebang, and only the
+    /// real function is a definition — not the heredoc or comment mentions.
+    #[test]
+    fn shell_function_definition() {
+        let scope = fixture("mini-shell");
+        let filter = SearchFilter::default();
+        let script = scope.join("bin/deploy");
+        assert_eq!(
+            crate::read::sniff_file_type(&script, &fs::read(&script).unwrap()),
+            crate::types::FileType::Code(crate::types::Lang::Shell)
+        );
+        assert_eq!(
+            crate::read::detect_file_type(&script),
+            crate::types::FileType::Other,
+            "name-only detection never opens the file"
+        );
+
+        let result = search("deploy", &scope, &filter, None).unwrap();
+        let defs: Vec<_> = result.matches.iter().filter(|m| m.is_definition).collect();
+        assert_eq!(defs.len(), 1, "{:?}", result.matches);
+        assert!(defs[0].path.ends_with("bin/deploy"));
+        assert_eq!(defs[0].def_range, Some((7, 11)));
+
+        let result = search("upload_artifacts", &scope, &filter, None).unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        assert!(def.path.ends_with("lib/common.sh"));
+
+        let callers = crate::search::callers::find_callers("log", &scope, &filter).unwrap();
+        let mut functions: Vec<&str> = callers
+            .iter()
+            .map(|c| c.calling_function.as_str())
+            .collect();
+        functions.sort_unstable();
+        assert_eq!(functions, ["deploy", "upload_artifacts"]);
+    }
+
+    /// Members declared in `extension Session` belong to `Session`.
+    #[test]
+    fn dotted_symbol_search_swift_extension() {
+        let result = search(
+            "Session.retry",
+            &fixture("swift-extension"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        assert!(def.path.ends_with("Session+Retry.swift"), "{:?}", def.path);
+        assert_eq!(def.def_name.as_deref(), Some("Session.retry"));
+        assert_eq!(def.def_range, Some((4, 7)));
+
+        // Free functions and protocols come from the AST, with full ranges
+        for (dir, name, range) in [
+            ("swift-extension", "makeSession", (10, 12)),
+            ("mini-swift", "RequestDelegate", (3, 5)),
+        ] {
+            let result = search(name, &fixture(dir), &SearchFilter::default(), None).unwrap();
+            let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+            assert_eq!(def.def_range, Some(range), "{name}");
+        }
     }
 
     /// Integration test: `Session.request` should find the `request` method
     /// inside the `Session` class in mini-swift.
     #[test]
     fn dotted_symbol_search_swift() {
-        let result = search("Session.request", &fixture("mini-swift"), None).unwrap();
+        let result = search(
+            "Session.request",
+            &fixture("mini-swift"),
+            &SearchFilter::default(),
+            None,
+        )
+        .unwrap();
         assert!(
             result.definitions > 0,
             "should find Session.request definition, got 0 defs out of {} matches",
@@ -1098,4 +2449,43 @@ class User implements Serializable, Loggable {
         assert_eq!(def.def_name.as_deref(), Some("Session.request"));
         assert!(def.def_range.is_some());
     }
+
+    #[test]
+    fn c_macro_and_typedef_are_definitions() {
+        let scope =
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini-c");
+        let filter = SearchFilter::default();
+
+        let result = search("BUFFER_MAX", &scope, &filter, None).unwrap();
+        let def = result.matches.iter().find(|m| m.is_definition).unwrap();
+        assert!(def.path.ends_with("buffer.h"));
+        assert_eq!(def.line, 7);
+        assert_eq!(result.definitions, 1);
+
+        for (name, line) in [("Buffer", 9), ("buffer_free_fn", 15)] {
+            let result = search(name, &scope, &filter, None).unwrap();
+            let def = result.matches.iter().find(|m| m.is_definition);
+            assert_eq!(def.map(|m| m.line), Some(line), "{name}");
+        }
+    }
+
+    #[test]
+    fn lang_override_only_covers_undetected_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("build"), "fn helper() {}\n").unwrap();
+        fs::write(dir.path().join("tasks.py"), "def helper():\n    pass\n").unwrap();
+        let filter = SearchFilter {
+            lang: Some(Lang::Rust),
+            ..SearchFilter::default()
+        };
+        let result = search("helper", dir.path(), &filter, None).unwrap();
+        let mut defs: Vec<&str> = result
+            .matches
+            .iter()
+            .filter(|m| m.is_definition)
+            .map(|m| m.text.as_str())
+            .collect();
+        defs.sort_unstable();
+        assert_eq!(defs, ["def helper():", "fn helper() {}"]);
+    }
 }
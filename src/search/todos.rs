@@ -0,0 +1,222 @@
+//! Annotation markers (`TODO`, `FIXME`, ...) in comments, grouped by file with
+//! the function each one sits in.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use grep_matcher::Matcher;
+use grep_regex::RegexMatcher;
+
+use super::content::CommentMap;
+use super::{MAX_SEARCH_FILE_SIZE, SearchFilter};
+use crate::error::GleanError;
+use crate::format;
+use crate::read::detect_file_type;
+use crate::types::FileType;
+
+/// Markers scanned for when the caller doesn't name any.
+pub const DEFAULT_MARKERS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// Most markers listed; the header still reports the full count.
+const MAX_TODOS: usize = 200;
+
+/// One marker found in a comment.
+#[derive(Debug)]
+pub struct Todo {
+    pub path: PathBuf,
+    pub line: u32,
+    pub marker: String,
+    /// The comment line, trimmed.
+    pub text: String,
+    /// Enclosing function/type, when the file has a grammar and the marker
+    /// isn't at top level.
+    pub symbol: Option<String>,
+}
+
+/// Every marker in comments under `scope`, ordered by path then line.
+pub fn find_todos(
+    scope: &Path,
+    filter: &SearchFilter,
+    markers: &[&str],
+) -> Result<Vec<Todo>, GleanError> {
+    let markers = if markers.is_empty() {
+        DEFAULT_MARKERS
+    } else {
+        markers
+    };
+    let alternation: Vec<String> = markers.iter().map(|m| marker_pattern(m)).collect();
+    let pattern = alternation.join("|");
+    let matcher = RegexMatcher::new(&pattern).map_err(|e| GleanError::InvalidQuery {
+        query: markers.join(","),
+        reason: e.to_string(),
+    })?;
+
+    let mut todos = super::walk_collect(scope, filter, None, Some(MAX_SEARCH_FILE_SIZE), |entry| {
+        let path = entry.path();
+        let file_type = detect_file_type(path);
+        if !matches!(
+            file_type,
+            FileType::Code(_) | FileType::StructuredData | FileType::Notebook
+        ) {
+            return Vec::new();
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        if matcher.find(content.as_bytes()).ok().flatten().is_none() {
+            return Vec::new();
+        }
+        let comments = CommentMap::new(file_type, &content);
+        let lines: Vec<&str> = content.lines().collect();
+
+        let mut found = Vec::new();
+        let mut offset = 0;
+        for (i, line) in content.split_inclusive('\n').enumerate() {
+            let mut hit = None;
+            let _ = matcher.find_iter(line.as_bytes(), |m| {
+                if comments.contains(line, offset, m.start()) {
+                    hit = Some((m.start(), line[m].to_string()));
+                }
+                hit.is_none()
+            });
+            if let Some((col, marker)) = hit {
+                found.push(Todo {
+                    path: path.to_path_buf(),
+                    line: (i + 1) as u32,
+                    marker,
+                    text: line.trim().to_string(),
+                    symbol: comments
+                        .enclosing_symbol(offset + col, &lines)
+                        .map(|(name, _)| name),
+                });
+            }
+            offset += line.len();
+        }
+        found
+    });
+
+    todos.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    Ok(todos)
+}
+
+/// Whole-word pattern for a marker. Word boundaries only go on word-character
+/// ends, so `@deprecated` still matches after a space.
+fn marker_pattern(marker: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let start = if is_word(marker.chars().next()) {
+        r"\b"
+    } else {
+        ""
+    };
+    let end = if is_word(marker.chars().last()) {
+        r"\b"
+    } else {
+        ""
+    };
+    format!("{start}{}{end}", regex_syntax::escape(marker))
+}
+
+/// Render markers grouped under one `## file` header each.
+pub fn format_todos(scope: &Path, todos: &[Todo], absolute: bool) -> String {
+    let mut per_marker: BTreeMap<&str, usize> = BTreeMap::new();
+    for t in todos {
+        *per_marker.entry(t.marker.as_str()).or_default() += 1;
+    }
+    let mut by_file: Vec<(&Path, Vec<&Todo>)> = Vec::new();
+    for t in todos.iter().take(MAX_TODOS) {
+        match by_file.last_mut() {
+            Some((path, group)) if *path == t.path => group.push(t),
+            _ => by_file.push((&t.path, vec![t])),
+        }
+    }
+
+    let summary: Vec<String> = per_marker
+        .iter()
+        .map(|(m, n)| format!("{m}: {n}"))
+        .collect();
+    let files = todos
+        .iter()
+        .map(|t| &t.path)
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    let mut out = format!(
+        "# TODOs in {} — {} markers in {files} files",
+        scope.display(),
+        todos.len()
+    );
+    if !summary.is_empty() {
+        let _ = write!(out, " ({})", summary.join(", "));
+    }
+
+    for (path, group) in by_file {
+        let _ = write!(out, "\n\n## {}", format::rel(path, scope, absolute));
+        for t in group {
+            let symbol = t.symbol.as_deref().unwrap_or("<top-level>");
+            let _ = write!(out, "\n  [{}] {symbol}  {}", t.line, t.text);
+        }
+    }
+
+    if todos.len() > MAX_TODOS {
+        let _ = write!(
+            out,
+            "\n\n... and {} more markers. Narrow with scope or markers.",
+            todos.len() - MAX_TODOS
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn todos_grouped_with_enclosing_function() {
+        let scope = fixture("todos");
+        let todos = find_todos(&scope, &SearchFilter::default(), &[]).unwrap();
+
+        let in_worker: Vec<(&str, Option<&str>)> = todos
+            .iter()
+            .filter(|t| t.path.ends_with("worker.ts"))
+            .map(|t| (t.marker.as_str(), t.symbol.as_deref()))
+            .collect();
+        assert_eq!(
+            in_worker,
+            [("TODO", Some("retry")), ("FIXME", Some("schedule"))]
+        );
+
+        let output = format_todos(&scope, &todos, false);
+        let worker = output.find("## worker.ts").expect("worker.ts group");
+        let section = &output[worker..];
+        let section = &section[..section[2..].find("\n## ").map_or(section.len(), |i| i + 2)];
+        assert!(
+            section.contains("[4] retry  // TODO: add exponential backoff"),
+            "{output}"
+        );
+        assert!(section.contains("[13] schedule  /* FIXME"), "{output}");
+        // String literals are not markers
+        assert!(!output.contains("not a real marker"), "{output}");
+    }
+
+    #[test]
+    fn custom_markers_replace_defaults() {
+        let scope = fixture("todos");
+        let todos = find_todos(&scope, &SearchFilter::default(), &["XXX"]).unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].symbol.as_deref(), Some("push"));
+    }
+
+    #[test]
+    fn marker_pattern_bounds_only_word_ends() {
+        assert_eq!(marker_pattern("TODO"), r"\bTODO\b");
+        assert_eq!(marker_pattern("@deprecated"), r"@deprecated\b");
+    }
+}
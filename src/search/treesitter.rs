@@ -1,14 +1,117 @@
 //! Shared tree-sitter utilities used by symbol search and caller search.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::ops::Range;
+
+use crate::types::Lang;
+
+thread_local! {
+    /// One parser per grammar per thread. Walker and rayon workers reuse them
+    /// across files instead of allocating one and setting its language per parse.
+    static PARSERS: RefCell<HashMap<tree_sitter::Language, tree_sitter::Parser>> =
+        RefCell::new(HashMap::new());
+}
+
 /// Parse content into a tree-sitter Tree. Returns `None` if the language
 /// can't be set or parsing fails.
 pub(crate) fn parse_tree(
     content: &str,
     ts_lang: &tree_sitter::Language,
 ) -> Option<tree_sitter::Tree> {
-    let mut parser = tree_sitter::Parser::new();
-    parser.set_language(ts_lang).ok()?;
-    parser.parse(content, None)
+    PARSERS.with_borrow_mut(|parsers| {
+        let parser = match parsers.entry(ts_lang.clone()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let mut parser = tree_sitter::Parser::new();
+                parser.set_language(ts_lang).ok()?;
+                e.insert(parser)
+            }
+        };
+        // A parse that failed part-way leaves state behind; start each file clean
+        parser.reset();
+        parser.parse(content, None)
+    })
+}
+
+/// Byte ranges of comments in document order. Covers every grammar's
+/// `comment`/`line_comment`/`block_comment` kinds plus Python docstrings
+/// (a bare string statement directly in a module or block).
+pub(crate) fn comment_ranges(tree: &tree_sitter::Tree) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    collect_comments(tree.root_node(), &mut ranges);
+    ranges
+}
+
+fn collect_comments(node: tree_sitter::Node, ranges: &mut Vec<Range<usize>>) {
+    if node.kind().contains("comment") || is_docstring(node) {
+        ranges.push(node.byte_range());
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_comments(child, ranges);
+    }
+}
+
+/// Byte ranges of string literals in document order: the `STRING_KINDS` of
+/// every grammar. Python docstrings are comments (`comment_ranges`), not
+/// strings.
+pub(crate) fn string_ranges(tree: &tree_sitter::Tree) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    collect_strings(tree.root_node(), &mut ranges);
+    ranges
+}
+
+fn collect_strings(node: tree_sitter::Node, ranges: &mut Vec<Range<usize>>) {
+    let kind = node.kind();
+    if node.is_named() && STRING_KINDS.contains(&kind) {
+        if !is_docstring(node) {
+            ranges.push(node.byte_range());
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_strings(child, ranges);
+    }
+}
+
+/// String literal node kinds across tree-sitter grammars. Exact names, so
+/// code that merely sits near a string (bash `heredoc_redirect`, Swift
+/// `key_path_string_expression`, Java `string_interpolation`) never counts.
+const STRING_KINDS: &[&str] = &[
+    "string",
+    "string_literal",
+    "raw_string",
+    "raw_string_literal",
+    "interpreted_string_literal",
+    "verbatim_string_literal",
+    "interpolated_string_expression",
+    "template_string",
+    "line_string_literal",
+    "multi_line_string_literal",
+    "multiline_string_literal",
+    "multiline_string",
+    "encapsed_string",
+    "ansi_c_string",
+    "translated_string",
+    "system_lib_string",
+    "string_array",
+    // Heredocs: PHP wraps the whole literal, bash and Ruby only the body
+    "heredoc",
+    "nowdoc",
+    "heredoc_body",
+];
+
+fn is_docstring(node: tree_sitter::Node) -> bool {
+    node.kind() == "string"
+        && node
+            .parent()
+            .filter(|p| p.kind() == "expression_statement" && p.named_child_count() == 1)
+            .and_then(|p| p.parent())
+            .is_some_and(|g| matches!(g.kind(), "module" | "block"))
 }
 
 /// Definition node kinds across tree-sitter grammars.
@@ -48,18 +151,198 @@ pub(crate) const DEFINITION_KINDS: &[&str] = &[
     "init_declaration",
     "typealias_declaration",
     "property_declaration",
+    // C#
+    "struct_declaration",
+    "record_declaration",
+    "constructor_declaration",
+    "delegate_declaration",
+    // PHP
+    "trait_declaration",
+    "const_declaration",
+    // Kotlin
+    "object_declaration",
+    "type_alias",
     // Zig
     "test_declaration",
     "using_namespace_declaration",
+    // C/C++ macros and typedefs
+    "preproc_def",
+    "preproc_function_def",
+    "type_definition",
     // Exports
     "export_statement",
 ];
 
+/// The name a C/C++ `typedef` declares: the type identifier inside its
+/// declarator, which may be wrapped in pointer or function-pointer syntax.
+pub(crate) fn typedef_name(node: tree_sitter::Node, lines: &[&str]) -> Option<String> {
+    let mut stack = vec![node.child_by_field_name("declarator")?];
+    while let Some(n) = stack.pop() {
+        if n.kind() == "type_identifier" {
+            return Some(node_text_simple(n, lines));
+        }
+        let mut cursor = n.walk();
+        let children: Vec<_> = n.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    None
+}
+
+/// Ruby's definition kinds. Other grammars reuse these names for things that
+/// aren't definitions — JS's class expression is a `class`, Python's root
+/// and a TS ambient declaration are a `module` — so they count only in Ruby.
+const RUBY_DEFINITION_KINDS: &[&str] = &["method", "singleton_method", "class", "module"];
+
+/// Variable and constant declarations — not definitions in `Strict` mode.
+const VARIABLE_KINDS: &[&str] = &[
+    "lexical_declaration",
+    "variable_declaration",
+    "const_item",
+    "static_item",
+];
+
+/// Values that make a JS/TS binding a function: `const f = () => ...`.
+const FUNCTION_VALUE_KINDS: &[&str] = &[
+    "arrow_function",
+    "function_expression",
+    "function",
+    "generator_function",
+];
+
+/// Which tree-sitter nodes symbol search treats as definitions.
+///
+/// `Standard` (the default) counts the kinds in `DEFINITION_KINDS` — Rust,
+/// C and Zig constants included — but not JS/TS `const`/`let`/`var` bindings.
+/// `Loose` adds those bindings: `export const X = ...` is API. `Strict` drops
+/// variable and constant declarations and keeps functions, types, traits and
+/// modules; a binding whose value is a function (`const f = () => ...`) still
+/// counts. Note strict also drops Zig's `const Foo = struct { ... }`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DefinitionMode {
+    #[default]
+    Standard,
+    Loose,
+    Strict,
+}
+
+impl DefinitionMode {
+    /// `standard`, `loose` or `strict`.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "standard" => Some(DefinitionMode::Standard),
+            "loose" => Some(DefinitionMode::Loose),
+            "strict" => Some(DefinitionMode::Strict),
+            _ => None,
+        }
+    }
+
+    /// Whether `node` counts as a definition under this mode.
+    pub(crate) fn is_definition(self, node: tree_sitter::Node) -> bool {
+        if !is_definition_kind(node) {
+            return false;
+        }
+        // `export const X = ...` wraps a variable declaration
+        let inner = if node.kind() == "export_statement" {
+            node.child_by_field_name("declaration").unwrap_or(node)
+        } else {
+            node
+        };
+        match (self, js_binding(inner)) {
+            (DefinitionMode::Standard, binding) => binding.is_none(),
+            (DefinitionMode::Loose, _) => true,
+            (DefinitionMode::Strict, Some(declarator)) => declarator
+                .child_by_field_name("value")
+                .is_some_and(|v| FUNCTION_VALUE_KINDS.contains(&v.kind())),
+            (DefinitionMode::Strict, None) => !VARIABLE_KINDS.contains(&inner.kind()),
+        }
+    }
+}
+
+/// The first declarator of a JS/TS `const`/`let`/`var` declaration. C#'s
+/// `variable_declaration` has declarators too, but also a `type`.
+fn js_binding(node: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    if !matches!(node.kind(), "lexical_declaration" | "variable_declaration")
+        || node.child_by_field_name("type").is_some()
+    {
+        return None;
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|c| c.kind() == "variable_declarator")
+}
+
+/// Whether `node` has a definition kind in its grammar.
+fn is_definition_kind(node: tree_sitter::Node) -> bool {
+    let kind = node.kind();
+    DEFINITION_KINDS.contains(&kind)
+        || (RUBY_DEFINITION_KINDS.contains(&kind)
+            && crate::read::outline::code::outline_language(Lang::Ruby)
+                .is_some_and(|ruby| *node.language() == ruby))
+}
+
+/// Names defined by a Ruby `attr_accessor :name, :email` (or `attr_reader`,
+/// `attr_writer`) call — one implicit method per symbol. Empty for any other node.
+pub(crate) fn ruby_accessor_names(node: tree_sitter::Node, lines: &[&str]) -> Vec<String> {
+    if node.kind() != "call" || node.child_by_field_name("receiver").is_some() {
+        return Vec::new();
+    }
+    let is_accessor = node.child_by_field_name("method").is_some_and(|m| {
+        matches!(
+            node_text_simple(m, lines).as_str(),
+            "attr_accessor" | "attr_reader" | "attr_writer"
+        )
+    });
+    let Some(args) = node
+        .child_by_field_name("arguments")
+        .filter(|_| is_accessor)
+    else {
+        return Vec::new();
+    };
+    let mut cursor = args.walk();
+    args.named_children(&mut cursor)
+        .filter(|a| matches!(a.kind(), "simple_symbol" | "string"))
+        .map(|a| {
+            node_text_simple(a, lines)
+                .trim_start_matches(':')
+                .trim_matches(['"', '\''])
+                .to_string()
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Walk up from `node` to the nearest definition containing it.
+/// Returns the definition's name (`<anonymous>` if it has none) and line range.
+pub(crate) fn enclosing_symbol(
+    node: tree_sitter::Node,
+    lines: &[&str],
+) -> Option<(String, (u32, u32))> {
+    let mut current = Some(node);
+    while let Some(n) = current {
+        if is_definition_kind(n) {
+            let name =
+                extract_definition_name(n, lines).unwrap_or_else(|| "<anonymous>".to_string());
+            let range = (
+                n.start_position().row as u32 + 1,
+                n.end_position().row as u32 + 1,
+            );
+            return Some((name, range));
+        }
+        current = n.parent();
+    }
+    None
+}
+
 /// Extract the name defined by a tree-sitter definition node.
 ///
 /// Walks standard field names (`name`, `identifier`, `declarator`) and handles
 /// nested declarators and export statements.
 pub(crate) fn extract_definition_name(node: tree_sitter::Node, lines: &[&str]) -> Option<String> {
+    if node.kind() == "type_definition" {
+        return typedef_name(node, lines);
+    }
+
     // Try standard field names
     for field in &["name", "identifier", "declarator"] {
         if let Some(child) = node.child_by_field_name(field) {
@@ -76,6 +359,17 @@ pub(crate) fn extract_definition_name(node: tree_sitter::Node, lines: &[&str]) -
         }
     }
 
+    // Go `type Store interface { ... }`: the name is on the `type_spec` inside
+    if node.kind() == "type_declaration" {
+        let mut cursor = node.walk();
+        let spec = node
+            .named_children(&mut cursor)
+            .find(|c| matches!(c.kind(), "type_spec" | "type_alias"));
+        if let Some(name) = spec.and_then(|s| s.child_by_field_name("name")) {
+            return Some(node_text_simple(name, lines));
+        }
+    }
+
     // Rust impl_item: `impl Type { ... }` — the type is in the `type` field, not `name`.
     if node.kind() == "impl_item"
         && let Some(type_node) = node.child_by_field_name("type")
@@ -86,6 +380,40 @@ pub(crate) fn extract_definition_name(node: tree_sitter::Node, lines: &[&str]) -
         }
     }
 
+    // Kotlin `val x = y`: the name is inside `variable_declaration`, and the
+    // initializer may itself be a bare identifier, so check before the scan
+    if node.kind() == "property_declaration" {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() == "variable_declaration" {
+                let mut inner = child.walk();
+                for grandchild in child.children(&mut inner) {
+                    if grandchild.kind() == "identifier" {
+                        return Some(node_text_simple(grandchild, lines));
+                    }
+                }
+            }
+            // PHP `private UserService $service;`
+            if child.kind() == "property_element"
+                && let Some(name) = child.child_by_field_name("name")
+            {
+                return Some(node_text_simple(name, lines));
+            }
+        }
+    }
+
+    // PHP `const PER_PAGE = 20;`
+    if node.kind() == "const_declaration" {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() == "const_element" {
+                let mut inner = child.walk();
+                let name = child.children(&mut inner).find(|c| c.kind() == "name");
+                return name.map(|n| node_text_simple(n, lines));
+            }
+        }
+    }
+
     // Fallback: scan positional children for an identifier node.
     // Needed for Zig's variable_declaration where identifier is a child, not a field.
     {
@@ -100,6 +428,28 @@ pub(crate) fn extract_definition_name(node: tree_sitter::Node, lines: &[&str]) -
         }
     }
 
+    // JS/TS `const X = ...`: the name lives on the first `variable_declarator` child
+    if matches!(node.kind(), "lexical_declaration" | "variable_declaration") {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() == "variable_declarator"
+                && let Some(id) = child.child_by_field_name("name")
+            {
+                return Some(node_text_simple(id, lines));
+            }
+        }
+    }
+
+    // C# `private string _conn;`: a `variable_declaration` holds the declarators
+    if node.kind() == "field_declaration" {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() == "variable_declaration" {
+                return extract_definition_name(child, lines);
+            }
+        }
+    }
+
     // For export_statement, check the declaration child
     if node.kind() == "export_statement" {
         let mut cursor = node.walk();
@@ -113,6 +463,33 @@ pub(crate) fn extract_definition_name(node: tree_sitter::Node, lines: &[&str]) -
     None
 }
 
+/// Method names a Go interface declares, for a `type_declaration` (or its
+/// `type_spec`) whose type is an `interface_type`. Embedded interfaces and
+/// type constraints are skipped. `None` for any other type.
+pub(crate) fn extract_go_interface_methods(
+    node: tree_sitter::Node,
+    lines: &[&str],
+) -> Option<Vec<String>> {
+    let spec = if node.kind() == "type_declaration" {
+        let mut cursor = node.walk();
+        node.named_children(&mut cursor)
+            .find(|c| c.kind() == "type_spec")?
+    } else {
+        node
+    };
+    let iface = spec
+        .child_by_field_name("type")
+        .filter(|t| t.kind() == "interface_type")?;
+    let mut cursor = iface.walk();
+    let methods = iface
+        .named_children(&mut cursor)
+        .filter(|c| matches!(c.kind(), "method_elem" | "method_spec"))
+        .filter_map(|c| c.child_by_field_name("name"))
+        .map(|n| node_text_simple(n, lines))
+        .collect();
+    Some(methods)
+}
+
 /// Extract the trait name from a Rust `impl_item` node.
 /// For `impl Trait for Type`, returns the trait name.
 /// For bare `impl Type`, returns `None`.
@@ -132,6 +509,37 @@ pub(crate) fn extract_impl_type(node: tree_sitter::Node, lines: &[&str]) -> Opti
     if text.is_empty() { None } else { Some(text) }
 }
 
+/// Base class names of a Python `class_definition`: `class Foo(Base, models.Model)`
+/// gives `["Base", "Model"]` — dotted bases match on their last segment,
+/// `Generic[T]` on its subscripted value. Keyword arguments (`metaclass=`) are skipped.
+pub(crate) fn extract_python_bases(node: tree_sitter::Node, lines: &[&str]) -> Vec<String> {
+    let Some(args) = node
+        .child_by_field_name("superclasses")
+        .filter(|_| node.kind() == "class_definition")
+    else {
+        return Vec::new();
+    };
+    let mut bases = Vec::new();
+    let mut cursor = args.walk();
+    for child in args.named_children(&mut cursor) {
+        let mut base = child;
+        if base.kind() == "subscript"
+            && let Some(value) = base.child_by_field_name("value")
+        {
+            base = value;
+        }
+        let name = match base.kind() {
+            "identifier" => Some(base),
+            "attribute" => base.child_by_field_name("attribute"),
+            _ => None,
+        };
+        if let Some(name) = name {
+            bases.push(node_text_simple(name, lines));
+        }
+    }
+    bases
+}
+
 /// Extract interface names from a class declaration's `implements` clause.
 /// Works for TypeScript (`class Foo implements Bar, Baz`) and Java.
 /// Handles nesting: `class_declaration` → `class_heritage` → `implements_clause`.
@@ -151,7 +559,8 @@ fn collect_interfaces_from_clause(clause: tree_sitter::Node, lines: &[&str]) ->
     let mut cursor = clause.walk();
     for child in clause.children(&mut cursor) {
         let kind = child.kind();
-        if kind == "type_identifier" || kind == "identifier" {
+        // PHP names are `name` nodes
+        if kind == "type_identifier" || kind == "identifier" || kind == "name" {
             let text = node_text_simple(child, lines);
             if !text.is_empty() {
                 interfaces.push(text);
@@ -163,6 +572,23 @@ fn collect_interfaces_from_clause(clause: tree_sitter::Node, lines: &[&str]) ->
             if !text.is_empty() {
                 interfaces.push(text);
             }
+        } else if kind == "generic_name" || kind == "qualified_name" {
+            // C# `IComparable<T>` names its identifier positionally;
+            // `System.IDisposable` and PHP `\App\Contracts\Auth` match on
+            // their last segment
+            let name = child.child_by_field_name("name").or_else(|| {
+                let mut inner = child.walk();
+                child
+                    .children(&mut inner)
+                    .filter(|c| matches!(c.kind(), "identifier" | "name"))
+                    .last()
+            });
+            if let Some(name) = name {
+                let text = node_text_simple(name, lines);
+                if !text.is_empty() {
+                    interfaces.push(text);
+                }
+            }
         }
     }
     interfaces
@@ -172,7 +598,11 @@ fn find_implements_clause(node: tree_sitter::Node) -> Option<tree_sitter::Node>
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         let kind = child.kind();
-        if kind == "implements_clause" || kind == "super_interfaces" {
+        // C# `class Foo : Base, IBar` lists the base class and interfaces together
+        if matches!(
+            kind,
+            "implements_clause" | "super_interfaces" | "base_list" | "class_interface_clause"
+        ) {
             return Some(child);
         }
         // TypeScript nests: class_declaration → class_heritage → implements_clause
@@ -206,3 +636,71 @@ pub(crate) fn node_text_simple(node: tree_sitter::Node, lines: &[&str]) -> Strin
         String::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read::outline::code::outline_language;
+
+    /// Parsers are pooled per grammar and thread, and a reused parser
+    /// produces the same tree as a fresh one.
+    #[test]
+    fn parsers_reused_per_language() {
+        std::thread::spawn(|| {
+            let rust = outline_language(Lang::Rust).unwrap();
+            let python = outline_language(Lang::Python).unwrap();
+            let first = parse_tree("fn a() {}", &rust).unwrap();
+            parse_tree("def b(): pass", &python).unwrap();
+            let again = parse_tree("fn a() {}", &rust).unwrap();
+
+            assert_eq!(first.root_node().to_sexp(), again.root_node().to_sexp());
+            assert_eq!(PARSERS.with_borrow(HashMap::len), 2);
+        })
+        .join()
+        .unwrap();
+    }
+
+    /// `class` and `module` are Ruby definitions, but a JS class expression
+    /// is a `class` too and isn't one.
+    #[test]
+    fn ruby_kinds_count_only_in_ruby() {
+        let first_of = |code: &str, lang: Lang, kind: &str| {
+            let tree = parse_tree(code, &outline_language(lang).unwrap()).unwrap();
+            let mut stack = vec![tree.root_node()];
+            while let Some(node) = stack.pop() {
+                if node.kind() == kind {
+                    return DefinitionMode::Standard.is_definition(node);
+                }
+                let mut cursor = node.walk();
+                stack.extend(node.children(&mut cursor));
+            }
+            panic!("no {kind} node in {code}");
+        };
+        assert!(first_of(
+            "class User
+end
+",
+            Lang::Ruby,
+            "class"
+        ));
+        assert!(first_of(
+            "module Auth
+end
+",
+            Lang::Ruby,
+            "module"
+        ));
+        assert!(!first_of("f(class {});\n", Lang::JavaScript, "class"));
+    }
+
+    /// A bash heredoc counts only its body, not the pipeline on the
+    /// redirect line.
+    #[test]
+    fn heredoc_redirect_is_not_a_string() {
+        let code = "cat <<EOF | grep token\nsecret\nEOF\n";
+        let tree = parse_tree(code, &outline_language(Lang::Shell).unwrap()).unwrap();
+        let strings: Vec<&str> = string_ranges(&tree).into_iter().map(|r| &code[r]).collect();
+        assert!(strings.iter().all(|s| !s.contains("grep")), "{strings:?}");
+        assert!(strings.iter().any(|s| s.contains("secret")), "{strings:?}");
+    }
+}
@@ -1,8 +1,15 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+/// Saved state older than this belongs to a finished task, not a reconnect.
+const PERSIST_TTL: Duration = Duration::from_hours(2);
 
 /// Tracks MCP activity across calls.
 /// Stored alongside `OutlineCache` in server state.
@@ -13,6 +20,18 @@ pub struct Session {
     symbols: Mutex<HashMap<String, usize>>, // query → search count
     dir_hits: Mutex<HashMap<String, usize>>, // dir → count
     expanded: Mutex<HashSet<String>>,       // "path:line" → expanded status
+    store: Mutex<Option<PathBuf>>,          // where `save` writes, once persisting
+}
+
+/// On-disk form of a `Session`, for `persist_to` and `save`.
+#[derive(Serialize, Deserialize, Default)]
+struct Saved {
+    reads: usize,
+    searches: usize,
+    maps: usize,
+    symbols: HashMap<String, usize>,
+    dir_hits: HashMap<String, usize>,
+    expanded: HashSet<String>,
 }
 
 impl Session {
@@ -24,6 +43,71 @@ impl Session {
             symbols: Mutex::new(HashMap::new()),
             dir_hits: Mutex::new(HashMap::new()),
             expanded: Mutex::new(HashSet::new()),
+            store: Mutex::new(None),
+        }
+    }
+
+    /// File holding the persisted session for a workspace root, in a
+    /// directory only this user writes to: `$XDG_RUNTIME_DIR/glean`, else the
+    /// disk cache directory. `None` when neither is known.
+    #[must_use]
+    pub fn store_path(root: &Path) -> Option<PathBuf> {
+        let mut hash = crate::cache::Fnv::default();
+        hash.write(root.as_os_str().as_encoded_bytes());
+        let dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .filter(|d| !d.is_empty())
+            .map(|d| PathBuf::from(d).join("glean"))
+            .or_else(crate::cache::OutlineCache::default_disk_dir)?;
+        Some(dir.join(format!("session-{:016x}.json", hash.0)))
+    }
+
+    /// Resume from `file` if an earlier server saved recent state there, and
+    /// write back to it on every `save`. Unreadable or stale files start fresh.
+    pub fn persist_to(&self, file: PathBuf) {
+        let fresh = fs::metadata(&file)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+            .is_some_and(|age| age < PERSIST_TTL);
+        let saved = fresh
+            .then(|| fs::read_to_string(&file).ok())
+            .flatten()
+            .and_then(|raw| serde_json::from_str::<Saved>(&raw).ok());
+        if let Some(saved) = saved {
+            self.reads.store(saved.reads, Ordering::Relaxed);
+            self.searches.store(saved.searches, Ordering::Relaxed);
+            self.maps.store(saved.maps, Ordering::Relaxed);
+            *lock(&self.symbols) = saved.symbols;
+            *lock(&self.dir_hits) = saved.dir_hits;
+            *lock(&self.expanded) = saved.expanded;
+        }
+        *lock(&self.store) = Some(file);
+    }
+
+    /// Write the session to its `persist_to` file; a no-op otherwise.
+    /// Written via a temp file and rename so a crash can't leave half a file.
+    pub fn save(&self) {
+        let Some(file) = lock(&self.store).clone() else {
+            return;
+        };
+        let saved = Saved {
+            reads: self.reads.load(Ordering::Relaxed),
+            searches: self.searches.load(Ordering::Relaxed),
+            maps: self.maps.load(Ordering::Relaxed),
+            symbols: lock(&self.symbols).clone(),
+            dir_hits: lock(&self.dir_hits).clone(),
+            expanded: lock(&self.expanded).clone(),
+        };
+        let Ok(body) = serde_json::to_vec(&saved) else {
+            return;
+        };
+        let tmp = file.with_extension(format!("tmp{}", std::process::id()));
+        let written = file
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| write_new(&tmp, &body));
+        if written.is_err() || fs::rename(&tmp, &file).is_err() {
+            let _ = fs::remove_file(&tmp);
         }
     }
 
@@ -134,12 +218,37 @@ impl Session {
     }
 }
 
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 impl Default for Session {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Write `body` to a file that must not exist yet, so a symlink planted at
+/// `path` is never followed. A leftover from a crashed save is replaced.
+fn write_new(path: &Path, body: &[u8]) -> std::io::Result<()> {
+    use std::io::Write as _;
+
+    let open = || {
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+    };
+    let mut file = match open() {
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            fs::remove_file(path)?;
+            open()?
+        }
+        file => file?,
+    };
+    file.write_all(body)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +297,37 @@ mod tests {
         assert!(summary.contains("Searches: 0"), "searches: {summary}");
         assert!(!session.is_expanded(Path::new("x.rs"), 1));
     }
+
+    #[test]
+    fn persisted_session_resumes_after_reconnect() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("glean/session.json");
+
+        // A leftover temp file from a crashed save doesn't block the next
+        fs::create_dir(dir.path().join("glean")).unwrap();
+        fs::write(
+            file.with_extension(format!("tmp{}", std::process::id())),
+            "{",
+        )
+        .unwrap();
+
+        let first = Session::new();
+        first.persist_to(file.clone());
+        first.record_search("foo");
+        first.record_expand(Path::new("x.rs"), 7);
+        first.save();
+
+        let second = Session::new();
+        second.persist_to(file.clone());
+        assert!(second.is_expanded(Path::new("x.rs"), 7));
+        assert!(second.summary().contains("foo (1)"));
+
+        // Without opting in nothing is written
+        let stateless = Session::new();
+        stateless.record_search("bar");
+        stateless.save();
+        let third = Session::new();
+        third.persist_to(file);
+        assert!(!third.summary().contains("bar"));
+    }
 }
@@ -0,0 +1,155 @@
+//! Where file contents come from when they aren't on disk. Reads and searches
+//! go straight to the filesystem by default; an embedder holding unsaved
+//! editor buffers hands in a `FileSource` and calls `run_source` instead.
+//!
+//! The disk path never goes through this trait — it keeps its mmap reads and
+//! parallel walk — so the default costs nothing.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A provider of file contents, keyed by path.
+pub trait FileSource: Sync {
+    /// Raw bytes of `path`. `ErrorKind::NotFound` when the source lacks it.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Every file under `scope`, in a stable order.
+    fn files(&self, scope: &Path) -> Vec<PathBuf>;
+
+    /// When `path` last changed. Keys the outline cache, so a source whose
+    /// contents change must report a new time with each change.
+    fn modified(&self, path: &Path) -> Option<SystemTime>;
+
+    /// Whether `path` is a file in this source.
+    fn contains(&self, path: &Path) -> bool {
+        self.modified(path).is_some()
+    }
+}
+
+/// Files held in memory: nothing touches the disk.
+#[derive(Debug, Default)]
+pub struct MemorySource {
+    files: BTreeMap<PathBuf, (String, SystemTime)>,
+}
+
+impl MemorySource {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a file. Replacing stamps a new modification time, so
+    /// outlines cached for the old contents are not reused.
+    pub fn insert(&mut self, path: impl Into<PathBuf>, content: impl Into<String>) {
+        self.files
+            .insert(path.into(), (content.into(), SystemTime::now()));
+    }
+}
+
+impl FileSource for MemorySource {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files
+            .get(path)
+            .map(|(content, _)| content.clone().into_bytes())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn files(&self, scope: &Path) -> Vec<PathBuf> {
+        self.files
+            .keys()
+            .filter(|p| p.starts_with(scope))
+            .cloned()
+            .collect()
+    }
+
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        self.files.get(path).map(|(_, mtime)| *mtime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::OutlineCache;
+
+    fn project() -> (MemorySource, PathBuf) {
+        let scope = PathBuf::from("/glean-virtual/project");
+        assert!(!scope.exists());
+        let mut source = MemorySource::new();
+        source.insert(
+            scope.join("src/auth.rs"),
+            "pub struct Session {\n    user: String,\n}\n\nimpl Session {\n    pub fn refresh_token(&self) -> String {\n        self.user.clone()\n    }\n}\n",
+        );
+        source.insert(
+            scope.join("src/main.rs"),
+            "fn main() {\n    let token = session.refresh_token();\n}\n",
+        );
+        (source, scope)
+    }
+
+    #[test]
+    fn symbol_search_reads_from_memory() {
+        let (source, scope) = project();
+        let out = crate::run_source(
+            &source,
+            "refresh_token",
+            &scope,
+            None,
+            None,
+            &OutlineCache::new(),
+        )
+        .unwrap();
+
+        assert!(out.contains("2 matches (1 definitions, 1 usages)"), "{out}");
+        assert!(out.contains("src/auth.rs:6-8 [definition]"), "{out}");
+        assert!(out.contains("src/main.rs:2 [usage]"), "{out}");
+
+        let dotted = crate::search::symbol::search_source(
+            &source,
+            "Session.refresh_token",
+            &scope,
+            &crate::SearchFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(dotted.definitions, 1);
+
+        // Line counts and mtimes come from the source, not a stat of the path
+        let def = dotted.matches.iter().find(|m| m.is_definition).unwrap();
+        assert_eq!(def.file_lines, 9);
+        assert_eq!(Some(def.mtime), source.modified(&def.path));
+    }
+
+    #[test]
+    fn file_query_reads_buffer() {
+        let (mut source, scope) = project();
+        let cache = OutlineCache::new();
+        let out = crate::run_source(&source, "src/main.rs", &scope, None, None, &cache).unwrap();
+        assert!(out.contains("session.refresh_token()"), "{out}");
+
+        source.insert(scope.join("src/main.rs"), "fn main() {}\n");
+        let out =
+            crate::run_source(&source, "src/main.rs", &scope, Some("1-1"), None, &cache).unwrap();
+        assert!(out.contains("fn main() {}"), "{out}");
+    }
+
+    #[test]
+    fn stdin_input_content_searched() {
+        let log = "start\nINFO ok\nERROR disk full\nINFO retry\nERROR timeout\n".to_string();
+        let cache = OutlineCache::new();
+        let filter = crate::SearchFilter::default();
+
+        let out = crate::run_stdin(log.clone(), "ERROR", None, &filter, &cache).unwrap();
+        assert!(
+            out.starts_with("# Search: \"ERROR\" in stdin — 2 matches"),
+            "{out}"
+        );
+        assert!(out.contains("<stdin>:3"), "{out}");
+        assert!(out.contains("<stdin>:5"), "{out}");
+
+        let out = crate::run_stdin(log, "/IN[F]O r/", None, &filter, &cache).unwrap();
+        assert!(out.contains("<stdin>:4"), "{out}");
+        assert!(!out.contains("<stdin>:2"), "{out}");
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 /// What kind of query the user issued.
@@ -15,7 +15,7 @@ pub enum QueryType {
 /// Programming language, carried through the type system so downstream
 /// code never re-detects. Adding a language means adding an arm here
 /// and the compiler tells you everywhere else.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Lang {
     Rust,
     TypeScript,
@@ -31,18 +31,75 @@ pub enum Lang {
     Kotlin,
     CSharp,
     Zig,
+    Php,
+    Shell,
     Dockerfile,
     Make,
 }
 
+impl Lang {
+    /// Names `--lang` accepts, as listed in its error message.
+    pub const NAMES: &[&str] = &[
+        "rust",
+        "typescript",
+        "tsx",
+        "javascript",
+        "python",
+        "go",
+        "java",
+        "c",
+        "cpp",
+        "ruby",
+        "swift",
+        "kotlin",
+        "csharp",
+        "zig",
+        "php",
+        "shell",
+        "dockerfile",
+        "make",
+    ];
+
+    /// Parse a `--lang` name, case-insensitively. Accepts the names in
+    /// `NAMES` plus the usual short forms (`rs`, `ts`, `py`, `c++`, ...).
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Self::Rust,
+            "typescript" | "ts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "javascript" | "js" | "jsx" => Self::JavaScript,
+            "python" | "py" => Self::Python,
+            "go" | "golang" => Self::Go,
+            "java" => Self::Java,
+            "c" => Self::C,
+            "cpp" | "c++" | "cxx" => Self::Cpp,
+            "ruby" | "rb" => Self::Ruby,
+            "swift" => Self::Swift,
+            "kotlin" | "kt" => Self::Kotlin,
+            "csharp" | "c#" | "cs" => Self::CSharp,
+            "zig" => Self::Zig,
+            "php" => Self::Php,
+            "shell" | "sh" | "bash" | "zsh" => Self::Shell,
+            "dockerfile" | "docker" => Self::Dockerfile,
+            "make" | "makefile" => Self::Make,
+            _ => return None,
+        })
+    }
+}
+
 /// File type as detected by extension. Determines outline strategy.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum FileType {
     Code(Lang),
     Markdown,
     StructuredData,
     Tabular,
     Log,
+    /// Dependency lockfile (`Cargo.lock`, `package-lock.json`, `yarn.lock`).
+    Lockfile,
+    /// Jupyter notebook (`.ipynb`) — JSON, but outlined cell by cell.
+    Notebook,
     Other,
 }
 
@@ -56,11 +113,16 @@ pub enum ViewMode {
     HeadTail,
     Empty,
     Generated,
+    /// Minified bundle summarised instead of shown.
+    Minified,
+    Lockfile,
     #[expect(dead_code)]
     Binary,
     #[expect(dead_code)]
     Error,
     Section,
+    /// Full content with comments blanked (`strip_comments`).
+    Stripped,
 }
 
 impl std::fmt::Display for ViewMode {
@@ -72,9 +134,12 @@ impl std::fmt::Display for ViewMode {
             Self::HeadTail => write!(f, "head+tail"),
             Self::Empty => write!(f, "empty"),
             Self::Generated => write!(f, "generated — skipped"),
+            Self::Minified => write!(f, "minified"),
+            Self::Lockfile => write!(f, "lockfile"),
             Self::Binary => write!(f, "skipped"),
             Self::Error => write!(f, "error"),
             Self::Section => write!(f, "section"),
+            Self::Stripped => write!(f, "full, comments stripped"),
         }
     }
 }
@@ -88,6 +153,9 @@ pub struct Match {
     pub column: u32,
     pub text: String,
     pub is_definition: bool,
+    /// A definition that implements or extends the query rather than
+    /// declaring it: `impl Trait for Type`, `Foo implements Bar`, a subclass.
+    pub implementation: bool,
     pub exact: bool,
     pub file_lines: u32,
     pub mtime: SystemTime,
@@ -134,6 +202,8 @@ pub enum OutlineKind {
     Enum,
     Constant,
     Variable,
+    /// C/C++ `#define`.
+    Macro,
     Export,
     #[expect(dead_code)]
     Property,
@@ -149,6 +219,53 @@ pub fn estimate_tokens(byte_len: u64) -> u64 {
     byte_len.div_ceil(4)
 }
 
+/// Tokens in `text`: the cl100k BPE count with the `tokenizer` feature,
+/// else [`estimate_tokens`] of its length.
+#[must_use]
+pub fn count_tokens(text: &str) -> u64 {
+    #[cfg(feature = "tokenizer")]
+    {
+        tiktoken_rs::cl100k_base_singleton()
+            .encode_ordinary(text)
+            .len() as u64
+    }
+    #[cfg(not(feature = "tokenizer"))]
+    {
+        estimate_tokens(text.len() as u64)
+    }
+}
+
+/// Roughly how many bytes of `text` (`tokens_in_text` tokens long) hold
+/// `tokens` tokens — the inverse of [`count_tokens`].
+#[must_use]
+pub fn bytes_for_tokens(text: &str, tokens_in_text: u64, tokens: u64) -> usize {
+    if cfg!(feature = "tokenizer") && tokens_in_text > 0 {
+        (u128::from(tokens) * text.len() as u128 / u128::from(tokens_in_text)) as usize
+    } else {
+        (tokens * 4) as usize
+    }
+}
+
+/// Tokens in the file at `path`, `byte_len` bytes long. Without the
+/// `tokenizer` feature (or for files too big to tokenize quickly) this is the
+/// size estimate — no read.
+#[must_use]
+pub fn file_tokens(path: &Path, byte_len: u64) -> u64 {
+    #[cfg(feature = "tokenizer")]
+    if byte_len <= TOKENIZE_FILE_CAP
+        && let Ok(content) = std::fs::read_to_string(path)
+    {
+        return count_tokens(&content);
+    }
+    let _ = path;
+    estimate_tokens(byte_len)
+}
+
+/// Files above this are estimated even with the tokenizer: BPE runs at a few
+/// MB/s and a listing may cover thousands of files.
+#[cfg(feature = "tokenizer")]
+const TOKENIZE_FILE_CAP: u64 = 256 * 1024;
+
 /// UTF-8 safe string truncation. Never panics on multi-byte characters.
 #[must_use]
 pub fn truncate_str(s: &str, max: usize) -> &str {
@@ -0,0 +1,33 @@
+//! Platform-specific paths and handles.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The current user's home directory.
+#[cfg(unix)]
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+/// The current user's home directory.
+#[cfg(windows)]
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("USERPROFILE").map(PathBuf::from)
+}
+
+#[cfg(unix)]
+fn open_raw(path: &Path) -> io::Result<std::os::fd::RawFd> {
+    use std::os::fd::IntoRawFd;
+    Ok(std::fs::File::open(path)?.into_raw_fd())
+}
+
+#[cfg(windows)]
+fn open_raw(path: &Path) -> io::Result<std::os::windows::io::RawHandle> {
+    use std::os::windows::io::IntoRawHandle;
+    Ok(std::fs::File::open(path)?.into_raw_handle())
+}
+
+/// Where glean keeps per-user state.
+pub fn state_dir() -> Option<PathBuf> {
+    home_dir().map(|h| h.join(".glean"))
+}
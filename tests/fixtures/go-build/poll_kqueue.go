@@ -0,0 +1,7 @@
+//go:build darwin && (arm64 || amd64)
+
+package poller
+
+func pollWait(fd int) error {
+	return kqueueWait(fd)
+}
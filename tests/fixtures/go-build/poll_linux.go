@@ -0,0 +1,5 @@
+package poller
+
+func pollWait(fd int) error {
+	return epollWait(fd)
+}
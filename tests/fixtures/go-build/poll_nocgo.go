@@ -0,0 +1,8 @@
+//go:build !windows && !cgo
+
+package poller
+
+// pollWaitFallback is used when no native poller is available.
+func pollWaitFallback(fd int) error {
+	return nil
+}
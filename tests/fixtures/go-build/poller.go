@@ -0,0 +1,10 @@
+package poller
+
+// Poller waits for readiness on a file descriptor.
+type Poller struct {
+	fd int
+}
+
+func (p *Poller) Wait() error {
+	return pollWait(p.fd)
+}
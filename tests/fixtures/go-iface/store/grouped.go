@@ -0,0 +1,17 @@
+package store
+
+type (
+	// Closer releases whatever a store holds open.
+	Closer interface {
+		Close() error
+	}
+
+	// Flusher writes out buffered values.
+	Flusher interface {
+		Flush() error
+	}
+)
+
+func (m *MemoryStore) Close() error {
+	return nil
+}
@@ -0,0 +1,16 @@
+package store
+
+import "sync"
+
+// MemoryStore keeps values in a map guarded by a mutex.
+type MemoryStore struct {
+	mu   sync.Mutex
+	data map[string][]byte
+}
+
+func (m *MemoryStore) Put(key string, value []byte) error {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	m.data[key] = value
+	return nil
+}
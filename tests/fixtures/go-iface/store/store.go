@@ -0,0 +1,16 @@
+package store
+
+// Store is a minimal key-value store.
+type Store interface {
+	Get(key string) ([]byte, error)
+	Put(key string, value []byte) error
+}
+
+// ReadOnly serves lookups but cannot be written to.
+type ReadOnly struct {
+	data map[string][]byte
+}
+
+func (r ReadOnly) Get(key string) ([]byte, error) {
+	return r.data[key], nil
+}
@@ -0,0 +1,15 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/me/proj/internal/store"
+	"github.com/other/lib"
+)
+
+import u "github.com/me/proj/internal/util"
+
+func main() {
+	s := store.New()
+	fmt.Println(u.Title(s.Name()), lib.Version)
+}
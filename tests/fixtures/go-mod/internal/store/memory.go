@@ -0,0 +1,7 @@
+package store
+
+type memory struct{}
+
+func newMemory() *memory { return &memory{} }
+
+func (m *memory) Name() string { return "memory" }
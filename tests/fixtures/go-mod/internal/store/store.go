@@ -0,0 +1,9 @@
+package store
+
+type Store interface {
+	Name() string
+}
+
+func New() Store {
+	return newMemory()
+}
@@ -0,0 +1,9 @@
+package store
+
+import "testing"
+
+func TestNew(t *testing.T) {
+	if New().Name() != "memory" {
+		t.Fatal("unexpected store")
+	}
+}
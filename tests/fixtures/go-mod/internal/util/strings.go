@@ -0,0 +1,7 @@
+package util
+
+import "strings"
+
+func Title(s string) string {
+	return strings.ToUpper(s[:1]) + s[1:]
+}
@@ -0,0 +1,16 @@
+#include <stdlib.h>
+#include <string.h>
+#include "buffer.h"
+
+Buffer *buffer_new(size_t cap) {
+    Buffer *buf = malloc(sizeof(Buffer));
+    buf->cap = BUFFER_MAX(cap, BUFFER_DEFAULT_CAPACITY);
+    buf->len = 0;
+    buf->data = malloc(buf->cap);
+    return buf;
+}
+
+void buffer_push(Buffer *buf, const char *bytes, size_t n) {
+    memcpy(buf->data + buf->len, bytes, n);
+    buf->len += n;
+}
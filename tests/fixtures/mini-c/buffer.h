@@ -0,0 +1,20 @@
+#ifndef BUFFER_H
+#define BUFFER_H
+
+#include <stddef.h>
+
+#define BUFFER_DEFAULT_CAPACITY 4096
+#define BUFFER_MAX(a, b) ((a) > (b) ? (a) : (b))
+
+typedef struct {
+    char *data;
+    size_t len;
+    size_t cap;
+} Buffer;
+
+typedef void (*buffer_free_fn)(Buffer *buf);
+
+Buffer *buffer_new(size_t cap);
+void buffer_push(Buffer *buf, const char *bytes, size_t n);
+
+#endif
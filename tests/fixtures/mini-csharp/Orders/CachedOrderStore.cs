@@ -0,0 +1,15 @@
+using System.Collections.Generic;
+
+namespace Shop.Orders;
+
+public class CachedOrderStore : IOrderStore
+{
+    private readonly Dictionary<int, Order> _cache = new();
+
+    public Order Find(int id)
+    {
+        return _cache.TryGetValue(id, out var order) ? order : Load(id);
+    }
+
+    private Order Load(int id) => null;
+}
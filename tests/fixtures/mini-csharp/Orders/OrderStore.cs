@@ -0,0 +1,32 @@
+using System;
+using System.Collections.Generic;
+
+namespace Shop.Orders
+{
+    /// <summary>Stores orders.</summary>
+    public interface IOrderStore
+    {
+        Order Find(int id);
+    }
+
+    public class SqlOrderStore : BaseStore, IOrderStore, IDisposable
+    {
+        private readonly string _conn;
+
+        public SqlOrderStore(string conn) { _conn = conn; }
+
+        public Order Find(int id) => null;
+
+        public string Name { get; set; }
+
+        public void Dispose() { }
+    }
+
+    public struct Money { public decimal Amount; }
+
+    public record Order(int Id, Money Total);
+
+    public enum Status { Open, Closed }
+
+    public delegate void Changed(Order o);
+}
@@ -0,0 +1,13 @@
+package minigo
+
+import (
+	"net/http/httptest"
+	"testing"
+)
+
+func TestServeHTTP(t *testing.T) {
+	e := &Engine{}
+	w := httptest.NewRecorder()
+	r := httptest.NewRequest("GET", "/", nil)
+	e.ServeHTTP(w, r)
+}
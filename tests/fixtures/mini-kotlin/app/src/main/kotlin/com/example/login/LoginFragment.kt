@@ -0,0 +1,12 @@
+package com.example.login
+
+import androidx.fragment.app.Fragment
+
+class LoginFragment : Fragment() {
+    private lateinit var viewModel: LoginViewModel
+
+    fun bind(model: LoginViewModel) {
+        viewModel = model
+        viewModel.login("guest")
+    }
+}
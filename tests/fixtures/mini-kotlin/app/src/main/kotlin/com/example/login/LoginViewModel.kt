@@ -0,0 +1,17 @@
+package com.example.login
+
+import androidx.lifecycle.ViewModel
+
+/** Drives the login screen. */
+class LoginViewModel(private val repo: AuthRepository) : ViewModel() {
+    val state = repo.state
+
+    fun login(user: String) {
+        repo.signIn(user)
+    }
+}
+
+interface AuthRepository {
+    val state: String
+    fun signIn(user: String)
+}
@@ -0,0 +1,9 @@
+package com.example.login
+
+object Session {
+    var user: String? = null
+
+    fun clear() {
+        user = null
+    }
+}
@@ -0,0 +1,29 @@
+<?php
+
+namespace App\Http\Controllers;
+
+use App\Models\User;
+use App\Services\UserService;
+use Illuminate\Http\Request;
+
+/**
+ * Shows user profiles.
+ */
+class UserController extends Controller implements HasMiddleware
+{
+    use AuthorizesRequests;
+
+    private UserService $service;
+    const PER_PAGE = 20;
+
+    public function __construct(UserService $service)
+    {
+        $this->service = $service;
+    }
+
+    public function show(Request $request, int $id)
+    {
+        $user = $this->service->find($id);
+        return view('users.show', ['user' => User::format($user)]);
+    }
+}
@@ -0,0 +1,32 @@
+<?php
+
+namespace App\Services;
+
+interface Finder
+{
+    public function find(int $id);
+}
+
+trait Loggable
+{
+    public function log(string $message): void
+    {
+        error_log($message);
+    }
+}
+
+class UserService implements Finder
+{
+    use Loggable;
+
+    public function find(int $id)
+    {
+        $this->log("find {$id}");
+        return normalize_id($id);
+    }
+}
+
+function normalize_id($id)
+{
+    return (int) $id;
+}
@@ -0,0 +1,7 @@
+<?php
+
+use App\Http\Controllers\UserController;
+
+// Route::get('/users/{id}', [UserController::class, 'show']);
+$controller = app(UserController::class);
+$controller->show(request(), 1);
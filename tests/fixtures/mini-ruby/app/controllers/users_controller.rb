@@ -0,0 +1,7 @@
+class UsersController
+  def show
+    user = User.new(params[:name], params[:email])
+    user.save
+    render json: user.display_name
+  end
+end
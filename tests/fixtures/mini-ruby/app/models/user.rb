@@ -0,0 +1,27 @@
+# A registered account.
+class User
+  attr_accessor :name, :email
+  attr_reader :created_at
+
+  def initialize(name, email)
+    @name = name
+    @email = email
+  end
+
+  def display_name
+    name.capitalize
+  end
+
+  def each_role
+    roles.each { |role| yield role }
+  end
+
+  def save
+    validate
+    Repository.store self
+  end
+
+  def validate
+    raise ArgumentError, "email required" if email.nil?
+  end
+end
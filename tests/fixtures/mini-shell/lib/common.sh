@@ -0,0 +1,11 @@
+#!/bin/sh
+
+log() {
+    echo "[$(date +%T)] $*" >&2
+}
+
+# Also called by deploy() once the build is tagged.
+function upload_artifacts {
+    log "uploading"
+    rsync -a dist/ "deploy@$1:/srv/app"
+}
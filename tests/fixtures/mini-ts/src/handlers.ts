@@ -0,0 +1,30 @@
+import { ZodString } from "./schemas";
+
+export type Handler<T> = (input: T) => Promise<void>;
+
+/** Retry an async handler until it succeeds or attempts run out. */
+export const withRetry = async <TInput extends object, TOutput>(
+  handler: (input: TInput) => Promise<TOutput>,
+  attempts: number = 3,
+): Promise<TOutput | undefined> => {
+  for (let i = 0; i < attempts; i++) {
+    try {
+      return await handler({} as TInput);
+    } catch {
+      continue;
+    }
+  }
+  return undefined;
+};
+
+const isString = (schema: ZodString, value: unknown): value is string =>
+  typeof value === "string" && schema !== undefined;
+
+export const DEFAULT_ATTEMPTS = 3;
+
+interface Registry {
+  register<T extends object>(
+    name: string,
+    handler: Handler<T>,
+  ): () => void;
+}
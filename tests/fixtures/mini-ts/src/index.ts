@@ -0,0 +1,4 @@
+export { ZodError } from "./errors";
+export type { ZodIssue } from "./errors";
+export * from "./schemas";
+export { safeParse } from "./parse";
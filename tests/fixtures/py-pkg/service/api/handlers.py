@@ -0,0 +1,2 @@
+def list_users():
+    return []
@@ -0,0 +1,10 @@
+import os
+import service.db
+from service.util import slugify
+from . import handlers
+from ..models import User
+
+
+def route(path):
+    service.db.connect()
+    return handlers.list_users(), slugify(path), User, os.sep
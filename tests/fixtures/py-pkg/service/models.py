@@ -0,0 +1,7 @@
+class User:
+    class Meta:
+        def table(self):
+            return "users"
+
+    def table(self):
+        return Meta.table(self)
@@ -0,0 +1,14 @@
+/// Reads settings. Call parse_config once; `parse_config_path` names the file.
+pub fn parse_config(text: &str) -> Config {
+    Config {
+        raw: text.to_string(),
+    }
+}
+
+pub fn parse_config_path() -> &'static str {
+    "app.toml"
+}
+
+pub struct Config {
+    pub raw: String,
+}
@@ -0,0 +1,6 @@
+mod config;
+
+fn main() {
+    let a = parse_config(&read()); let b = parse_config("");
+    println!("{} {} {}", a.raw, b.raw, config::parse_config_path());
+}
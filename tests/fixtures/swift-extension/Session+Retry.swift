@@ -0,0 +1,12 @@
+import Foundation
+
+extension Session {
+    func retry(_ request: DataRequest) -> DataRequest {
+        request.cancel()
+        return self.request(request.url)
+    }
+}
+
+func makeSession() -> Session {
+    return Session()
+}
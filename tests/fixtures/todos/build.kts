@@ -0,0 +1,6 @@
+plugins {
+    kotlin("jvm")
+}
+
+// TODO: pin the toolchain version
+val marker = "TODO"
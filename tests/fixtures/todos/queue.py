@@ -0,0 +1,13 @@
+class Queue:
+    """A FIFO queue.
+
+    HACK: backed by a plain list; fine for small workloads.
+    """
+
+    def __init__(self):
+        self.items = []
+
+    def push(self, item):
+        # XXX: no capacity limit
+        self.items.append(item)
+        return "TODO"
@@ -0,0 +1,15 @@
+// Task scheduling helpers.
+
+export function retry(task: () => void, attempts: number): void {
+  // TODO: add exponential backoff between attempts
+  const label = "TODO: not a real marker";
+  for (let i = 0; i < attempts; i++) {
+    task();
+  }
+  console.log(label);
+}
+
+export function schedule(task: () => void, delayMs: number): void {
+  /* FIXME: clamp negative delays */
+  setTimeout(task, delayMs);
+}
@@ -0,0 +1,8 @@
+import React from "react";
+import { Price } from "@components/Price";
+import { currency } from "@config";
+import { formatPrice } from "src/lib/format";
+
+export function App() {
+  return <Price cents={formatPrice.length} />;
+}
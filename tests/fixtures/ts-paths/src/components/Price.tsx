@@ -0,0 +1,5 @@
+import { formatPrice } from "@/lib/format";
+
+export function Price({ cents }: { cents: number }) {
+  return <span>{formatPrice(cents)}</span>;
+}
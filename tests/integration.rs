@@ -105,6 +105,23 @@ fn swift_class_definition_first() {
     );
 }
 
+/// A Laravel-style controller method: the definition comes first and the
+/// route file's call is a usage.
+#[test]
+fn php_controller_method_definition_first() {
+    let output = run("show", &fixture("mini-php"));
+
+    let first_section = output.split("\n\n##").nth(1).expect("should have sections");
+    assert!(
+        first_section.contains("UserController.php:24-28 [definition]"),
+        "first section should be the controller method:\n{first_section}"
+    );
+    assert!(
+        output.contains("routes/web.php:7 [usage]"),
+        "route call should be a usage:\n{output}"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Content search: result precision
 // ---------------------------------------------------------------------------